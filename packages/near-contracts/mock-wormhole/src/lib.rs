@@ -0,0 +1,56 @@
+//! Test-only stand-in for the Wormhole core contract. Implements the subset of the
+//! interface `seal-initiator` calls (see its `wormhole.rs`) and records every published
+//! message so integration tests can assert on it. Never deploy this to a real network.
+
+use near_sdk::json_types::U128;
+use near_sdk::{env, log, near, AccountId};
+
+#[near(serializers = [borsh, json])]
+#[derive(Clone)]
+pub struct PublishedMessage {
+    pub emitter: AccountId,
+    /// Hex-encoded payload, as passed to `publish_message`
+    pub data: String,
+    pub nonce: u32,
+    pub consistency_level: u8,
+    pub sequence: u64,
+}
+
+#[near(contract_state)]
+#[derive(Default)]
+pub struct MockWormhole {
+    sequence: u64,
+    messages: Vec<PublishedMessage>,
+}
+
+#[near]
+impl MockWormhole {
+    /// Record the message and return its sequence (starting at 0). The fee is zero.
+    #[payable]
+    pub fn publish_message(&mut self, data: String, nonce: u32, consistency_level: u8) -> u64 {
+        let sequence = self.sequence;
+        self.sequence += 1;
+        let emitter = env::predecessor_account_id();
+        log!("MockWormhole: {} published seq={}", emitter, sequence);
+        self.messages.push(PublishedMessage {
+            emitter,
+            data,
+            nonce,
+            consistency_level,
+            sequence,
+        });
+        sequence
+    }
+
+    pub fn message_fee(&self) -> U128 {
+        U128(0)
+    }
+
+    /// Accepts any emitter.
+    #[payable]
+    pub fn register_emitter(&mut self) {}
+
+    pub fn get_messages(&self) -> Vec<PublishedMessage> {
+        self.messages.clone()
+    }
+}
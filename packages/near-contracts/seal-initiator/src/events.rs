@@ -15,6 +15,7 @@ struct SealInitiatedData {
     solana_receiver: String,
     wormhole_sequence: u64,
     source_chain_id: u16,
+    dest_chain: u16,
 }
 
 #[derive(Serialize)]
@@ -32,6 +33,7 @@ pub fn emit_seal_initiated(
     token_uri: &str,
     solana_receiver: &[u8; 32],
     wormhole_sequence: u64,
+    dest_chain: u16,
 ) {
     let data = SealInitiatedData {
         nft_contract: nft_contract.to_string(),
@@ -41,6 +43,7 @@ pub fn emit_seal_initiated(
         solana_receiver: hex::encode(solana_receiver),
         wormhole_sequence,
         source_chain_id: 15,
+        dest_chain,
     };
 
     let event = serde_json::json!({
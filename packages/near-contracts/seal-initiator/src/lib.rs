@@ -5,8 +5,9 @@ mod wormhole;
 
 use near_contract_standards::non_fungible_token::core::NonFungibleTokenReceiver;
 use near_contract_standards::non_fungible_token::Token;
-use near_sdk::borsh::BorshSerialize;
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
 use near_sdk::collections::{LookupMap, LookupSet};
+use near_sdk::json_types::U128;
 use near_sdk::{
     env, log, near, require, AccountId, BorshStorageKey, Gas, NearToken, PanicOnDefault, Promise,
     PromiseOrValue, PromiseResult,
@@ -15,14 +16,34 @@ use sha2::{Digest, Sha256};
 
 use events::{emit_seal_initiated, emit_token_uri_unavailable};
 use payload::build_seal_payload;
-use types::{PendingSeal, SealMsg, SealRecord};
+use types::{ConsistencyStatus, ContractStats, PendingSeal, PendingSealV1, SealMsg, SealRecord, SealRecordV1};
 use wormhole::{ext_nft, ext_wormhole};
 
 // Gas allocations for cross-contract calls
 const GAS_NFT_TOKEN: Gas = Gas::from_tgas(10);
-const GAS_CALLBACK_METADATA: Gas = Gas::from_tgas(70);
+/// Wormhole `message_fee()` view and its callback, each
+const GAS_MESSAGE_FEE: Gas = Gas::from_tgas(10);
+/// Covers the publish call plus the largest allowed Wormhole callback
+const GAS_CALLBACK_METADATA: Gas = Gas::from_tgas(90);
+/// Upper bound for a caller-supplied metadata callback budget (tx limit is 300 TGas)
+const MAX_GAS_CALLBACK_METADATA_TGAS: u64 = 200;
 const GAS_WORMHOLE_PUBLISH: Gas = Gas::from_tgas(50);
-const GAS_CALLBACK_WORMHOLE: Gas = Gas::from_tgas(10);
+/// Default `on_wormhole_published` budget; it writes the pending seal and the seal record
+const DEFAULT_GAS_CALLBACK_WORMHOLE_TGAS: u64 = 20;
+/// Owner-settable range for the Wormhole callback budget. The upper bound keeps
+/// publish + callback inside the default metadata callback budget.
+const MIN_GAS_CALLBACK_WORMHOLE_TGAS: u64 = 10;
+const MAX_GAS_CALLBACK_WORMHOLE_TGAS: u64 = 30;
+/// Covers the publish call plus its callback, chained from the one-shot metadata callback
+const GAS_CALLBACK_ONESHOT_METADATA: Gas = Gas::from_tgas(90);
+
+/// Wormhole consistency levels accepted by `set_consistency_level`
+const CONSISTENCY_LEVEL_CONFIRMED: u8 = 0;
+const CONSISTENCY_LEVEL_FINALIZED: u8 = 1;
+const CONSISTENCY_LEVEL_INSTANT: u8 = 200;
+
+/// Maximum length of the payload hash domain set at `new()`
+const MAX_HASH_DOMAIN_LENGTH: usize = 64;
 
 /// Maximum token URI length (matches EVM constant)
 const MAX_URI_LENGTH: usize = 2048;
@@ -33,12 +54,26 @@ const STORAGE_DEPOSIT_MIN: NearToken = NearToken::from_millinear(10); // 0.01 NE
 /// Minimum time before emergency unlock is allowed (24 hours in nanoseconds)
 const EMERGENCY_UNLOCK_DELAY_NS: u64 = 24 * 60 * 60 * 1_000_000_000;
 
+/// How long a started publish blocks emergency unlock if its callback never clears
+/// the marker (1 hour in nanoseconds); far longer than a publish takes to land
+const PUBLISH_IN_FLIGHT_NS: u64 = 60 * 60 * 1_000_000_000;
+
+/// Layout version of `SealInitiator`; v1 is the layout before `state_version` existed
+const STATE_VERSION: u32 = 2;
+
 #[derive(BorshSerialize, BorshStorageKey)]
 #[borsh(crate = "near_sdk::borsh")]
 enum StorageKey {
     SealedNfts,
     PendingSeals,
     SealRecords,
+    BaseUriFallbacks,
+    AllowedNftContracts,
+    PublishesInFlight,
+    /// v2 prefixes; `migrate` moves v1 entries here under the v2 seal key
+    SealedNftsV2,
+    PendingSealsV2,
+    SealRecordsV2,
 }
 
 #[near(contract_state)]
@@ -48,7 +83,7 @@ pub struct SealInitiator {
     wormhole_account: AccountId,
     /// Monotonically increasing nonce for Wormhole messages
     sequence: u64,
-    /// Replay protection: SHA256(nft_contract || token_id) -> bool
+    /// Replay protection: seal key (see `compute_seal_key`) -> bool
     sealed_nfts: LookupSet<Vec<u8>>,
     /// Pending seals (NFT locked, Wormhole not yet published)
     pending_seals: LookupMap<Vec<u8>, PendingSeal>,
@@ -58,39 +93,199 @@ pub struct SealInitiator {
     owner: AccountId,
     /// Whether the contract is paused
     paused: bool,
+    /// NFTs currently locked (pending or completed)
+    total_sealed: u64,
+    /// Seals published to Wormhole
+    total_completed: u64,
+    /// Locked NFTs not yet published
+    total_pending: u64,
+    /// Owner-configured `base_uri` per NFT contract, used as `{base_uri}/{token_id}`
+    /// when a token has neither `reference` nor `media`
+    base_uri_fallbacks: LookupMap<AccountId, String>,
+    /// Static gas for `on_wormhole_published` / `on_oneshot_published`, in TGas
+    wormhole_callback_gas_tgas: u64,
+    /// Consistency level forwarded to `publish_message` (default: finalized)
+    consistency_level: u8,
+    /// Prefix for the SHA256 field encodings in the payload, fixed at `new()`;
+    /// empty = plain SHA256 (see `payload::encode_near_account`)
+    hash_domain: String,
+    /// NFT contracts accepted by `nft_on_transfer`; empty = any contract
+    allowed_nft_contracts: LookupSet<AccountId>,
+    /// Size of `allowed_nft_contracts` (LookupSet can't report it)
+    allowed_nft_contract_count: u64,
+    /// seal_key -> `locked_until` (ns) for seals whose Wormhole publish has started but
+    /// not been recorded; `emergency_unlock` is refused until it clears or expires
+    publishes_in_flight: LookupMap<Vec<u8>, u64>,
+    /// Owner-configured placeholder URI sealed when no token URI can be resolved
+    /// (`token_uri_unavailable`), so the reborn NFT is never blank
+    fallback_uri: Option<String>,
+    /// `STATE_VERSION` this state was written with; `migrate` runs only on older state
+    state_version: u32,
+    /// Wormhole `message_fee()` as last read by `refresh_message_fee`; the minimum
+    /// deposit `complete_seal_initiation` accepts. None until first read, and sealing
+    /// through `complete_seal_initiation` or one-shot is refused until then
+    message_fee: Option<NearToken>,
+    /// Block timestamp (ns) of the last Wormhole publish seen to succeed; 0 = never
+    last_successful_publish_ns: u64,
+    /// Destinations other than Solana that `nft_on_transfer` accepts; empty = Solana
+    /// only. Enable a chain only once the orchestrator can mint there
+    enabled_dest_chains: Vec<u16>,
+}
+
+/// `SealInitiator` as originally deployed (v1, before `state_version`): the seal maps
+/// live under the `SealedNfts` / `PendingSeals` / `SealRecords` prefixes, keyed by
+/// `legacy_seal_key`. Only read by `migrate`.
+#[derive(BorshDeserialize, BorshSerialize)]
+#[borsh(crate = "near_sdk::borsh")]
+struct SealInitiatorV1 {
+    wormhole_account: AccountId,
+    sequence: u64,
+    sealed_nfts: LookupSet<Vec<u8>>,
+    pending_seals: LookupMap<Vec<u8>, PendingSealV1>,
+    seal_records: LookupMap<Vec<u8>, SealRecordV1>,
+    owner: AccountId,
+    paused: bool,
 }
 
 #[near]
 impl SealInitiator {
     /// Deploy-time initialization.
+    ///
+    /// `hash_domain` separates this deployment's payload field hashes from every other
+    /// deployment's. It can't be changed later, and consumers of the payload must
+    /// use the same value.
     #[init]
-    pub fn new(wormhole_account: AccountId) -> Self {
+    pub fn new(wormhole_account: AccountId, hash_domain: Option<String>) -> Self {
         require!(
             env::is_valid_account_id(wormhole_account.as_bytes()),
             "Invalid Wormhole account"
         );
+        let hash_domain = hash_domain.unwrap_or_default();
+        require!(hash_domain.len() <= MAX_HASH_DOMAIN_LENGTH, "Hash domain too long");
+        require!(!hash_domain.contains('\0'), "Hash domain must not contain NUL");
         Self {
             wormhole_account,
             sequence: 0,
-            sealed_nfts: LookupSet::new(StorageKey::SealedNfts),
-            pending_seals: LookupMap::new(StorageKey::PendingSeals),
-            seal_records: LookupMap::new(StorageKey::SealRecords),
+            sealed_nfts: LookupSet::new(StorageKey::SealedNftsV2),
+            pending_seals: LookupMap::new(StorageKey::PendingSealsV2),
+            seal_records: LookupMap::new(StorageKey::SealRecordsV2),
             owner: env::predecessor_account_id(),
             paused: false,
+            total_sealed: 0,
+            total_completed: 0,
+            total_pending: 0,
+            base_uri_fallbacks: LookupMap::new(StorageKey::BaseUriFallbacks),
+            wormhole_callback_gas_tgas: DEFAULT_GAS_CALLBACK_WORMHOLE_TGAS,
+            consistency_level: CONSISTENCY_LEVEL_FINALIZED,
+            hash_domain,
+            allowed_nft_contracts: LookupSet::new(StorageKey::AllowedNftContracts),
+            allowed_nft_contract_count: 0,
+            publishes_in_flight: LookupMap::new(StorageKey::PublishesInFlight),
+            fallback_uri: None,
+            state_version: STATE_VERSION,
+            message_fee: None,
+            last_successful_publish_ns: 0,
+            enabled_dest_chains: Vec::new(),
+        }
+    }
+
+    /// Upgrade a v1 state in place. Each listed NFT's `sealed_nfts`, `pending_seals`
+    /// and `seal_records` entries move from `legacy_seal_key` under the v1 prefixes to
+    /// `compute_seal_key` under the v2 prefixes, converted to the current layout
+    /// (Solana destination, `publishing = false`); the seal counters are rebuilt from
+    /// them. Call once from the contract account (deploy + function call batch) with
+    /// every sealed or pending (nft_contract, token_id), e.g. from the `NFT locked`
+    /// logs; entries left out stay unreachable under the v1 prefixes. Settings added
+    /// since v1 start at their `new()` defaults. Refused once `state_version` is current.
+    #[private]
+    #[init(ignore_state)]
+    pub fn migrate(entries: Vec<(AccountId, String)>) -> Self {
+        let raw = env::storage_read(b"STATE").expect("No state to migrate");
+        // v1 predates `state_version`, so it doesn't parse as the current layout
+        let state_version = SealInitiator::try_from_slice(&raw).map_or(1, |state| state.state_version);
+        require!(state_version < STATE_VERSION, "State already migrated");
+        let old = SealInitiatorV1::try_from_slice(&raw).expect("Unrecognized state layout");
+        let (mut old_sealed, mut old_pending, mut old_records) =
+            (old.sealed_nfts, old.pending_seals, old.seal_records);
+
+        let mut contract = Self {
+            wormhole_account: old.wormhole_account,
+            sequence: old.sequence,
+            sealed_nfts: LookupSet::new(StorageKey::SealedNftsV2),
+            pending_seals: LookupMap::new(StorageKey::PendingSealsV2),
+            seal_records: LookupMap::new(StorageKey::SealRecordsV2),
+            owner: old.owner,
+            paused: old.paused,
+            total_sealed: 0,
+            total_completed: 0,
+            total_pending: 0,
+            base_uri_fallbacks: LookupMap::new(StorageKey::BaseUriFallbacks),
+            wormhole_callback_gas_tgas: DEFAULT_GAS_CALLBACK_WORMHOLE_TGAS,
+            consistency_level: CONSISTENCY_LEVEL_FINALIZED,
+            hash_domain: String::new(),
+            allowed_nft_contracts: LookupSet::new(StorageKey::AllowedNftContracts),
+            allowed_nft_contract_count: 0,
+            publishes_in_flight: LookupMap::new(StorageKey::PublishesInFlight),
+            fallback_uri: None,
+            state_version: STATE_VERSION,
+            message_fee: None,
+            last_successful_publish_ns: 0,
+            enabled_dest_chains: Vec::new(),
+        };
+
+        let mut records = 0u64;
+        for (nft_contract, token_id) in entries {
+            let old_key = legacy_seal_key(&nft_contract, &token_id);
+            let seal_key = contract.compute_seal_key(&nft_contract, &token_id);
+            if old_sealed.remove(&old_key) {
+                contract.sealed_nfts.insert(&seal_key);
+                contract.total_sealed += 1;
+            }
+            if let Some(seal) = old_pending.remove(&old_key) {
+                let seal = PendingSeal::from(seal);
+                if seal.completed {
+                    contract.total_completed += 1;
+                } else {
+                    contract.total_pending += 1;
+                }
+                contract.pending_seals.insert(&seal_key, &seal);
+            }
+            if let Some(record) = old_records.remove(&old_key) {
+                contract.seal_records.insert(&seal_key, &record.into());
+                records += 1;
+            }
         }
+        log!(
+            "Migrated {} sealed, {} pending and {} recorded seals to state v{}",
+            contract.total_sealed,
+            contract.total_completed + contract.total_pending,
+            records,
+            STATE_VERSION
+        );
+        contract
     }
 
     // ── Core Flow ──
 
     /// Step 2: Complete the seal by querying metadata and publishing Wormhole VAA.
     /// Permissionless (anyone can call). Requires attached NEAR for Wormhole fee.
+    ///
+    /// The deposit must cover the cached Wormhole `message_fee` (see
+    /// `refresh_message_fee`), checked before anything is spawned; exactly the fee is
+    /// forwarded and any surplus is returned to the caller. Refused until the fee has
+    /// been read once.
+    ///
+    /// `metadata_gas_tgas` raises the metadata callback budget for NFTs with large
+    /// metadata (default 90 TGas, capped at 200 TGas).
     #[payable]
     pub fn complete_seal_initiation(
         &mut self,
         nft_contract: AccountId,
         token_id: String,
+        metadata_gas_tgas: Option<u64>,
     ) -> Promise {
         require!(!self.paused, "Contract is paused");
+        let metadata_gas = metadata_callback_gas(metadata_gas_tgas);
 
         let seal_key = self.compute_seal_key(&nft_contract, &token_id);
         let pending = self
@@ -98,6 +293,7 @@ impl SealInitiator {
             .get(&seal_key)
             .expect("No pending seal found for this NFT");
         require!(!pending.completed, "Seal already completed");
+        let fee = self.take_message_fee(env::attached_deposit());
 
         // Cross-contract call to NFT contract to get metadata
         ext_nft::ext(nft_contract.clone())
@@ -105,8 +301,8 @@ impl SealInitiator {
             .nft_token(token_id.clone())
             .then(
                 Self::ext(env::current_account_id())
-                    .with_static_gas(GAS_CALLBACK_METADATA)
-                    .with_attached_deposit(env::attached_deposit())
+                    .with_static_gas(metadata_gas)
+                    .with_attached_deposit(fee)
                     .on_nft_metadata_received(
                         nft_contract,
                         token_id,
@@ -117,7 +313,69 @@ impl SealInitiator {
             )
     }
 
+    /// Owner-only fallback for NFT contracts whose `nft_token` is missing, gated or
+    /// non-standard: skips the metadata call and publishes with `token_uri` directly.
+    ///
+    /// TRUST: the URI is asserted by the owner, not read from the NFT contract, so the
+    /// reborn NFT's metadata is only as trustworthy as the owner.
+    #[payable]
+    pub fn complete_seal_with_uri(
+        &mut self,
+        nft_contract: AccountId,
+        token_id: String,
+        token_uri: String,
+    ) -> Promise {
+        require!(
+            env::predecessor_account_id() == self.owner,
+            "Only owner can complete with a supplied URI"
+        );
+        require!(!self.paused, "Contract is paused");
+        require!(token_uri.len() <= MAX_URI_LENGTH, "URI too long");
+
+        let seal_key = self.compute_seal_key(&nft_contract, &token_id);
+        let mut pending = self
+            .pending_seals
+            .get(&seal_key)
+            .expect("No pending seal found for this NFT");
+        require!(!pending.completed, "Seal already completed");
+        require!(!pending.publishing, "Seal publish already in progress");
+        pending.publishing = true;
+        self.pending_seals.insert(&seal_key, &pending);
+        self.mark_publish_in_flight(&seal_key);
+
+        log!(
+            "Completing seal with owner-supplied URI: contract={} token_id={}",
+            nft_contract,
+            token_id
+        );
+
+        self.publish_seal(
+            &nft_contract,
+            &token_id,
+            &pending.deposit_address,
+            &pending.solana_receiver,
+            &token_uri,
+            env::attached_deposit(),
+        )
+        .then(
+            Self::ext(env::current_account_id())
+                .with_static_gas(Gas::from_tgas(self.wormhole_callback_gas_tgas))
+                .on_wormhole_published(
+                    nft_contract,
+                    token_id,
+                    pending.deposit_address,
+                    pending.solana_receiver,
+                    token_uri,
+                    seal_key,
+                ),
+        )
+    }
+
     /// Callback: metadata received from NFT contract.
+    ///
+    /// Two `complete_seal_initiation` calls for one seal can both get here. Only the
+    /// first publishes: the other finds `publishing` (or `completed`) set and refunds
+    /// its deposit to the signer instead.
     #[private]
     #[payable]
     pub fn on_nft_metadata_received(
@@ -128,59 +386,120 @@ impl SealInitiator {
         solana_receiver: [u8; 32],
         seal_key: Vec<u8>,
     ) -> Promise {
-        // Parse the nft_token result
-        require!(
-            env::promise_results_count() == 1,
-            "Expected one promise result"
-        );
-        #[allow(deprecated)]
-        let token: Option<Token> = match env::promise_result(0) {
-            PromiseResult::Successful(data) => {
-                serde_json::from_slice(&data).expect("Failed to parse nft_token result")
-            }
-            _ => env::panic_str("nft_token call failed"),
-        };
-        let token = token.expect("NFT not found");
-
-        // Extract URI: prefer reference, fall back to media
-        let token_uri = token
-            .metadata
-            .as_ref()
-            .and_then(|m| m.reference.as_ref().or(m.media.as_ref()))
-            .cloned()
-            .unwrap_or_default();
-
-        if token_uri.is_empty() {
-            emit_token_uri_unavailable(nft_contract.as_str(), &token_id);
+        let mut pending = self.pending_seals.get(&seal_key).expect("No pending seal found for this NFT");
+        if pending.publishing || pending.completed {
+            log!("Seal publish already in progress or done: nft={} token_id={}", nft_contract, token_id);
+            return Promise::new(env::signer_account_id()).transfer(env::attached_deposit());
         }
+        pending.publishing = true;
+        self.pending_seals.insert(&seal_key, &pending);
 
-        require!(token_uri.len() <= MAX_URI_LENGTH, "URI too long");
+        let token_uri = match self.token_uri_from_result(&nft_contract, &token_id) {
+            Ok(uri) => uri,
+            Err(reason) => env::panic_str(reason),
+        };
+        self.mark_publish_in_flight(&seal_key);
 
-        // Build binary payload (wire format)
-        let payload_bytes = build_seal_payload(
-            nft_contract.as_str(),
+        self.publish_seal(
+            &nft_contract,
             &token_id,
             &deposit_address,
             &solana_receiver,
             &token_uri,
-        );
+            env::attached_deposit(),
+        )
+        .then(
+            Self::ext(env::current_account_id())
+                .with_static_gas(Gas::from_tgas(self.wormhole_callback_gas_tgas))
+                .on_wormhole_published(
+                    nft_contract,
+                    token_id,
+                    deposit_address,
+                    solana_receiver,
+                    token_uri,
+                    seal_key,
+                ),
+        )
+    }
 
-        // Hex-encode for Wormhole NEAR contract
-        let payload_hex = hex::encode(&payload_bytes);
+    /// Callback: Wormhole message published. Idempotent: re-running it for a seal that is
+    /// already recorded under the same sequence changes nothing.
+    #[private]
+    pub fn on_wormhole_published(
+        &mut self,
+        nft_contract: AccountId,
+        token_id: String,
+        deposit_address: String,
+        solana_receiver: [u8; 32],
+        token_uri: String,
+        seal_key: Vec<u8>,
+    ) {
+        let wormhole_sequence = match Self::wormhole_sequence_from_result() {
+            Ok(sequence) => sequence,
+            Err(reason) => {
+                // Not terminal: clear `publishing` so the seal can be completed again
+                log!("Seal publish failed ({}): nft={} token_id={}", reason, nft_contract, token_id);
+                self.clear_publishing(&seal_key);
+                return;
+            }
+        };
+        self.last_successful_publish_ns = env::block_timestamp();
 
-        let nonce = (self.sequence & 0xFFFF_FFFF) as u32;
-        self.sequence += 1;
-        let _local_sequence = self.sequence - 1;
+        self.record_completed_seal(
+            nft_contract,
+            token_id,
+            deposit_address,
+            solana_receiver,
+            token_uri,
+            seal_key,
+            wormhole_sequence,
+        );
+    }
 
-        // Cross-contract call to Wormhole publish_message
-        ext_wormhole::ext(self.wormhole_account.clone())
-            .with_attached_deposit(env::attached_deposit())
-            .with_static_gas(GAS_WORMHOLE_PUBLISH)
-            .publish_message(payload_hex, nonce)
+    /// One-shot callback: metadata received during `nft_on_transfer`.
+    /// Resolves to `true` (return the NFT) if the metadata is unusable, and to `false`
+    /// (keep it) without publishing if a `complete_seal_initiation` got there first.
+    #[private]
+    pub fn on_oneshot_metadata_received(
+        &mut self,
+        nft_contract: AccountId,
+        token_id: String,
+        deposit_address: String,
+        solana_receiver: [u8; 32],
+        seal_key: Vec<u8>,
+    ) -> PromiseOrValue<bool> {
+        let mut pending = self.pending_seals.get(&seal_key).expect("No pending seal found for this NFT");
+        if pending.publishing || pending.completed {
+            log!("Seal publish already in progress or done: nft={} token_id={}", nft_contract, token_id);
+            return PromiseOrValue::Value(false);
+        }
+        let token_uri = match self.token_uri_from_result(&nft_contract, &token_id) {
+            Ok(uri) => uri,
+            Err(reason) => {
+                log!("One-shot seal failed ({}), refunding NFT", reason);
+                self.release_pending_seal(seal_key);
+                return PromiseOrValue::Value(true);
+            }
+        };
+        pending.publishing = true;
+        self.pending_seals.insert(&seal_key, &pending);
+        self.mark_publish_in_flight(&seal_key);
+
+        // No deposit is available inside nft_on_transfer; `nft_on_transfer` only takes
+        // this path while the cached Wormhole message fee is known to be zero
+        let promise = self
+            .publish_seal(
+                &nft_contract,
+                &token_id,
+                &deposit_address,
+                &solana_receiver,
+                &token_uri,
+                NearToken::from_yoctonear(0),
+            )
             .then(
                 Self::ext(env::current_account_id())
-                    .with_static_gas(GAS_CALLBACK_WORMHOLE)
-                    .on_wormhole_published(
+                    .with_static_gas(Gas::from_tgas(self.wormhole_callback_gas_tgas))
+                    .on_oneshot_published(
                         nft_contract,
                         token_id,
                         deposit_address,
@@ -188,12 +507,14 @@ impl SealInitiator {
                         token_uri,
                         seal_key,
                     ),
-            )
+            );
+        PromiseOrValue::Promise(promise)
     }
 
-    /// Callback: Wormhole message published.
+    /// One-shot callback: Wormhole publish finished. Returns `false` (keep the NFT)
+    /// on success and `true` (return it) if the publish failed.
     #[private]
-    pub fn on_wormhole_published(
+    pub fn on_oneshot_published(
         &mut self,
         nft_contract: AccountId,
         token_id: String,
@@ -201,55 +522,94 @@ impl SealInitiator {
         solana_receiver: [u8; 32],
         token_uri: String,
         seal_key: Vec<u8>,
-    ) {
-        require!(
-            env::promise_results_count() == 1,
-            "Expected one promise result"
-        );
+    ) -> bool {
+        match Self::wormhole_sequence_from_result() {
+            Ok(wormhole_sequence) => {
+                self.last_successful_publish_ns = env::block_timestamp();
+                self.record_completed_seal(
+                    nft_contract,
+                    token_id,
+                    deposit_address,
+                    solana_receiver,
+                    token_uri,
+                    seal_key,
+                    wormhole_sequence,
+                );
+                false
+            }
+            Err(reason) => {
+                log!("One-shot seal failed ({}), refunding NFT", reason);
+                self.release_pending_seal(seal_key);
+                true
+            }
+        }
+    }
+
+    /// Re-read Wormhole's `message_fee()` into the cached minimum deposit for
+    /// `complete_seal_initiation`. Permissionless; call after Wormhole changes its fee.
+    pub fn refresh_message_fee(&mut self) -> Promise {
+        ext_wormhole::ext(self.wormhole_account.clone())
+            .with_static_gas(GAS_MESSAGE_FEE)
+            .message_fee()
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_MESSAGE_FEE)
+                    .on_message_fee_received(),
+            )
+    }
+
+    /// Callback: Wormhole `message_fee()` result.
+    #[private]
+    pub fn on_message_fee_received(&mut self) -> U128 {
+        require!(env::promise_results_count() == 1, "Expected one promise result");
         #[allow(deprecated)]
-        let wormhole_sequence: u64 = match env::promise_result(0) {
+        let fee: U128 = match env::promise_result(0) {
             PromiseResult::Successful(data) => {
-                serde_json::from_slice(&data).expect("Failed to parse Wormhole sequence")
+                serde_json::from_slice(&data).unwrap_or_else(|_| env::panic_str("Failed to parse message fee"))
             }
-            _ => env::panic_str("Wormhole publish_message failed"),
+            _ => env::panic_str("Wormhole message_fee call failed"),
         };
+        self.message_fee = Some(NearToken::from_yoctonear(fee.0));
+        log!("Wormhole message fee: {} yoctoNEAR", fee.0);
+        fee
+    }
 
-        // Mark pending seal as completed
-        if let Some(mut pending) = self.pending_seals.get(&seal_key) {
-            pending.completed = true;
-            pending.wormhole_sequence = wormhole_sequence;
-            self.pending_seals.insert(&seal_key, &pending);
-        }
+    /// Check that `wormhole_account` answers `message_fee()`. Resolves to `true` if it
+    /// responded (and refreshes the cached fee), `false` otherwise, e.g. when the
+    /// account is wrong. Permissionless.
+    pub fn check_wormhole_health(&mut self) -> Promise {
+        ext_wormhole::ext(self.wormhole_account.clone())
+            .with_static_gas(GAS_MESSAGE_FEE)
+            .message_fee()
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_MESSAGE_FEE)
+                    .on_wormhole_health_checked(),
+            )
+    }
 
-        // Store completed seal record
-        let record = SealRecord {
-            nft_contract: nft_contract.clone(),
-            token_id: token_id.clone(),
-            deposit_address: deposit_address.clone(),
-            solana_receiver,
-            token_uri: token_uri.clone(),
-            wormhole_sequence,
-            source_chain_id: payload::WORMHOLE_CHAIN_ID_NEAR,
-            sealed_at: env::block_timestamp(),
+    /// Callback: `message_fee()` result for `check_wormhole_health`. Never panics.
+    #[private]
+    pub fn on_wormhole_health_checked(&mut self) -> bool {
+        if env::promise_results_count() != 1 {
+            return false;
+        }
+        #[allow(deprecated)]
+        let fee = match env::promise_result(0) {
+            PromiseResult::Successful(data) => serde_json::from_slice::<U128>(&data).ok(),
+            _ => None,
         };
-        self.seal_records.insert(&seal_key, &record);
-
-        // Emit NEP-297 event
-        emit_seal_initiated(
-            nft_contract.as_str(),
-            &token_id,
-            &deposit_address,
-            &token_uri,
-            &solana_receiver,
-            wormhole_sequence,
-        );
-
-        log!(
-            "SealInitiated: nft={} token_id={} seq={}",
-            nft_contract,
-            token_id,
-            wormhole_sequence
-        );
+        match fee {
+            Some(fee) => {
+                self.message_fee = Some(NearToken::from_yoctonear(fee.0));
+                log!("Wormhole {} is reachable, message fee {} yoctoNEAR", self.wormhole_account, fee.0);
+                true
+            }
+            None => {
+                log!("Wormhole {} did not answer message_fee", self.wormhole_account);
+                false
+            }
+        }
     }
 
     // ── Admin ──
@@ -287,91 +647,314 @@ impl SealInitiator {
         self.wormhole_account = new_wormhole_account;
     }
 
-    /// Pause/unpause the contract.
-    pub fn set_paused(&mut self, paused: bool) {
+    /// Set the Wormhole consistency level for published seals: 1 = finalized (default),
+    /// 0 = confirmed, 200 = instant. Lower finality lets the relayer act sooner at the
+    /// cost of reorg risk.
+    pub fn set_consistency_level(&mut self, consistency_level: u8) {
         require!(
             env::predecessor_account_id() == self.owner,
             "Only owner"
         );
-        self.paused = paused;
+        require!(
+            matches!(
+                consistency_level,
+                CONSISTENCY_LEVEL_CONFIRMED | CONSISTENCY_LEVEL_FINALIZED | CONSISTENCY_LEVEL_INSTANT
+            ),
+            "Unsupported consistency level"
+        );
+        self.consistency_level = consistency_level;
     }
 
-    /// Transfer ownership.
-    pub fn transfer_ownership(&mut self, new_owner: AccountId) {
+    /// Set the static gas (TGas) attached to the Wormhole publish callback.
+    pub fn set_wormhole_callback_gas(&mut self, tgas: u64) {
         require!(
             env::predecessor_account_id() == self.owner,
             "Only owner"
         );
-        self.owner = new_owner;
+        require!(
+            (MIN_GAS_CALLBACK_WORMHOLE_TGAS..=MAX_GAS_CALLBACK_WORMHOLE_TGAS).contains(&tgas),
+            "Callback gas out of range"
+        );
+        self.wormhole_callback_gas_tgas = tgas;
     }
 
-    /// Emergency unlock: release a stuck NFT back to its original deposit address.
-    ///
-    /// Only callable by the owner, and only for pending seals that have NOT been
-    /// completed (Wormhole message not yet published). Enforces a minimum delay
-    /// (24 hours) since the NFT was locked to prevent abuse.
-    ///
-    /// This is a safety valve for cases where complete_seal_initiation cannot
-    /// succeed (e.g., Wormhole is down, metadata call fails permanently).
-    pub fn emergency_unlock(
+    /// Re-run the completion write for a seal whose Wormhole message was published but
+    /// whose callback failed (e.g. ran out of gas), leaving it pending. Owner only: the
+    /// sequence and URI come from the published VAA, not from a promise result. Only a
+    /// seal with a publish started, and a sequence this contract has already emitted,
+    /// can be completed this way.
+    pub fn retry_seal_record(
         &mut self,
         nft_contract: AccountId,
         token_id: String,
-    ) -> Promise {
+        token_uri: String,
+        wormhole_sequence: u64,
+    ) {
         require!(
             env::predecessor_account_id() == self.owner,
-            "Only owner can emergency unlock"
+            "Only owner"
         );
-        require!(!self.paused, "Contract is paused");
-
+        require!(token_uri.len() <= MAX_URI_LENGTH, "URI too long");
         let seal_key = self.compute_seal_key(&nft_contract, &token_id);
         let pending = self
             .pending_seals
             .get(&seal_key)
             .expect("No pending seal found for this NFT");
-
-        require!(!pending.completed, "Seal already completed, cannot unlock");
-
-        // Enforce minimum delay to prevent premature unlocks
-        let elapsed = env::block_timestamp() - pending.created_at;
         require!(
-            elapsed >= EMERGENCY_UNLOCK_DELAY_NS,
-            "Emergency unlock not yet available (24h delay)"
+            pending.publishing || self.is_publish_in_flight(&seal_key),
+            "No Wormhole publish started for this seal"
         );
-
-        // Clean up state: remove from sealed set and pending map
-        self.sealed_nfts.remove(&seal_key);
-        self.pending_seals.remove(&seal_key);
-
-        log!(
-            "EmergencyUnlock: contract={} token_id={} returning to={}",
+        require!(wormhole_sequence < self.sequence, "Wormhole sequence not yet emitted");
+        self.record_completed_seal(
             nft_contract,
             token_id,
-            pending.deposit_address
+            pending.deposit_address,
+            pending.solana_receiver,
+            token_uri,
+            seal_key,
+            wormhole_sequence,
         );
-
-        // Transfer NFT back via nft_transfer
-        let deposit_account: AccountId = pending.deposit_address.parse()
-            .expect("Invalid deposit address for NFT return");
-        Promise::new(nft_contract).function_call(
-            "nft_transfer".to_string(),
-            serde_json::json!({
-                "receiver_id": deposit_account,
-                "token_id": token_id,
-            })
-            .to_string()
-            .into_bytes(),
-            NearToken::from_yoctonear(1), // 1 yoctoNEAR required for nft_transfer
-            Gas::from_tgas(15),
-        )
     }
 
-    // ── View Functions ──
-
-    /// Check if an NFT has been sealed.
-    pub fn is_sealed(&self, nft_contract: AccountId, token_id: String) -> bool {
+    /// Reconcile `pending_seals` and `seal_records` for one NFT and return the resulting
+    /// status. The seal record is authoritative when present: it is only written from
+    /// a successful Wormhole publish. A completed pending seal without a record gets a
+    /// record rebuilt from it, with an empty `token_uri`.
+    pub fn repair_seal_record(&mut self, nft_contract: AccountId, token_id: String) -> ConsistencyStatus {
+        require!(
+            env::predecessor_account_id() == self.owner,
+            "Only owner"
+        );
         let seal_key = self.compute_seal_key(&nft_contract, &token_id);
-        self.sealed_nfts.contains(&seal_key)
+        match self.check_seal_consistency(nft_contract.clone(), token_id.clone()) {
+            ConsistencyStatus::CompletedWithoutRecord => {
+                let pending = self.pending_seals.get(&seal_key).unwrap();
+                let record = SealRecord {
+                    nft_contract: nft_contract.clone(),
+                    token_id: token_id.clone(),
+                    deposit_address: pending.deposit_address,
+                    solana_receiver: pending.solana_receiver,
+                    token_uri: String::new(),
+                    wormhole_sequence: pending.wormhole_sequence,
+                    source_chain_id: payload::WORMHOLE_CHAIN_ID_NEAR,
+                    sealed_at: env::block_timestamp(),
+                    dest_chain: pending.dest_chain,
+                };
+                log!("Repaired missing seal record (token_uri unknown) seq={}", record.wormhole_sequence);
+                self.seal_records.insert(&seal_key, &record);
+            }
+            ConsistencyStatus::RecordWithoutCompletion => {
+                let record = self.seal_records.get(&seal_key).unwrap();
+                let pending = match self.pending_seals.get(&seal_key) {
+                    Some(pending) => {
+                        if !pending.completed {
+                            self.total_pending = self.total_pending.saturating_sub(1);
+                            self.total_completed += 1;
+                        }
+                        pending
+                    }
+                    None => {
+                        if self.sealed_nfts.insert(&seal_key) {
+                            self.total_sealed += 1;
+                        }
+                        self.total_completed += 1;
+                        PendingSeal {
+                            nft_contract: record.nft_contract.clone(),
+                            token_id: record.token_id.clone(),
+                            deposit_address: record.deposit_address.clone(),
+                            solana_receiver: record.solana_receiver,
+                            completed: true,
+                            wormhole_sequence: record.wormhole_sequence,
+                            created_at: record.sealed_at,
+                            dest_chain: record.dest_chain,
+                            publishing: false,
+                        }
+                    }
+                };
+                let pending = PendingSeal {
+                    completed: true,
+                    wormhole_sequence: record.wormhole_sequence,
+                    publishing: false,
+                    ..pending
+                };
+                log!("Repaired pending seal from record seq={}", record.wormhole_sequence);
+                self.pending_seals.insert(&seal_key, &pending);
+            }
+            status => return status,
+        }
+        self.check_seal_consistency(nft_contract, token_id)
+    }
+
+    /// Enable (`Some(base_uri)`) or disable (`None`) the `{base_uri}/{token_id}` URI
+    /// fallback for an NFT contract. Use the contract's `nft_metadata().base_uri`.
+    pub fn set_base_uri_fallback(&mut self, nft_contract: AccountId, base_uri: Option<String>) {
+        require!(
+            env::predecessor_account_id() == self.owner,
+            "Only owner"
+        );
+        match base_uri {
+            Some(base_uri) => {
+                require!(!base_uri.is_empty(), "Empty base_uri");
+                require!(base_uri.len() <= MAX_URI_LENGTH, "URI too long");
+                self.base_uri_fallbacks.insert(&nft_contract, &base_uri);
+            }
+            None => {
+                self.base_uri_fallbacks.remove(&nft_contract);
+            }
+        }
+    }
+
+    /// Set (`Some(uri)`) or clear (`None`) the placeholder URI sealed when a token has
+    /// no reference, media or base_uri fallback.
+    pub fn set_fallback_uri(&mut self, fallback_uri: Option<String>) {
+        require!(
+            env::predecessor_account_id() == self.owner,
+            "Only owner"
+        );
+        if let Some(uri) = &fallback_uri {
+            require!(!uri.is_empty(), "Empty fallback_uri");
+            require!(uri.len() <= MAX_URI_LENGTH, "URI too long");
+        }
+        self.fallback_uri = fallback_uri;
+    }
+
+    /// Accept seals from `nft_contract`. Once any contract is added, seals from
+    /// contracts not in the list are refunded.
+    pub fn add_allowed_nft_contract(&mut self, nft_contract: AccountId) {
+        require!(
+            env::predecessor_account_id() == self.owner,
+            "Only owner"
+        );
+        if self.allowed_nft_contracts.insert(&nft_contract) {
+            self.allowed_nft_contract_count += 1;
+        }
+    }
+
+    /// Stop accepting seals from `nft_contract`. Removing the last entry makes
+    /// sealing permissionless again. Already-locked NFTs are unaffected.
+    pub fn remove_allowed_nft_contract(&mut self, nft_contract: AccountId) {
+        require!(
+            env::predecessor_account_id() == self.owner,
+            "Only owner"
+        );
+        if self.allowed_nft_contracts.remove(&nft_contract) {
+            self.allowed_nft_contract_count -= 1;
+        }
+    }
+
+    /// Accept seals bound for `dest_chain`. Solana is always accepted; other chains
+    /// are refunded until enabled here.
+    pub fn enable_dest_chain(&mut self, dest_chain: u16) {
+        require!(
+            env::predecessor_account_id() == self.owner,
+            "Only owner"
+        );
+        require!(dest_chain != payload::WORMHOLE_CHAIN_ID_SOLANA, "Solana is always enabled");
+        require!(payload::receiver_format(dest_chain).is_some(), "Unsupported destination chain");
+        if !self.enabled_dest_chains.contains(&dest_chain) {
+            self.enabled_dest_chains.push(dest_chain);
+        }
+    }
+
+    /// Stop accepting seals bound for `dest_chain`. Already-locked NFTs are unaffected.
+    pub fn disable_dest_chain(&mut self, dest_chain: u16) {
+        require!(
+            env::predecessor_account_id() == self.owner,
+            "Only owner"
+        );
+        self.enabled_dest_chains.retain(|&chain| chain != dest_chain);
+    }
+
+    /// Pause/unpause the contract.
+    pub fn set_paused(&mut self, paused: bool) {
+        require!(
+            env::predecessor_account_id() == self.owner,
+            "Only owner"
+        );
+        self.paused = paused;
+    }
+
+    /// Transfer ownership.
+    pub fn transfer_ownership(&mut self, new_owner: AccountId) {
+        require!(
+            env::predecessor_account_id() == self.owner,
+            "Only owner"
+        );
+        self.owner = new_owner;
+    }
+
+    /// Emergency unlock: release a stuck NFT back to its original deposit address.
+    ///
+    /// Only callable by the owner, and only for pending seals that have NOT been
+    /// completed (Wormhole message not yet published). Enforces a minimum delay
+    /// (24 hours) since the NFT was locked to prevent abuse.
+    ///
+    /// This is a safety valve for cases where complete_seal_initiation cannot
+    /// succeed (e.g., Wormhole is down, metadata call fails permanently).
+    pub fn emergency_unlock(
+        &mut self,
+        nft_contract: AccountId,
+        token_id: String,
+    ) -> Promise {
+        require!(
+            env::predecessor_account_id() == self.owner,
+            "Only owner can emergency unlock"
+        );
+        require!(!self.paused, "Contract is paused");
+
+        let seal_key = self.compute_seal_key(&nft_contract, &token_id);
+        let pending = self
+            .pending_seals
+            .get(&seal_key)
+            .expect("No pending seal found for this NFT");
+
+        require!(!pending.completed, "Seal already completed, cannot unlock");
+        // The Wormhole message may still land; unlocking now could return an NFT
+        // that is about to be reborn
+        require!(
+            !self.is_publish_in_flight(&seal_key),
+            "Seal publish in flight, cannot unlock"
+        );
+
+        // Enforce minimum delay to prevent premature unlocks
+        let elapsed = env::block_timestamp() - pending.created_at;
+        require!(
+            elapsed >= EMERGENCY_UNLOCK_DELAY_NS,
+            "Emergency unlock not yet available (24h delay)"
+        );
+
+        // Clean up state: remove from sealed set and pending map
+        self.release_pending_seal(seal_key);
+
+        log!(
+            "EmergencyUnlock: contract={} token_id={} returning to={}",
+            nft_contract,
+            token_id,
+            pending.deposit_address
+        );
+
+        // Transfer NFT back via nft_transfer
+        let deposit_account: AccountId = pending.deposit_address.parse()
+            .expect("Invalid deposit address for NFT return");
+        Promise::new(nft_contract).function_call(
+            "nft_transfer".to_string(),
+            serde_json::json!({
+                "receiver_id": deposit_account,
+                "token_id": token_id,
+            })
+            .to_string()
+            .into_bytes(),
+            NearToken::from_yoctonear(1), // 1 yoctoNEAR required for nft_transfer
+            Gas::from_tgas(15),
+        )
+    }
+
+    // ── View Functions ──
+
+    /// Check if an NFT has been sealed.
+    pub fn is_sealed(&self, nft_contract: AccountId, token_id: String) -> bool {
+        let seal_key = self.compute_seal_key(&nft_contract, &token_id);
+        self.sealed_nfts.contains(&seal_key)
     }
 
     /// Check if a pending seal exists but is not yet completed.
@@ -393,6 +976,55 @@ impl SealInitiator {
         self.seal_records.get(&seal_key)
     }
 
+    /// Hex-encoded seal payload for a locked NFT, byte-for-byte what `publish_message`
+    /// receives. Uses the recorded URI once the seal has completed, otherwise `token_uri`
+    /// (what the relayer expects `nft_token` to return; empty if omitted).
+    pub fn get_payload_preview(
+        &self,
+        nft_contract: AccountId,
+        token_id: String,
+        token_uri: Option<String>,
+    ) -> String {
+        let seal_key = self.compute_seal_key(&nft_contract, &token_id);
+        let pending = self.pending_seals.get(&seal_key).expect("No pending seal");
+        let token_uri = self
+            .seal_records
+            .get(&seal_key)
+            .map(|record| record.token_uri)
+            .or(token_uri)
+            .filter(|uri| !uri.is_empty())
+            .or_else(|| self.fallback_uri.clone())
+            .unwrap_or_default();
+        hex::encode(build_seal_payload(
+            &self.hash_domain,
+            nft_contract.as_str(),
+            &token_id,
+            &pending.deposit_address,
+            pending.dest_chain,
+            &pending.solana_receiver,
+            &token_uri,
+        ))
+    }
+
+    /// `(completed, wormhole_sequence)` for a locked NFT, or None if it was never locked.
+    /// Cheaper than `get_seal_record` for relayers polling for completion; the
+    /// sequence is 0 until the seal completes.
+    pub fn get_completion(&self, nft_contract: AccountId, token_id: String) -> Option<(bool, u64)> {
+        let seal_key = self.compute_seal_key(&nft_contract, &token_id);
+        self.pending_seals
+            .get(&seal_key)
+            .map(|pending| (pending.completed, pending.wormhole_sequence))
+    }
+
+    /// Aggregate seal counters (`LookupMap` can't be iterated, so these are tracked on write).
+    pub fn get_stats(&self) -> ContractStats {
+        ContractStats {
+            total_sealed: self.total_sealed,
+            total_completed: self.total_completed,
+            total_pending: self.total_pending,
+        }
+    }
+
     /// Get the NEAR Wormhole chain ID (always 15).
     pub fn get_chain_id(&self) -> u16 {
         payload::WORMHOLE_CHAIN_ID_NEAR
@@ -408,105 +1040,1626 @@ impl SealInitiator {
         self.wormhole_account.clone()
     }
 
-    // ── Internal ──
+    /// Whether `pending_seals` and `seal_records` agree for this NFT.
+    pub fn check_seal_consistency(&self, nft_contract: AccountId, token_id: String) -> ConsistencyStatus {
+        let seal_key = self.compute_seal_key(&nft_contract, &token_id);
+        match (self.pending_seals.get(&seal_key), self.seal_records.get(&seal_key)) {
+            (None, None) => ConsistencyStatus::NotFound,
+            (Some(pending), None) if !pending.completed => ConsistencyStatus::Pending,
+            (Some(_), None) => ConsistencyStatus::CompletedWithoutRecord,
+            (Some(pending), Some(record))
+                if pending.completed && pending.wormhole_sequence == record.wormhole_sequence =>
+            {
+                ConsistencyStatus::Completed
+            }
+            (_, Some(_)) => ConsistencyStatus::RecordWithoutCompletion,
+        }
+    }
 
-    /// Compute replay protection key: SHA256(nft_contract || token_id)
-    fn compute_seal_key(&self, nft_contract: &AccountId, token_id: &str) -> Vec<u8> {
-        let mut hasher = Sha256::new();
-        hasher.update(nft_contract.as_str().as_bytes());
-        hasher.update(token_id.as_bytes());
-        hasher.finalize().to_vec()
+    /// Get the Wormhole consistency level used for published seals.
+    pub fn get_consistency_level(&self) -> u8 {
+        self.consistency_level
     }
-}
 
-// ── NFT Receiver (Step 1: Lock NFT) ──
+    /// Get the static gas (TGas) attached to the Wormhole publish callback.
+    pub fn get_wormhole_callback_gas(&self) -> u64 {
+        self.wormhole_callback_gas_tgas
+    }
 
-#[near]
-impl NonFungibleTokenReceiver for SealInitiator {
-    /// Called automatically when nft_transfer_call sends an NFT to this contract.
-    ///
-    /// Returns `PromiseOrValue::Value(false)` = keep the NFT (locked permanently).
-    /// Returns `PromiseOrValue::Value(true)` = refund the NFT on error.
-    fn nft_on_transfer(
-        &mut self,
-        sender_id: AccountId,
-        #[allow(unused_variables)]
-        previous_owner_id: AccountId,
-        token_id: String,
-        msg: String,
-    ) -> PromiseOrValue<bool> {
-        let nft_contract = env::predecessor_account_id();
+    /// Domain prefix for the payload's hashed fields (empty = none).
+    pub fn get_hash_domain(&self) -> String {
+        self.hash_domain.clone()
+    }
 
-        // Check not paused
-        if self.paused {
-            return PromiseOrValue::Value(true);
-        }
+    /// Whether `nft_on_transfer` accepts seals from this contract.
+    pub fn is_nft_contract_allowed(&self, nft_contract: AccountId) -> bool {
+        self.allowed_nft_contract_count == 0 || self.allowed_nft_contracts.contains(&nft_contract)
+    }
 
-        // NOTE: nft_on_transfer is called by the NFT contract as a cross-contract
-        // callback — env::attached_deposit() is always 0 here regardless of what
-        // the user attached to nft_transfer_call. Storage costs are covered by the
-        // contract's own balance.
+    /// Whether `nft_on_transfer` accepts seals bound for this chain.
+    pub fn is_dest_chain_enabled(&self, dest_chain: u16) -> bool {
+        dest_chain == payload::WORMHOLE_CHAIN_ID_SOLANA || self.enabled_dest_chains.contains(&dest_chain)
+    }
 
-        // Parse seal parameters from msg
-        let seal_msg: SealMsg = match serde_json::from_str(&msg) {
-            Ok(m) => m,
-            Err(_) => {
-                log!("Invalid seal msg JSON, refunding NFT");
-                return PromiseOrValue::Value(true);
-            }
-        };
+    /// Get the configured `base_uri` fallback for an NFT contract, if any.
+    pub fn get_base_uri_fallback(&self, nft_contract: AccountId) -> Option<String> {
+        self.base_uri_fallbacks.get(&nft_contract)
+    }
 
-        // Validate solana_receiver is 32 bytes (64 hex chars)
-        let receiver_hex = seal_msg
-            .solana_receiver
-            .strip_prefix("0x")
-            .unwrap_or(&seal_msg.solana_receiver);
-        if receiver_hex.len() != 64 {
-            log!("solana_receiver must be 32 bytes hex, refunding NFT");
-            return PromiseOrValue::Value(true);
+    /// Get the placeholder URI sealed when metadata is unavailable, if any.
+    pub fn get_fallback_uri(&self) -> Option<String> {
+        self.fallback_uri.clone()
+    }
+
+    /// Block timestamp (ns) of the last successful Wormhole publish; 0 if none yet.
+    pub fn get_last_successful_publish_ns(&self) -> u64 {
+        self.last_successful_publish_ns
+    }
+
+    /// Minimum deposit for `complete_seal_initiation` (cached Wormhole message fee);
+    /// None until `refresh_message_fee` has run.
+    pub fn get_message_fee(&self) -> Option<U128> {
+        self.message_fee.map(|fee| U128(fee.as_yoctonear()))
+    }
+
+    // ── Internal ──
+
+    /// Extract the token URI from an `nft_token` promise result.
+    /// Prefers `reference`, falls back to `media`, then to `{base_uri}/{token_id}` if the
+    /// owner enabled a base_uri fallback for this contract. If none of those yield a URI,
+    /// emits `token_uri_unavailable` and uses the owner's `fallback_uri` (if set).
+    fn token_uri_from_result(&self, nft_contract: &AccountId, token_id: &str) -> Result<String, &'static str> {
+        if env::promise_results_count() != 1 {
+            return Err("Expected one promise result");
         }
-        let solana_receiver: [u8; 32] = match hex::decode(receiver_hex) {
-            Ok(bytes) => match bytes.try_into() {
-                Ok(arr) => arr,
-                Err(_) => {
-                    log!("solana_receiver must be exactly 32 bytes, refunding NFT");
-                    return PromiseOrValue::Value(true);
-                }
-            },
-            Err(_) => {
-                log!("Invalid hex in solana_receiver, refunding NFT");
-                return PromiseOrValue::Value(true);
+        #[allow(deprecated)]
+        let token: Option<Token> = match env::promise_result(0) {
+            PromiseResult::Successful(data) => {
+                serde_json::from_slice(&data).map_err(|_| "Failed to parse nft_token result")?
             }
+            _ => return Err("nft_token call failed"),
         };
+        let token = token.ok_or("NFT not found")?;
 
-        // Replay protection
-        let seal_key = self.compute_seal_key(&nft_contract, &token_id);
-        if self.sealed_nfts.contains(&seal_key) {
-            log!("AlreadySealed: this NFT has already been sealed, refunding");
-            return PromiseOrValue::Value(true);
-        }
-        self.sealed_nfts.insert(&seal_key);
+        let token_uri = token
+            .metadata
+            .as_ref()
+            .and_then(|m| m.reference.as_ref().or(m.media.as_ref()))
+            .cloned()
+            .unwrap_or_default();
+        let token_uri = match self.base_uri_fallbacks.get(nft_contract) {
+            Some(base_uri) if token_uri.is_empty() => base_uri_token_url(&base_uri, token_id),
+            _ => token_uri,
+        };
 
-        // Record pending seal
-        let pending = PendingSeal {
-            nft_contract: nft_contract.clone(),
-            token_id: token_id.clone(),
-            deposit_address: seal_msg.deposit_address,
-            solana_receiver,
-            completed: false,
-            wormhole_sequence: 0,
-            created_at: env::block_timestamp(),
+        let token_uri = if token_uri.is_empty() {
+            emit_token_uri_unavailable(nft_contract.as_str(), token_id);
+            self.fallback_uri.clone().unwrap_or_default()
+        } else {
+            token_uri
         };
-        self.pending_seals.insert(&seal_key, &pending);
+        if token_uri.len() > MAX_URI_LENGTH {
+            return Err("URI too long");
+        }
+        Ok(token_uri)
+    }
 
-        log!(
-            "NFT locked: contract={} token_id={} sender={}",
-            nft_contract,
+    /// Extract the sequence number from a `publish_message` promise result.
+    fn wormhole_sequence_from_result() -> Result<u64, &'static str> {
+        if env::promise_results_count() != 1 {
+            return Err("Expected one promise result");
+        }
+        #[allow(deprecated)]
+        match env::promise_result(0) {
+            PromiseResult::Successful(data) => {
+                serde_json::from_slice(&data).map_err(|_| "Failed to parse Wormhole sequence")
+            }
+            _ => Err("Wormhole publish_message failed"),
+        }
+    }
+
+    /// Build the seal payload and call Wormhole `publish_message`.
+    fn publish_seal(
+        &mut self,
+        nft_contract: &AccountId,
+        token_id: &str,
+        deposit_address: &str,
+        solana_receiver: &[u8; 32],
+        token_uri: &str,
+        deposit: NearToken,
+    ) -> Promise {
+        let dest_chain = self.pending_dest_chain(nft_contract, token_id);
+
+        // Build binary payload (wire format)
+        let payload_bytes = build_seal_payload(
+            &self.hash_domain,
+            nft_contract.as_str(),
             token_id,
-            sender_id
+            deposit_address,
+            dest_chain,
+            solana_receiver,
+            token_uri,
         );
 
+        // Hex-encode for Wormhole NEAR contract
+        let payload_hex = hex::encode(&payload_bytes);
+
+        let nonce = (self.sequence & 0xFFFF_FFFF) as u32;
+        self.sequence += 1;
+
+        ext_wormhole::ext(self.wormhole_account.clone())
+            .with_attached_deposit(deposit)
+            .with_static_gas(GAS_WORMHOLE_PUBLISH)
+            .publish_message(payload_hex, nonce, self.consistency_level)
+    }
+
+    /// Mark the pending seal completed, store its record and emit `seal_initiated`.
+    /// A no-op if the record already exists for this sequence, so a retried callback
+    /// neither double-counts nor re-emits; a different sequence for a recorded seal panics.
+    #[allow(clippy::too_many_arguments)]
+    fn record_completed_seal(
+        &mut self,
+        nft_contract: AccountId,
+        token_id: String,
+        deposit_address: String,
+        solana_receiver: [u8; 32],
+        token_uri: String,
+        seal_key: Vec<u8>,
+        wormhole_sequence: u64,
+    ) {
+        if let Some(existing) = self.seal_records.get(&seal_key) {
+            require!(
+                existing.wormhole_sequence == wormhole_sequence,
+                "Seal already recorded with a different sequence"
+            );
+            log!("Seal already recorded: nft={} token_id={}", nft_contract, token_id);
+            return;
+        }
+
+        let dest_chain = self.pending_dest_chain(&nft_contract, &token_id);
+        self.publishes_in_flight.remove(&seal_key);
+
+        // Mark pending seal as completed
+        if let Some(mut pending) = self.pending_seals.get(&seal_key) {
+            if !pending.completed {
+                self.total_pending = self.total_pending.saturating_sub(1);
+                self.total_completed += 1;
+            }
+            pending.completed = true;
+            pending.wormhole_sequence = wormhole_sequence;
+            pending.publishing = false;
+            self.pending_seals.insert(&seal_key, &pending);
+        }
+
+        // Store completed seal record
+        let record = SealRecord {
+            nft_contract: nft_contract.clone(),
+            token_id: token_id.clone(),
+            deposit_address: deposit_address.clone(),
+            solana_receiver,
+            token_uri: token_uri.clone(),
+            wormhole_sequence,
+            source_chain_id: payload::WORMHOLE_CHAIN_ID_NEAR,
+            sealed_at: env::block_timestamp(),
+            dest_chain,
+        };
+        self.seal_records.insert(&seal_key, &record);
+
+        // Emit NEP-297 event
+        emit_seal_initiated(
+            nft_contract.as_str(),
+            &token_id,
+            &deposit_address,
+            &token_uri,
+            &solana_receiver,
+            wormhole_sequence,
+            dest_chain,
+        );
+
+        log!(
+            "SealInitiated: nft={} token_id={} seq={}",
+            nft_contract,
+            token_id,
+            wormhole_sequence
+        );
+    }
+
+    /// Destination chain recorded for a pending seal (Solana if none is recorded).
+    fn pending_dest_chain(&self, nft_contract: &AccountId, token_id: &str) -> u16 {
+        let seal_key = self.compute_seal_key(nft_contract, token_id);
+        self.pending_seals
+            .get(&seal_key)
+            .map(|p| p.dest_chain)
+            .unwrap_or(payload::WORMHOLE_CHAIN_ID_SOLANA)
+    }
+
+    /// Forget a pending seal so the NFT can be returned (and sealed again later).
+    fn release_pending_seal(&mut self, seal_key: Vec<u8>) {
+        self.publishes_in_flight.remove(&seal_key);
+        if self.sealed_nfts.remove(&seal_key) {
+            self.total_sealed = self.total_sealed.saturating_sub(1);
+        }
+        if let Some(pending) = self.pending_seals.remove(&seal_key) {
+            if !pending.completed {
+                self.total_pending = self.total_pending.saturating_sub(1);
+            }
+        }
+    }
+
+    /// Require `deposit` to cover the cached message fee and return the fee. The surplus
+    /// goes back to the caller right away rather than riding along into the callbacks.
+    fn take_message_fee(&self, deposit: NearToken) -> NearToken {
+        let fee = self
+            .message_fee
+            .unwrap_or_else(|| env::panic_str("Wormhole message fee unknown, call refresh_message_fee"));
+        require!(deposit >= fee, "Attached deposit does not cover the Wormhole message fee");
+        let surplus = deposit.saturating_sub(fee);
+        if !surplus.is_zero() {
+            // A dropped promise is still scheduled
+            let _ = Promise::new(env::predecessor_account_id()).transfer(surplus);
+        }
+        fee
+    }
+
+    /// Allow a failed publish to be retried through `complete_seal_initiation`.
+    fn clear_publishing(&mut self, seal_key: &Vec<u8>) {
+        if let Some(mut pending) = self.pending_seals.get(seal_key) {
+            if pending.publishing {
+                pending.publishing = false;
+                self.pending_seals.insert(seal_key, &pending);
+            }
+        }
+    }
+
+    /// Block emergency unlock for this seal until its publish is recorded or
+    /// `PUBLISH_IN_FLIGHT_NS` passes. Call only when the publish is spawned. A live
+    /// marker is left as is, so repeated attempts can't keep extending it; a failed
+    /// callback leaves the marker to expire.
+    fn mark_publish_in_flight(&mut self, seal_key: &Vec<u8>) {
+        if self.is_publish_in_flight(seal_key) {
+            return;
+        }
+        let locked_until = env::block_timestamp() + PUBLISH_IN_FLIGHT_NS;
+        self.publishes_in_flight.insert(seal_key, &locked_until);
+    }
+
+    fn is_publish_in_flight(&self, seal_key: &Vec<u8>) -> bool {
+        self.publishes_in_flight
+            .get(seal_key)
+            .is_some_and(|locked_until| env::block_timestamp() < locked_until)
+    }
+
+    /// Compute replay protection key: SHA256(nft_contract || 0x00 || token_id).
+    /// Account IDs never contain NUL, so no two (contract, token) pairs share a key.
+    fn compute_seal_key(&self, nft_contract: &AccountId, token_id: &str) -> Vec<u8> {
+        let mut hasher = Sha256::new();
+        hasher.update(nft_contract.as_str().as_bytes());
+        hasher.update([0u8]);
+        hasher.update(token_id.as_bytes());
+        hasher.finalize().to_vec()
+    }
+}
+
+/// v1 seal key: SHA256(nft_contract || token_id). Ambiguous at the contract/token
+/// boundary ("a.near" + "1" vs "a.nea" + "r1"); only used by `migrate`.
+fn legacy_seal_key(nft_contract: &AccountId, token_id: &str) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    hasher.update(nft_contract.as_str().as_bytes());
+    hasher.update(token_id.as_bytes());
+    hasher.finalize().to_vec()
+}
+
+/// `{base_uri}/{token_id}`, without doubling a trailing slash on `base_uri`.
+fn base_uri_token_url(base_uri: &str, token_id: &str) -> String {
+    format!("{}/{}", base_uri.trim_end_matches('/'), token_id)
+}
+
+/// Metadata callback gas: the default, or a caller override between the default
+/// and `MAX_GAS_CALLBACK_METADATA_TGAS`.
+fn metadata_callback_gas(override_tgas: Option<u64>) -> Gas {
+    match override_tgas {
+        None => GAS_CALLBACK_METADATA,
+        Some(tgas) => {
+            require!(
+                tgas >= GAS_CALLBACK_METADATA.as_tgas() && tgas <= MAX_GAS_CALLBACK_METADATA_TGAS,
+                "metadata_gas_tgas out of range"
+            );
+            Gas::from_tgas(tgas)
+        }
+    }
+}
+
+// ── NFT Receiver (Step 1: Lock NFT) ──
+
+#[near]
+impl NonFungibleTokenReceiver for SealInitiator {
+    /// Called automatically when nft_transfer_call sends an NFT to this contract.
+    ///
+    /// Returns `PromiseOrValue::Value(false)` = keep the NFT (locked permanently).
+    /// Returns `PromiseOrValue::Value(true)` = refund the NFT on error.
+    /// With `"oneshot": true` returns a promise resolving to the same, after publishing.
+    fn nft_on_transfer(
+        &mut self,
+        sender_id: AccountId,
+        #[allow(unused_variables)]
+        previous_owner_id: AccountId,
+        token_id: String,
+        msg: String,
+    ) -> PromiseOrValue<bool> {
+        let nft_contract = env::predecessor_account_id();
+
+        // Check not paused
+        if self.paused {
+            return PromiseOrValue::Value(true);
+        }
+
+        if !self.is_nft_contract_allowed(nft_contract.clone()) {
+            log!("NFT contract {} not allowed, refunding NFT", nft_contract);
+            return PromiseOrValue::Value(true);
+        }
+
+        // NOTE: nft_on_transfer is called by the NFT contract as a cross-contract
+        // callback — env::attached_deposit() is always 0 here regardless of what
+        // the user attached to nft_transfer_call. Storage costs are covered by the
+        // contract's own balance.
+
+        // Parse seal parameters from msg
+        let seal_msg: SealMsg = match serde_json::from_str(&msg) {
+            Ok(m) => m,
+            Err(_) => {
+                log!("Invalid seal msg JSON, refunding NFT");
+                return PromiseOrValue::Value(true);
+            }
+        };
+
+        // No deposit reaches this call, so a one-shot publish can't pay a message fee;
+        // an unread fee may not be zero either
+        if seal_msg.oneshot && !self.message_fee.is_some_and(|fee| fee.is_zero()) {
+            log!("One-shot sealing needs a known zero Wormhole message fee, refunding NFT");
+            return PromiseOrValue::Value(true);
+        }
+
+        // Validate the receiver for the destination chain (Solana by default)
+        let dest_chain = seal_msg.dest_chain();
+        if !self.is_dest_chain_enabled(dest_chain) {
+            log!("Destination chain {} is not enabled, refunding NFT", dest_chain);
+            return PromiseOrValue::Value(true);
+        }
+        let solana_receiver = match payload::encode_dest_receiver(dest_chain, seal_msg.receiver()) {
+            Ok(receiver) => receiver,
+            Err(reason) => {
+                log!("{}, refunding NFT", reason);
+                return PromiseOrValue::Value(true);
+            }
+        };
+
+        // Replay protection
+        let seal_key = self.compute_seal_key(&nft_contract, &token_id);
+        if self.sealed_nfts.contains(&seal_key) {
+            log!("AlreadySealed: this NFT has already been sealed, refunding");
+            return PromiseOrValue::Value(true);
+        }
+        self.sealed_nfts.insert(&seal_key);
+
+        // Record pending seal
+        let pending = PendingSeal {
+            nft_contract: nft_contract.clone(),
+            token_id: token_id.clone(),
+            deposit_address: seal_msg.deposit_address,
+            solana_receiver,
+            completed: false,
+            wormhole_sequence: 0,
+            created_at: env::block_timestamp(),
+            dest_chain,
+            publishing: false,
+        };
+        self.pending_seals.insert(&seal_key, &pending);
+        self.total_sealed += 1;
+        self.total_pending += 1;
+
+        log!(
+            "NFT locked: contract={} token_id={} sender={}",
+            nft_contract,
+            token_id,
+            sender_id
+        );
+
+        // One-shot: fetch metadata and publish now; the chain resolves to whether
+        // the NFT should be returned
+        if seal_msg.oneshot {
+            let promise = ext_nft::ext(nft_contract.clone())
+                .with_static_gas(GAS_NFT_TOKEN)
+                .nft_token(token_id.clone())
+                .then(
+                    Self::ext(env::current_account_id())
+                        .with_static_gas(GAS_CALLBACK_ONESHOT_METADATA)
+                        .on_oneshot_metadata_received(
+                            nft_contract,
+                            token_id,
+                            pending.deposit_address,
+                            solana_receiver,
+                            seal_key,
+                        ),
+                );
+            return PromiseOrValue::Promise(promise);
+        }
+
         // Return false = keep the NFT (locked)
         PromiseOrValue::Value(false)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::VMContextBuilder;
+    use near_sdk::testing_env;
+
+    const RECEIVER_HEX: &str = "abababababababababababababababababababababababababababababababab";
+    /// Hex dWallet pubkey; also a valid implicit account for emergency_unlock's return
+    const DEPOSIT_HEX: &str = "cdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcd";
+
+    fn account(id: &str) -> AccountId {
+        id.parse().unwrap()
+    }
+
+    fn set_context(predecessor: &str, promise_results: Vec<PromiseResult>) {
+        set_context_at(predecessor, 0, promise_results);
+    }
+
+    fn set_context_at(predecessor: &str, block_timestamp: u64, promise_results: Vec<PromiseResult>) {
+        let context = VMContextBuilder::new()
+            .current_account_id(account("seal.near"))
+            .predecessor_account_id(account(predecessor))
+            .block_timestamp(block_timestamp)
+            .build();
+        testing_env!(
+            context,
+            near_sdk::test_vm_config(),
+            near_sdk::RuntimeFeesConfig::test(),
+            Default::default(),
+            promise_results
+        );
+    }
+
+    /// A fresh contract whose cached Wormhole message fee has been read as zero.
+    fn setup() -> SealInitiator {
+        set_context("owner.near", vec![]);
+        let mut contract = SealInitiator::new(account("wormhole.near"), None);
+        set_message_fee(&mut contract, 0);
+        contract
+    }
+
+    fn oneshot_msg() -> String {
+        format!(
+            r#"{{"deposit_address":"{}","solana_receiver":"{}","oneshot":true}}"#,
+            DEPOSIT_HEX, RECEIVER_HEX
+        )
+    }
+
+    /// Lock token "1" of nft.near via a one-shot nft_on_transfer; returns its seal key.
+    fn lock_oneshot(contract: &mut SealInitiator) -> Vec<u8> {
+        set_context("nft.near", vec![]);
+        let result =
+            contract.nft_on_transfer(account("alice.near"), account("alice.near"), "1".into(), oneshot_msg());
+        assert!(matches!(result, PromiseOrValue::Promise(_)));
+        assert!(contract.is_pending(account("nft.near"), "1".into()));
+        contract.compute_seal_key(&account("nft.near"), "1")
+    }
+
+    fn token_result(uri: &str) -> PromiseResult {
+        let token = serde_json::json!({
+            "token_id": "1",
+            "owner_id": "seal.near",
+            "metadata": { "reference": uri },
+        });
+        PromiseResult::Successful(serde_json::to_vec(&token).unwrap())
+    }
+
+    #[test]
+    fn test_oneshot_happy_path_keeps_nft() {
+        let mut contract = setup();
+        let seal_key = lock_oneshot(&mut contract);
+        let receiver: [u8; 32] = hex::decode(RECEIVER_HEX).unwrap().try_into().unwrap();
+
+        set_context("seal.near", vec![token_result("ipfs://meta")]);
+        let result = contract.on_oneshot_metadata_received(
+            account("nft.near"),
+            "1".into(),
+            DEPOSIT_HEX.into(),
+            receiver,
+            seal_key.clone(),
+        );
+        assert!(matches!(result, PromiseOrValue::Promise(_)));
+        assert_eq!(contract.get_sequence(), 1);
+        assert!(contract.pending_seals.get(&seal_key).unwrap().publishing);
+        assert!(contract.is_publish_in_flight(&seal_key));
+
+        set_context("seal.near", vec![PromiseResult::Successful(b"7".to_vec())]);
+        let refund = contract.on_oneshot_published(
+            account("nft.near"),
+            "1".into(),
+            DEPOSIT_HEX.into(),
+            receiver,
+            "ipfs://meta".into(),
+            seal_key,
+        );
+        assert!(!refund);
+
+        let record = contract.get_seal_record(account("nft.near"), "1".into()).unwrap();
+        assert_eq!(record.wormhole_sequence, 7);
+        assert_eq!(record.token_uri, "ipfs://meta");
+        assert!(contract.is_sealed(account("nft.near"), "1".into()));
+        assert!(!contract.is_pending(account("nft.near"), "1".into()));
+    }
+
+    #[test]
+    fn test_oneshot_metadata_failure_refunds_nft() {
+        let mut contract = setup();
+        let seal_key = lock_oneshot(&mut contract);
+
+        set_context("seal.near", vec![PromiseResult::Failed]);
+        let result = contract.on_oneshot_metadata_received(
+            account("nft.near"),
+            "1".into(),
+            DEPOSIT_HEX.into(),
+            [0xAB; 32],
+            seal_key,
+        );
+        assert!(matches!(result, PromiseOrValue::Value(true)));
+        assert!(!contract.is_sealed(account("nft.near"), "1".into()));
+        assert!(!contract.is_pending(account("nft.near"), "1".into()));
+        assert_eq!(contract.get_sequence(), 0);
+    }
+
+    #[test]
+    fn test_oneshot_publish_failure_refunds_nft() {
+        let mut contract = setup();
+        let seal_key = lock_oneshot(&mut contract);
+
+        set_context("seal.near", vec![PromiseResult::Failed]);
+        let refund = contract.on_oneshot_published(
+            account("nft.near"),
+            "1".into(),
+            DEPOSIT_HEX.into(),
+            [0xAB; 32],
+            "ipfs://meta".into(),
+            seal_key,
+        );
+        assert!(refund);
+        assert!(!contract.is_sealed(account("nft.near"), "1".into()));
+        assert!(contract.get_seal_record(account("nft.near"), "1".into()).is_none());
+    }
+
+    #[test]
+    fn test_oneshot_skips_publish_after_racing_completion() {
+        let mut contract = setup();
+        let seal_key = lock_oneshot(&mut contract);
+        let receiver: [u8; 32] = hex::decode(RECEIVER_HEX).unwrap().try_into().unwrap();
+
+        // A complete_seal_initiation lands between the lock and the one-shot callback
+        set_context("seal.near", vec![token_result("ipfs://meta")]);
+        let _ = contract.on_nft_metadata_received(
+            account("nft.near"),
+            "1".into(),
+            DEPOSIT_HEX.into(),
+            receiver,
+            seal_key.clone(),
+        );
+        assert_eq!(contract.get_sequence(), 1);
+
+        set_context("seal.near", vec![token_result("ipfs://meta")]);
+        let result = contract.on_oneshot_metadata_received(
+            account("nft.near"),
+            "1".into(),
+            DEPOSIT_HEX.into(),
+            receiver,
+            seal_key,
+        );
+        assert!(matches!(result, PromiseOrValue::Value(false)));
+        assert_eq!(contract.get_sequence(), 1);
+        assert!(contract.is_sealed(account("nft.near"), "1".into()));
+    }
+
+    #[test]
+    fn test_oneshot_refused_while_message_fee_set() {
+        let mut contract = setup();
+        set_message_fee(&mut contract, 1_000);
+
+        set_context("nft.near", vec![]);
+        let result =
+            contract.nft_on_transfer(account("alice.near"), account("alice.near"), "1".into(), oneshot_msg());
+        assert!(matches!(result, PromiseOrValue::Value(true)));
+        assert!(!contract.is_sealed(account("nft.near"), "1".into()));
+        assert_stats(&contract, 0, 0, 0);
+    }
+
+    #[test]
+    fn test_default_msg_keeps_two_step_flow() {
+        let mut contract = setup();
+        set_context("nft.near", vec![]);
+        let msg = format!(r#"{{"deposit_address":"{}","solana_receiver":"{}"}}"#, DEPOSIT_HEX, RECEIVER_HEX);
+        let result = contract.nft_on_transfer(account("alice.near"), account("alice.near"), "1".into(), msg);
+        assert!(matches!(result, PromiseOrValue::Value(false)));
+        assert!(contract.is_pending(account("nft.near"), "1".into()));
+    }
+
+    fn lock_two_step(contract: &mut SealInitiator, token_id: &str) {
+        set_context("nft.near", vec![]);
+        let msg = format!(r#"{{"deposit_address":"{}","solana_receiver":"{}"}}"#, DEPOSIT_HEX, RECEIVER_HEX);
+        let result = contract.nft_on_transfer(account("alice.near"), account("alice.near"), token_id.into(), msg);
+        assert!(matches!(result, PromiseOrValue::Value(false)));
+    }
+
+    fn assert_stats(contract: &SealInitiator, sealed: u64, completed: u64, pending: u64) {
+        let stats = contract.get_stats();
+        assert_eq!(
+            (stats.total_sealed, stats.total_completed, stats.total_pending),
+            (sealed, completed, pending)
+        );
+    }
+
+    #[test]
+    fn test_stats_follow_seal_lifecycle() {
+        let mut contract = setup();
+        assert_stats(&contract, 0, 0, 0);
+
+        lock_two_step(&mut contract, "1");
+        lock_two_step(&mut contract, "2");
+        assert_stats(&contract, 2, 0, 2);
+
+        let seal_key = contract.compute_seal_key(&account("nft.near"), "1");
+        set_context("seal.near", vec![PromiseResult::Successful(b"3".to_vec())]);
+        contract.on_wormhole_published(
+            account("nft.near"),
+            "1".into(),
+            DEPOSIT_HEX.into(),
+            [0xAB; 32],
+            "ipfs://meta".into(),
+            seal_key,
+        );
+        assert_stats(&contract, 2, 1, 1);
+    }
+
+    #[test]
+    fn test_stats_after_emergency_unlock() {
+        let mut contract = setup();
+        lock_two_step(&mut contract, "1");
+        assert_stats(&contract, 1, 0, 1);
+
+        set_context_at("owner.near", EMERGENCY_UNLOCK_DELAY_NS, vec![]);
+        let _ = contract.emergency_unlock(account("nft.near"), "1".into());
+        assert_stats(&contract, 0, 0, 0);
+        assert!(!contract.is_sealed(account("nft.near"), "1".into()));
+    }
+
+    #[test]
+    fn test_stats_after_oneshot_refund() {
+        let mut contract = setup();
+        let seal_key = lock_oneshot(&mut contract);
+        assert_stats(&contract, 1, 0, 1);
+
+        set_context("seal.near", vec![PromiseResult::Failed]);
+        contract.on_oneshot_published(
+            account("nft.near"),
+            "1".into(),
+            DEPOSIT_HEX.into(),
+            [0xAB; 32],
+            "ipfs://meta".into(),
+            seal_key,
+        );
+        assert_stats(&contract, 0, 0, 0);
+    }
+
+    #[test]
+    fn test_evm_destination_recorded_and_validated() {
+        let mut contract = setup();
+        contract.enable_dest_chain(2);
+        set_context("nft.near", vec![]);
+        let bad = format!(r#"{{"deposit_address":"{}","dest_chain":2,"dest_receiver":"{}"}}"#, DEPOSIT_HEX, RECEIVER_HEX);
+        let result = contract.nft_on_transfer(account("alice.near"), account("alice.near"), "1".into(), bad);
+        assert!(matches!(result, PromiseOrValue::Value(true)));
+
+        let evm_receiver = "11".repeat(20);
+        let good = format!(r#"{{"deposit_address":"{}","dest_chain":2,"dest_receiver":"0x{}"}}"#, DEPOSIT_HEX, evm_receiver);
+        let result = contract.nft_on_transfer(account("alice.near"), account("alice.near"), "1".into(), good);
+        assert!(matches!(result, PromiseOrValue::Value(false)));
+
+        let seal_key = contract.compute_seal_key(&account("nft.near"), "1");
+        let receiver = payload::encode_dest_receiver(2, &evm_receiver).unwrap();
+        set_context("seal.near", vec![PromiseResult::Successful(b"4".to_vec())]);
+        contract.on_wormhole_published(
+            account("nft.near"),
+            "1".into(),
+            DEPOSIT_HEX.into(),
+            receiver,
+            "ipfs://meta".into(),
+            seal_key,
+        );
+        let record = contract.get_seal_record(account("nft.near"), "1".into()).unwrap();
+        assert_eq!(record.dest_chain, 2);
+    }
+
+    #[test]
+    fn test_disabled_dest_chain_refunded() {
+        let mut contract = setup();
+        assert!(!contract.is_dest_chain_enabled(2));
+        set_context("nft.near", vec![]);
+        let msg = format!(r#"{{"deposit_address":"{}","dest_chain":2,"dest_receiver":"{}"}}"#, DEPOSIT_HEX, "11".repeat(20));
+        let result = contract.nft_on_transfer(account("alice.near"), account("alice.near"), "1".into(), msg);
+        assert!(matches!(result, PromiseOrValue::Value(true)));
+        assert!(!contract.is_pending(account("nft.near"), "1".into()));
+        assert_stats(&contract, 0, 0, 0);
+
+        set_context("owner.near", vec![]);
+        contract.enable_dest_chain(2);
+        assert!(contract.is_dest_chain_enabled(2));
+        contract.disable_dest_chain(2);
+        assert!(!contract.is_dest_chain_enabled(2));
+    }
+
+    #[test]
+    #[should_panic(expected = "Unsupported destination chain")]
+    fn test_enable_unknown_dest_chain_rejected() {
+        let mut contract = setup();
+        contract.enable_dest_chain(u16::MAX);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only owner")]
+    fn test_enable_dest_chain_owner_only() {
+        let mut contract = setup();
+        set_context("alice.near", vec![]);
+        contract.enable_dest_chain(2);
+    }
+
+    #[test]
+    fn test_metadata_gas_override_bounds() {
+        assert_eq!(metadata_callback_gas(None), GAS_CALLBACK_METADATA);
+        assert_eq!(metadata_callback_gas(Some(150)), Gas::from_tgas(150));
+        assert_eq!(
+            metadata_callback_gas(Some(MAX_GAS_CALLBACK_METADATA_TGAS)),
+            Gas::from_tgas(MAX_GAS_CALLBACK_METADATA_TGAS)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "metadata_gas_tgas out of range")]
+    fn test_metadata_gas_override_too_high() {
+        metadata_callback_gas(Some(MAX_GAS_CALLBACK_METADATA_TGAS + 1));
+    }
+
+    #[test]
+    fn test_large_metadata_completes_with_raised_gas() {
+        let mut contract = setup();
+        lock_two_step(&mut contract, "1");
+
+        set_context_at("relayer.near", 0, vec![]);
+        let _ = contract.complete_seal_initiation(account("nft.near"), "1".into(), Some(150));
+
+        // Callback with a reference near MAX_URI_LENGTH still publishes
+        let receiver: [u8; 32] = hex::decode(RECEIVER_HEX).unwrap().try_into().unwrap();
+        let seal_key = contract.compute_seal_key(&account("nft.near"), "1");
+        let long_uri = format!("ipfs://{}", "a".repeat(MAX_URI_LENGTH - 7));
+        set_context("seal.near", vec![token_result(&long_uri)]);
+        let _ = contract.on_nft_metadata_received(
+            account("nft.near"),
+            "1".into(),
+            DEPOSIT_HEX.into(),
+            receiver,
+            seal_key,
+        );
+        assert_eq!(contract.get_sequence(), 1);
+    }
+
+    #[test]
+    fn test_complete_seal_with_uri_skips_nft_token() {
+        // "no-metadata.near" never gets an nft_token call: the owner supplies the URI
+        let mut contract = setup();
+        set_context("no-metadata.near", vec![]);
+        let msg = format!(r#"{{"deposit_address":"{}","solana_receiver":"{}"}}"#, DEPOSIT_HEX, RECEIVER_HEX);
+        let result = contract.nft_on_transfer(account("alice.near"), account("alice.near"), "9".into(), msg);
+        assert!(matches!(result, PromiseOrValue::Value(false)));
+
+        set_context("owner.near", vec![]);
+        let _ = contract.complete_seal_with_uri(account("no-metadata.near"), "9".into(), "ipfs://manual".into());
+        assert_eq!(contract.get_sequence(), 1);
+
+        let receiver: [u8; 32] = hex::decode(RECEIVER_HEX).unwrap().try_into().unwrap();
+        let seal_key = contract.compute_seal_key(&account("no-metadata.near"), "9");
+        set_context("seal.near", vec![PromiseResult::Successful(b"11".to_vec())]);
+        contract.on_wormhole_published(
+            account("no-metadata.near"),
+            "9".into(),
+            DEPOSIT_HEX.into(),
+            receiver,
+            "ipfs://manual".into(),
+            seal_key,
+        );
+        let record = contract.get_seal_record(account("no-metadata.near"), "9".into()).unwrap();
+        assert_eq!(record.token_uri, "ipfs://manual");
+        assert_eq!(record.wormhole_sequence, 11);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only owner can complete with a supplied URI")]
+    fn test_complete_seal_with_uri_owner_only() {
+        let mut contract = setup();
+        lock_two_step(&mut contract, "1");
+        set_context("relayer.near", vec![]);
+        let _ = contract.complete_seal_with_uri(account("nft.near"), "1".into(), "ipfs://x".into());
+    }
+
+    fn token_result_with(metadata: serde_json::Value) -> PromiseResult {
+        let token = serde_json::json!({ "token_id": "7", "owner_id": "seal.near", "metadata": metadata });
+        PromiseResult::Successful(serde_json::to_vec(&token).unwrap())
+    }
+
+    #[test]
+    fn test_base_uri_fallback_url() {
+        let mut contract = setup();
+        contract.set_base_uri_fallback(account("nft.near"), Some("https://arweave.net/abc/".into()));
+
+        set_context("seal.near", vec![token_result_with(serde_json::json!({ "title": "No media" }))]);
+        let uri = contract.token_uri_from_result(&account("nft.near"), "7").unwrap();
+        assert_eq!(uri, "https://arweave.net/abc/7");
+
+        // Per-token reference still wins
+        set_context("seal.near", vec![token_result_with(serde_json::json!({ "reference": "ipfs://ref" }))]);
+        assert_eq!(contract.token_uri_from_result(&account("nft.near"), "7").unwrap(), "ipfs://ref");
+    }
+
+    #[test]
+    fn test_base_uri_fallback_per_contract_toggle() {
+        let mut contract = setup();
+        contract.set_base_uri_fallback(account("nft.near"), Some("https://base.example".into()));
+        assert_eq!(
+            contract.get_base_uri_fallback(account("nft.near")),
+            Some("https://base.example".to_string())
+        );
+
+        // Other contracts are unaffected
+        set_context("seal.near", vec![token_result_with(serde_json::json!({}))]);
+        assert_eq!(contract.token_uri_from_result(&account("other.near"), "7").unwrap(), "");
+
+        set_context("owner.near", vec![]);
+        contract.set_base_uri_fallback(account("nft.near"), None);
+        set_context("seal.near", vec![token_result_with(serde_json::json!({}))]);
+        assert_eq!(contract.token_uri_from_result(&account("nft.near"), "7").unwrap(), "");
+    }
+
+    #[test]
+    #[should_panic(expected = "Only owner")]
+    fn test_base_uri_fallback_owner_only() {
+        let mut contract = setup();
+        set_context("alice.near", vec![]);
+        contract.set_base_uri_fallback(account("nft.near"), Some("https://base.example".into()));
+    }
+
+    #[test]
+    fn test_fallback_uri_for_unavailable_metadata() {
+        let mut contract = setup();
+        contract.set_fallback_uri(Some("ipfs://placeholder".into()));
+        assert_eq!(contract.get_fallback_uri(), Some("ipfs://placeholder".to_string()));
+
+        set_context("seal.near", vec![token_result_with(serde_json::json!({ "title": "No media" }))]);
+        assert_eq!(
+            contract.token_uri_from_result(&account("nft.near"), "7").unwrap(),
+            "ipfs://placeholder"
+        );
+
+        // A present URI is sealed unchanged
+        set_context("seal.near", vec![token_result_with(serde_json::json!({ "reference": "ipfs://ref" }))]);
+        assert_eq!(contract.token_uri_from_result(&account("nft.near"), "7").unwrap(), "ipfs://ref");
+
+        set_context("owner.near", vec![]);
+        contract.set_fallback_uri(None);
+        set_context("seal.near", vec![token_result_with(serde_json::json!({}))]);
+        assert_eq!(contract.token_uri_from_result(&account("nft.near"), "7").unwrap(), "");
+    }
+
+    #[test]
+    #[should_panic(expected = "Only owner")]
+    fn test_fallback_uri_owner_only() {
+        let mut contract = setup();
+        set_context("alice.near", vec![]);
+        contract.set_fallback_uri(Some("ipfs://placeholder".into()));
+    }
+
+    #[test]
+    fn test_wormhole_callback_gas_configurable() {
+        let mut contract = setup();
+        assert_eq!(contract.get_wormhole_callback_gas(), DEFAULT_GAS_CALLBACK_WORMHOLE_TGAS);
+        contract.set_wormhole_callback_gas(MAX_GAS_CALLBACK_WORMHOLE_TGAS);
+        assert_eq!(contract.get_wormhole_callback_gas(), MAX_GAS_CALLBACK_WORMHOLE_TGAS);
+        // Publish + largest callback still fits the default metadata callback budget
+        assert!(GAS_WORMHOLE_PUBLISH.as_tgas() + MAX_GAS_CALLBACK_WORMHOLE_TGAS < GAS_CALLBACK_METADATA.as_tgas());
+    }
+
+    #[test]
+    #[should_panic(expected = "Callback gas out of range")]
+    fn test_wormhole_callback_gas_bounds() {
+        let mut contract = setup();
+        contract.set_wormhole_callback_gas(MIN_GAS_CALLBACK_WORMHOLE_TGAS - 1);
+    }
+
+    #[test]
+    fn test_retry_after_out_of_gas_callback_completes_record() {
+        let mut contract = setup();
+        lock_two_step(&mut contract, "1");
+        set_context("relayer.near", vec![]);
+        let _ = contract.complete_seal_initiation(account("nft.near"), "1".into(), None);
+        start_publish(&mut contract, "1", 0);
+
+        // Wormhole published, but on_wormhole_published ran out of gas: its state
+        // changes were reverted, so the seal is still pending with no record
+        assert!(contract.get_seal_record(account("nft.near"), "1".into()).is_none());
+        assert_stats(&contract, 1, 0, 1);
+
+        set_context("owner.near", vec![]);
+        contract.retry_seal_record(account("nft.near"), "1".into(), "ipfs://meta".into(), 0);
+        let record = contract.get_seal_record(account("nft.near"), "1".into()).unwrap();
+        assert_eq!(record.wormhole_sequence, 0);
+        assert_stats(&contract, 1, 1, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "No Wormhole publish started for this seal")]
+    fn test_retry_seal_record_requires_publish() {
+        let mut contract = setup();
+        lock_two_step(&mut contract, "1");
+        set_context("owner.near", vec![]);
+        contract.retry_seal_record(account("nft.near"), "1".into(), "ipfs://meta".into(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Wormhole sequence not yet emitted")]
+    fn test_retry_seal_record_rejects_future_sequence() {
+        let mut contract = setup();
+        lock_two_step(&mut contract, "1");
+        start_publish(&mut contract, "1", 0);
+
+        set_context("owner.near", vec![]);
+        let sequence = contract.get_sequence();
+        contract.retry_seal_record(account("nft.near"), "1".into(), "ipfs://meta".into(), sequence);
+    }
+
+    #[test]
+    fn test_wormhole_callback_is_idempotent() {
+        let mut contract = setup();
+        lock_two_step(&mut contract, "1");
+        let receiver: [u8; 32] = hex::decode(RECEIVER_HEX).unwrap().try_into().unwrap();
+        let seal_key = contract.compute_seal_key(&account("nft.near"), "1");
+
+        for timestamp in [5, 9] {
+            set_context_at("seal.near", timestamp, vec![PromiseResult::Successful(b"42".to_vec())]);
+            contract.on_wormhole_published(
+                account("nft.near"),
+                "1".into(),
+                DEPOSIT_HEX.into(),
+                receiver,
+                "ipfs://meta".into(),
+                seal_key.clone(),
+            );
+        }
+
+        let record = contract.get_seal_record(account("nft.near"), "1".into()).unwrap();
+        assert_eq!(record.sealed_at, 5);
+        assert_stats(&contract, 1, 1, 0);
+    }
+
+    fn completed_record(contract: &mut SealInitiator, token_id: &str, sequence: u64) {
+        let receiver: [u8; 32] = hex::decode(RECEIVER_HEX).unwrap().try_into().unwrap();
+        let seal_key = contract.compute_seal_key(&account("nft.near"), token_id);
+        set_context("seal.near", vec![PromiseResult::Successful(sequence.to_string().into_bytes())]);
+        contract.on_wormhole_published(
+            account("nft.near"),
+            token_id.into(),
+            DEPOSIT_HEX.into(),
+            receiver,
+            "ipfs://meta".into(),
+            seal_key,
+        );
+    }
+
+    #[test]
+    fn test_consistency_status_for_normal_lifecycle() {
+        let mut contract = setup();
+        let status = |c: &SealInitiator| c.check_seal_consistency(account("nft.near"), "1".into());
+        assert_eq!(status(&contract), ConsistencyStatus::NotFound);
+        lock_two_step(&mut contract, "1");
+        assert_eq!(status(&contract), ConsistencyStatus::Pending);
+        completed_record(&mut contract, "1", 42);
+        assert_eq!(status(&contract), ConsistencyStatus::Completed);
+    }
+
+    #[test]
+    fn test_repair_missing_record() {
+        let mut contract = setup();
+        lock_two_step(&mut contract, "1");
+        completed_record(&mut contract, "1", 42);
+
+        // Seed: record lost, pending still says completed
+        let seal_key = contract.compute_seal_key(&account("nft.near"), "1");
+        contract.seal_records.remove(&seal_key);
+        assert_eq!(
+            contract.check_seal_consistency(account("nft.near"), "1".into()),
+            ConsistencyStatus::CompletedWithoutRecord
+        );
+
+        set_context("owner.near", vec![]);
+        let status = contract.repair_seal_record(account("nft.near"), "1".into());
+        assert_eq!(status, ConsistencyStatus::Completed);
+        let record = contract.get_seal_record(account("nft.near"), "1".into()).unwrap();
+        assert_eq!(record.wormhole_sequence, 42);
+        assert_eq!(record.token_uri, "");
+        assert_stats(&contract, 1, 1, 0);
+    }
+
+    #[test]
+    fn test_repair_incomplete_pending_from_record() {
+        let mut contract = setup();
+        lock_two_step(&mut contract, "1");
+        completed_record(&mut contract, "1", 42);
+
+        // Seed: pending rolled back to not-completed while the record survived
+        let seal_key = contract.compute_seal_key(&account("nft.near"), "1");
+        let mut pending = contract.pending_seals.get(&seal_key).unwrap();
+        pending.completed = false;
+        pending.wormhole_sequence = 0;
+        contract.pending_seals.insert(&seal_key, &pending);
+        contract.total_pending = 1;
+        contract.total_completed = 0;
+        assert_eq!(
+            contract.check_seal_consistency(account("nft.near"), "1".into()),
+            ConsistencyStatus::RecordWithoutCompletion
+        );
+
+        set_context("owner.near", vec![]);
+        let status = contract.repair_seal_record(account("nft.near"), "1".into());
+        assert_eq!(status, ConsistencyStatus::Completed);
+        assert_eq!(contract.pending_seals.get(&seal_key).unwrap().wormhole_sequence, 42);
+        assert_stats(&contract, 1, 1, 0);
+    }
+
+    #[test]
+    fn test_repair_consistent_seal_is_noop() {
+        let mut contract = setup();
+        lock_two_step(&mut contract, "1");
+        set_context("owner.near", vec![]);
+        assert_eq!(
+            contract.repair_seal_record(account("nft.near"), "1".into()),
+            ConsistencyStatus::Pending
+        );
+        assert_stats(&contract, 1, 0, 1);
+    }
+
+    /// Arguments of the `publish_message` call scheduled in this test context.
+    fn published_args() -> serde_json::Value {
+        use near_sdk::mock::MockAction;
+        let args = near_sdk::test_utils::get_created_receipts()
+            .into_iter()
+            .flat_map(|receipt| receipt.actions)
+            .find_map(|action| match action {
+                MockAction::FunctionCallWeight { method_name, args, .. } if method_name == b"publish_message" => {
+                    Some(args)
+                }
+                _ => None,
+            })
+            .expect("publish_message was not called");
+        serde_json::from_slice(&args).unwrap()
+    }
+
+    #[test]
+    fn test_consistency_level_defaults_to_finalized() {
+        let mut contract = setup();
+        lock_two_step(&mut contract, "1");
+        set_context("owner.near", vec![]);
+        let _ = contract.complete_seal_with_uri(account("nft.near"), "1".into(), "ipfs://x".into());
+        assert_eq!(published_args()["consistency_level"], CONSISTENCY_LEVEL_FINALIZED);
+    }
+
+    #[test]
+    fn test_configured_consistency_level_forwarded() {
+        let mut contract = setup();
+        contract.set_consistency_level(CONSISTENCY_LEVEL_INSTANT);
+        assert_eq!(contract.get_consistency_level(), CONSISTENCY_LEVEL_INSTANT);
+
+        lock_two_step(&mut contract, "1");
+        set_context("owner.near", vec![]);
+        let _ = contract.complete_seal_with_uri(account("nft.near"), "1".into(), "ipfs://x".into());
+        assert_eq!(published_args()["consistency_level"], CONSISTENCY_LEVEL_INSTANT);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unsupported consistency level")]
+    fn test_unsupported_consistency_level_rejected() {
+        let mut contract = setup();
+        contract.set_consistency_level(7);
+    }
+
+    #[test]
+    fn test_allowed_nft_contract_can_seal() {
+        let mut contract = setup();
+        assert!(contract.is_nft_contract_allowed(account("nft.near")));
+        contract.add_allowed_nft_contract(account("nft.near"));
+        lock_two_step(&mut contract, "1");
+        assert!(contract.is_pending(account("nft.near"), "1".into()));
+        assert_stats(&contract, 1, 0, 1);
+    }
+
+    #[test]
+    fn test_disallowed_nft_contract_refunded() {
+        let mut contract = setup();
+        contract.add_allowed_nft_contract(account("trusted.near"));
+        assert!(!contract.is_nft_contract_allowed(account("nft.near")));
+
+        set_context("nft.near", vec![]);
+        let msg = format!(r#"{{"deposit_address":"{}","solana_receiver":"{}"}}"#, DEPOSIT_HEX, RECEIVER_HEX);
+        let result = contract.nft_on_transfer(account("alice.near"), account("alice.near"), "1".into(), msg);
+        assert!(matches!(result, PromiseOrValue::Value(true)));
+        assert!(!contract.is_pending(account("nft.near"), "1".into()));
+        assert_stats(&contract, 0, 0, 0);
+    }
+
+    #[test]
+    fn test_removing_last_allowed_contract_reopens_sealing() {
+        let mut contract = setup();
+        contract.add_allowed_nft_contract(account("trusted.near"));
+        contract.add_allowed_nft_contract(account("trusted.near"));
+        contract.remove_allowed_nft_contract(account("trusted.near"));
+        assert!(contract.is_nft_contract_allowed(account("nft.near")));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only owner")]
+    fn test_allowed_nft_contracts_owner_only() {
+        let mut contract = setup();
+        set_context("alice.near", vec![]);
+        contract.add_allowed_nft_contract(account("nft.near"));
+    }
+
+    #[test]
+    fn test_payload_preview_matches_published_bytes() {
+        let mut contract = setup();
+        lock_two_step(&mut contract, "1");
+        let preview = contract.get_payload_preview(account("nft.near"), "1".into(), Some("ipfs://x".into()));
+
+        set_context("owner.near", vec![]);
+        let _ = contract.complete_seal_with_uri(account("nft.near"), "1".into(), "ipfs://x".into());
+        assert_eq!(published_args()["data"], preview);
+    }
+
+    #[test]
+    fn test_payload_preview_uses_recorded_uri() {
+        let mut contract = setup();
+        lock_two_step(&mut contract, "1");
+        let expected = contract.get_payload_preview(account("nft.near"), "1".into(), Some("ipfs://meta".into()));
+        completed_record(&mut contract, "1", 0);
+        assert_eq!(contract.get_payload_preview(account("nft.near"), "1".into(), None), expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "No pending seal")]
+    fn test_payload_preview_requires_locked_nft() {
+        let contract = setup();
+        contract.get_payload_preview(account("nft.near"), "1".into(), None);
+    }
+
+    #[test]
+    fn test_hash_domain_applied_to_published_payload() {
+        set_context("owner.near", vec![]);
+        let mut contract = SealInitiator::new(account("wormhole.near"), Some("ika-testnet".into()));
+        assert_eq!(contract.get_hash_domain(), "ika-testnet");
+        lock_two_step(&mut contract, "1");
+        set_context("owner.near", vec![]);
+        let _ = contract.complete_seal_with_uri(account("nft.near"), "1".into(), "ipfs://x".into());
+
+        let data = hex::decode(published_args()["data"].as_str().unwrap()).unwrap();
+        assert_eq!(&data[3..35], &payload::encode_near_account("ika-testnet", "nft.near"));
+        assert_eq!(&data[35..67], &payload::encode_near_token_id("ika-testnet", "1"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Hash domain too long")]
+    fn test_hash_domain_length_bounded() {
+        set_context("owner.near", vec![]);
+        SealInitiator::new(account("wormhole.near"), Some("x".repeat(MAX_HASH_DOMAIN_LENGTH + 1)));
+    }
+
+    #[test]
+    fn test_get_completion_tracks_seal_lifecycle() {
+        let mut contract = setup();
+        let completion = |c: &SealInitiator| c.get_completion(account("nft.near"), "1".into());
+        assert_eq!(completion(&contract), None);
+
+        lock_two_step(&mut contract, "1");
+        assert_eq!(completion(&contract), Some((false, 0)));
+
+        completed_record(&mut contract, "1", 42);
+        assert_eq!(completion(&contract), Some((true, 42)));
+    }
+
+    /// Run the metadata callback for token `token_id` of nft.near at `at`, spawning its publish.
+    fn start_publish(contract: &mut SealInitiator, token_id: &str, at: u64) {
+        let receiver: [u8; 32] = hex::decode(RECEIVER_HEX).unwrap().try_into().unwrap();
+        let seal_key = contract.compute_seal_key(&account("nft.near"), token_id);
+        set_context_at("seal.near", at, vec![token_result("ipfs://meta")]);
+        let _ = contract.on_nft_metadata_received(
+            account("nft.near"),
+            token_id.into(),
+            DEPOSIT_HEX.into(),
+            receiver,
+            seal_key,
+        );
+    }
+
+    #[test]
+    fn test_completion_attempts_do_not_block_or_extend_unlock() {
+        let mut contract = setup();
+        lock_two_step(&mut contract, "1");
+        let seal_key = contract.compute_seal_key(&account("nft.near"), "1");
+
+        // Calls whose publish never spawns (e.g. the metadata call fails) leave no marker
+        set_context("mallory.near", vec![]);
+        let _ = contract.complete_seal_initiation(account("nft.near"), "1".into(), None);
+        assert!(!contract.is_publish_in_flight(&seal_key));
+
+        // A spawned publish marks once; a later attempt doesn't push the deadline out
+        start_publish(&mut contract, "1", 0);
+        contract.clear_publishing(&seal_key);
+        start_publish(&mut contract, "1", PUBLISH_IN_FLIGHT_NS - 1);
+        assert_eq!(contract.publishes_in_flight.get(&seal_key), Some(PUBLISH_IN_FLIGHT_NS));
+    }
+
+    #[test]
+    #[should_panic(expected = "Seal publish in flight, cannot unlock")]
+    fn test_emergency_unlock_blocked_while_publish_in_flight() {
+        let mut contract = setup();
+        lock_two_step(&mut contract, "1");
+
+        // Relayer starts completing just before the unlock delay elapses; the publish
+        // is still in flight when the owner tries to unlock
+        set_context_at("relayer.near", EMERGENCY_UNLOCK_DELAY_NS - 1, vec![]);
+        let _ = contract.complete_seal_initiation(account("nft.near"), "1".into(), None);
+        start_publish(&mut contract, "1", EMERGENCY_UNLOCK_DELAY_NS - 1);
+
+        set_context_at("owner.near", EMERGENCY_UNLOCK_DELAY_NS, vec![]);
+        let _ = contract.emergency_unlock(account("nft.near"), "1".into());
+    }
+
+    #[test]
+    fn test_in_flight_marker_cleared_by_callback_and_expires() {
+        let mut contract = setup();
+        lock_two_step(&mut contract, "1");
+        lock_two_step(&mut contract, "2");
+        let key = |id: &str| contract.compute_seal_key(&account("nft.near"), id);
+        let (key_1, key_2) = (key("1"), key("2"));
+
+        start_publish(&mut contract, "1", 0);
+        start_publish(&mut contract, "2", 0);
+        assert!(contract.is_publish_in_flight(&key_1));
+
+        // Token 1 publishes and records: the marker clears
+        completed_record(&mut contract, "1", 7);
+        assert!(!contract.is_publish_in_flight(&key_1));
+
+        // Token 2's callback never lands: the marker lapses and unlock works again
+        set_context_at("owner.near", EMERGENCY_UNLOCK_DELAY_NS + PUBLISH_IN_FLIGHT_NS, vec![]);
+        assert!(!contract.is_publish_in_flight(&key_2));
+        let _ = contract.emergency_unlock(account("nft.near"), "2".into());
+        assert!(!contract.is_sealed(account("nft.near"), "2".into()));
+    }
+
+    /// A v1 state holding token "1" (pending) and token "2" (completed, with its record).
+    fn write_v1_state() {
+        set_context("seal.near", vec![]);
+        let (key_1, key_2) = (legacy_seal_key(&account("nft.near"), "1"), legacy_seal_key(&account("nft.near"), "2"));
+        let mut sealed_nfts = LookupSet::new(StorageKey::SealedNfts);
+        let mut pending_seals = LookupMap::new(StorageKey::PendingSeals);
+        let mut seal_records = LookupMap::new(StorageKey::SealRecords);
+        for (seal_key, id, completed) in [(&key_1, "1", false), (&key_2, "2", true)] {
+            sealed_nfts.insert(seal_key);
+            pending_seals.insert(
+                seal_key,
+                &PendingSealV1 {
+                    nft_contract: account("nft.near"),
+                    token_id: id.into(),
+                    deposit_address: DEPOSIT_HEX.into(),
+                    solana_receiver: [0xab; 32],
+                    completed,
+                    wormhole_sequence: if completed { 4 } else { 0 },
+                    created_at: 0,
+                },
+            );
+        }
+        seal_records.insert(
+            &key_2,
+            &SealRecordV1 {
+                nft_contract: account("nft.near"),
+                token_id: "2".into(),
+                deposit_address: DEPOSIT_HEX.into(),
+                solana_receiver: [0xab; 32],
+                token_uri: "ipfs://two".into(),
+                wormhole_sequence: 4,
+                source_chain_id: payload::WORMHOLE_CHAIN_ID_NEAR,
+                sealed_at: 0,
+            },
+        );
+        env::state_write(&SealInitiatorV1 {
+            wormhole_account: account("wormhole.near"),
+            sequence: 5,
+            sealed_nfts,
+            pending_seals,
+            seal_records,
+            owner: account("owner.near"),
+            paused: false,
+        });
+    }
+
+    #[test]
+    fn test_migrate_rekeys_v1_state() {
+        write_v1_state();
+        let contract = SealInitiator::migrate(vec![
+            (account("nft.near"), "1".into()),
+            (account("nft.near"), "2".into()),
+        ]);
+
+        assert_eq!(contract.state_version, STATE_VERSION);
+        assert_eq!(contract.get_sequence(), 5);
+        assert_eq!(contract.get_wormhole_callback_gas(), DEFAULT_GAS_CALLBACK_WORMHOLE_TGAS);
+        assert_stats(&contract, 2, 1, 1);
+        assert!(contract.is_sealed(account("nft.near"), "1".into()));
+        assert!(contract.is_sealed(account("nft.near"), "2".into()));
+        assert!(contract.is_pending(account("nft.near"), "1".into()));
+        assert!(!contract.is_pending(account("nft.near"), "2".into()));
+        assert_eq!(
+            contract.check_seal_consistency(account("nft.near"), "2".into()),
+            ConsistencyStatus::Completed
+        );
+        let record = contract.get_seal_record(account("nft.near"), "2".into()).unwrap();
+        assert_eq!((record.token_uri.as_str(), record.dest_chain), ("ipfs://two", payload::WORMHOLE_CHAIN_ID_SOLANA));
+        let key_1 = contract.compute_seal_key(&account("nft.near"), "1");
+        assert!(!contract.pending_seals.get(&key_1).unwrap().publishing);
+
+        // Nothing is left under the v1 prefixes and keys
+        let (old_1, old_2) = (legacy_seal_key(&account("nft.near"), "1"), legacy_seal_key(&account("nft.near"), "2"));
+        assert_ne!(old_1, key_1);
+        assert!(!LookupSet::<Vec<u8>>::new(StorageKey::SealedNfts).contains(&old_1));
+        assert!(LookupMap::<Vec<u8>, PendingSealV1>::new(StorageKey::PendingSeals).get(&old_1).is_none());
+        assert!(LookupMap::<Vec<u8>, SealRecordV1>::new(StorageKey::SealRecords).get(&old_2).is_none());
+    }
+
+    #[test]
+    fn test_seal_key_separates_contract_and_token() {
+        let contract = setup();
+        let (a, b) = ((account("a.near"), "1"), (account("a.nea"), "r1"));
+        assert_eq!(legacy_seal_key(&a.0, a.1), legacy_seal_key(&b.0, b.1));
+        assert_ne!(contract.compute_seal_key(&a.0, a.1), contract.compute_seal_key(&b.0, b.1));
+    }
+
+    #[test]
+    #[should_panic(expected = "State already migrated")]
+    fn test_migrate_runs_once() {
+        write_v1_state();
+        let contract = SealInitiator::migrate(vec![(account("nft.near"), "1".into())]);
+        env::state_write(&contract);
+        SealInitiator::migrate(vec![(account("nft.near"), "1".into())]);
+    }
+
+    #[test]
+    fn test_racing_completions_publish_once() {
+        let mut contract = setup();
+        lock_two_step(&mut contract, "1");
+        let receiver: [u8; 32] = hex::decode(RECEIVER_HEX).unwrap().try_into().unwrap();
+        let seal_key = contract.compute_seal_key(&account("nft.near"), "1");
+
+        // Both completions pass the entry checks before either callback runs
+        set_context("relayer.near", vec![]);
+        let _ = contract.complete_seal_initiation(account("nft.near"), "1".into(), None);
+        let _ = contract.complete_seal_initiation(account("nft.near"), "1".into(), None);
+
+        for _ in 0..2 {
+            set_context("seal.near", vec![token_result("ipfs://meta")]);
+            let _ = contract.on_nft_metadata_received(
+                account("nft.near"),
+                "1".into(),
+                DEPOSIT_HEX.into(),
+                receiver,
+                seal_key.clone(),
+            );
+        }
+        assert_eq!(contract.get_sequence(), 1);
+        assert!(contract.pending_seals.get(&seal_key).unwrap().publishing);
+
+        // The publish lands: terminal, flag cleared, a late callback still can't republish
+        completed_record(&mut contract, "1", 0);
+        assert!(!contract.pending_seals.get(&seal_key).unwrap().publishing);
+        set_context("seal.near", vec![token_result("ipfs://meta")]);
+        let _ = contract.on_nft_metadata_received(
+            account("nft.near"),
+            "1".into(),
+            DEPOSIT_HEX.into(),
+            receiver,
+            seal_key,
+        );
+        assert_eq!(contract.get_sequence(), 1);
+        assert_stats(&contract, 1, 1, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Seal publish already in progress")]
+    fn test_complete_seal_with_uri_refused_while_publishing() {
+        let mut contract = setup();
+        lock_two_step(&mut contract, "1");
+        start_publish(&mut contract, "1", 0);
+
+        set_context("owner.near", vec![]);
+        let _ = contract.complete_seal_with_uri(account("nft.near"), "1".into(), "ipfs://manual".into());
+    }
+
+    #[test]
+    fn test_complete_seal_with_uri_blocks_metadata_publish() {
+        let mut contract = setup();
+        lock_two_step(&mut contract, "1");
+        let seal_key = contract.compute_seal_key(&account("nft.near"), "1");
+
+        set_context("owner.near", vec![]);
+        let _ = contract.complete_seal_with_uri(account("nft.near"), "1".into(), "ipfs://manual".into());
+        assert!(contract.pending_seals.get(&seal_key).unwrap().publishing);
+
+        // A relayer's metadata callback arriving meanwhile refunds instead of publishing
+        start_publish(&mut contract, "1", 0);
+        assert_eq!(contract.get_sequence(), 1);
+    }
+
+    #[test]
+    fn test_failed_publish_clears_publishing() {
+        let mut contract = setup();
+        lock_two_step(&mut contract, "1");
+        let receiver: [u8; 32] = hex::decode(RECEIVER_HEX).unwrap().try_into().unwrap();
+        let seal_key = contract.compute_seal_key(&account("nft.near"), "1");
+
+        set_context("seal.near", vec![token_result("ipfs://meta")]);
+        let _ = contract.on_nft_metadata_received(
+            account("nft.near"),
+            "1".into(),
+            DEPOSIT_HEX.into(),
+            receiver,
+            seal_key.clone(),
+        );
+        set_context("seal.near", vec![PromiseResult::Failed]);
+        contract.on_wormhole_published(
+            account("nft.near"),
+            "1".into(),
+            DEPOSIT_HEX.into(),
+            receiver,
+            "ipfs://meta".into(),
+            seal_key.clone(),
+        );
+        let pending = contract.pending_seals.get(&seal_key).unwrap();
+        assert!(!pending.publishing && !pending.completed);
+    }
+
+    fn set_context_with_deposit(predecessor: &str, deposit: NearToken) {
+        let context = VMContextBuilder::new()
+            .current_account_id(account("seal.near"))
+            .predecessor_account_id(account(predecessor))
+            .attached_deposit(deposit)
+            .build();
+        testing_env!(context);
+    }
+
+    /// Cache a Wormhole message fee of `fee` yoctoNEAR through the refresh callback.
+    fn set_message_fee(contract: &mut SealInitiator, fee: u128) {
+        set_context("seal.near", vec![PromiseResult::Successful(format!("\"{}\"", fee).into_bytes())]);
+        assert_eq!(contract.on_message_fee_received(), U128(fee));
+        assert_eq!(contract.get_message_fee(), Some(U128(fee)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Attached deposit does not cover the Wormhole message fee")]
+    fn test_complete_seal_rejects_insufficient_deposit() {
+        let mut contract = setup();
+        lock_two_step(&mut contract, "1");
+        set_message_fee(&mut contract, 1_000);
+
+        set_context_with_deposit("relayer.near", NearToken::from_yoctonear(999));
+        let _ = contract.complete_seal_initiation(account("nft.near"), "1".into(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Wormhole message fee unknown, call refresh_message_fee")]
+    fn test_complete_seal_refused_until_fee_read() {
+        set_context("owner.near", vec![]);
+        let mut contract = SealInitiator::new(account("wormhole.near"), None);
+        assert_eq!(contract.get_message_fee(), None);
+        lock_two_step(&mut contract, "1");
+
+        set_context_with_deposit("relayer.near", NearToken::from_near(1));
+        let _ = contract.complete_seal_initiation(account("nft.near"), "1".into(), None);
+    }
+
+    #[test]
+    fn test_oneshot_refused_until_fee_read() {
+        set_context("owner.near", vec![]);
+        let mut contract = SealInitiator::new(account("wormhole.near"), None);
+
+        set_context("nft.near", vec![]);
+        let result =
+            contract.nft_on_transfer(account("alice.near"), account("alice.near"), "1".into(), oneshot_msg());
+        assert!(matches!(result, PromiseOrValue::Value(true)));
+        assert!(!contract.is_sealed(account("nft.near"), "1".into()));
+    }
+
+    #[test]
+    fn test_complete_seal_with_sufficient_deposit_proceeds() {
+        let mut contract = setup();
+        lock_two_step(&mut contract, "1");
+        set_message_fee(&mut contract, 1_000);
+        let seal_key = contract.compute_seal_key(&account("nft.near"), "1");
+
+        set_context_with_deposit("relayer.near", NearToken::from_yoctonear(1_500));
+        let _ = contract.complete_seal_initiation(account("nft.near"), "1".into(), None);
+        assert!(!contract.is_publish_in_flight(&seal_key));
+
+        // The fee rides along to the metadata callback; the 500 yocto surplus is refunded
+        use near_sdk::mock::MockAction;
+        let receipts = near_sdk::test_utils::get_created_receipts();
+        let actions = || receipts.iter().flat_map(|receipt| receipt.actions.iter());
+        assert!(actions().any(|action| matches!(
+            action,
+            MockAction::FunctionCallWeight { method_name, attached_deposit, .. }
+                if method_name == b"on_nft_metadata_received" && *attached_deposit == NearToken::from_yoctonear(1_000)
+        )));
+        let refund = receipts.iter().find(|receipt| receipt.receiver_id == account("relayer.near")).unwrap();
+        assert!(matches!(
+            refund.actions[..],
+            [MockAction::Transfer { deposit, .. }] if deposit == NearToken::from_yoctonear(500)
+        ));
+    }
+
+    #[test]
+    fn test_last_successful_publish_updated() {
+        let mut contract = setup();
+        lock_two_step(&mut contract, "1");
+        lock_two_step(&mut contract, "2");
+        assert_eq!(contract.get_last_successful_publish_ns(), 0);
+        let receiver: [u8; 32] = hex::decode(RECEIVER_HEX).unwrap().try_into().unwrap();
+
+        set_context_at("seal.near", 77, vec![PromiseResult::Successful(b"3".to_vec())]);
+        contract.on_wormhole_published(
+            account("nft.near"),
+            "1".into(),
+            DEPOSIT_HEX.into(),
+            receiver,
+            "ipfs://meta".into(),
+            contract.compute_seal_key(&account("nft.near"), "1"),
+        );
+        assert_eq!(contract.get_last_successful_publish_ns(), 77);
+
+        // A failed publish leaves it alone
+        set_context_at("seal.near", 99, vec![PromiseResult::Failed]);
+        contract.on_wormhole_published(
+            account("nft.near"),
+            "2".into(),
+            DEPOSIT_HEX.into(),
+            receiver,
+            "ipfs://meta".into(),
+            contract.compute_seal_key(&account("nft.near"), "2"),
+        );
+        assert_eq!(contract.get_last_successful_publish_ns(), 77);
+    }
+
+    #[test]
+    fn test_wormhole_health_check_result() {
+        let mut contract = setup();
+        set_context("seal.near", vec![PromiseResult::Successful(b"\"250\"".to_vec())]);
+        assert!(contract.on_wormhole_health_checked());
+        assert_eq!(contract.get_message_fee(), Some(U128(250)));
+
+        set_context("seal.near", vec![PromiseResult::Failed]);
+        assert!(!contract.on_wormhole_health_checked());
+        assert_eq!(contract.get_message_fee(), Some(U128(250)));
+    }
+}
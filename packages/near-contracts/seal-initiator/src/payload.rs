@@ -3,18 +3,83 @@ use sha2::{Digest, Sha256};
 /// Wormhole chain ID for NEAR
 pub const WORMHOLE_CHAIN_ID_NEAR: u16 = 15;
 
+/// Wormhole chain ID for Solana (default destination)
+pub const WORMHOLE_CHAIN_ID_SOLANA: u16 = 1;
+
 /// Payload type for Seal Attestation
 pub const PAYLOAD_TYPE_SEAL: u8 = 0x01;
 
+/// Payload type for Seal Attestation carrying an explicit destination chain
+pub const PAYLOAD_TYPE_SEAL_DEST: u8 = 0x02;
+
+/// Set on `payload_type` when the payload ends with a checksum trailer
+pub const PAYLOAD_FLAG_CHECKSUM: u8 = 0x80;
+
+/// Trailer length: last 4 bytes of SHA256 over everything before it
+pub const CHECKSUM_LEN: usize = 4;
+
+/// Receiver encodings supported per destination chain.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ReceiverFormat {
+    /// 32-byte address used as-is (Solana, Sui, Aptos)
+    Raw32,
+    /// 20-byte EVM address, left-padded to 32 bytes
+    Evm20,
+}
+
+/// Receiver format for a Wormhole destination chain, or None if unsupported.
+pub fn receiver_format(dest_chain: u16) -> Option<ReceiverFormat> {
+    match dest_chain {
+        1 | 21 | 22 => Some(ReceiverFormat::Raw32),
+        2 | 4 | 5 | 6 | 23 | 24 | 30 | 10002..=10005 => Some(ReceiverFormat::Evm20),
+        _ => None,
+    }
+}
+
+/// Decode a hex receiver for `dest_chain` into the 32-byte payload slot.
+pub fn encode_dest_receiver(dest_chain: u16, receiver_hex: &str) -> Result<[u8; 32], &'static str> {
+    let format = receiver_format(dest_chain).ok_or("Unsupported destination chain")?;
+    let clean = receiver_hex.strip_prefix("0x").unwrap_or(receiver_hex);
+    let bytes = hex::decode(clean).map_err(|_| "Invalid hex in receiver")?;
+
+    let mut result = [0u8; 32];
+    match format {
+        ReceiverFormat::Raw32 => {
+            if bytes.len() != 32 {
+                return Err("Receiver must be 32 bytes hex for this chain");
+            }
+            result.copy_from_slice(&bytes);
+        }
+        ReceiverFormat::Evm20 => {
+            if bytes.len() != 20 {
+                return Err("Receiver must be a 20-byte EVM address for this chain");
+            }
+            result[12..].copy_from_slice(&bytes);
+        }
+    }
+    Ok(result)
+}
+
+/// SHA256 of `value`, domain-separated as `domain || 0x00 || value` when `domain` is
+/// non-empty. An empty domain hashes `value` alone (the original encoding).
+fn domain_hash(domain: &str, value: &str) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    if !domain.is_empty() {
+        hasher.update(domain.as_bytes());
+        hasher.update([0u8]);
+    }
+    hasher.update(value.as_bytes());
+    hasher.finalize().into()
+}
+
 /// Encode a NEAR account ID (string) into 32 bytes via SHA256.
 ///
 /// NEAR account IDs are variable-length strings (e.g., "alice.near").
 /// EVM uses left-padded 20-byte addresses. To produce a deterministic
-/// 32-byte value from a string, we use SHA256.
-pub fn encode_near_account(account_id: &str) -> [u8; 32] {
-    let mut hasher = Sha256::new();
-    hasher.update(account_id.as_bytes());
-    hasher.finalize().into()
+/// 32-byte value from a string, we use SHA256, prefixed with the
+/// deployment's hash domain.
+pub fn encode_near_account(domain: &str, account_id: &str) -> [u8; 32] {
+    domain_hash(domain, account_id)
 }
 
 /// Decode a hex-encoded 32-byte value (e.g., a dWallet pubkey).
@@ -33,59 +98,90 @@ pub fn decode_hex_32(hex_str: &str) -> [u8; 32] {
 ///
 /// NEAR token IDs are strings (e.g., "1", "cool-nft-42").
 /// The wire format requires a 32-byte big-endian value.
-/// SHA256 produces a deterministic 32-byte hash.
-pub fn encode_near_token_id(token_id: &str) -> [u8; 32] {
-    let mut hasher = Sha256::new();
-    hasher.update(token_id.as_bytes());
-    hasher.finalize().into()
+/// SHA256 produces a deterministic 32-byte hash, prefixed with the
+/// deployment's hash domain.
+pub fn encode_near_token_id(domain: &str, token_id: &str) -> [u8; 32] {
+    domain_hash(domain, token_id)
+}
+
+/// Checksum trailer for `body`: the last `CHECKSUM_LEN` bytes of SHA256(body).
+pub fn payload_checksum(body: &[u8]) -> [u8; CHECKSUM_LEN] {
+    let hash: [u8; 32] = Sha256::digest(body).into();
+    let mut checksum = [0u8; CHECKSUM_LEN];
+    checksum.copy_from_slice(&hash[32 - CHECKSUM_LEN..]);
+    checksum
 }
 
 /// Build the binary Wormhole payload matching the canonical wire format.
 ///
 /// Wire format (from docs/WIRE-FORMAT-SPEC.md):
 ///   Offset  Size  Field            Encoding
-///   0       1     payload_type     u8 = 0x01
+///   0       1     payload_type     u8 = 0x81 (0x01 | checksum flag)
 ///   1       2     source_chain     u16 big-endian = 15 (NEAR)
-///   3       32    nft_contract     SHA256(nft_contract_account_id)
-///   35      32    token_id         SHA256(token_id_string)
+///   3       32    nft_contract     SHA256([domain || 0x00 ||] nft_contract_account_id)
+///   35      32    token_id         SHA256([domain || 0x00 ||] token_id_string)
 ///   67      32    deposit_address  SHA256(deposit_account_id)
 ///   99      32    receiver         raw Solana pubkey (32 bytes)
 ///   131     var   token_uri        raw UTF-8, no length prefix
+///   end-4   4     checksum         last 4 bytes of SHA256(all preceding bytes)
+///
+/// Total minimum: 135 bytes (empty URI)
 ///
-/// Total minimum: 131 bytes (empty URI)
+/// For any destination other than Solana the payload type is 0x02 and a
+/// big-endian u16 `dest_chain` follows `source_chain`, shifting later fields
+/// by 2 (type 0x82, minimum 137 bytes). Solana-bound payloads are unchanged.
+///
+/// The checksum lets the decoder reject a payload whose URI was truncated or
+/// altered in transit; `PAYLOAD_FLAG_CHECKSUM` tells it the trailer is present.
+///
+/// `domain` is the deployment's hash domain (see `domain_hash`); it only changes
+/// the two hashed fields, never the layout.
 pub fn build_seal_payload(
+    domain: &str,
     nft_contract: &str,
     token_id: &str,
     deposit_address: &str,
-    solana_receiver: &[u8; 32],
+    dest_chain: u16,
+    receiver: &[u8; 32],
     token_uri: &str,
 ) -> Vec<u8> {
-    let mut payload = Vec::with_capacity(131 + token_uri.len());
+    let mut payload = Vec::with_capacity(133 + token_uri.len() + CHECKSUM_LEN);
 
-    // [0] payload_type = 0x01
-    payload.push(PAYLOAD_TYPE_SEAL);
+    // [0] payload_type = 0x01 (Solana) / 0x02 (explicit destination), checksum flag set
+    let explicit_dest = dest_chain != WORMHOLE_CHAIN_ID_SOLANA;
+    let payload_type = if explicit_dest { PAYLOAD_TYPE_SEAL_DEST } else { PAYLOAD_TYPE_SEAL };
+    payload.push(payload_type | PAYLOAD_FLAG_CHECKSUM);
 
     // [1-2] source_chain = 15 (big-endian u16)
     payload.extend_from_slice(&WORMHOLE_CHAIN_ID_NEAR.to_be_bytes());
 
-    // [3-34] nft_contract = SHA256(account_id)
-    payload.extend_from_slice(&encode_near_account(nft_contract));
+    // [3-4] dest_chain (big-endian u16), 0x02 payloads only
+    if explicit_dest {
+        payload.extend_from_slice(&dest_chain.to_be_bytes());
+    }
+
+    // [3-34] nft_contract = SHA256(domain-separated account_id)
+    payload.extend_from_slice(&encode_near_account(domain, nft_contract));
 
-    // [35-66] token_id = SHA256(token_id_string)
-    payload.extend_from_slice(&encode_near_token_id(token_id));
+    // [35-66] token_id = SHA256(domain-separated token_id_string)
+    payload.extend_from_slice(&encode_near_token_id(domain, token_id));
 
     // [67-98] deposit_address = raw dWallet pubkey (hex-decoded, NOT hashed)
     // The deposit_address is a 32-byte dWallet pubkey passed as hex,
     // matching the Sui DWalletRegistry key format.
     payload.extend_from_slice(&decode_hex_32(deposit_address));
 
-    // [99-130] receiver = raw Solana pubkey
-    payload.extend_from_slice(solana_receiver);
+    // [99-130] receiver = 32-byte slot (EVM addresses left-padded)
+    payload.extend_from_slice(receiver);
 
     // [131+] token_uri = raw UTF-8 bytes, no length prefix
     payload.extend_from_slice(token_uri.as_bytes());
 
-    debug_assert!(payload.len() >= 131, "Payload too short");
+    // [end-4..end] checksum trailer over everything above
+    let checksum = payload_checksum(&payload);
+    payload.extend_from_slice(&checksum);
+
+    debug_assert!(payload.len() >= 131 + CHECKSUM_LEN, "Payload too short");
 
     payload
 }
@@ -98,15 +194,17 @@ mod tests {
     fn test_payload_encoding_minimum() {
         let deposit_hex = "aa".repeat(32); // 64 hex chars = 32 bytes
         let payload = build_seal_payload(
+            "",
             "nft.paras.near",
             "42",
             &deposit_hex,
+            WORMHOLE_CHAIN_ID_SOLANA,
             &[0xAA; 32],
             "", // empty URI
         );
 
-        assert_eq!(payload.len(), 131);
-        assert_eq!(payload[0], 0x01); // payload_type
+        assert_eq!(payload.len(), 131 + CHECKSUM_LEN);
+        assert_eq!(payload[0], 0x81); // payload_type (0x01 | checksum flag)
         assert_eq!(payload[1], 0x00); // source_chain high byte
         assert_eq!(payload[2], 0x0F); // source_chain low byte (15)
     }
@@ -116,25 +214,29 @@ mod tests {
         let uri = "ipfs://QmTest123";
         let deposit_hex = "bb".repeat(32);
         let payload = build_seal_payload(
+            "",
             "nft.paras.near",
             "42",
             &deposit_hex,
+            WORMHOLE_CHAIN_ID_SOLANA,
             &[0xBB; 32],
             uri,
         );
 
-        assert_eq!(payload.len(), 131 + uri.len());
-        assert_eq!(&payload[131..], uri.as_bytes());
+        assert_eq!(payload.len(), 131 + uri.len() + CHECKSUM_LEN);
+        assert_eq!(&payload[131..131 + uri.len()], uri.as_bytes());
     }
 
     #[test]
     fn test_nft_contract_encoding() {
-        let expected = encode_near_account("nft.paras.near");
+        let expected = encode_near_account("", "nft.paras.near");
         let deposit_hex = "00".repeat(32);
         let payload = build_seal_payload(
+            "",
             "nft.paras.near",
             "1",
             &deposit_hex,
+            WORMHOLE_CHAIN_ID_SOLANA,
             &[0; 32],
             "",
         );
@@ -143,12 +245,14 @@ mod tests {
 
     #[test]
     fn test_token_id_encoding() {
-        let expected = encode_near_token_id("42");
+        let expected = encode_near_token_id("", "42");
         let deposit_hex = "00".repeat(32);
         let payload = build_seal_payload(
+            "",
             "nft.near",
             "42",
             &deposit_hex,
+            WORMHOLE_CHAIN_ID_SOLANA,
             &[0; 32],
             "",
         );
@@ -161,9 +265,11 @@ mod tests {
         let deposit_hex = "ab".repeat(32);
         let expected = [0xABu8; 32];
         let payload = build_seal_payload(
+            "",
             "nft.near",
             "1",
             &deposit_hex,
+            WORMHOLE_CHAIN_ID_SOLANA,
             &[0; 32],
             "",
         );
@@ -175,9 +281,11 @@ mod tests {
         let receiver = [0x42u8; 32];
         let deposit_hex = "00".repeat(32);
         let payload = build_seal_payload(
+            "",
             "nft.near",
             "1",
             &deposit_hex,
+            WORMHOLE_CHAIN_ID_SOLANA,
             &receiver,
             "",
         );
@@ -186,11 +294,11 @@ mod tests {
 
     #[test]
     fn test_encode_account_deterministic() {
-        let a = encode_near_account("alice.near");
-        let b = encode_near_account("alice.near");
+        let a = encode_near_account("", "alice.near");
+        let b = encode_near_account("", "alice.near");
         assert_eq!(a, b);
 
-        let c = encode_near_account("bob.near");
+        let c = encode_near_account("", "bob.near");
         assert_ne!(a, c);
     }
 
@@ -198,4 +306,85 @@ mod tests {
     fn test_chain_id() {
         assert_eq!(WORMHOLE_CHAIN_ID_NEAR, 15);
     }
+
+    #[test]
+    fn test_solana_destination_keeps_legacy_layout() {
+        let receiver = encode_dest_receiver(WORMHOLE_CHAIN_ID_SOLANA, &"42".repeat(32)).unwrap();
+        let payload = build_seal_payload(
+            "",
+            "nft.near",
+            "1",
+            &"00".repeat(32),
+            WORMHOLE_CHAIN_ID_SOLANA,
+            &receiver,
+            "",
+        );
+        assert_eq!(payload.len(), 131 + CHECKSUM_LEN);
+        assert_eq!(payload[0], PAYLOAD_TYPE_SEAL | PAYLOAD_FLAG_CHECKSUM);
+        assert_eq!(&payload[99..131], &[0x42u8; 32]);
+    }
+
+    #[test]
+    fn test_evm_destination_carries_dest_chain() {
+        let uri = "ipfs://QmTest123";
+        let receiver = encode_dest_receiver(2, &format!("0x{}", "11".repeat(20))).unwrap();
+        let payload = build_seal_payload("", "nft.near", "1", &"00".repeat(32), 2, &receiver, uri);
+
+        assert_eq!(payload.len(), 133 + uri.len() + CHECKSUM_LEN);
+        assert_eq!(payload[0], PAYLOAD_TYPE_SEAL_DEST | PAYLOAD_FLAG_CHECKSUM);
+        assert_eq!(&payload[1..3], &[0x00, 0x0F]); // source_chain = NEAR
+        assert_eq!(&payload[3..5], &[0x00, 0x02]); // dest_chain = Ethereum
+        assert_eq!(&payload[5..37], &encode_near_account("", "nft.near"));
+        assert_eq!(&payload[101..113], &[0u8; 12]); // left padding
+        assert_eq!(&payload[113..133], &[0x11u8; 20]);
+        assert_eq!(&payload[133..133 + uri.len()], uri.as_bytes());
+    }
+
+    #[test]
+    fn test_dest_receiver_validated_per_chain() {
+        // 32-byte receiver is not an EVM address and vice versa
+        assert!(encode_dest_receiver(2, &"11".repeat(32)).is_err());
+        assert!(encode_dest_receiver(WORMHOLE_CHAIN_ID_SOLANA, &"11".repeat(20)).is_err());
+        assert!(encode_dest_receiver(21, &"11".repeat(32)).is_ok());
+        assert!(encode_dest_receiver(WORMHOLE_CHAIN_ID_NEAR, &"11".repeat(32)).is_err());
+        assert!(encode_dest_receiver(WORMHOLE_CHAIN_ID_SOLANA, "zz").is_err());
+    }
+
+    #[test]
+    fn test_domains_separate_field_encodings() {
+        assert_ne!(encode_near_account("ika-mainnet", "nft.near"), encode_near_account("ika-testnet", "nft.near"));
+        assert_ne!(encode_near_token_id("ika-mainnet", "1"), encode_near_token_id("ika-testnet", "1"));
+        assert_ne!(encode_near_account("ika-mainnet", "nft.near"), encode_near_account("", "nft.near"));
+
+        let deposit_hex = "00".repeat(32);
+        let payload = |domain| {
+            build_seal_payload(domain, "nft.near", "1", &deposit_hex, WORMHOLE_CHAIN_ID_SOLANA, &[0; 32], "")
+        };
+        let (a, b) = (payload("ika-mainnet"), payload("ika-testnet"));
+        assert_ne!(a[3..67], b[3..67]);
+        assert_eq!(a[67..], b[67..]);
+        assert_eq!(&a[3..35], &encode_near_account("ika-mainnet", "nft.near"));
+    }
+
+    #[test]
+    fn test_empty_domain_keeps_plain_sha256() {
+        let expected: [u8; 32] = Sha256::digest(b"nft.near").into();
+        assert_eq!(encode_near_account("", "nft.near"), expected);
+    }
+
+    #[test]
+    fn test_checksum_trailer_covers_whole_payload() {
+        let deposit_hex = "00".repeat(32);
+        let payload =
+            build_seal_payload("", "nft.near", "1", &deposit_hex, WORMHOLE_CHAIN_ID_SOLANA, &[0; 32], "ipfs://x");
+        let (body, trailer) = payload.split_at(payload.len() - CHECKSUM_LEN);
+        assert_eq!(trailer, &payload_checksum(body));
+
+        // A single flipped URI byte no longer matches the trailer
+        let mut corrupted = body.to_vec();
+        *corrupted.last_mut().unwrap() ^= 0x01;
+        assert_ne!(trailer, &payload_checksum(&corrupted));
+        // Nor does a truncated URI
+        assert_ne!(trailer, &payload_checksum(&body[..body.len() - 1]));
+    }
 }
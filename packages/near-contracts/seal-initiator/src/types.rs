@@ -12,10 +12,48 @@ pub struct PendingSeal {
     pub nft_contract: AccountId,
     pub token_id: String,
     pub deposit_address: String,
+    /// 32-byte receiver slot on `dest_chain` (name predates non-Solana destinations)
     pub solana_receiver: [u8; 32],
     pub completed: bool,
     pub wormhole_sequence: u64,
     pub created_at: u64,
+    /// Wormhole chain ID the NFT is reincarnated on
+    pub dest_chain: u16,
+    /// Set while a publish is under way (metadata callback, `complete_seal_with_uri` or
+    /// one-shot), so no other completion path can publish again. Cleared when the seal
+    /// is recorded or its publish fails.
+    pub publishing: bool,
+}
+
+/// `PendingSeal` as stored by state v1, before `dest_chain` and `publishing`.
+/// Only read by `migrate`.
+#[derive(BorshDeserialize, BorshSerialize)]
+#[borsh(crate = "near_sdk::borsh")]
+pub struct PendingSealV1 {
+    pub nft_contract: AccountId,
+    pub token_id: String,
+    pub deposit_address: String,
+    pub solana_receiver: [u8; 32],
+    pub completed: bool,
+    pub wormhole_sequence: u64,
+    pub created_at: u64,
+}
+
+impl From<PendingSealV1> for PendingSeal {
+    fn from(seal: PendingSealV1) -> Self {
+        Self {
+            nft_contract: seal.nft_contract,
+            token_id: seal.token_id,
+            deposit_address: seal.deposit_address,
+            solana_receiver: seal.solana_receiver,
+            completed: seal.completed,
+            wormhole_sequence: seal.wormhole_sequence,
+            created_at: seal.created_at,
+            // v1 only sealed to Solana
+            dest_chain: crate::payload::WORMHOLE_CHAIN_ID_SOLANA,
+            publishing: false,
+        }
+    }
 }
 
 /// Full seal record stored after Wormhole publish completes.
@@ -31,6 +69,37 @@ pub struct SealRecord {
     pub wormhole_sequence: u64,
     pub source_chain_id: u16,
     pub sealed_at: u64,
+    pub dest_chain: u16,
+}
+
+/// `SealRecord` as stored by state v1, before `dest_chain`. Only read by `migrate`.
+#[derive(BorshDeserialize, BorshSerialize)]
+#[borsh(crate = "near_sdk::borsh")]
+pub struct SealRecordV1 {
+    pub nft_contract: AccountId,
+    pub token_id: String,
+    pub deposit_address: String,
+    pub solana_receiver: [u8; 32],
+    pub token_uri: String,
+    pub wormhole_sequence: u64,
+    pub source_chain_id: u16,
+    pub sealed_at: u64,
+}
+
+impl From<SealRecordV1> for SealRecord {
+    fn from(record: SealRecordV1) -> Self {
+        Self {
+            nft_contract: record.nft_contract,
+            token_id: record.token_id,
+            deposit_address: record.deposit_address,
+            solana_receiver: record.solana_receiver,
+            token_uri: record.token_uri,
+            wormhole_sequence: record.wormhole_sequence,
+            source_chain_id: record.source_chain_id,
+            sealed_at: record.sealed_at,
+            dest_chain: crate::payload::WORMHOLE_CHAIN_ID_SOLANA,
+        }
+    }
 }
 
 /// JSON payload the user passes in nft_transfer_call's `msg` parameter.
@@ -41,10 +110,69 @@ pub struct SealRecord {
 ///     token_id: "123",
 ///     msg: '{"deposit_address":"alice.near","solana_receiver":"<64 hex chars>"}',
 ///   )
+///
+/// For other destinations pass `"dest_chain": <wormhole chain id>` and
+/// `"dest_receiver": "<hex address>"` (20 bytes for EVM chains, 32 otherwise).
+///
+/// Add `"oneshot": true` to fetch metadata and publish in the same call; the NFT is
+/// returned if that fails, or right away while the Wormhole message fee is non-zero.
+/// Attach enough gas for the whole chain (~200 TGas).
 #[derive(Serialize, Deserialize, NearSchema)]
 #[serde(crate = "near_sdk::serde")]
 pub struct SealMsg {
     pub deposit_address: String,
-    /// 64-char hex-encoded 32-byte Solana pubkey
+    /// 64-char hex-encoded 32-byte Solana pubkey (used when `dest_receiver` is absent)
+    #[serde(default)]
     pub solana_receiver: String,
+    /// Destination Wormhole chain ID; defaults to Solana
+    #[serde(default)]
+    pub dest_chain: Option<u16>,
+    /// Hex-encoded receiver on `dest_chain`, validated per chain
+    #[serde(default)]
+    pub dest_receiver: Option<String>,
+    /// Seal in one transaction instead of waiting for complete_seal_initiation
+    #[serde(default)]
+    pub oneshot: bool,
+}
+
+/// Aggregate seal counters returned by `get_stats`.
+#[derive(Serialize, Deserialize, NearSchema)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ContractStats {
+    /// NFTs currently locked (pending or completed); decremented on unlock/refund
+    pub total_sealed: u64,
+    /// Seals whose Wormhole message was published
+    pub total_completed: u64,
+    /// Locked NFTs still waiting for publication
+    pub total_pending: u64,
+}
+
+/// Agreement between `pending_seals` and `seal_records` for one NFT,
+/// returned by `check_seal_consistency`.
+#[derive(Serialize, Deserialize, NearSchema, Debug, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum ConsistencyStatus {
+    /// Neither map knows this NFT
+    NotFound,
+    /// Locked, not yet published, no record (consistent)
+    Pending,
+    /// Completed pending seal and matching record (consistent)
+    Completed,
+    /// Pending seal marked completed but its record is missing
+    CompletedWithoutRecord,
+    /// Record exists but the pending seal is missing, not completed, or disagrees on
+    /// the Wormhole sequence
+    RecordWithoutCompletion,
+}
+
+impl SealMsg {
+    /// Destination chain, defaulting to Solana.
+    pub fn dest_chain(&self) -> u16 {
+        self.dest_chain.unwrap_or(crate::payload::WORMHOLE_CHAIN_ID_SOLANA)
+    }
+
+    /// Receiver hex string for the destination chain.
+    pub fn receiver(&self) -> &str {
+        self.dest_receiver.as_deref().unwrap_or(&self.solana_receiver)
+    }
 }
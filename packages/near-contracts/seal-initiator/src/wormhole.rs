@@ -8,8 +8,10 @@ pub trait WormholeCore {
     /// Publish a message to Wormhole guardians.
     /// `data`: hex-encoded payload bytes
     /// `nonce`: arbitrary u32 nonce
+    /// `consistency_level`: finality guardians wait for before signing
+    /// (see `CONSISTENCY_LEVEL_*` in lib.rs)
     /// Returns: sequence number (u64)
-    fn publish_message(&self, data: String, nonce: u32) -> u64;
+    fn publish_message(&self, data: String, nonce: u32, consistency_level: u8) -> u64;
 
     /// Get the current message fee in yoctoNEAR.
     fn message_fee(&self) -> U128;
@@ -0,0 +1,147 @@
+//! End-to-end seal flow against a local sandbox: test-nft + seal-initiator + mock-wormhole.
+//!
+//! Builds all three contracts with cargo-near and downloads the NEAR sandbox on first run.
+//! Covers the cross-contract gas and callback wiring the unit tests can't reach.
+
+use near_workspaces::types::NearToken;
+use near_workspaces::{Account, Contract};
+use serde_json::{json, Value};
+
+const RECEIVER_HEX: &str = "abababababababababababababababababababababababababababababababab";
+const DEPOSIT_HEX: &str = "cdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcd";
+
+struct Env {
+    alice: Account,
+    nft: Contract,
+    seal: Contract,
+    wormhole: Contract,
+}
+
+async fn setup() -> anyhow::Result<Env> {
+    let worker = near_workspaces::sandbox().await?;
+
+    let wormhole = worker.dev_deploy(&near_workspaces::compile_project("../mock-wormhole").await?).await?;
+    let nft = worker.dev_deploy(&near_workspaces::compile_project("../test-nft").await?).await?;
+    let seal = worker.dev_deploy(&near_workspaces::compile_project("./").await?).await?;
+    let alice = worker.dev_create_account().await?;
+
+    nft.call("new")
+        .args_json(json!({ "owner_id": alice.id() }))
+        .transact()
+        .await?
+        .into_result()?;
+    seal.call("new")
+        .args_json(json!({ "wormhole_account": wormhole.id() }))
+        .transact()
+        .await?
+        .into_result()?;
+    // Sealing is refused until the Wormhole message fee has been read once
+    seal.call("refresh_message_fee").max_gas().transact().await?.into_result()?;
+
+    alice
+        .call(nft.id(), "nft_mint")
+        .args_json(json!({
+            "token_id": "1",
+            "receiver_id": alice.id(),
+            "token_metadata": { "title": "Sealed #1", "reference": "ipfs://meta-1" },
+        }))
+        .deposit(NearToken::from_millinear(100))
+        .transact()
+        .await?
+        .into_result()?;
+
+    Ok(Env { alice, nft, seal, wormhole })
+}
+
+#[tokio::test]
+async fn test_two_step_seal_flow() -> anyhow::Result<()> {
+    let Env { alice, nft, seal, wormhole } = setup().await?;
+
+    // 1. Lock: nft_transfer_call -> nft_on_transfer
+    let msg = json!({ "deposit_address": DEPOSIT_HEX, "solana_receiver": RECEIVER_HEX }).to_string();
+    alice
+        .call(nft.id(), "nft_transfer_call")
+        .args_json(json!({ "receiver_id": seal.id(), "token_id": "1", "msg": msg }))
+        .deposit(NearToken::from_yoctonear(1))
+        .max_gas()
+        .transact()
+        .await?
+        .into_result()?;
+
+    let owner: Value = nft.view("nft_token").args_json(json!({ "token_id": "1" })).await?.json()?;
+    assert_eq!(owner["owner_id"], seal.id().as_str());
+    let pending: bool = seal
+        .view("is_pending")
+        .args_json(json!({ "nft_contract": nft.id(), "token_id": "1" }))
+        .await?
+        .json()?;
+    assert!(pending);
+
+    // 2. Complete: nft_token -> on_nft_metadata_received -> publish_message -> on_wormhole_published
+    let outcome = alice
+        .call(seal.id(), "complete_seal_initiation")
+        .args_json(json!({ "nft_contract": nft.id(), "token_id": "1", "metadata_gas_tgas": null }))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome.failures());
+
+    let record: Value = seal
+        .view("get_seal_record")
+        .args_json(json!({ "nft_contract": nft.id(), "token_id": "1" }))
+        .await?
+        .json()?;
+    assert_eq!(record["token_uri"], "ipfs://meta-1");
+    assert_eq!(record["wormhole_sequence"], 0);
+    assert_eq!(record["source_chain_id"], 15);
+
+    let event = outcome
+        .logs()
+        .into_iter()
+        .find_map(|log| log.strip_prefix("EVENT_JSON:"))
+        .expect("seal_initiated event not emitted");
+    let event: Value = serde_json::from_str(event)?;
+    assert_eq!(event["event"], "seal_initiated");
+    assert_eq!(event["data"][0]["token_uri"], "ipfs://meta-1");
+    assert_eq!(event["data"][0]["solana_receiver"], RECEIVER_HEX);
+
+    let messages: Value = wormhole.view("get_messages").await?.json()?;
+    assert_eq!(messages.as_array().unwrap().len(), 1);
+    assert_eq!(messages[0]["emitter"], seal.id().as_str());
+    assert_eq!(messages[0]["consistency_level"], 1);
+
+    let stats: Value = seal.view("get_stats").await?.json()?;
+    assert_eq!(stats["total_completed"], 1);
+    assert_eq!(stats["total_pending"], 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_oneshot_seal_flow() -> anyhow::Result<()> {
+    let Env { alice, nft, seal, wormhole } = setup().await?;
+
+    let msg = json!({ "deposit_address": DEPOSIT_HEX, "solana_receiver": RECEIVER_HEX, "oneshot": true })
+        .to_string();
+    let outcome = alice
+        .call(nft.id(), "nft_transfer_call")
+        .args_json(json!({ "receiver_id": seal.id(), "token_id": "1", "msg": msg }))
+        .deposit(NearToken::from_yoctonear(1))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_success(), "{:?}", outcome.failures());
+
+    // The mock charges no fee, so the one-shot publish succeeds and the NFT stays locked
+    let owner: Value = nft.view("nft_token").args_json(json!({ "token_id": "1" })).await?.json()?;
+    assert_eq!(owner["owner_id"], seal.id().as_str());
+    let record: Value = seal
+        .view("get_seal_record")
+        .args_json(json!({ "nft_contract": nft.id(), "token_id": "1" }))
+        .await?
+        .json()?;
+    assert_eq!(record["token_uri"], "ipfs://meta-1");
+
+    let messages: Value = wormhole.view("get_messages").await?.json()?;
+    assert_eq!(messages.as_array().unwrap().len(), 1);
+    Ok(())
+}
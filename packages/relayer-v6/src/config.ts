@@ -79,6 +79,8 @@ export function getConfig(): RelayerConfig {
     optimismRpcUrl: process.env.OPTIMISM_RPC_URL || '',
 
     nearRpcUrl: process.env.NEAR_RPC_URL || 'https://rpc.testnet.near.org',
+    // Must match the NEAR SealInitiator's `hash_domain` or field hashes won't match
+    nearHashDomain: process.env.NEAR_HASH_DOMAIN || '',
 
     // Arweave via Irys
     irysPrivateKey: process.env.IRYS_PRIVATE_KEY || '',
@@ -90,6 +92,9 @@ export function getConfig(): RelayerConfig {
     // Royalties on new collections (basis points, 500 = 5%)
     royaltyBasisPoints: parseInt(process.env.ROYALTY_BASIS_POINTS || '500', 10),
 
+    // Must cover IKA signing plus mint retries (~0.4s per slot, 216000 ≈ 1 day)
+    signatureValiditySlots: parseInt(process.env.SIGNATURE_VALIDITY_SLOTS || '216000', 10),
+
     // Core voter weight plugin for SPL Governance NFT voting
     coreVoterProgramId: process.env.CORE_VOTER_PROGRAM_ID || 'E5thJCWofTMbmyhUhCai3hZiruFtYmmscDio6GwFCGaW',
 
@@ -1014,7 +1014,7 @@ export class Relayer {
     const sui = new SuiClient({ url: config.suiRpcUrl });
     const suiKeypair = this.loadSuiKeypair(config.suiKeypairPath);
 
-    this.vaaIngester = new VAAIngester(sui, suiKeypair, this.suiTxQueue);
+    this.vaaIngester = new VAAIngester(sui, suiKeypair, this.suiTxQueue, this.solanaSubmitter);
     await this.vaaIngester.start();
     logger.info("VAA ingester started");
   }
@@ -1273,6 +1273,11 @@ export class Relayer {
       receiver,
       collectionName,
       messageHash,
+      wormholeSequence: BigInt(event.wormhole_sequence),
+      sourceSequence: BigInt(event.source_sequence),
+      sourceBlockHint: toBytes(event.source_block_hint),
+      validUntilSlot: BigInt(event.valid_until_slot),
+      hashAlgo: Number(event.hash_algo),
       daoTreasury: new Uint8Array(32), // Placeholder — overridden by realm-creator before mint
     };
   }
@@ -1446,6 +1451,9 @@ export class Relayer {
     // Token URI as UTF-8 bytes
     const tokenUriBytes = new TextEncoder().encode(tokenUri);
 
+    // Last Solana slot the dWallet signature may be redeemed in
+    const validUntilSlot = await this.solanaSubmitter.validUntilSlot();
+
     const { Transaction } = await import("@mysten/sui/transactions");
     const tx = new Transaction();
 
@@ -1461,6 +1469,7 @@ export class Relayer {
         tx.pure.vector("u8", Array.from(tokenUriBytes)),
         tx.pure.vector("u8", Array.from(depositAddressBytes)),
         tx.pure.vector("u8", Array.from(receiverBytes)),
+        tx.pure.u64(validUntilSlot),
         tx.object("0x6"), // Clock
       ],
     });
@@ -1548,8 +1557,8 @@ export class Relayer {
     }
 
     if (chain === "near") {
-      // NEAR: sha256(account_id) → 32 bytes
-      return new Uint8Array(createHash("sha256").update(nftContract).digest());
+      // NEAR: sha256([domain || 0x00 ||] account_id) → 32 bytes
+      return this.nearFieldHash(nftContract);
     }
 
     if (chain === "aptos") {
@@ -1566,6 +1575,19 @@ export class Relayer {
     throw new Error(`Cannot encode nft_contract for chain: ${sourceChain}`);
   }
 
+  /**
+   * Hash a NEAR account/token ID the way the SealInitiator's payload does,
+   * prefixed with its hash domain (matches `payload::domain_hash`).
+   */
+  private nearFieldHash(value: string): Uint8Array {
+    const domain = getConfig().nearHashDomain;
+    const hash = createHash("sha256");
+    if (domain) {
+      hash.update(domain).update(Buffer.from([0]));
+    }
+    return new Uint8Array(hash.update(value).digest());
+  }
+
   /**
    * Encode token ID to bytes for Sui.
    */
@@ -1612,8 +1634,8 @@ export class Relayer {
     }
 
     if (chain === "near") {
-      // NEAR: sha256(token_id_string)
-      return new Uint8Array(createHash("sha256").update(tokenId).digest());
+      // NEAR: sha256([domain || 0x00 ||] token_id_string)
+      return this.nearFieldHash(tokenId);
     }
 
     throw new Error(`Cannot encode token_id for chain: ${sourceChain}`);
@@ -33,6 +33,9 @@ const SEED_PROVENANCE = Buffer.from('provenance');
 const SEED_COLLECTION = Buffer.from('reborn_collection');
 const SEED_MINT_AUTHORITY = Buffer.from('mint_authority');
 const SEED_MINT_CONFIG = Buffer.from('mint_config');
+const SEED_ASSET_PROVENANCE = Buffer.from('asset_provenance');
+const SEED_RECEIVER_OVERRIDE = Buffer.from('receiver_override');
+const SEED_COLLECTION_REGISTRY = Buffer.from('collection_registry');
 
 // ─── Anchor instruction discriminator ─────────────────────────────────────────
 /**
@@ -60,10 +63,9 @@ const DISCRIMINATOR_INIT_REBORN_COLLECTION = anchorDiscriminator('init_reborn_co
  *   2          source_chain (u16)
  *   4 + N      nft_contract (Vec<u8>)
  *   4 + N      name (String)
+ *   4 + N      symbol (String)
  *   32         collection_asset_address (Pubkey)  ← we want this
- *   8          total_minted (u64)
- *   1          is_initialized (bool)
- *   1          bump (u8)
+ *   ...        total_minted, flags, receiver_allowlist_root, bump (not read)
  */
 function parseCollectionAssetAddress(data: Buffer): PublicKey | null {
   if (data.length < 8) return null;
@@ -83,11 +85,30 @@ function parseCollectionAssetAddress(data: Buffer): PublicKey | null {
   const nameLen = data.readUInt32LE(offset);
   offset += 4 + nameLen;
 
+  // symbol: String (4-byte LE length prefix + UTF-8 bytes)
+  if (offset + 4 > data.length) return null;
+  const symbolLen = data.readUInt32LE(offset);
+  offset += 4 + symbolLen;
+
   // collection_asset_address: Pubkey (32 bytes)
   if (offset + 32 > data.length) return null;
   return new PublicKey(data.subarray(offset, offset + 32));
 }
 
+/**
+ * Parse new_receiver from a ReceiverOverride PDA.
+ *
+ * ReceiverOverride layout:
+ *   8          discriminator
+ *   32         original_receiver (Pubkey)
+ *   32         new_receiver (Pubkey)  ← we want this
+ *   1          bump (u8)
+ */
+function parseOverrideReceiver(data: Buffer): PublicKey | null {
+  if (data.length < 8 + 32 + 32) return null;
+  return new PublicKey(data.subarray(8 + 32, 8 + 64));
+}
+
 // ─── Borsh encoding helpers ───────────────────────────────────────────────────
 
 /**
@@ -107,6 +128,14 @@ function parseCollectionAssetAddress(data: Buffer): PublicKey | null {
  *   u32 + [] – collection_name (String)
  *   u16      – royalty_basis_points
  *   [u8; 32] – dao_treasury (Pubkey)
+ *   u64      – wormhole_sequence  ┐
+ *   u64      – source_sequence    │ signed alongside token_uri/token_id/receiver;
+ *   [u8; 32] – source_block_hint  │ the program rebuilds the message hash from them
+ *   u64      – valid_until_slot   │
+ *   u8       – hash_algo          ┘
+ *   u8       – delegate (Option<Pubkey>, always None)
+ *   u8       – symbol (Option<String>, always None)
+ *   u32      – receiver_proof (Vec<[u8; 32]>, always empty)
  *
  * NOTE: dwallet_pubkey is NOT included — it's loaded from the MintConfig PDA on-chain.
  */
@@ -120,10 +149,16 @@ function encodeMintRebornArgs(
   collectionName: string,
   royaltyBasisPoints: number,
   daoTreasury: Uint8Array,
+  wormholeSequence: bigint,
+  sourceSequence: bigint,
+  sourceBlockHint: Uint8Array,
+  validUntilSlot: bigint,
+  hashAlgo: number,
 ): Buffer {
   if (signature.length !== 64) throw new Error('signature must be 64 bytes');
   if (sigHash.length !== 32) throw new Error('sigHash must be 32 bytes');
   if (daoTreasury.length !== 32) throw new Error('daoTreasury must be 32 bytes');
+  if (sourceBlockHint.length !== 32) throw new Error('sourceBlockHint must be 32 bytes');
 
   const tokenUriBytes = Buffer.from(tokenUri, 'utf-8');
   const collectionNameBytes = Buffer.from(collectionName, 'utf-8');
@@ -138,7 +173,15 @@ function encodeMintRebornArgs(
     4 + tokenUriBytes.length +     // String
     4 + collectionNameBytes.length + // String
     2 +   // royalty_basis_points u16
-    32;   // dao_treasury Pubkey
+    32 +  // dao_treasury Pubkey
+    8 +   // wormhole_sequence u64
+    8 +   // source_sequence u64
+    32 +  // source_block_hint [u8; 32]
+    8 +   // valid_until_slot u64
+    1 +   // hash_algo u8
+    1 +   // delegate Option<Pubkey> (None)
+    1 +   // symbol Option<String> (None)
+    4;    // receiver_proof Vec<[u8; 32]> (empty)
 
   const buf = Buffer.alloc(totalLen);
   let offset = 0;
@@ -193,7 +236,22 @@ function encodeMintRebornArgs(
 
   // 10. dao_treasury: Pubkey (32 bytes, raw)
   buf.set(daoTreasury, offset);
-  // offset += 32; // not needed — last field
+  offset += 32;
+
+  // 11-15. Signed message fields
+  buf.writeBigUInt64LE(wormholeSequence, offset);
+  offset += 8;
+  buf.writeBigUInt64LE(sourceSequence, offset);
+  offset += 8;
+  buf.set(sourceBlockHint, offset);
+  offset += 32;
+  buf.writeBigUInt64LE(validUntilSlot, offset);
+  offset += 8;
+  buf.writeUInt8(hashAlgo, offset);
+  offset += 1;
+
+  // 16-18. delegate = None, symbol = None, receiver_proof = [] (Buffer.alloc zero-fills)
+  // offset += 1 + 1 + 4; // not needed — last fields
 
   return buf;
 }
@@ -238,6 +296,15 @@ export class SolanaSubmitter {
     this.programId = new PublicKey(config.solanaProgramId);
   }
 
+  /**
+   * Last Solana slot a signature requested now should stay redeemable in:
+   * the current slot plus `signatureValiditySlots`. Signed as `valid_until_slot`.
+   */
+  async validUntilSlot(): Promise<bigint> {
+    const slot = await this.connection.getSlot('confirmed');
+    return BigInt(slot) + BigInt(getConfig().signatureValiditySlots);
+  }
+
   /** Verify Solana RPC is reachable. */
   async checkConnection(): Promise<boolean> {
     try {
@@ -386,6 +453,11 @@ export class SolanaSubmitter {
       collectionName,
       receiver,
       messageHash,
+      wormholeSequence,
+      sourceSequence,
+      sourceBlockHint,
+      validUntilSlot,
+      hashAlgo,
     } = seal;
 
     // ── 1. Derive PDAs ────────────────────────────────────────────────────────
@@ -422,9 +494,43 @@ export class SolanaSubmitter {
       this.programId,
     );
 
+    // Reverse index asset → provenance, created by mint_reborn
+    const [assetLinkPda] = PublicKey.findProgramAddressSync(
+      [SEED_ASSET_PROVENANCE, assetKeypair.publicKey.toBuffer()],
+      this.programId,
+    );
+
+    // Receiver override PDA — always passed, whether or not an override exists
+    const [receiverOverridePda] = PublicKey.findProgramAddressSync(
+      [SEED_RECEIVER_OVERRIDE, sourceChainBuf, Buffer.from(nftContract), Buffer.from(tokenId)],
+      this.programId,
+    );
+
+    const [registryPda] = PublicKey.findProgramAddressSync(
+      [SEED_COLLECTION_REGISTRY],
+      this.programId,
+    );
+
     // ── 2. Receiver public key ────────────────────────────────────────────────
     const receiverPubkey = new PublicKey(receiver);
 
+    // An admin-set override redirects the mint; the program then requires the
+    // redirected owner as `override_receiver`
+    let overrideReceiver: PublicKey | null = null;
+    const overrideInfo = await this.connection.getAccountInfo(receiverOverridePda);
+    if (overrideInfo && overrideInfo.owner.equals(this.programId)) {
+      overrideReceiver = parseOverrideReceiver(overrideInfo.data);
+      if (!overrideReceiver) throw new Error('Malformed ReceiverOverride account');
+      logger.info(
+        { overrideReceiver: overrideReceiver.toBase58() },
+        'Receiver override found — minting to redirected owner',
+      );
+    }
+
+    // The registry is only written when MintConfig.maintain_registry is set, and is
+    // then required on first mints. Pass it whenever it exists.
+    const registryExists = (await this.connection.getAccountInfo(registryPda)) !== null;
+
     // ── 3. Determine collection_asset account ────────────────────────────────
     // The collection PDA (our program's metadata) is different from the Metaplex
     // Core collection_asset. On the first mint for a source collection, we generate
@@ -473,7 +579,7 @@ export class SolanaSubmitter {
     // instructions sysvar to verify the dWallet signature.
     const ed25519Ix = Ed25519Program.createInstructionWithPublicKey({
       publicKey: dwalletPubkey,   // 32-byte Ed25519 public key
-      message: messageHash,        // 32-byte SHA256 message hash
+      message: messageHash,        // 32-byte signed message hash
       signature: signature,        // 64-byte Ed25519 signature
     });
 
@@ -490,9 +596,15 @@ export class SolanaSubmitter {
       collectionName,
       royaltyBasisPoints,
       seal.daoTreasury,
+      wormholeSequence,
+      sourceSequence,
+      sourceBlockHint,
+      validUntilSlot,
+      hashAlgo,
     );
 
     // ── 6. Build mint_reborn instruction ─────────────────────────────────────
+    // Anchor reads the program ID in an optional account's slot as None
     const isFirstMint = collectionAssetKeypair !== null;
     const mintRebornIx = {
       programId: this.programId,
@@ -505,10 +617,14 @@ export class SolanaSubmitter {
         { pubkey: mintAuthorityPda,         isSigner: false, isWritable: false }, // mint_authority (PDA signer for CPIs)
         { pubkey: collectionAssetPubkey,    isSigner: isFirstMint, isWritable: true }, // collection_asset (Metaplex Core collection)
         { pubkey: assetKeypair.publicKey,   isSigner: true,  isWritable: true  }, // asset
+        { pubkey: assetLinkPda,             isSigner: false, isWritable: true  }, // asset_link (asset → provenance)
         { pubkey: MPL_CORE_PROGRAM_ID,      isSigner: false, isWritable: false }, // mpl_core_program
         { pubkey: SystemProgram.programId,  isSigner: false, isWritable: false }, // system_program
         { pubkey: SYSVAR_INSTRUCTIONS_PUBKEY, isSigner: false, isWritable: false }, // instructions_sysvar
         { pubkey: mintConfigPda,            isSigner: false, isWritable: false }, // config (MintConfig PDA)
+        { pubkey: receiverOverridePda,      isSigner: false, isWritable: false }, // receiver_override
+        { pubkey: overrideReceiver ?? this.programId, isSigner: false, isWritable: false }, // override_receiver (optional)
+        { pubkey: registryExists ? registryPda : this.programId, isSigner: false, isWritable: registryExists }, // registry (optional)
       ],
       data: ixData,
     };
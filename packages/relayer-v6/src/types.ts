@@ -40,6 +40,16 @@ export interface SealSignedEvent {
   vaa_hash: number[] | string;
   /** Unix timestamp of the seal event */
   timestamp: number | string;
+  /** Wormhole sequence covered by the signature (u64, 0 for centralized seals) */
+  wormhole_sequence: number | string;
+  /** Source-chain sequence covered by the signature (u64) */
+  source_sequence: number | string;
+  /** 32-byte source block hint covered by the signature (vector<u8>, zeros if unknown) */
+  source_block_hint: number[] | string;
+  /** Last Solana slot the signature can be redeemed in (u64) */
+  valid_until_slot: number | string;
+  /** Hash algorithm of message_hash (0 = sha256, 1 = keccak256) */
+  hash_algo: number;
 }
 
 /**
@@ -84,8 +94,14 @@ export interface ProcessedSeal {
   receiver: Uint8Array;
   /** Reborn collection name derived on-chain */
   collectionName: string;
-  /** 32-byte SHA256 message hash */
+  /** 32-byte signed message hash (sha256 or keccak256, see hashAlgo) */
   messageHash: Uint8Array;
+  /** Signed fields mint_reborn needs to rebuild messageHash */
+  wormholeSequence: bigint;
+  sourceSequence: bigint;
+  sourceBlockHint: Uint8Array;
+  validUntilSlot: bigint;
+  hashAlgo: number;
   /** 32-byte Solana public key of the DAO treasury (royalty recipient) */
   daoTreasury: Uint8Array;
 }
@@ -333,6 +349,8 @@ export interface RelayerConfig {
   optimismRpcUrl: string;
   aptosRpcUrl: string;
   nearRpcUrl: string;
+  /** Hash domain the NEAR SealInitiator was initialized with ('' = none) */
+  nearHashDomain: string;
 
   // Arweave uploads via Irys
   irysPrivateKey: string;
@@ -347,6 +365,9 @@ export interface RelayerConfig {
   // Royalties
   royaltyBasisPoints: number;
 
+  // Solana slots a seal signature stays redeemable for (signed as valid_until_slot)
+  signatureValiditySlots: number;
+
   // Core voter weight plugin program ID (for SPL Governance NFT voting)
   coreVoterProgramId: string;
 
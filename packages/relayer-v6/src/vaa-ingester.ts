@@ -22,6 +22,7 @@ import { getConfig } from './config.js';
 import { logger } from './logger.js';
 import { getAllVaaSequences, saveVaaSequence } from './db.js';
 import type { SuiTxQueue } from './sui-tx-queue.js';
+import type { SolanaSubmitter } from './solana-submitter.js';
 import type {
   SourceChainEmitter,
   WormholescanVAAEntry,
@@ -32,6 +33,7 @@ export class VAAIngester {
   private readonly sui: SuiClient;
   private readonly keypair: Ed25519Keypair;
   private readonly txQueue: SuiTxQueue;
+  private readonly solana: SolanaSubmitter;
   private readonly emitters: SourceChainEmitter[];
   private timer: ReturnType<typeof setInterval> | null = null;
   private _isRunning = false;
@@ -42,10 +44,11 @@ export class VAAIngester {
   /** Set of VAA IDs currently being processed (prevents duplicate submissions) */
   private readonly inflight = new Set<string>();
 
-  constructor(sui: SuiClient, keypair: Ed25519Keypair, txQueue: SuiTxQueue) {
+  constructor(sui: SuiClient, keypair: Ed25519Keypair, txQueue: SuiTxQueue, solana: SolanaSubmitter) {
     this.sui = sui;
     this.keypair = keypair;
     this.txQueue = txQueue;
+    this.solana = solana;
     const config = getConfig();
     this.emitters = config.sourceChainEmitters;
     this.lastSequences = getAllVaaSequences();
@@ -332,6 +335,9 @@ export class VAAIngester {
   ): Promise<string> {
     const config = getConfig();
 
+    // Last Solana slot the dWallet signature may be redeemed in
+    const validUntilSlot = await this.solana.validUntilSlot();
+
     const tx = new Transaction();
     tx.moveCall({
       target: `${config.suiPackageId}::orchestrator::process_vaa`,
@@ -342,6 +348,7 @@ export class VAAIngester {
         tx.object(config.suiRegistryObjectId),
         tx.pure.vector('u8', Array.from(vaaBytes)),
         tx.pure.id(dwalletId),
+        tx.pure.u64(validUntilSlot),
         tx.object('0x6'), // Clock
       ],
     });
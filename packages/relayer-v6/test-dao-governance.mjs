@@ -99,6 +99,18 @@ function anchorDisc(name) {
   return createHash('sha256').update('global:' + name).digest().slice(0, 8);
 }
 
+// VoterWeightAction variant indices (ika-core-voter / SPL Governance)
+const VoterWeightAction = { CastVote: 0, CommentProposal: 1, CreateGovernance: 2, CreateProposal: 3, SignOffProposal: 4 };
+
+// update_voter_weight_record(action, target: Option<Pubkey>)
+function updateVoterWeightData(action, target = null) {
+  return Buffer.concat([
+    anchorDisc('update_voter_weight_record'),
+    Buffer.from([action]),
+    target ? Buffer.concat([Buffer.from([1]), target.toBuffer()]) : Buffer.from([0]),
+  ]);
+}
+
 function sleep(ms) {
   return new Promise(r => setTimeout(r, ms));
 }
@@ -449,11 +461,12 @@ async function main() {
     programId: CORE_VOTER_PROGRAM_ID,
     keys: [
       { pubkey: registrar, isSigner: false, isWritable: false },
+      { pubkey: realmAddress, isSigner: false, isWritable: false },
       { pubkey: proposerVwr, isSigner: false, isWritable: true },
       { pubkey: proposerPubkey, isSigner: true, isWritable: false },
       { pubkey: nftAssets[0], isSigner: false, isWritable: false },
     ],
-    data: anchorDisc('update_voter_weight_record'),
+    data: updateVoterWeightData(VoterWeightAction.CreateProposal),
   }));
 
   // Create the proposal
@@ -521,11 +534,12 @@ async function main() {
       programId: CORE_VOTER_PROGRAM_ID,
       keys: [
         { pubkey: registrar, isSigner: false, isWritable: false },
+        { pubkey: realmAddress, isSigner: false, isWritable: false },
         { pubkey: voterVwr, isSigner: false, isWritable: true },
         { pubkey: voterPubkey, isSigner: true, isWritable: false },
         { pubkey: nftAsset, isSigner: false, isWritable: false },
       ],
-      data: anchorDisc('update_voter_weight_record'),
+      data: updateVoterWeightData(VoterWeightAction.CastVote, proposalAddress),
     }));
 
     // Cast vote
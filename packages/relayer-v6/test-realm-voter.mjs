@@ -74,6 +74,18 @@ function anchorDisc(name) {
   return createHash('sha256').update('global:' + name).digest().slice(0, 8);
 }
 
+// VoterWeightAction variant indices (ika-core-voter / SPL Governance)
+const VoterWeightAction = { CastVote: 0, CommentProposal: 1, CreateGovernance: 2, CreateProposal: 3, SignOffProposal: 4 };
+
+// update_voter_weight_record(action, target: Option<Pubkey>)
+function updateVoterWeightData(action, target = null) {
+  return Buffer.concat([
+    anchorDisc('update_voter_weight_record'),
+    Buffer.from([action]),
+    target ? Buffer.concat([Buffer.from([1]), target.toBuffer()]) : Buffer.from([0]),
+  ]);
+}
+
 // ─── Main Test ───────────────────────────────────────────────────────────────
 
 async function main() {
@@ -343,12 +355,13 @@ async function main() {
     programId: CORE_VOTER_PROGRAM_ID,
     keys: [
       { pubkey: registrar, isSigner: false, isWritable: false },
+      { pubkey: realmAddress, isSigner: false, isWritable: false },
       { pubkey: vwrPda, isSigner: false, isWritable: true },
       { pubkey: payer, isSigner: true, isWritable: false },
       // remaining_accounts: the NFT asset
       { pubkey: assetPubkey, isSigner: false, isWritable: false },
     ],
-    data: anchorDisc('update_voter_weight_record'),
+    data: updateVoterWeightData(VoterWeightAction.CreateProposal),
   }));
 
   const tx7Sig = await sendAndConfirmTransaction(conn, tx7, [relayerKeypair]);
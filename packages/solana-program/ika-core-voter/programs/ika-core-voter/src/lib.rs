@@ -12,9 +12,36 @@ const MPL_CORE_PROGRAM_ID: Pubkey = Pubkey::new_from_array([
     0xc9, 0x7e, 0xbe, 0x2d, 0x23, 0x5b, 0xa7, 0x48,
 ]);
 
+/// Canonical SPL Governance v3 program ID.
+/// GovER5Lthms3bLBqWub97yVrMmEogzX7xNjdXpPPCVZw
+const SPL_GOVERNANCE_PROGRAM_ID: Pubkey = Pubkey::new_from_array([
+    0xea, 0xe4, 0x35, 0xbd, 0xee, 0x75, 0xb7, 0x34,
+    0xcd, 0x59, 0x3e, 0xcf, 0x9a, 0x30, 0x4b, 0x80,
+    0x24, 0xba, 0x28, 0x98, 0x67, 0xb7, 0x69, 0xb1,
+    0xf9, 0x3c, 0xa7, 0xbb, 0xb8, 0x8e, 0x46, 0xfe,
+]);
+
+/// Governance programs a registrar may be created for. Extend this list (and redeploy)
+/// to support additional audited deployments of SPL Governance.
+const TRUSTED_GOVERNANCE_PROGRAMS: &[Pubkey] = &[SPL_GOVERNANCE_PROGRAM_ID];
+
 /// Maximum number of collections a single registrar can track.
 const MAX_COLLECTIONS: usize = 10;
 
+/// Maximum number of whitelisted `UpdateAuthority::Address` authorities per registrar.
+const MAX_ADDRESS_AUTHORITIES: usize = 5;
+
+/// Maximum number of proposal snapshots a registrar keeps. Registering more is refused
+/// until the realm authority removes a finished proposal's snapshot.
+const MAX_PROPOSAL_SNAPSHOTS: usize = 8;
+
+/// Maximum number of assets counted by one weight update, keeping a worst-case update
+/// (soulbound plugin walk on every asset) well inside the compute budget.
+const MAX_ASSETS_PER_UPDATE: usize = 32;
+
+/// Maximum `weight_decimals`; beyond this a single whole vote leaves little u64 headroom.
+const MAX_WEIGHT_DECIMALS: u8 = 9;
+
 // ─── Accounts ────────────────────────────────────────────────────────────────
 
 /// Per-realm registrar that tracks which Metaplex Core collections are eligible to vote.
@@ -28,6 +55,43 @@ pub struct Registrar {
     pub governing_token_mint: Pubkey,
     /// Configured collections (collection address → vote weight).
     pub collections: Vec<CollectionConfig>,
+    /// Optional cap on a single voter's weight (anti-whale). `None` = unbounded.
+    pub max_voter_weight_per_owner: Option<u64>,
+    /// If set, assets only count once locked (via `lock_asset`) for at least this many slots.
+    pub min_hold_slots: Option<u64>,
+    /// Whitelisted `UpdateAuthority::Address` keys (e.g. a DAO key acting as a logical
+    /// collection) and the weight each of their assets carries.
+    pub address_authorities: Vec<AddressAuthorityConfig>,
+    /// How many slots past its update a voter weight stays valid, so repeated votes can
+    /// reuse it instead of recomputing. `None` = valid only in the update slot.
+    pub weight_validity_slots: Option<u64>,
+    /// Snapshot slots pinned per proposal for `snapshot_voter_weight`, oldest first.
+    pub proposal_snapshots: Vec<ProposalSnapshot>,
+    /// Every weight in this registrar (collection and address-authority weights, voter
+    /// weights, the max voter weight and the per-voter cap) is fixed-point with this many
+    /// decimals: with 2, a weight of 50 is 0.5 votes. Sums stay exact integer sums of the
+    /// stored values. SPL Governance only compares raw u64s, so vote thresholds are
+    /// unaffected, but absolute realm settings such as `min_community_weight_to_create_proposal`
+    /// must be given in the same fixed-point units.
+    pub weight_decimals: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ProposalSnapshot {
+    /// SPL Governance proposal address.
+    pub proposal: Pubkey,
+    /// Slot at which voter weight is measured for this proposal.
+    pub snapshot_slot: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct AddressAuthorityConfig {
+    /// Update authority address of the eligible assets.
+    pub authority: Pubkey,
+    /// Vote weight per NFT with this update authority.
+    pub weight: u64,
+    /// Number of NFTs with this update authority; feeds the realm's max voter weight.
+    pub known_supply: u64,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
@@ -36,9 +100,86 @@ pub struct CollectionConfig {
     pub collection: Pubkey,
     /// Vote weight per NFT from this collection.
     pub weight: u64,
+    /// Number of NFTs in the collection; feeds the realm's max voter weight.
+    pub known_supply: u64,
+    /// Weight multiplier (basis points, 10_000 = 1x) for soulbound assets, i.e. assets
+    /// with a frozen PermanentFreezeDelegate plugin. 0 disables the multiplier.
+    pub soulbound_multiplier_bps: u16,
+}
+
+/// Update authority of a verified Core asset.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AssetAuthority {
+    /// `UpdateAuthority::Collection(collection)`
+    Collection(Pubkey),
+    /// `UpdateAuthority::Address(address)`
+    Address(Pubkey),
+}
+
+impl AssetAuthority {
+    /// The collection or address the asset's weight is registered under.
+    pub fn key(&self) -> Pubkey {
+        match self {
+            AssetAuthority::Collection(key) | AssetAuthority::Address(key) => *key,
+        }
+    }
 }
 
 impl Registrar {
+    /// Per-NFT weight for an asset with `authority`, if it is registered.
+    pub fn weight_for(&self, authority: &AssetAuthority) -> Option<u64> {
+        match authority {
+            AssetAuthority::Collection(collection) => {
+                self.collections.iter().find(|c| c.collection == *collection).map(|c| c.weight)
+            }
+            AssetAuthority::Address(address) => {
+                self.address_authorities.iter().find(|a| a.authority == *address).map(|a| a.weight)
+            }
+        }
+    }
+
+    /// Soulbound multiplier for an asset's collection, if one is configured.
+    pub fn soulbound_multiplier_bps(&self, authority: &AssetAuthority) -> Option<u16> {
+        match authority {
+            AssetAuthority::Collection(collection) => self
+                .collections
+                .iter()
+                .find(|c| c.collection == *collection)
+                .map(|c| c.soulbound_multiplier_bps)
+                .filter(|bps| *bps != 0),
+            AssetAuthority::Address(_) => None,
+        }
+    }
+
+    /// Snapshot slot pinned for `proposal`, if any.
+    pub fn snapshot_slot_for(&self, proposal: &Pubkey) -> Option<u64> {
+        self.proposal_snapshots.iter().find(|p| p.proposal == *proposal).map(|p| p.snapshot_slot)
+    }
+
+    /// Space: discriminator(8) + 3 pubkeys(96) + vec prefix(4) + MAX_COLLECTIONS * (32+8+8+2)
+    ///        + option<u64>(9) + option<u64>(9) + vec prefix(4) + MAX_ADDRESS_AUTHORITIES * (32+8+8)
+    ///        + option<u64>(9) + vec prefix(4) + MAX_PROPOSAL_SNAPSHOTS * (32+8) + u8(1)
+    pub const SPACE: usize = 8 + 96 + 4 + MAX_COLLECTIONS * 50 + 9 + 9 + 4 + MAX_ADDRESS_AUTHORITIES * 48 + 9
+        + 4 + MAX_PROPOSAL_SNAPSHOTS * 40 + 1;
+}
+
+/// Registrar layout before the per-voter cap and later settings were added: just the
+/// realm linkage and `(collection, weight)` pairs. Only read by `upgrade_registrar`.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct LegacyRegistrar {
+    pub governance_program_id: Pubkey,
+    pub realm: Pubkey,
+    pub governing_token_mint: Pubkey,
+    pub collections: Vec<LegacyCollectionConfig>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct LegacyCollectionConfig {
+    pub collection: Pubkey,
+    pub weight: u64,
+}
+
+impl LegacyRegistrar {
     /// Space: discriminator(8) + 3 pubkeys(96) + vec prefix(4) + MAX_COLLECTIONS * (32+8)
     pub const SPACE: usize = 8 + 96 + 4 + MAX_COLLECTIONS * 40;
 }
@@ -73,6 +214,29 @@ impl VoterWeightRecord {
     pub const SPACE: usize = 8 + 96 + 8 + 9 + 2 + 33 + 8;
 }
 
+/// Opt-in hold record for `min_hold_slots`. Seeds: ["asset-lock", registrar, asset, owner].
+///
+/// This does not take custody of the asset; it only records since when `owner` has
+/// held it. A new owner must create their own lock and wait out `min_hold_slots` again.
+///
+/// The lock stops counting once the asset's `seq` moves on from `asset_seq`. Core only
+/// tracks `seq` on some assets, so an asset sent away and back can keep an old lock
+/// alive; while it is away, anyone can close the lock with `close_stale_asset_lock`.
+#[account]
+pub struct AssetLock {
+    pub registrar: Pubkey,
+    pub asset: Pubkey,
+    pub owner: Pubkey,
+    pub locked_at_slot: u64,
+    /// The asset's AssetV1 `seq` when the lock was created.
+    pub asset_seq: Option<u64>,
+}
+
+impl AssetLock {
+    /// Space: discriminator(8) + 3 pubkeys(96) + u64(8) + option<u64>(9)
+    pub const SPACE: usize = 8 + 96 + 8 + 9;
+}
+
 /// Max voter weight record for the realm.
 #[account]
 pub struct MaxVoterWeightRecord {
@@ -88,6 +252,50 @@ impl MaxVoterWeightRecord {
     pub const SPACE: usize = 8 + 64 + 8 + 9 + 8;
 }
 
+// ─── Events ──────────────────────────────────────────────────────────────────
+
+/// Why an asset passed to `update_voter_weight_record` added no weight.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SkipReason {
+    /// Account is not owned by the Metaplex Core program.
+    NotCoreAsset,
+    /// Account data is not an AssetV1.
+    NotAssetV1,
+    /// Asset is not owned by the voter.
+    WrongOwner,
+    /// Collection / update authority is not registered in the registrar.
+    UnregisteredCollection,
+    /// Asset account was already passed earlier in the same call.
+    Duplicate,
+    /// `min_hold_slots` is set (or a snapshot is taken) and the asset's hold record is
+    /// missing, too recent, or newer than the snapshot slot.
+    HoldNotMature,
+}
+
+#[event]
+pub struct AssetSkipped {
+    pub asset: Pubkey,
+    pub reason: SkipReason,
+}
+
+/// Weight one registered collection (or address authority) contributed to a voter.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct CollectionWeight {
+    pub collection: Pubkey,
+    pub weight: u64,
+}
+
+/// Emitted at the end of every voter weight update. `per_collection` has one entry per
+/// registered collection/address authority that contributed, so it holds at most
+/// `MAX_COLLECTIONS + MAX_ADDRESS_AUTHORITIES` entries. Entries are uncapped; `total` is
+/// the recorded weight, i.e. after `max_voter_weight_per_owner`.
+#[event]
+pub struct VoterWeightBreakdown {
+    pub owner: Pubkey,
+    pub per_collection: Vec<CollectionWeight>,
+    pub total: u64,
+}
+
 // ─── Instructions ────────────────────────────────────────────────────────────
 
 #[program]
@@ -101,28 +309,256 @@ pub mod ika_core_voter {
         registrar.realm = ctx.accounts.realm.key();
         registrar.governing_token_mint = ctx.accounts.governing_token_mint.key();
         registrar.collections = Vec::new();
+        registrar.max_voter_weight_per_owner = None;
+        registrar.min_hold_slots = None;
+        registrar.address_authorities = Vec::new();
+        registrar.weight_validity_slots = None;
+        registrar.proposal_snapshots = Vec::new();
+        registrar.weight_decimals = 0;
         Ok(())
     }
 
-    /// Add or update a Metaplex Core collection in the registrar.
-    pub fn configure_collection(
+    /// Create a registrar for the realm's new community mint, copying every collection,
+    /// address authority and setting from the current one. Proposal snapshots are not
+    /// copied (they belong to the old mint's proposals). The old registrar is left as is.
+    /// Only the realm authority can call this.
+    ///
+    /// Voter weight records and hold records are keyed by mint/registrar, so after a
+    /// migration each voter:
+    ///   1. calls `create_voter_weight_record` with the new mint,
+    ///   2. re-creates any `lock_asset` hold records against the new registrar (the hold
+    ///      clock restarts), then `update_voter_weight_record` as usual,
+    ///   3. optionally closes the old record with `close_voter_weight_record` and old
+    ///      hold records with `unlock_asset` to reclaim rent.
+    /// The realm authority also calls `create_max_voter_weight_record` for the new mint.
+    pub fn migrate_registrar_mint(ctx: Context<MigrateRegistrarMint>) -> Result<()> {
+        let new_mint = ctx.accounts.new_governing_token_mint.key();
+        require_keys_neq!(
+            new_mint,
+            ctx.accounts.registrar.governing_token_mint,
+            CoreVoterError::GoverningMintUnchanged
+        );
+        ctx.accounts.new_registrar.set_inner(migrated_registrar(&ctx.accounts.registrar, new_mint));
+        Ok(())
+    }
+
+    /// Rewrite a registrar created with the original layout into the current one, growing
+    /// the account in place (the payer covers the extra rent). Collections keep their
+    /// weights with `known_supply = 0` and no soulbound multiplier; every newer setting
+    /// starts unset. Only the realm authority can call this.
+    pub fn upgrade_registrar(ctx: Context<UpgradeRegistrar>) -> Result<()> {
+        let info = ctx.accounts.registrar.to_account_info();
+        require!(info.owner == &crate::ID, CoreVoterError::RegistrarNotLegacy);
+        require!(info.data_len() == LegacyRegistrar::SPACE, CoreVoterError::RegistrarNotLegacy);
+        let legacy = {
+            let data = info.try_borrow_data()?;
+            require!(data[..8] == Registrar::DISCRIMINATOR, CoreVoterError::RegistrarNotLegacy);
+            LegacyRegistrar::deserialize(&mut &data[8..])?
+        };
+        require!(
+            realm_matches_legacy(&legacy, ctx.accounts.realm.key, ctx.accounts.realm.owner),
+            CoreVoterError::InvalidRealmOwner
+        );
+
+        let shortfall = Rent::get()?.minimum_balance(Registrar::SPACE).saturating_sub(info.lamports());
+        if shortfall > 0 {
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: ctx.accounts.payer.to_account_info(),
+                        to: info.clone(),
+                    },
+                ),
+                shortfall,
+            )?;
+        }
+        info.realloc(Registrar::SPACE, true)?;
+
+        let registrar = upgraded_registrar(legacy);
+        let mut data = info.try_borrow_mut_data()?;
+        registrar.try_serialize(&mut &mut data[..])?;
+        Ok(())
+    }
+
+    /// Set or clear the per-voter weight cap. Only the realm authority can call this.
+    pub fn set_max_voter_weight_per_owner(
+        ctx: Context<ConfigureRegistrar>,
+        max_voter_weight_per_owner: Option<u64>,
+    ) -> Result<()> {
+        ctx.accounts.registrar.max_voter_weight_per_owner = max_voter_weight_per_owner;
+        Ok(())
+    }
+
+    /// Set or clear the minimum hold duration. Only the realm authority can call this.
+    /// While set, `update_voter_weight_record` expects (asset, asset_lock) pairs.
+    pub fn set_min_hold_slots(
+        ctx: Context<ConfigureRegistrar>,
+        min_hold_slots: Option<u64>,
+    ) -> Result<()> {
+        ctx.accounts.registrar.min_hold_slots = min_hold_slots;
+        Ok(())
+    }
+
+    /// Set or clear how long an updated voter weight stays valid. Longer windows save
+    /// recomputation but let weight lag behind transfers. Only the realm authority can call this.
+    pub fn set_weight_validity_slots(
+        ctx: Context<ConfigureRegistrar>,
+        weight_validity_slots: Option<u64>,
+    ) -> Result<()> {
+        ctx.accounts.registrar.weight_validity_slots = weight_validity_slots;
+        Ok(())
+    }
+
+    /// Set the fixed-point precision of all weights. Only allowed while no collections or
+    /// address authorities are configured, since it changes what existing weights mean.
+    /// Only the realm authority can call this.
+    pub fn set_weight_decimals(ctx: Context<ConfigureRegistrar>, weight_decimals: u8) -> Result<()> {
+        let registrar = &mut ctx.accounts.registrar;
+        check_weight_decimals(registrar, weight_decimals)?;
+        registrar.weight_decimals = weight_decimals;
+        Ok(())
+    }
+
+    /// Pin the slot at which voter weight is measured for `proposal` (normally its creation
+    /// slot). A pinned slot can't be changed. Only the realm authority can call this.
+    pub fn register_proposal_snapshot(
+        ctx: Context<ConfigureRegistrar>,
+        proposal: Pubkey,
+        snapshot_slot: u64,
+    ) -> Result<()> {
+        record_proposal_snapshot(
+            &mut ctx.accounts.registrar.proposal_snapshots,
+            ProposalSnapshot { proposal, snapshot_slot },
+            Clock::get()?.slot,
+        )
+    }
+
+    /// Drop the snapshot pinned for `proposal` to free a slot. Call it only once voting on
+    /// the proposal has ended: without a snapshot, live weight can vote on it again.
+    /// Only the realm authority can call this.
+    pub fn remove_proposal_snapshot(ctx: Context<ConfigureRegistrar>, proposal: Pubkey) -> Result<()> {
+        let snapshots = &mut ctx.accounts.registrar.proposal_snapshots;
+        let index = snapshots
+            .iter()
+            .position(|p| p.proposal == proposal)
+            .ok_or(CoreVoterError::ProposalSnapshotNotFound)?;
+        snapshots.remove(index);
+        Ok(())
+    }
+
+    /// Whitelist (or update) an `UpdateAuthority::Address` key so its assets count with
+    /// `weight`, and recompute the realm's max voter weight. A weight of 0 removes it.
+    /// Only the realm authority can call this.
+    pub fn configure_address_authority(
         ctx: Context<ConfigureCollection>,
-        collection: Pubkey,
+        authority: Pubkey,
         weight: u64,
+        known_supply: u64,
     ) -> Result<()> {
         let registrar = &mut ctx.accounts.registrar;
 
-        // Update existing or add new
-        if let Some(existing) = registrar.collections.iter_mut().find(|c| c.collection == collection) {
+        if weight == 0 {
+            registrar.address_authorities.retain(|a| a.authority != authority);
+        } else if let Some(existing) = registrar.address_authorities.iter_mut().find(|a| a.authority == authority) {
             existing.weight = weight;
+            existing.known_supply = known_supply;
         } else {
             require!(
-                registrar.collections.len() < MAX_COLLECTIONS,
-                CoreVoterError::MaxCollectionsReached
+                registrar.address_authorities.len() < MAX_ADDRESS_AUTHORITIES,
+                CoreVoterError::MaxAddressAuthoritiesReached
             );
-            registrar.collections.push(CollectionConfig { collection, weight });
+            registrar.address_authorities.push(AddressAuthorityConfig { authority, weight, known_supply });
         }
 
+        ctx.accounts.max_voter_weight_record.max_voter_weight =
+            max_voter_weight(&registrar.collections, &registrar.address_authorities)?;
+        Ok(())
+    }
+
+    /// Start the hold clock for an asset the voter currently owns.
+    pub fn lock_asset(ctx: Context<LockAsset>) -> Result<()> {
+        let owner = ctx.accounts.owner.key();
+        let authority = verify_core_nft_ownership(&ctx.accounts.asset, &owner)?;
+        require!(authority.is_ok(), CoreVoterError::AssetNotOwned);
+
+        let asset_seq = parse_asset_v1_header(&ctx.accounts.asset.try_borrow_data()?)
+            .ok_or(CoreVoterError::InvalidAssetData)?
+            .seq;
+        let lock = &mut ctx.accounts.asset_lock;
+        lock.registrar = ctx.accounts.registrar.key();
+        lock.asset = ctx.accounts.asset.key();
+        lock.owner = owner;
+        lock.locked_at_slot = Clock::get()?.slot;
+        lock.asset_seq = asset_seq;
+        Ok(())
+    }
+
+    /// Close a hold record and reclaim its rent.
+    pub fn unlock_asset(_ctx: Context<UnlockAsset>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Close a hold record whose owner no longer holds the asset (or whose asset has
+    /// changed since it was locked), refunding the rent to the lock's owner. Anyone can
+    /// call this, so a transferred asset can't keep its old hold clock by coming back.
+    pub fn close_stale_asset_lock(ctx: Context<CloseStaleAssetLock>) -> Result<()> {
+        require!(
+            is_lock_stale(&ctx.accounts.asset_lock, &ctx.accounts.asset)?,
+            CoreVoterError::AssetLockNotStale
+        );
+        Ok(())
+    }
+
+    /// Add or update a Metaplex Core collection in the registrar, and recompute the
+    /// realm's max voter weight from the updated collection set.
+    ///
+    /// Like every instruction using `ConfigureCollection`, this needs the realm's
+    /// `MaxVoterWeightRecord`, so run `create_max_voter_weight_record` first.
+    pub fn configure_collection(
+        ctx: Context<ConfigureCollection>,
+        collection: Pubkey,
+        weight: u64,
+        known_supply: u64,
+        soulbound_multiplier_bps: u16,
+    ) -> Result<()> {
+        let registrar = &mut ctx.accounts.registrar;
+        upsert_collections(
+            &mut registrar.collections,
+            &[CollectionConfig { collection, weight, known_supply, soulbound_multiplier_bps }],
+        )?;
+
+        ctx.accounts.max_voter_weight_record.max_voter_weight =
+            max_voter_weight(&registrar.collections, &registrar.address_authorities)?;
+        Ok(())
+    }
+
+    /// Upsert several collections in one call. Duplicates within the batch resolve
+    /// last-write-wins; nothing is changed if the result would exceed capacity.
+    pub fn configure_collections_batch(
+        ctx: Context<ConfigureCollection>,
+        configs: Vec<CollectionConfig>,
+    ) -> Result<()> {
+        let registrar = &mut ctx.accounts.registrar;
+        upsert_collections(&mut registrar.collections, &configs)?;
+
+        ctx.accounts.max_voter_weight_record.max_voter_weight =
+            max_voter_weight(&registrar.collections, &registrar.address_authorities)?;
+        Ok(())
+    }
+
+    /// Remove a collection from the registrar and lower the max voter weight accordingly.
+    pub fn remove_collection(ctx: Context<ConfigureCollection>, collection: Pubkey) -> Result<()> {
+        let registrar = &mut ctx.accounts.registrar;
+        let index = registrar
+            .collections
+            .iter()
+            .position(|c| c.collection == collection)
+            .ok_or(CoreVoterError::CollectionNotFound)?;
+        registrar.collections.remove(index);
+
+        ctx.accounts.max_voter_weight_record.max_voter_weight =
+            max_voter_weight(&registrar.collections, &registrar.address_authorities)?;
         Ok(())
     }
 
@@ -140,172 +576,895 @@ pub mod ika_core_voter {
         Ok(())
     }
 
+    /// Close the signer's own voter weight record and send its rent to `rent_destination`.
+    /// Refused while the record's weight is still valid (i.e. possibly mid-vote).
+    pub fn close_voter_weight_record(ctx: Context<CloseVoterWeightRecord>) -> Result<()> {
+        check_voter_weight_record_closable(
+            &ctx.accounts.voter_weight_record,
+            &ctx.accounts.governing_token_owner.key(),
+            Clock::get()?.slot,
+        )
+    }
+
     /// Update voter weight by counting owned Metaplex Core NFTs.
     ///
+    /// The weight expires `weight_validity_slots` after this slot (or at the end of it if
+    /// unset); until then governance accepts the cached record without another update.
+    ///
     /// The voter passes their Core asset accounts as `remaining_accounts`.
     /// Each asset is verified for: correct program owner, AssetV1 key, voter ownership,
-    /// and membership in a registered collection. Duplicate assets are rejected.
-    pub fn update_voter_weight_record(ctx: Context<UpdateVoterWeightRecord>) -> Result<()> {
+    /// and membership in a registered collection. Duplicate assets count once.
+    /// With `min_hold_slots` set, pass `[asset, asset_lock]` pairs instead.
+    /// At most `MAX_ASSETS_PER_UPDATE` assets are accepted; larger holders should pass
+    /// their highest-weight assets (use `simulate_voter_weight` to compare selections).
+    /// Every asset that adds no weight emits an `AssetSkipped` event with the reason, and
+    /// a final `VoterWeightBreakdown` event reports the weight per collection.
+    ///
+    /// The record is scoped to `action` and `target`, which governance checks. `CastVote`
+    /// needs the proposal as `target` and is refused for proposals with a registered
+    /// snapshot; those are voted on with `snapshot_voter_weight`.
+    pub fn update_voter_weight_record(
+        ctx: Context<UpdateVoterWeightRecord>,
+        action: VoterWeightAction,
+        target: Option<Pubkey>,
+    ) -> Result<()> {
         let registrar = &ctx.accounts.registrar;
-        let record = &mut ctx.accounts.voter_weight_record;
         let voter = ctx.accounts.governing_token_owner.key();
+        let tally = update_voter_weight(
+            registrar,
+            &registrar.key(),
+            ctx.remaining_accounts,
+            Clock::get()?.slot,
+            action,
+            target,
+            &mut ctx.accounts.voter_weight_record,
+        )?;
+        emit!(tally.breakdown(voter));
+        Ok(())
+    }
 
-        let mut total_weight: u64 = 0;
-        let mut seen_assets: BTreeSet<Pubkey> = BTreeSet::new();
-
-        for asset_info in ctx.remaining_accounts.iter() {
-            // Reject duplicate asset accounts
-            require!(
-                seen_assets.insert(asset_info.key()),
-                CoreVoterError::DuplicateAsset
-            );
-
-            let (collection, verified) = verify_core_nft_ownership(asset_info, &voter)?;
-
-            if !verified {
-                continue;
-            }
-
-            // Look up the collection weight in the registrar
-            if let Some(config) = registrar.collections.iter().find(|c| c.collection == collection) {
-                total_weight = total_weight
-                    .checked_add(config.weight)
-                    .ok_or(CoreVoterError::ArithmeticOverflow)?;
-            }
-            // If the collection isn't registered, we just skip it (no error)
-        }
+    /// Update voter weight as of the slot pinned for `proposal` instead of the current slot,
+    /// so NFTs acquired after the proposal appeared don't count.
+    ///
+    /// `snapshot_slot` must equal the slot registered via `register_proposal_snapshot`.
+    /// Always takes `[asset, asset_lock]` pairs: an asset only counts if the voter's hold
+    /// record existed at the snapshot slot (and, with `min_hold_slots`, was old enough then).
+    ///
+    /// SPL Governance only reads the `VoterWeightRecord`, so the record is tied to the
+    /// proposal through `weight_action = CastVote` and `weight_action_target = proposal`;
+    /// governance refuses it for any other action or proposal. Run this in the same
+    /// transaction as (or right before) `CastVote`.
+    pub fn snapshot_voter_weight(
+        ctx: Context<UpdateVoterWeightRecord>,
+        proposal: Pubkey,
+        snapshot_slot: u64,
+    ) -> Result<()> {
+        let registrar = &ctx.accounts.registrar;
+        let record = &mut ctx.accounts.voter_weight_record;
+        let voter = ctx.accounts.governing_token_owner.key();
 
+        let pinned = registrar.snapshot_slot_for(&proposal).ok_or(CoreVoterError::ProposalSnapshotNotFound)?;
+        require!(pinned == snapshot_slot, CoreVoterError::SnapshotSlotMismatch);
+
+        let tally = compute_voter_weight_at(
+            registrar,
+            &registrar.key(),
+            &voter,
+            ctx.remaining_accounts,
+            snapshot_slot,
+            Some(registrar.min_hold_slots.unwrap_or(0)),
+            &mut |asset, reason| {
+                emit!(AssetSkipped { asset, reason });
+            },
+        )?;
+        let total_weight = tally.total;
+        emit!(tally.breakdown(voter));
+
+        let current_slot = Clock::get()?.slot;
         record.voter_weight = total_weight;
-        // Expire after this slot to force re-verification on each vote
-        record.voter_weight_expiry = Some(Clock::get()?.slot);
-        record.weight_action = None;
-        record.weight_action_target = None;
+        record.voter_weight_expiry = Some(voter_weight_expiry(current_slot, registrar.weight_validity_slots));
+        record.weight_action = Some(VoterWeightAction::CastVote);
+        record.weight_action_target = Some(proposal);
 
         Ok(())
     }
 
-    /// Create or update the max voter weight record.
-    /// For Core collections there's no fixed supply cap, so we set a large value.
+    /// Compute a voter's weight exactly like `update_voter_weight_record` without
+    /// writing anything. Takes the same `remaining_accounts`.
+    ///
+    /// Clients call this through `simulateTransaction` (no signature needed; the owner
+    /// need not sign) and read the u64 from the transaction's return data
+    /// (Anchor `.view()`); it is also logged.
+    pub fn simulate_voter_weight(ctx: Context<SimulateVoterWeight>) -> Result<u64> {
+        let registrar = &ctx.accounts.registrar;
+        let weight = compute_voter_weight(
+            registrar,
+            &registrar.key(),
+            &ctx.accounts.governing_token_owner.key(),
+            ctx.remaining_accounts,
+            Clock::get()?.slot,
+        )?;
+        msg!("Simulated voter weight: {}", weight);
+        Ok(weight)
+    }
+
+    /// Create the max voter weight record. Must run before `configure_collection` and the
+    /// other `ConfigureCollection` instructions, which keep it in sync from then on.
+    ///
+    /// `max_voter_weight` is only used while the registrar has no collections or address
+    /// authorities; otherwise (e.g. after `migrate_registrar_mint`) the record starts at
+    /// the value computed from the registrar.
     pub fn create_max_voter_weight_record(
         ctx: Context<CreateMaxVoterWeightRecord>,
         max_voter_weight: u64,
     ) -> Result<()> {
+        let registrar = &ctx.accounts.registrar;
+        let initial = if registrar.collections.is_empty() && registrar.address_authorities.is_empty() {
+            max_voter_weight
+        } else {
+            crate::max_voter_weight(&registrar.collections, &registrar.address_authorities)?
+        };
         let record = &mut ctx.accounts.max_voter_weight_record;
         record.realm = ctx.accounts.realm.key();
         record.governing_token_mint = ctx.accounts.governing_token_mint.key();
-        record.max_voter_weight = max_voter_weight;
+        record.max_voter_weight = initial;
         record.max_voter_weight_expiry = None; // never expires
         record.reserved = [0u8; 8];
         Ok(())
     }
 }
 
-// ─── Core Asset Verification ─────────────────────────────────────────────────
+// ─── Weight Computation ──────────────────────────────────────────────────────
 
-/// Verify a Metaplex Core asset is owned by the voter and belongs to a collection.
+/// Sum the registrar weight of every verified asset in `assets`, then apply the
+/// registrar's per-voter cap. Duplicate asset accounts are rejected.
 ///
-/// Parses raw account bytes at fixed offsets (only 66 bytes needed):
-///   Byte 0:      Key enum (must be 1 = AssetV1)
-///   Bytes 1-32:  owner (must match voter)
-///   Byte 33:     UpdateAuthority discriminant (must be 2 = Collection)
-///   Bytes 34-65: UpdateAuthority pubkey (the collection address)
-///
-/// Returns (collection_pubkey, true) on success, or (Pubkey::default(), false) on failure.
-fn verify_core_nft_ownership(
-    asset_info: &AccountInfo,
+/// With `min_hold_slots` set, `assets` is `[asset, asset_lock]` pairs and an asset only
+/// counts if its lock belongs to this registrar/voter and is old enough.
+fn compute_voter_weight(
+    registrar: &Registrar,
+    registrar_key: &Pubkey,
     voter: &Pubkey,
-) -> Result<(Pubkey, bool)> {
-    // Must be owned by the Metaplex Core program
-    if asset_info.owner != &MPL_CORE_PROGRAM_ID {
-        return Ok((Pubkey::default(), false));
-    }
+    assets: &[AccountInfo],
+    current_slot: u64,
+) -> Result<u64> {
+    Ok(compute_voter_weight_tally(registrar, registrar_key, voter, assets, current_slot)?.total)
+}
 
-    let data = asset_info.try_borrow_data()?;
+/// Body of `update_voter_weight_record`: compute the live weight of the record owner's
+/// `assets` and write it to `record`, scoped to `action`/`target`. Returns the tally for
+/// the breakdown event.
+fn update_voter_weight(
+    registrar: &Registrar,
+    registrar_key: &Pubkey,
+    assets: &[AccountInfo],
+    current_slot: u64,
+    action: VoterWeightAction,
+    target: Option<Pubkey>,
+    record: &mut VoterWeightRecord,
+) -> Result<WeightTally> {
+    check_live_weight_action(registrar, action, target)?;
+    let voter = record.governing_token_owner;
+    let tally = compute_voter_weight_tally(registrar, registrar_key, &voter, assets, current_slot)?;
+
+    record.voter_weight = tally.total;
+    record.voter_weight_expiry = Some(voter_weight_expiry(current_slot, registrar.weight_validity_slots));
+    record.weight_action = Some(action);
+    record.weight_action_target = target;
+    Ok(tally)
+}
+
+/// `compute_voter_weight`, keeping the per-collection breakdown.
+fn compute_voter_weight_tally(
+    registrar: &Registrar,
+    registrar_key: &Pubkey,
+    voter: &Pubkey,
+    assets: &[AccountInfo],
+    current_slot: u64,
+) -> Result<WeightTally> {
+    compute_voter_weight_at(
+        registrar,
+        registrar_key,
+        voter,
+        assets,
+        current_slot,
+        registrar.min_hold_slots,
+        &mut |asset, reason| {
+            emit!(AssetSkipped { asset, reason });
+        },
+    )
+}
+
+/// Result of a weight computation: the capped total plus what each collection added.
+struct WeightTally {
+    per_collection: Vec<CollectionWeight>,
+    total: u64,
+}
 
-    // Need at least 66 bytes for the fields we parse
-    if data.len() < 66 {
-        return Ok((Pubkey::default(), false));
+impl WeightTally {
+    /// Credit `weight` to `collection`, keeping first-seen order.
+    fn add(&mut self, collection: Pubkey, weight: u64) -> Result<()> {
+        match self.per_collection.iter_mut().find(|c| c.collection == collection) {
+            Some(entry) => {
+                entry.weight = entry.weight.checked_add(weight).ok_or(CoreVoterError::ArithmeticOverflow)?
+            }
+            None => self.per_collection.push(CollectionWeight { collection, weight }),
+        }
+        self.total = self.total.checked_add(weight).ok_or(CoreVoterError::ArithmeticOverflow)?;
+        Ok(())
     }
 
-    // Key must be AssetV1 (1)
-    if data[0] != 1 {
-        return Ok((Pubkey::default(), false));
+    fn breakdown(self, owner: Pubkey) -> VoterWeightBreakdown {
+        VoterWeightBreakdown { owner, per_collection: self.per_collection, total: self.total }
     }
+}
 
-    // Owner must match voter (bytes 1..33)
-    if &data[1..33] != voter.as_ref() {
-        return Ok((Pubkey::default(), false));
+/// `compute_voter_weight` evaluated as of `as_of_slot` with an explicit hold requirement
+/// (`Some(_)` means `assets` is `[asset, asset_lock]` pairs), reporting every asset that
+/// contributes no weight to `on_skip`.
+fn compute_voter_weight_at(
+    registrar: &Registrar,
+    registrar_key: &Pubkey,
+    voter: &Pubkey,
+    assets: &[AccountInfo],
+    as_of_slot: u64,
+    min_hold_slots: Option<u64>,
+    on_skip: &mut dyn FnMut(Pubkey, SkipReason),
+) -> Result<WeightTally> {
+    let stride = if min_hold_slots.is_some() { 2 } else { 1 };
+    require!(assets.len() % stride == 0, CoreVoterError::InvalidAccountPairs);
+    require!(assets.len() / stride <= MAX_ASSETS_PER_UPDATE, CoreVoterError::TooManyAssets);
+
+    let mut tally = WeightTally { per_collection: Vec::new(), total: 0 };
+    let mut seen_assets: BTreeSet<Pubkey> = BTreeSet::new();
+
+    for chunk in assets.chunks(stride) {
+        let asset_info = &chunk[0];
+
+        // Duplicate asset accounts only count once
+        if !seen_assets.insert(asset_info.key()) {
+            on_skip(asset_info.key(), SkipReason::Duplicate);
+            continue;
+        }
+
+        let authority = match verify_core_nft_ownership(asset_info, voter)? {
+            Ok(authority) => authority,
+            Err(reason) => {
+                on_skip(asset_info.key(), reason);
+                continue;
+            }
+        };
+
+        if let Some(min_hold_slots) = min_hold_slots {
+            if !is_hold_mature(&chunk[1], registrar_key, asset_info, voter, min_hold_slots, as_of_slot)? {
+                on_skip(asset_info.key(), SkipReason::HoldNotMature);
+                continue;
+            }
+        }
+
+        // Look up the collection (or whitelisted address) weight in the registrar
+        let Some(mut weight) = registrar.weight_for(&authority) else {
+            on_skip(asset_info.key(), SkipReason::UnregisteredCollection);
+            continue;
+        };
+        if let Some(bps) = registrar.soulbound_multiplier_bps(&authority) {
+            if is_soulbound(&asset_info.try_borrow_data()?) {
+                weight = apply_multiplier_bps(weight, bps);
+            }
+        }
+        tally.add(authority.key(), weight)?;
     }
 
-    // UpdateAuthority discriminant must be Collection (2)
-    if data[33] != 2 {
-        return Ok((Pubkey::default(), false));
+    tally.total = apply_weight_cap(tally.total, registrar.max_voter_weight_per_owner);
+    Ok(tally)
+}
+
+/// Whether `lock_info` is this voter's `AssetLock` for `asset_info`, the asset is unchanged
+/// since it was locked, the lock was created no later than `current_slot`, and it has been
+/// held for at least `min_hold_slots`.
+fn is_hold_mature(
+    lock_info: &AccountInfo,
+    registrar_key: &Pubkey,
+    asset_info: &AccountInfo,
+    voter: &Pubkey,
+    min_hold_slots: u64,
+    current_slot: u64,
+) -> Result<bool> {
+    if lock_info.owner != &crate::ID {
+        return Ok(false);
+    }
+    let data = lock_info.try_borrow_data()?;
+    let lock = match AssetLock::try_deserialize(&mut &data[..]) {
+        Ok(lock) => lock,
+        Err(_) => return Ok(false),
+    };
+    if lock.registrar != *registrar_key || lock.asset != *asset_info.key || lock.owner != *voter {
+        return Ok(false);
+    }
+    let asset_seq = parse_asset_v1_header(&asset_info.try_borrow_data()?).map(|header| header.seq);
+    if asset_seq != Some(lock.asset_seq) {
+        return Ok(false);
     }
+    Ok(lock.locked_at_slot <= current_slot && current_slot - lock.locked_at_slot >= min_hold_slots)
+}
 
-    // Extract collection pubkey (bytes 34..66)
-    let collection = Pubkey::try_from(&data[34..66]).map_err(|_| CoreVoterError::InvalidAssetData)?;
+/// Add or update `updates` in `collections` (last write wins for repeated collections).
+/// Capacity is checked up front, so on error `collections` is left untouched.
+fn upsert_collections(collections: &mut Vec<CollectionConfig>, updates: &[CollectionConfig]) -> Result<()> {
+    let new_keys: BTreeSet<Pubkey> = updates
+        .iter()
+        .map(|u| u.collection)
+        .filter(|key| !collections.iter().any(|c| c.collection == *key))
+        .collect();
+    require!(
+        collections.len() + new_keys.len() <= MAX_COLLECTIONS,
+        CoreVoterError::MaxCollectionsReached
+    );
+
+    for update in updates {
+        if let Some(existing) = collections.iter_mut().find(|c| c.collection == update.collection) {
+            existing.weight = update.weight;
+            existing.known_supply = update.known_supply;
+            existing.soulbound_multiplier_bps = update.soulbound_multiplier_bps;
+        } else {
+            collections.push(update.clone());
+        }
+    }
+    Ok(())
+}
 
-    Ok((collection, true))
+/// Copy of `registrar` for `new_mint`, without the old mint's proposal snapshots.
+fn migrated_registrar(registrar: &Registrar, new_mint: Pubkey) -> Registrar {
+    Registrar {
+        governance_program_id: registrar.governance_program_id,
+        realm: registrar.realm,
+        governing_token_mint: new_mint,
+        collections: registrar.collections.clone(),
+        max_voter_weight_per_owner: registrar.max_voter_weight_per_owner,
+        min_hold_slots: registrar.min_hold_slots,
+        address_authorities: registrar.address_authorities.clone(),
+        weight_validity_slots: registrar.weight_validity_slots,
+        proposal_snapshots: Vec::new(),
+        weight_decimals: registrar.weight_decimals,
+    }
 }
 
-// ─── Instruction Contexts ────────────────────────────────────────────────────
+/// `weight_decimals` must be in range and can only change on a registrar without weights.
+fn check_weight_decimals(registrar: &Registrar, weight_decimals: u8) -> Result<()> {
+    require!(weight_decimals <= MAX_WEIGHT_DECIMALS, CoreVoterError::InvalidWeightDecimals);
+    require!(
+        registrar.collections.is_empty() && registrar.address_authorities.is_empty(),
+        CoreVoterError::WeightDecimalsLocked
+    );
+    Ok(())
+}
 
-#[derive(Accounts)]
-pub struct CreateRegistrar<'info> {
-    #[account(
-        init,
-        payer = payer,
-        space = Registrar::SPACE,
-        seeds = [b"registrar", realm.key().as_ref(), governing_token_mint.key().as_ref()],
-        bump,
-    )]
-    pub registrar: Account<'info, Registrar>,
+/// Pin a proposal's snapshot slot; refused when `MAX_PROPOSAL_SNAPSHOTS` are pinned.
+fn record_proposal_snapshot(
+    snapshots: &mut Vec<ProposalSnapshot>,
+    snapshot: ProposalSnapshot,
+    current_slot: u64,
+) -> Result<()> {
+    require!(snapshot.snapshot_slot <= current_slot, CoreVoterError::SnapshotSlotInFuture);
+    require!(
+        !snapshots.iter().any(|p| p.proposal == snapshot.proposal),
+        CoreVoterError::ProposalSnapshotExists
+    );
+    require!(snapshots.len() < MAX_PROPOSAL_SNAPSHOTS, CoreVoterError::ProposalSnapshotsFull);
+    snapshots.push(snapshot);
+    Ok(())
+}
 
-    /// CHECK: The SPL Governance program ID. Must be an executable program.
-    #[account(constraint = governance_program_id.executable @ CoreVoterError::InvalidGovernanceProgram)]
-    pub governance_program_id: UncheckedAccount<'info>,
+/// A live (current-slot) weight may be scoped to any action except voting on a proposal
+/// with a pinned snapshot. `CastVote` must name its proposal, otherwise governance would
+/// accept the record for every proposal.
+fn check_live_weight_action(registrar: &Registrar, action: VoterWeightAction, target: Option<Pubkey>) -> Result<()> {
+    if matches!(action, VoterWeightAction::CastVote) {
+        let proposal = target.ok_or(CoreVoterError::CastVoteTargetRequired)?;
+        require!(registrar.snapshot_slot_for(&proposal).is_none(), CoreVoterError::ProposalHasSnapshot);
+    }
+    Ok(())
+}
 
-    /// CHECK: The realm account. Must be owned by the governance program.
-    #[account(
-        constraint = realm.owner == governance_program_id.key @ CoreVoterError::InvalidRealmOwner
-    )]
-    pub realm: UncheckedAccount<'info>,
+/// Realm max voter weight: the most weight every known NFT could add, i.e.
+/// `sum(weight_i * known_supply_i)` over collections and address authorities, with a
+/// collection's per-NFT weight raised by its soulbound multiplier when that exceeds 1x.
+fn max_voter_weight(collections: &[CollectionConfig], address_authorities: &[AddressAuthorityConfig]) -> Result<u64> {
+    let per_collection = collections.iter().map(|c| {
+        (apply_multiplier_bps(c.weight, c.soulbound_multiplier_bps.max(10_000)), c.known_supply)
+    });
+    let per_address = address_authorities.iter().map(|a| (a.weight, a.known_supply));
+    per_collection.chain(per_address).try_fold(0u64, |total, (weight, supply)| {
+        weight
+            .checked_mul(supply)
+            .and_then(|w| total.checked_add(w))
+            .ok_or_else(|| error!(CoreVoterError::ArithmeticOverflow))
+    })
+}
 
-    /// CHECK: The community governance token mint.
-    pub governing_token_mint: UncheckedAccount<'info>,
+/// Clamp a voter's weight to the registrar cap, if one is set.
+fn apply_weight_cap(total_weight: u64, cap: Option<u64>) -> u64 {
+    match cap {
+        Some(cap) => total_weight.min(cap),
+        None => total_weight,
+    }
+}
 
-    /// The realm authority (must be signer).
-    pub realm_authority: Signer<'info>,
+/// Last slot in which a weight updated at `update_slot` is still valid.
+fn voter_weight_expiry(update_slot: u64, weight_validity_slots: Option<u64>) -> u64 {
+    update_slot.saturating_add(weight_validity_slots.unwrap_or(0))
+}
 
-    #[account(mut)]
-    pub payer: Signer<'info>,
+/// Whether governance would still accept the record's weight at `current_slot`
+/// (SPL Governance rejects a record once the current slot is past its expiry).
+fn is_voter_weight_valid(record: &VoterWeightRecord, current_slot: u64) -> bool {
+    match record.voter_weight_expiry {
+        Some(expiry) => current_slot <= expiry,
+        None => true,
+    }
+}
 
-    pub system_program: Program<'info, System>,
+/// A voter weight record can be closed by its owner once its weight window has passed.
+fn check_voter_weight_record_closable(
+    record: &VoterWeightRecord,
+    signer: &Pubkey,
+    current_slot: u64,
+) -> Result<()> {
+    require_keys_eq!(record.governing_token_owner, *signer, CoreVoterError::NotRecordOwner);
+    if record.voter_weight_expiry.is_some() {
+        require!(!is_voter_weight_valid(record, current_slot), CoreVoterError::VoterWeightRecordActive);
+    }
+    Ok(())
 }
 
-#[derive(Accounts)]
-pub struct ConfigureCollection<'info> {
-    #[account(
-        mut,
-        seeds = [b"registrar", registrar.realm.as_ref(), registrar.governing_token_mint.as_ref()],
-        bump,
-    )]
-    pub registrar: Account<'info, Registrar>,
+/// Whether `program_id` is in `TRUSTED_GOVERNANCE_PROGRAMS`.
+fn is_trusted_governance_program(program_id: &Pubkey) -> bool {
+    TRUSTED_GOVERNANCE_PROGRAMS.contains(program_id)
+}
 
-    /// CHECK: The realm account. Must be owned by the stored governance program and match the registrar's realm.
-    #[account(
-        constraint = realm.key() == registrar.realm @ CoreVoterError::InvalidRealmOwner,
-        constraint = realm.owner == &registrar.governance_program_id @ CoreVoterError::InvalidRealmOwner,
-    )]
-    pub realm: UncheckedAccount<'info>,
+/// Whether a realm account is the registrar's realm and still owned by its governance program.
+fn realm_matches(registrar: &Registrar, realm_key: &Pubkey, realm_owner: &Pubkey) -> bool {
+    *realm_key == registrar.realm && *realm_owner == registrar.governance_program_id
+}
 
-    /// The realm authority (must be signer).
-    pub realm_authority: Signer<'info>,
+/// `realm_matches` for a registrar still in the legacy layout.
+fn realm_matches_legacy(registrar: &LegacyRegistrar, realm_key: &Pubkey, realm_owner: &Pubkey) -> bool {
+    *realm_key == registrar.realm && *realm_owner == registrar.governance_program_id
 }
 
-#[derive(Accounts)]
-pub struct CreateVoterWeightRecord<'info> {
-    #[account(
+/// Current-layout registrar carrying over a legacy registrar's realm and collections.
+fn upgraded_registrar(legacy: LegacyRegistrar) -> Registrar {
+    Registrar {
+        governance_program_id: legacy.governance_program_id,
+        realm: legacy.realm,
+        governing_token_mint: legacy.governing_token_mint,
+        collections: legacy
+            .collections
+            .into_iter()
+            .map(|c| CollectionConfig {
+                collection: c.collection,
+                weight: c.weight,
+                known_supply: 0,
+                soulbound_multiplier_bps: 0,
+            })
+            .collect(),
+        max_voter_weight_per_owner: None,
+        min_hold_slots: None,
+        address_authorities: Vec::new(),
+        weight_validity_slots: None,
+        proposal_snapshots: Vec::new(),
+        weight_decimals: 0,
+    }
+}
+
+/// GovernanceAccountType::RealmV1 and RealmV2
+const REALM_ACCOUNT_TYPES: [u8; 2] = [1, 16];
+
+/// Authority of an SPL Governance realm, or `None` if it has none or `data` isn't a realm.
+///
+///   account_type u8 (RealmV1 = 1, RealmV2 = 16), community_mint Pubkey,
+///   RealmConfig { legacy1 u8, legacy2 u8, reserved [u8; 6],
+///                 min_community_weight_to_create_governance u64,
+///                 community_mint_max_voter_weight_source (u8 tag + u64),
+///                 council_mint Option<Pubkey> },
+///   reserved [u8; 6], legacy1 u16, authority Option<Pubkey>
+fn parse_realm_authority(data: &[u8]) -> Option<Pubkey> {
+    if !REALM_ACCOUNT_TYPES.contains(data.first()?) {
+        return None;
+    }
+    let mut pos = 1 + 32 + 1 + 1 + 6 + 8 + 9;
+    pos += match *data.get(pos)? {
+        0 => 1,
+        1 => 33,
+        _ => return None,
+    }; // council_mint
+    pos += 6 + 2;
+    match *data.get(pos)? {
+        1 => Pubkey::try_from(data.get(pos + 1..pos + 33)?).ok(),
+        _ => None,
+    }
+}
+
+/// Whether `authority` is the current authority of the realm account.
+fn is_realm_authority(realm: &AccountInfo, authority: &Pubkey) -> bool {
+    realm.try_borrow_data().ok().and_then(|data| parse_realm_authority(&data)) == Some(*authority)
+}
+
+// ─── Core Asset Verification ─────────────────────────────────────────────────
+
+/// Verify a Metaplex Core asset is owned by the voter and return its update authority.
+///
+/// The whole AssetV1 header must parse (see `asset_v1_header_len`) before any field is
+/// trusted; the fields used are at fixed offsets:
+///   Byte 0:      Key enum (must be 1 = AssetV1)
+///   Bytes 1-32:  owner (must match voter)
+///   Byte 33:     UpdateAuthority discriminant (1 = Address, 2 = Collection)
+///   Bytes 34-65: UpdateAuthority pubkey (the address or collection)
+///
+/// Returns `Ok(authority)` on success, or the reason the asset doesn't qualify.
+fn verify_core_nft_ownership(
+    asset_info: &AccountInfo,
+    voter: &Pubkey,
+) -> Result<std::result::Result<AssetAuthority, SkipReason>> {
+    // Must be owned by the Metaplex Core program
+    if asset_info.owner != &MPL_CORE_PROGRAM_ID {
+        return Ok(Err(SkipReason::NotCoreAsset));
+    }
+
+    let data = asset_info.try_borrow_data()?;
+
+    // Key must be AssetV1 (1) with a well-formed header; burned, truncated, padded or
+    // otherwise unexpected layouts are rejected rather than read at fixed offsets
+    if asset_v1_header_len(&data).is_none() {
+        return Ok(Err(SkipReason::NotAssetV1));
+    }
+
+    // Owner must match voter (bytes 1..33)
+    if &data[1..33] != voter.as_ref() {
+        return Ok(Err(SkipReason::WrongOwner));
+    }
+
+    // UpdateAuthority discriminant must be Address (1) or Collection (2); None (0) never counts
+    let discriminant = data[33];
+    if discriminant == 0 {
+        return Ok(Err(SkipReason::UnregisteredCollection));
+    }
+
+    // Extract the authority pubkey (bytes 34..66)
+    let key = Pubkey::try_from(&data[34..66]).map_err(|_| CoreVoterError::InvalidAssetData)?;
+    match discriminant {
+        1 => Ok(Ok(AssetAuthority::Address(key))),
+        _ => Ok(Ok(AssetAuthority::Collection(key))),
+    }
+}
+
+/// Key::PluginHeaderV1
+const KEY_PLUGIN_HEADER_V1: u8 = 3;
+
+/// Fields of a well-formed AssetV1 base struct used beyond the fixed offsets.
+struct AssetV1Header {
+    /// Length of the base struct.
+    len: usize,
+    seq: Option<u64>,
+}
+
+/// Length of a well-formed AssetV1 base struct, or `None` if `data` isn't one.
+fn asset_v1_header_len(data: &[u8]) -> Option<usize> {
+    parse_asset_v1_header(data).map(|header| header.len)
+}
+
+/// Parse a well-formed AssetV1 base struct, or `None` if `data` isn't one.
+///
+///   Byte 0:     Key (must be 1 = AssetV1; burned assets are resized to Key 0)
+///   Bytes 1-32: owner
+///   Byte 33:    UpdateAuthority discriminant (0 = None, 1/2 + 32-byte pubkey)
+///   then:       name (u32 LE len + bytes), uri (u32 LE len + bytes),
+///               seq Option<u64> (0, or 1 + 8 bytes)
+///
+/// The base struct must be followed by nothing or by a PluginHeaderV1 (Key 3 + u64
+/// offset); any other trailing bytes mean an unknown layout.
+fn parse_asset_v1_header(data: &[u8]) -> Option<AssetV1Header> {
+    let read_len = |at: usize| -> Option<usize> {
+        Some(u32::from_le_bytes(data.get(at..at.checked_add(4)?)?.try_into().ok()?) as usize)
+    };
+
+    if *data.first()? != 1 {
+        return None;
+    }
+    let mut pos = match *data.get(33)? {
+        0 => 34,
+        1 | 2 => 66,
+        _ => return None,
+    };
+    pos = pos.checked_add(4)?.checked_add(read_len(pos)?)?; // name
+    pos = pos.checked_add(4)?.checked_add(read_len(pos)?)?; // uri
+    let seq = match *data.get(pos)? {
+        0 => None,
+        1 => Some(u64::from_le_bytes(data.get(pos + 1..pos + 9)?.try_into().ok()?)),
+        _ => return None,
+    };
+    pos += if seq.is_some() { 9 } else { 1 };
+
+    match data.get(pos) {
+        None => Some(AssetV1Header { len: pos, seq }),
+        Some(&KEY_PLUGIN_HEADER_V1) if data.len() >= pos + 9 => Some(AssetV1Header { len: pos, seq }),
+        Some(_) => None,
+    }
+}
+
+/// Whether `lock`'s owner no longer holds its asset as it was when locked: the account
+/// isn't a Core AssetV1 any more, has another owner, or its `seq` has moved on.
+fn is_lock_stale(lock: &AssetLock, asset_info: &AccountInfo) -> Result<bool> {
+    if asset_info.owner != &MPL_CORE_PROGRAM_ID {
+        return Ok(true);
+    }
+    let data = asset_info.try_borrow_data()?;
+    Ok(match parse_asset_v1_header(&data) {
+        Some(header) => &data[1..33] != lock.owner.as_ref() || header.seq != lock.asset_seq,
+        None => true,
+    })
+}
+
+/// PluginType::PermanentFreezeDelegate (also its `Plugin` enum variant index)
+const PLUGIN_PERMANENT_FREEZE_DELEGATE: u8 = 5;
+
+/// Whether a Core AssetV1 carries a frozen PermanentFreezeDelegate plugin, i.e. can
+/// never be transferred. Any unexpected layout is treated as transferable.
+///
+/// Skips the AssetV1 base struct (`asset_v1_header_len`) to reach the plugin header:
+///   PluginHeaderV1: Key (3) + plugin_registry_offset u64 LE
+///   PluginRegistryV1 (at that offset): Key (4) + u32 LE record count, each record:
+///                   plugin_type u8 + Authority (u8, + 32 bytes if Address = 3) + offset u64 LE
+///   Plugin (at record offset): variant u8 (5) + `frozen` bool
+fn is_soulbound(data: &[u8]) -> bool {
+    parse_soulbound(data).unwrap_or(false)
+}
+
+fn parse_soulbound(data: &[u8]) -> Option<bool> {
+    let read_u32 = |at: usize| -> Option<usize> {
+        Some(u32::from_le_bytes(data.get(at..at + 4)?.try_into().ok()?) as usize)
+    };
+    let read_u64 = |at: usize| -> Option<usize> {
+        usize::try_from(u64::from_le_bytes(data.get(at..at + 8)?.try_into().ok()?)).ok()
+    };
+
+    let pos = asset_v1_header_len(data)?;
+    if pos == data.len() {
+        return Some(false);
+    }
+    let registry = read_u64(pos + 1)?;
+    if *data.get(registry)? != 4 {
+        return None;
+    }
+
+    let count = read_u32(registry + 1)?;
+    let mut record = registry + 5;
+    for _ in 0..count {
+        let plugin_type = *data.get(record)?;
+        let authority_len = if *data.get(record + 1)? == 3 { 33 } else { 1 };
+        let offset = read_u64(record + 1 + authority_len)?;
+        if plugin_type == PLUGIN_PERMANENT_FREEZE_DELEGATE {
+            return Some(*data.get(offset)? == PLUGIN_PERMANENT_FREEZE_DELEGATE && *data.get(offset + 1)? == 1);
+        }
+        record += 1 + authority_len + 8;
+    }
+    Some(false)
+}
+
+/// Scale `weight` by `bps / 10_000`, saturating at u64::MAX.
+fn apply_multiplier_bps(weight: u64, bps: u16) -> u64 {
+    u64::try_from(weight as u128 * bps as u128 / 10_000).unwrap_or(u64::MAX)
+}
+
+// ─── Instruction Contexts ────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct CreateRegistrar<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = Registrar::SPACE,
+        seeds = [b"registrar", realm.key().as_ref(), governing_token_mint.key().as_ref()],
+        bump,
+    )]
+    pub registrar: Account<'info, Registrar>,
+
+    /// CHECK: The SPL Governance program ID. Must be an executable, allowlisted program.
+    #[account(
+        constraint = governance_program_id.executable @ CoreVoterError::InvalidGovernanceProgram,
+        constraint = is_trusted_governance_program(governance_program_id.key) @ CoreVoterError::InvalidGovernanceProgram,
+    )]
+    pub governance_program_id: UncheckedAccount<'info>,
+
+    /// CHECK: The realm account. Must be owned by the governance program.
+    #[account(
+        constraint = realm.owner == governance_program_id.key @ CoreVoterError::InvalidRealmOwner
+    )]
+    pub realm: UncheckedAccount<'info>,
+
+    /// CHECK: The community governance token mint.
+    pub governing_token_mint: UncheckedAccount<'info>,
+
+    /// The realm authority (must be signer and the realm's current authority).
+    #[account(
+        constraint = is_realm_authority(&realm, realm_authority.key) @ CoreVoterError::InvalidRealmAuthority,
+    )]
+    pub realm_authority: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ConfigureCollection<'info> {
+    #[account(
+        mut,
+        seeds = [b"registrar", registrar.realm.as_ref(), registrar.governing_token_mint.as_ref()],
+        bump,
+    )]
+    pub registrar: Account<'info, Registrar>,
+
+    /// CHECK: The realm account. Must be owned by the stored governance program and match the registrar's realm.
+    #[account(
+        constraint = realm.key() == registrar.realm @ CoreVoterError::InvalidRealmOwner,
+        constraint = realm.owner == &registrar.governance_program_id @ CoreVoterError::InvalidRealmOwner,
+    )]
+    pub realm: UncheckedAccount<'info>,
+
+    /// The realm authority (must be signer and the realm's current authority).
+    #[account(
+        constraint = is_realm_authority(&realm, realm_authority.key) @ CoreVoterError::InvalidRealmAuthority,
+    )]
+    pub realm_authority: Signer<'info>,
+
+    /// Rewritten with the recomputed max voter weight on every collection change.
+    #[account(
+        mut,
+        seeds = [b"max-voter-weight-record", registrar.realm.as_ref(), registrar.governing_token_mint.as_ref()],
+        bump,
+    )]
+    pub max_voter_weight_record: Account<'info, MaxVoterWeightRecord>,
+}
+
+/// Registrar-wide settings; same realm-authority gating as `ConfigureCollection`.
+#[derive(Accounts)]
+pub struct ConfigureRegistrar<'info> {
+    #[account(
+        mut,
+        seeds = [b"registrar", registrar.realm.as_ref(), registrar.governing_token_mint.as_ref()],
+        bump,
+    )]
+    pub registrar: Account<'info, Registrar>,
+
+    /// CHECK: The realm account. Must be owned by the stored governance program and match the registrar's realm.
+    #[account(
+        constraint = realm.key() == registrar.realm @ CoreVoterError::InvalidRealmOwner,
+        constraint = realm.owner == &registrar.governance_program_id @ CoreVoterError::InvalidRealmOwner,
+    )]
+    pub realm: UncheckedAccount<'info>,
+
+    /// The realm authority (must be signer and the realm's current authority).
+    #[account(
+        constraint = is_realm_authority(&realm, realm_authority.key) @ CoreVoterError::InvalidRealmAuthority,
+    )]
+    pub realm_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpgradeRegistrar<'info> {
+    /// CHECK: Legacy registrar; owner, size and discriminator are checked in the instruction body.
+    #[account(
+        mut,
+        seeds = [b"registrar", realm.key().as_ref(), governing_token_mint.key().as_ref()],
+        bump,
+    )]
+    pub registrar: UncheckedAccount<'info>,
+
+    /// CHECK: The realm account; checked against the legacy registrar in the instruction body.
+    pub realm: UncheckedAccount<'info>,
+
+    /// CHECK: The registrar's community governance token mint (PDA seed only).
+    pub governing_token_mint: UncheckedAccount<'info>,
+
+    /// The realm authority (must be signer and the realm's current authority).
+    #[account(
+        constraint = is_realm_authority(&realm, realm_authority.key) @ CoreVoterError::InvalidRealmAuthority,
+    )]
+    pub realm_authority: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateRegistrarMint<'info> {
+    #[account(
+        seeds = [b"registrar", registrar.realm.as_ref(), registrar.governing_token_mint.as_ref()],
+        bump,
+    )]
+    pub registrar: Account<'info, Registrar>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = Registrar::SPACE,
+        seeds = [b"registrar", registrar.realm.as_ref(), new_governing_token_mint.key().as_ref()],
+        bump,
+    )]
+    pub new_registrar: Account<'info, Registrar>,
+
+    /// CHECK: The realm account. Must still be owned by the stored governance program and match the registrar's realm.
+    #[account(
+        constraint = realm_matches(&registrar, realm.key, realm.owner) @ CoreVoterError::InvalidRealmOwner,
+    )]
+    pub realm: UncheckedAccount<'info>,
+
+    /// CHECK: The realm's new community governance token mint.
+    pub new_governing_token_mint: UncheckedAccount<'info>,
+
+    /// The realm authority (must be signer and the realm's current authority).
+    #[account(
+        constraint = is_realm_authority(&realm, realm_authority.key) @ CoreVoterError::InvalidRealmAuthority,
+    )]
+    pub realm_authority: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct LockAsset<'info> {
+    pub registrar: Account<'info, Registrar>,
+
+    /// CHECK: Metaplex Core asset; ownership verified in the instruction body.
+    pub asset: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = AssetLock::SPACE,
+        seeds = [b"asset-lock", registrar.key().as_ref(), asset.key().as_ref(), owner.key().as_ref()],
+        bump,
+    )]
+    pub asset_lock: Account<'info, AssetLock>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UnlockAsset<'info> {
+    #[account(mut, has_one = owner, close = owner)]
+    pub asset_lock: Account<'info, AssetLock>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseStaleAssetLock<'info> {
+    #[account(mut, has_one = asset, has_one = owner, close = owner)]
+    pub asset_lock: Account<'info, AssetLock>,
+
+    /// CHECK: The locked Metaplex Core asset; checked in the instruction body.
+    pub asset: UncheckedAccount<'info>,
+
+    /// CHECK: The lock's owner; only receives the reclaimed rent.
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreateVoterWeightRecord<'info> {
+    #[account(
         init,
         payer = payer,
         space = VoterWeightRecord::SPACE,
@@ -334,6 +1493,20 @@ pub struct CreateVoterWeightRecord<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct CloseVoterWeightRecord<'info> {
+    /// Ownership and expiry are checked in the instruction body.
+    #[account(mut, close = rent_destination)]
+    pub voter_weight_record: Account<'info, VoterWeightRecord>,
+
+    /// The voter who owns the record (must be signer).
+    pub governing_token_owner: Signer<'info>,
+
+    /// CHECK: Any account may receive the reclaimed rent.
+    #[account(mut)]
+    pub rent_destination: UncheckedAccount<'info>,
+}
+
 #[derive(Accounts)]
 pub struct UpdateVoterWeightRecord<'info> {
     #[account(
@@ -342,6 +1515,13 @@ pub struct UpdateVoterWeightRecord<'info> {
     )]
     pub registrar: Account<'info, Registrar>,
 
+    /// CHECK: The realm account. Must still be owned by the stored governance program, so a
+    /// closed/migrated realm can't keep producing weight records governance won't honor.
+    #[account(
+        constraint = realm_matches(&registrar, realm.key, realm.owner) @ CoreVoterError::InvalidRealmOwner,
+    )]
+    pub realm: UncheckedAccount<'info>,
+
     #[account(
         mut,
         seeds = [
@@ -360,6 +1540,18 @@ pub struct UpdateVoterWeightRecord<'info> {
     pub governing_token_owner: Signer<'info>,
 }
 
+#[derive(Accounts)]
+pub struct SimulateVoterWeight<'info> {
+    #[account(
+        seeds = [b"registrar", registrar.realm.as_ref(), registrar.governing_token_mint.as_ref()],
+        bump,
+    )]
+    pub registrar: Account<'info, Registrar>,
+
+    /// CHECK: The voter whose weight is previewed. Read-only, no signature required.
+    pub governing_token_owner: UncheckedAccount<'info>,
+}
+
 #[derive(Accounts)]
 pub struct CreateMaxVoterWeightRecord<'info> {
     /// The registrar must exist for this realm (ensures realm is properly set up).
@@ -387,7 +1579,10 @@ pub struct CreateMaxVoterWeightRecord<'info> {
     /// CHECK: The community governance token mint.
     pub governing_token_mint: UncheckedAccount<'info>,
 
-    /// The realm authority (must be signer).
+    /// The realm authority (must be signer and the realm's current authority).
+    #[account(
+        constraint = is_realm_authority(&realm, realm_authority.key) @ CoreVoterError::InvalidRealmAuthority,
+    )]
     pub realm_authority: Signer<'info>,
 
     #[account(mut)]
@@ -412,4 +1607,837 @@ pub enum CoreVoterError {
     InvalidRealmOwner,
     #[msg("Governance program ID is not a valid executable program")]
     InvalidGovernanceProgram,
+    #[msg("Asset is not a Core asset owned by the signer")]
+    AssetNotOwned,
+    #[msg("Expected (asset, asset_lock) account pairs")]
+    InvalidAccountPairs,
+    #[msg("Collection is not configured in the registrar")]
+    CollectionNotFound,
+    #[msg("Maximum number of address authorities reached")]
+    MaxAddressAuthoritiesReached,
+    #[msg("Signer is not the voter weight record owner")]
+    NotRecordOwner,
+    #[msg("Voter weight record is still within its weight window")]
+    VoterWeightRecordActive,
+    #[msg("No snapshot slot is registered for this proposal")]
+    ProposalSnapshotNotFound,
+    #[msg("Snapshot slot does not match the slot registered for the proposal")]
+    SnapshotSlotMismatch,
+    #[msg("A snapshot slot is already registered for this proposal")]
+    ProposalSnapshotExists,
+    #[msg("Snapshot slot is in the future")]
+    SnapshotSlotInFuture,
+    #[msg("Weight decimals exceed the supported maximum")]
+    InvalidWeightDecimals,
+    #[msg("Weight decimals can't change while weights are configured")]
+    WeightDecimalsLocked,
+    #[msg("New governing token mint is the registrar's current mint")]
+    GoverningMintUnchanged,
+    #[msg("Too many assets for one update; pass at most MAX_ASSETS_PER_UPDATE")]
+    TooManyAssets,
+    #[msg("Proposal snapshot list is full; remove a finished proposal's snapshot first")]
+    ProposalSnapshotsFull,
+    #[msg("CastVote weight must target a proposal")]
+    CastVoteTargetRequired,
+    #[msg("Proposal has a snapshot; use snapshot_voter_weight")]
+    ProposalHasSnapshot,
+    #[msg("Signer is not the realm's authority")]
+    InvalidRealmAuthority,
+    #[msg("Registrar is not in the legacy layout")]
+    RegistrarNotLegacy,
+    #[msg("Asset is still held by the lock owner, unchanged")]
+    AssetLockNotStale,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fake Metaplex Core AssetV1 account owned by `owner` in `collection`, with empty
+    /// name/uri, no seq and no plugins.
+    struct FakeAsset {
+        key: Pubkey,
+        lamports: u64,
+        data: Vec<u8>,
+        program_owner: Pubkey,
+    }
+
+    impl FakeAsset {
+        fn new(owner: &Pubkey, collection: &Pubkey) -> Self {
+            Self::with_authority(owner, 2, collection)
+        }
+
+        fn with_authority(owner: &Pubkey, discriminant: u8, authority: &Pubkey) -> Self {
+            let mut data = vec![1u8];
+            data.extend_from_slice(owner.as_ref());
+            data.push(discriminant);
+            data.extend_from_slice(authority.as_ref());
+            data.extend_from_slice(&0u32.to_le_bytes()); // name
+            data.extend_from_slice(&0u32.to_le_bytes()); // uri
+            data.push(0); // seq: None
+            Self { key: Pubkey::new_unique(), lamports: 1, data, program_owner: MPL_CORE_PROGRAM_ID }
+        }
+
+        fn info(&mut self) -> AccountInfo<'_> {
+            AccountInfo::new(
+                &self.key,
+                false,
+                false,
+                &mut self.lamports,
+                &mut self.data,
+                &self.program_owner,
+                false,
+                0,
+            )
+        }
+    }
+
+    fn config(collection: Pubkey, weight: u64) -> CollectionConfig {
+        CollectionConfig { collection, weight, known_supply: 0, soulbound_multiplier_bps: 0 }
+    }
+
+    fn registrar(collections: Vec<CollectionConfig>, cap: Option<u64>) -> Registrar {
+        Registrar {
+            governance_program_id: Pubkey::new_unique(),
+            realm: Pubkey::new_unique(),
+            governing_token_mint: Pubkey::new_unique(),
+            collections,
+            max_voter_weight_per_owner: cap,
+            min_hold_slots: None,
+            address_authorities: Vec::new(),
+            weight_validity_slots: None,
+            proposal_snapshots: Vec::new(),
+            weight_decimals: 0,
+        }
+    }
+
+    /// Weight of `count` assets from a single collection with per-NFT `weight`.
+    fn weight_of(count: usize, weight: u64, cap: Option<u64>) -> u64 {
+        let voter = Pubkey::new_unique();
+        let collection = Pubkey::new_unique();
+        let registrar = registrar(vec![config(collection, weight)], cap);
+        let mut assets: Vec<FakeAsset> = (0..count).map(|_| FakeAsset::new(&voter, &collection)).collect();
+        let infos: Vec<AccountInfo> = assets.iter_mut().map(|a| a.info()).collect();
+        compute_voter_weight(&registrar, &Pubkey::new_unique(), &voter, &infos, 0).unwrap()
+    }
+
+    #[test]
+    fn test_weight_below_cap_unaffected() {
+        assert_eq!(weight_of(3, 10, Some(100)), 30);
+    }
+
+    #[test]
+    fn test_weight_above_cap_clamped() {
+        assert_eq!(weight_of(20, 10, Some(100)), 100);
+    }
+
+    #[test]
+    fn test_weight_without_cap_unbounded() {
+        assert_eq!(weight_of(20, 10, None), 200);
+    }
+
+    fn lock_account(registrar_key: Pubkey, asset: Pubkey, owner: Pubkey, locked_at_slot: u64) -> FakeAsset {
+        let mut data = Vec::new();
+        AssetLock { registrar: registrar_key, asset, owner, locked_at_slot, asset_seq: None }
+            .try_serialize(&mut data)
+            .unwrap();
+        FakeAsset { key: Pubkey::new_unique(), lamports: 1, data, program_owner: crate::ID }
+    }
+
+    /// Weight of one asset locked at slot 100, evaluated at `current_slot` with a 50-slot minimum.
+    fn held_weight(current_slot: u64) -> u64 {
+        let voter = Pubkey::new_unique();
+        let collection = Pubkey::new_unique();
+        let registrar_key = Pubkey::new_unique();
+        let mut registrar = registrar(vec![config(collection, 7)], None);
+        registrar.min_hold_slots = Some(50);
+
+        let mut asset = FakeAsset::new(&voter, &collection);
+        let mut lock = lock_account(registrar_key, asset.key, voter, 100);
+        let infos = vec![asset.info(), lock.info()];
+        compute_voter_weight(&registrar, &registrar_key, &voter, &infos, current_slot).unwrap()
+    }
+
+    #[test]
+    fn test_freshly_locked_asset_does_not_count() {
+        assert_eq!(held_weight(120), 0);
+    }
+
+    #[test]
+    fn test_matured_lock_counts() {
+        assert_eq!(held_weight(150), 7);
+    }
+
+    /// AssetV1 with a `seq` value instead of None.
+    fn asset_with_seq(owner: &Pubkey, collection: &Pubkey, seq: u64) -> FakeAsset {
+        let mut asset = FakeAsset::new(owner, collection);
+        *asset.data.last_mut().unwrap() = 1;
+        asset.data.extend_from_slice(&seq.to_le_bytes());
+        asset
+    }
+
+    #[test]
+    fn test_lock_outlived_by_asset_changes_does_not_count() {
+        let voter = Pubkey::new_unique();
+        let collection = Pubkey::new_unique();
+        let registrar_key = Pubkey::new_unique();
+        let mut registrar = registrar(vec![config(collection, 7)], None);
+        registrar.min_hold_slots = Some(50);
+
+        // Locked at seq 3; the asset was transferred away and back since (seq 5)
+        let mut asset = asset_with_seq(&voter, &collection, 5);
+        let mut data = Vec::new();
+        let lock =
+            AssetLock { registrar: registrar_key, asset: asset.key, owner: voter, locked_at_slot: 100, asset_seq: Some(3) };
+        lock.try_serialize(&mut data).unwrap();
+        let mut lock_info = FakeAsset { key: Pubkey::new_unique(), lamports: 1, data, program_owner: crate::ID };
+        let infos = vec![asset.info(), lock_info.info()];
+        assert_eq!(compute_voter_weight(&registrar, &registrar_key, &voter, &infos, 200).unwrap(), 0);
+        assert!(is_lock_stale(&lock, &infos[0]).unwrap());
+
+        // Same seq as at lock time: the lock counts and is not stale
+        let current = AssetLock { asset_seq: Some(5), ..lock };
+        assert!(!is_lock_stale(&current, &infos[0]).unwrap());
+    }
+
+    #[test]
+    fn test_lock_stale_once_asset_leaves_owner() {
+        let owner = Pubkey::new_unique();
+        let collection = Pubkey::new_unique();
+        let mut held = FakeAsset::new(&owner, &collection);
+        let lock = AssetLock {
+            registrar: Pubkey::new_unique(),
+            asset: held.key,
+            owner,
+            locked_at_slot: 100,
+            asset_seq: None,
+        };
+        assert!(!is_lock_stale(&lock, &held.info()).unwrap());
+
+        let mut transferred = FakeAsset::new(&Pubkey::new_unique(), &collection);
+        assert!(is_lock_stale(&lock, &transferred.info()).unwrap());
+        // Burned: resized down to Key::Uninitialized
+        let mut burned = FakeAsset::new(&owner, &collection);
+        burned.data = vec![0];
+        assert!(is_lock_stale(&lock, &burned.info()).unwrap());
+    }
+
+    #[test]
+    fn test_simulated_weight_matches_update() {
+        let voter = Pubkey::new_unique();
+        let collection = Pubkey::new_unique();
+        let registrar_key = Pubkey::new_unique();
+        let mut registrar = registrar(vec![config(collection, 4)], Some(10));
+        registrar.min_hold_slots = Some(50);
+        registrar.weight_validity_slots = Some(20);
+
+        // Three matured holds (12, capped to 10) and one lock too fresh to count
+        let mut pairs: Vec<(FakeAsset, FakeAsset)> = [100, 100, 100, 190]
+            .into_iter()
+            .map(|locked_at| {
+                let asset = FakeAsset::new(&voter, &collection);
+                let lock = lock_account(registrar_key, asset.key, voter, locked_at);
+                (asset, lock)
+            })
+            .collect();
+        let infos: Vec<AccountInfo> = pairs.iter_mut().flat_map(|(a, l)| [a.info(), l.info()]).collect();
+
+        // What simulate_voter_weight returns, then a real update of the same accounts
+        let simulated = compute_voter_weight(&registrar, &registrar_key, &voter, &infos, 200).unwrap();
+        let mut record = voter_weight_record(voter, None);
+        let proposal = Pubkey::new_unique();
+        update_voter_weight(&registrar, &registrar_key, &infos, 200, VoterWeightAction::CastVote, Some(proposal), &mut record)
+            .unwrap();
+
+        assert_eq!(simulated, 10);
+        assert_eq!(record.voter_weight, simulated);
+        assert_eq!(record.voter_weight_expiry, Some(220));
+        assert!(matches!(record.weight_action, Some(VoterWeightAction::CastVote)));
+        assert_eq!(record.weight_action_target, Some(proposal));
+    }
+
+    #[test]
+    fn test_realm_with_mismatched_owner_rejected() {
+        let registrar = registrar(vec![], None);
+        assert!(realm_matches(&registrar, &registrar.realm, &registrar.governance_program_id));
+        // Realm closed or migrated to another program
+        assert!(!realm_matches(&registrar, &registrar.realm, &Pubkey::new_unique()));
+        // Some other realm owned by the right program
+        assert!(!realm_matches(&registrar, &Pubkey::new_unique(), &registrar.governance_program_id));
+    }
+
+    /// RealmV2 account data with an optional council mint and authority.
+    fn realm_data(council_mint: Option<Pubkey>, authority: Option<Pubkey>) -> Vec<u8> {
+        let mut data = vec![16u8];
+        data.extend_from_slice(Pubkey::new_unique().as_ref()); // community_mint
+        data.extend_from_slice(&[0u8; 1 + 1 + 6]); // legacy1, legacy2, reserved
+        data.extend_from_slice(&10u64.to_le_bytes()); // min_community_weight_to_create_governance
+        data.push(0); // MintMaxVoterWeightSource::SupplyFraction
+        data.extend_from_slice(&10_000_000_000u64.to_le_bytes());
+        let push_option = |data: &mut Vec<u8>, key: Option<Pubkey>| match key {
+            Some(key) => {
+                data.push(1);
+                data.extend_from_slice(key.as_ref());
+            }
+            None => data.push(0),
+        };
+        push_option(&mut data, council_mint);
+        data.extend_from_slice(&[0u8; 6 + 2]); // reserved, legacy1
+        push_option(&mut data, authority);
+        data.extend_from_slice(&[0u8; 64]); // reserved_v2 and later fields
+        data
+    }
+
+    #[test]
+    fn test_realm_authority_parsed() {
+        let authority = Pubkey::new_unique();
+        assert_eq!(parse_realm_authority(&realm_data(None, Some(authority))), Some(authority));
+        assert_eq!(parse_realm_authority(&realm_data(Some(Pubkey::new_unique()), Some(authority))), Some(authority));
+        // Authority removed from the realm
+        assert_eq!(parse_realm_authority(&realm_data(None, None)), None);
+        // Not a realm
+        let mut governance = realm_data(None, Some(authority));
+        governance[0] = 18;
+        assert_eq!(parse_realm_authority(&governance), None);
+        // Truncated
+        assert_eq!(parse_realm_authority(&realm_data(None, Some(authority))[..70]), None);
+    }
+
+    #[test]
+    fn test_only_current_realm_authority_accepted() {
+        let authority = Pubkey::new_unique();
+        let mut realm = FakeAsset {
+            key: Pubkey::new_unique(),
+            lamports: 1,
+            data: realm_data(Some(Pubkey::new_unique()), Some(authority)),
+            program_owner: SPL_GOVERNANCE_PROGRAM_ID,
+        };
+        let info = realm.info();
+        assert!(is_realm_authority(&info, &authority));
+        assert!(!is_realm_authority(&info, &Pubkey::new_unique()));
+    }
+
+    #[test]
+    fn test_legacy_registrar_upgraded() {
+        let collection = Pubkey::new_unique();
+        let legacy = LegacyRegistrar {
+            governance_program_id: SPL_GOVERNANCE_PROGRAM_ID,
+            realm: Pubkey::new_unique(),
+            governing_token_mint: Pubkey::new_unique(),
+            collections: vec![LegacyCollectionConfig { collection, weight: 3 }],
+        };
+        let realm = legacy.realm;
+        assert!(realm_matches_legacy(&legacy, &realm, &SPL_GOVERNANCE_PROGRAM_ID));
+        assert!(!realm_matches_legacy(&legacy, &realm, &Pubkey::new_unique()));
+
+        let upgraded = upgraded_registrar(legacy);
+        assert_eq!(upgraded.realm, realm);
+        assert_eq!(upgraded.weight_for(&AssetAuthority::Collection(collection)), Some(3));
+        assert_eq!(upgraded.collections[0].known_supply, 0);
+        assert_eq!(upgraded.max_voter_weight_per_owner, None);
+
+        // The rewritten account fits the grown registrar
+        let mut data = Vec::new();
+        upgraded.try_serialize(&mut data).unwrap();
+        assert!(data.len() <= Registrar::SPACE);
+        assert!(LegacyRegistrar::SPACE < Registrar::SPACE);
+    }
+
+    #[test]
+    fn test_max_voter_weight_tracks_collections() {
+        let a = CollectionConfig { collection: Pubkey::new_unique(), weight: 2, known_supply: 100, soulbound_multiplier_bps: 0 };
+        let b = CollectionConfig { collection: Pubkey::new_unique(), weight: 5, known_supply: 10, soulbound_multiplier_bps: 0 };
+
+        let mut collections = vec![a.clone()];
+        assert_eq!(max_voter_weight(&collections, &[]).unwrap(), 200);
+
+        // Adding a collection raises the max
+        collections.push(b);
+        assert_eq!(max_voter_weight(&collections, &[]).unwrap(), 250);
+
+        // Removing one lowers it
+        collections.retain(|c| c.collection != a.collection);
+        assert_eq!(max_voter_weight(&collections, &[]).unwrap(), 50);
+    }
+
+    #[test]
+    fn test_max_voter_weight_overflow() {
+        let c = CollectionConfig { collection: Pubkey::new_unique(), weight: u64::MAX, known_supply: 2, soulbound_multiplier_bps: 0 };
+        assert!(max_voter_weight(&[c], &[]).is_err());
+    }
+
+    #[test]
+    fn test_max_voter_weight_counts_address_authorities_and_soulbound_bonus() {
+        let dao = AddressAuthorityConfig { authority: Pubkey::new_unique(), weight: 3, known_supply: 10 };
+        let boosted = CollectionConfig { soulbound_multiplier_bps: 15_000, known_supply: 4, ..config(Pubkey::new_unique(), 10) };
+        // A multiplier below 1x never lowers the max: transferable assets still count fully
+        let reduced = CollectionConfig { soulbound_multiplier_bps: 5_000, known_supply: 2, ..config(Pubkey::new_unique(), 10) };
+
+        assert_eq!(max_voter_weight(&[], &[dao.clone()]).unwrap(), 30);
+        assert_eq!(max_voter_weight(&[boosted.clone()], &[]).unwrap(), 60);
+        assert_eq!(max_voter_weight(&[reduced], &[]).unwrap(), 20);
+        assert_eq!(max_voter_weight(&[boosted], &[dao]).unwrap(), 90);
+
+        // Every voter weight stays within the max: four soulbound assets add up to exactly 60
+        assert_eq!(soulbound_weight(true) * 4, 60);
+    }
+
+    /// Weight of one `UpdateAuthority::Address` asset, with `dao_key` optionally whitelisted.
+    fn address_asset_weight(whitelisted: bool) -> u64 {
+        let voter = Pubkey::new_unique();
+        let dao_key = Pubkey::new_unique();
+        let mut registrar = registrar(vec![], None);
+        if whitelisted {
+            registrar.address_authorities.push(AddressAuthorityConfig { authority: dao_key, weight: 3, known_supply: 0 });
+        }
+        let mut asset = FakeAsset::with_authority(&voter, 1, &dao_key);
+        let infos = vec![asset.info()];
+        compute_voter_weight(&registrar, &Pubkey::new_unique(), &voter, &infos, 0).unwrap()
+    }
+
+    #[test]
+    fn test_whitelisted_address_authority_counts() {
+        assert_eq!(address_asset_weight(true), 3);
+    }
+
+    #[test]
+    fn test_address_authority_excluded_unless_whitelisted() {
+        assert_eq!(address_asset_weight(false), 0);
+
+        // A collection entry for the same key must not match an Address-authority asset
+        let voter = Pubkey::new_unique();
+        let key = Pubkey::new_unique();
+        let registrar = registrar(vec![config(key, 9)], None);
+        let mut asset = FakeAsset::with_authority(&voter, 1, &key);
+        let infos = vec![asset.info()];
+        assert_eq!(compute_voter_weight(&registrar, &Pubkey::new_unique(), &voter, &infos, 0).unwrap(), 0);
+    }
+
+    fn voter_weight_record(owner: Pubkey, expiry: Option<u64>) -> VoterWeightRecord {
+        VoterWeightRecord {
+            realm: Pubkey::new_unique(),
+            governing_token_mint: Pubkey::new_unique(),
+            governing_token_owner: owner,
+            voter_weight: 5,
+            voter_weight_expiry: expiry,
+            weight_action: None,
+            weight_action_target: None,
+            reserved: [0u8; 8],
+        }
+    }
+
+    #[test]
+    fn test_owner_closes_expired_record() {
+        let owner = Pubkey::new_unique();
+        assert!(check_voter_weight_record_closable(&voter_weight_record(owner, None), &owner, 10).is_ok());
+        assert!(check_voter_weight_record_closable(&voter_weight_record(owner, Some(9)), &owner, 10).is_ok());
+    }
+
+    #[test]
+    fn test_close_rejected_for_non_owner_or_active_record() {
+        let owner = Pubkey::new_unique();
+        let record = voter_weight_record(owner, Some(9));
+        assert!(check_voter_weight_record_closable(&record, &Pubkey::new_unique(), 10).is_err());
+        // Still within the weight window (expiry is the update slot)
+        assert!(check_voter_weight_record_closable(&record, &owner, 9).is_err());
+    }
+
+    #[test]
+    fn test_batch_configures_five_collections() {
+        let keys: Vec<Pubkey> = (0..5).map(|_| Pubkey::new_unique()).collect();
+        let mut updates: Vec<CollectionConfig> =
+            keys.iter().enumerate().map(|(i, k)| config(*k, i as u64 + 1)).collect();
+        // Repeated entry: last write wins
+        updates.push(config(keys[0], 42));
+
+        let mut collections = Vec::new();
+        upsert_collections(&mut collections, &updates).unwrap();
+
+        assert_eq!(collections.len(), 5);
+        let weights: Vec<u64> = collections.iter().map(|c| c.weight).collect();
+        assert_eq!(weights, vec![42, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_batch_over_capacity_changes_nothing() {
+        let mut collections: Vec<CollectionConfig> =
+            (0..MAX_COLLECTIONS - 1).map(|_| config(Pubkey::new_unique(), 1)).collect();
+        let existing = collections[0].collection;
+        let updates = vec![
+            config(existing, 99),
+            config(Pubkey::new_unique(), 1),
+            config(Pubkey::new_unique(), 1),
+        ];
+
+        assert!(upsert_collections(&mut collections, &updates).is_err());
+        assert_eq!(collections.len(), MAX_COLLECTIONS - 1);
+        assert_eq!(collections[0].weight, 1);
+    }
+
+    #[test]
+    fn test_canonical_governance_program_trusted() {
+        assert_eq!(
+            SPL_GOVERNANCE_PROGRAM_ID.to_string(),
+            "GovER5Lthms3bLBqWub97yVrMmEogzX7xNjdXpPPCVZw"
+        );
+        assert!(is_trusted_governance_program(&SPL_GOVERNANCE_PROGRAM_ID));
+    }
+
+    #[test]
+    fn test_arbitrary_governance_program_rejected() {
+        assert!(!is_trusted_governance_program(&Pubkey::new_unique()));
+        assert!(!is_trusted_governance_program(&MPL_CORE_PROGRAM_ID));
+    }
+
+    /// AssetV1 with empty name/uri, no seq, and a PermanentFreezeDelegate plugin.
+    fn asset_with_freeze_plugin(owner: &Pubkey, collection: &Pubkey, frozen: bool) -> FakeAsset {
+        let mut asset = FakeAsset::new(owner, collection);
+        let data = &mut asset.data;
+
+        let header = data.len();
+        let plugin = header + 9;
+        let registry = plugin + 2;
+        data.push(3); // PluginHeaderV1
+        data.extend_from_slice(&(registry as u64).to_le_bytes());
+        data.extend_from_slice(&[PLUGIN_PERMANENT_FREEZE_DELEGATE, frozen as u8]);
+        data.push(4); // PluginRegistryV1
+        data.extend_from_slice(&1u32.to_le_bytes());
+        data.extend_from_slice(&[PLUGIN_PERMANENT_FREEZE_DELEGATE, 2]); // UpdateAuthority
+        data.extend_from_slice(&(plugin as u64).to_le_bytes());
+        data.extend_from_slice(&0u32.to_le_bytes()); // external registry
+        asset
+    }
+
+    fn soulbound_weight(frozen: bool) -> u64 {
+        let voter = Pubkey::new_unique();
+        let collection = Pubkey::new_unique();
+        let mut cfg = config(collection, 10);
+        cfg.soulbound_multiplier_bps = 15_000;
+        let registrar = registrar(vec![cfg], None);
+        let mut asset = asset_with_freeze_plugin(&voter, &collection, frozen);
+        let infos = vec![asset.info()];
+        compute_voter_weight(&registrar, &Pubkey::new_unique(), &voter, &infos, 0).unwrap()
+    }
+
+    #[test]
+    fn test_soulbound_asset_gets_bonus() {
+        assert_eq!(soulbound_weight(true), 15);
+    }
+
+    #[test]
+    fn test_transferable_asset_gets_no_bonus() {
+        assert_eq!(soulbound_weight(false), 10);
+        // No plugins at all
+        assert!(!is_soulbound(&FakeAsset::new(&Pubkey::new_unique(), &Pubkey::new_unique()).data));
+    }
+
+    #[test]
+    fn test_skip_reasons_reported() {
+        let voter = Pubkey::new_unique();
+        let collection = Pubkey::new_unique();
+        let registrar = registrar(vec![config(collection, 1)], None);
+
+        let mut counted = FakeAsset::new(&voter, &collection);
+        let mut wrong_owner = FakeAsset::new(&Pubkey::new_unique(), &collection);
+        let mut not_asset_v1 = FakeAsset::new(&voter, &collection);
+        not_asset_v1.data[0] = 5; // CollectionV1
+        let mut unregistered = FakeAsset::new(&voter, &Pubkey::new_unique());
+
+        let counted_info = counted.info();
+        let infos = vec![
+            counted_info.clone(),
+            wrong_owner.info(),
+            not_asset_v1.info(),
+            unregistered.info(),
+            counted_info,
+        ];
+        let keys: Vec<Pubkey> = infos.iter().map(|i| i.key()).collect();
+
+        let mut skipped = Vec::new();
+        let weight = compute_voter_weight_at(
+            &registrar,
+            &Pubkey::new_unique(),
+            &voter,
+            &infos,
+            0,
+            registrar.min_hold_slots,
+            &mut |asset, reason| skipped.push((asset, reason)),
+        )
+        .unwrap()
+        .total;
+
+        assert_eq!(weight, 1);
+        assert_eq!(
+            skipped,
+            vec![
+                (keys[1], SkipReason::WrongOwner),
+                (keys[2], SkipReason::NotAssetV1),
+                (keys[3], SkipReason::UnregisteredCollection),
+                (keys[0], SkipReason::Duplicate),
+            ]
+        );
+    }
+
+    /// A record updated at slot 100 under a 10-slot validity window, checked at `vote_slot`.
+    fn cached_weight_valid(vote_slot: u64) -> bool {
+        let mut registrar = registrar(vec![], None);
+        registrar.weight_validity_slots = Some(10);
+        let expiry = voter_weight_expiry(100, registrar.weight_validity_slots);
+        is_voter_weight_valid(&voter_weight_record(Pubkey::new_unique(), Some(expiry)), vote_slot)
+    }
+
+    #[test]
+    fn test_second_vote_within_window_reuses_cached_weight() {
+        assert!(cached_weight_valid(100));
+        assert!(cached_weight_valid(105));
+        assert!(cached_weight_valid(110));
+    }
+
+    #[test]
+    fn test_cached_weight_expires_after_window() {
+        assert!(!cached_weight_valid(111));
+        // Without a window the weight is only good for the update slot
+        let expiry = voter_weight_expiry(100, None);
+        assert!(!is_voter_weight_valid(&voter_weight_record(Pubkey::new_unique(), Some(expiry)), 101));
+    }
+
+    #[test]
+    fn test_weight_bought_after_snapshot_does_not_count() {
+        let voter = Pubkey::new_unique();
+        let collection = Pubkey::new_unique();
+        let registrar_key = Pubkey::new_unique();
+        let registrar = registrar(vec![config(collection, 5)], None);
+
+        // Held before the proposal (snapshot at slot 100) vs. bought at slot 120
+        let mut before = FakeAsset::new(&voter, &collection);
+        let mut before_lock = lock_account(registrar_key, before.key, voter, 90);
+        let mut after = FakeAsset::new(&voter, &collection);
+        let mut after_lock = lock_account(registrar_key, after.key, voter, 120);
+        let after_key = after.key;
+        let infos = vec![before.info(), before_lock.info(), after.info(), after_lock.info()];
+
+        let mut skipped = Vec::new();
+        let weight = compute_voter_weight_at(
+            &registrar,
+            &registrar_key,
+            &voter,
+            &infos,
+            100,
+            Some(0),
+            &mut |asset, reason| skipped.push((asset, reason)),
+        )
+        .unwrap()
+        .total;
+        assert_eq!(weight, 5);
+        assert_eq!(skipped, vec![(after_key, SkipReason::HoldNotMature)]);
+
+        // The same assets both count for a live update at slot 150
+        let live = compute_voter_weight_at(&registrar, &registrar_key, &voter, &infos, 150, Some(0), &mut |_, _| {})
+            .unwrap()
+            .total;
+        assert_eq!(live, 10);
+    }
+
+    #[test]
+    fn test_proposal_snapshots_pinned_and_bounded() {
+        let mut snapshots = Vec::new();
+        let proposal = Pubkey::new_unique();
+        let pin = |proposal, snapshot_slot| ProposalSnapshot { proposal, snapshot_slot };
+
+        assert!(record_proposal_snapshot(&mut snapshots, pin(proposal, 101), 100).is_err());
+        record_proposal_snapshot(&mut snapshots, pin(proposal, 100), 100).unwrap();
+        // A pinned slot can't be moved
+        assert!(record_proposal_snapshot(&mut snapshots, pin(proposal, 50), 100).is_err());
+
+        for _ in 1..MAX_PROPOSAL_SNAPSHOTS {
+            record_proposal_snapshot(&mut snapshots, pin(Pubkey::new_unique(), 100), 100).unwrap();
+        }
+        assert_eq!(snapshots.len(), MAX_PROPOSAL_SNAPSHOTS);
+        // A full list refuses new proposals instead of evicting the oldest pin
+        assert_eq!(
+            record_proposal_snapshot(&mut snapshots, pin(Pubkey::new_unique(), 100), 100).unwrap_err(),
+            CoreVoterError::ProposalSnapshotsFull.into()
+        );
+        assert_eq!(snapshots.len(), MAX_PROPOSAL_SNAPSHOTS);
+        assert!(snapshots.iter().any(|p| p.proposal == proposal));
+    }
+
+    #[test]
+    fn test_live_cast_vote_refused_for_snapshotted_proposal() {
+        let mut registrar = registrar(vec![], None);
+        let snapshotted = Pubkey::new_unique();
+        registrar.proposal_snapshots.push(ProposalSnapshot { proposal: snapshotted, snapshot_slot: 1 });
+
+        assert_eq!(
+            check_live_weight_action(&registrar, VoterWeightAction::CastVote, Some(snapshotted)).unwrap_err(),
+            CoreVoterError::ProposalHasSnapshot.into()
+        );
+        // An untargeted CastVote would be accepted for every proposal
+        assert_eq!(
+            check_live_weight_action(&registrar, VoterWeightAction::CastVote, None).unwrap_err(),
+            CoreVoterError::CastVoteTargetRequired.into()
+        );
+        assert!(check_live_weight_action(&registrar, VoterWeightAction::CastVote, Some(Pubkey::new_unique())).is_ok());
+        assert!(check_live_weight_action(&registrar, VoterWeightAction::CreateProposal, None).is_ok());
+    }
+
+    #[test]
+    fn test_breakdown_sums_to_recorded_total() {
+        let voter = Pubkey::new_unique();
+        let common = Pubkey::new_unique();
+        let rare = Pubkey::new_unique();
+        let registrar = registrar(vec![config(common, 1), config(rare, 10)], None);
+
+        let mut assets = vec![
+            FakeAsset::new(&voter, &common),
+            FakeAsset::new(&voter, &rare),
+            FakeAsset::new(&voter, &common),
+        ];
+        let infos: Vec<AccountInfo> = assets.iter_mut().map(|a| a.info()).collect();
+
+        let tally = compute_voter_weight_at(&registrar, &Pubkey::new_unique(), &voter, &infos, 0, None, &mut |_, _| {})
+            .unwrap();
+        let recorded = tally.total;
+        let event = tally.breakdown(voter);
+
+        assert_eq!(event.owner, voter);
+        assert_eq!(
+            event.per_collection,
+            vec![
+                CollectionWeight { collection: common, weight: 2 },
+                CollectionWeight { collection: rare, weight: 10 },
+            ]
+        );
+        assert_eq!(event.per_collection.iter().map(|c| c.weight).sum::<u64>(), recorded);
+        assert_eq!(event.total, 12);
+    }
+
+    #[test]
+    fn test_fractional_weights_sum_in_fixed_point() {
+        let voter = Pubkey::new_unique();
+        let collection = Pubkey::new_unique();
+        let mut registrar = registrar(vec![], None);
+        check_weight_decimals(&registrar, 1).unwrap();
+        registrar.weight_decimals = 1;
+        // 0.5 votes per NFT at one decimal
+        registrar.collections.push(CollectionConfig { known_supply: 4, ..config(collection, 5) });
+
+        let mut assets: Vec<FakeAsset> = (0..3).map(|_| FakeAsset::new(&voter, &collection)).collect();
+        let infos: Vec<AccountInfo> = assets.iter_mut().map(|a| a.info()).collect();
+        // Three 0.5 NFTs = 1.5 votes
+        assert_eq!(compute_voter_weight(&registrar, &Pubkey::new_unique(), &voter, &infos, 0).unwrap(), 15);
+        // The realm max is in the same units: 4 * 0.5 = 2.0 votes
+        assert_eq!(max_voter_weight(&registrar.collections, &registrar.address_authorities).unwrap(), 20);
+    }
+
+    #[test]
+    fn test_weight_decimals_locked_once_configured() {
+        let empty = registrar(vec![], None);
+        assert!(check_weight_decimals(&empty, MAX_WEIGHT_DECIMALS).is_ok());
+        assert!(check_weight_decimals(&empty, MAX_WEIGHT_DECIMALS + 1).is_err());
+
+        let configured = registrar(vec![config(Pubkey::new_unique(), 1)], None);
+        assert!(check_weight_decimals(&configured, 2).is_err());
+    }
+
+    #[test]
+    fn test_migrated_registrar_carries_configs() {
+        let collection = Pubkey::new_unique();
+        let mut old = registrar(vec![config(collection, 3)], Some(50));
+        old.address_authorities.push(AddressAuthorityConfig { authority: Pubkey::new_unique(), weight: 2, known_supply: 5 });
+        old.min_hold_slots = Some(10);
+        old.weight_validity_slots = Some(5);
+        old.weight_decimals = 2;
+        old.proposal_snapshots.push(ProposalSnapshot { proposal: Pubkey::new_unique(), snapshot_slot: 1 });
+
+        let new_mint = Pubkey::new_unique();
+        let new = migrated_registrar(&old, new_mint);
+
+        assert_eq!(new.governing_token_mint, new_mint);
+        assert_eq!(new.realm, old.realm);
+        assert_eq!(new.governance_program_id, old.governance_program_id);
+        assert_eq!(new.weight_for(&AssetAuthority::Collection(collection)), Some(3));
+        assert_eq!(new.collections.len(), 1);
+        assert_eq!(new.address_authorities.len(), 1);
+        assert_eq!(new.address_authorities[0].weight, 2);
+        assert_eq!(new.max_voter_weight_per_owner, Some(50));
+        assert_eq!(new.min_hold_slots, Some(10));
+        assert_eq!(new.weight_validity_slots, Some(5));
+        assert_eq!(new.weight_decimals, 2);
+        assert!(new.proposal_snapshots.is_empty());
+        // Max voter weight for the new mint's record matches the old one
+        assert_eq!(
+            max_voter_weight(&new.collections, &new.address_authorities).unwrap(),
+            max_voter_weight(&old.collections, &old.address_authorities).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_update_at_asset_limit_succeeds() {
+        assert_eq!(weight_of(MAX_ASSETS_PER_UPDATE, 2, None), 2 * MAX_ASSETS_PER_UPDATE as u64);
+    }
+
+    #[test]
+    fn test_update_over_asset_limit_rejected_before_iteration() {
+        let voter = Pubkey::new_unique();
+        let collection = Pubkey::new_unique();
+        let registrar = registrar(vec![config(collection, 1)], None);
+        let mut asset = FakeAsset::new(&voter, &collection);
+        // The same account repeated: any iteration would report duplicates
+        let infos = vec![asset.info(); MAX_ASSETS_PER_UPDATE + 1];
+
+        let mut skipped = 0;
+        let result = compute_voter_weight_at(&registrar, &Pubkey::new_unique(), &voter, &infos, 0, None, &mut |_, _| {
+            skipped += 1
+        });
+        assert_eq!(result.err().unwrap(), CoreVoterError::TooManyAssets.into());
+        assert_eq!(skipped, 0);
+    }
+
+    /// Skip reason for a single asset whose data is rewritten by `mangle`.
+    fn verify_mangled(mangle: impl FnOnce(&mut Vec<u8>)) -> std::result::Result<AssetAuthority, SkipReason> {
+        let voter = Pubkey::new_unique();
+        let mut asset = FakeAsset::new(&voter, &Pubkey::new_unique());
+        mangle(&mut asset.data);
+        let info = asset.info();
+        verify_core_nft_ownership(&info, &voter).unwrap()
+    }
+
+    #[test]
+    fn test_well_formed_asset_headers_accepted() {
+        assert!(verify_mangled(|_| {}).is_ok());
+
+        // Non-empty name/uri and a seq value
+        let collection = Pubkey::new_unique();
+        let voter = Pubkey::new_unique();
+        let mut asset = FakeAsset::new(&voter, &collection);
+        asset.data.truncate(66);
+        for field in [&b"Reborn #1"[..], &b"https://example.com/1.json"[..]] {
+            asset.data.extend_from_slice(&(field.len() as u32).to_le_bytes());
+            asset.data.extend_from_slice(field);
+        }
+        asset.data.push(1);
+        asset.data.extend_from_slice(&7u64.to_le_bytes());
+        let info = asset.info();
+        assert_eq!(verify_core_nft_ownership(&info, &voter).unwrap(), Ok(AssetAuthority::Collection(collection)));
+    }
+
+    #[test]
+    fn test_malformed_asset_headers_rejected() {
+        let not_asset_v1 = Err(SkipReason::NotAssetV1);
+        // Trailing padding after the base struct instead of a plugin header
+        assert_eq!(verify_mangled(|d| d.extend_from_slice(&[0u8; 16])), not_asset_v1);
+        // Old fixed-offset layout without name/uri/seq
+        assert_eq!(verify_mangled(|d| d.truncate(66)), not_asset_v1);
+        // Name length pointing past the end of the account
+        assert_eq!(verify_mangled(|d| d[66..70].copy_from_slice(&u32::MAX.to_le_bytes())), not_asset_v1);
+        // Invalid Option<u64> tag for seq
+        assert_eq!(verify_mangled(|d| *d.last_mut().unwrap() = 2), not_asset_v1);
+        // Unknown UpdateAuthority discriminant
+        assert_eq!(verify_mangled(|d| d[33] = 7), not_asset_v1);
+        // Burned asset: resized down to Key::Uninitialized
+        assert_eq!(verify_mangled(|d| *d = vec![0]), not_asset_v1);
+        // Truncated plugin header
+        assert_eq!(verify_mangled(|d| d.extend_from_slice(&[KEY_PLUGIN_HEADER_V1, 0, 0])), not_asset_v1);
+    }
 }
@@ -4,25 +4,34 @@
 //! - Verifies Ed25519 signatures from IKA dWallet
 //! - Creates Metaplex Core collections per source collection
 //! - Mints reborn NFTs to receivers
+//! - Seals Solana-native NFTs and mints their reborns directly (`seal_and_mint_native`)
 //! - Stores provenance on-chain
 //!
 //! SECURITY: The shared IKA minting pubkey is stored in a MintConfig PDA
 //! (set once at init by admin). mint_reborn loads it from the PDA — never
 //! accepts it as instruction input. Heavy verification happens on Sui.
+//! Clients read the active minting pubkey and admin either by deriving
+//! `pdas::mint_config()` and deserializing `MintConfig`, or by simulating
+//! `get_mint_config` and decoding the `MintConfigView` return data.
 //!
 //! ENDIANNESS NOTE: PDA seeds use little-endian encoding for `source_chain`
-//! (via `to_le_bytes()`). The Sui wire format sends big-endian, so the relayer
-//! must convert before calling this program. This is consistent throughout all
-//! Solana-side PDA derivations; off-chain tooling must mirror the same encoding.
+//! (via `SourceChain::to_le_seed_bytes()`). The Sui wire format sends big-endian, so
+//! the relayer must convert (`SourceChain::from_wire_be`) before calling this program.
+//! This is consistent throughout all Solana-side PDA derivations; off-chain tooling
+//! must mirror the same encoding.
 
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::keccak;
 use anchor_lang::solana_program::sysvar;
 use anchor_lang::solana_program::sysvar::instructions as ix_sysvar;
+use anchor_spl::metadata::mpl_token_metadata::types::TokenStandard;
+use anchor_spl::token_2022::spl_token_2022::extension::ExtensionType;
 use mpl_core::instructions::{CreateV2CpiBuilder, CreateCollectionV2CpiBuilder, UpdateV1CpiBuilder};
 use mpl_core::types::{
-    DataState, Plugin, PluginAuthority, PluginAuthorityPair,
-    Royalties, Creator, RuleSet,
+    Attribute, Attributes, DataState, ImmutableMetadata, Plugin, PluginAuthority,
+    PluginAuthorityPair, Royalties, Creator, RuleSet, TransferDelegate,
 };
 use sha2::{Sha256, Digest};
 
@@ -35,16 +44,169 @@ pub mod constants {
     pub const PROVENANCE_SEED: &[u8] = b"provenance";
     pub const COLLECTION_REGISTRY_SEED: &[u8] = b"collection_registry";
     pub const COLLECTION_SEED: &[u8] = b"reborn_collection";
+    /// Optional PDA address for the MPL Core collection asset (instead of a keypair)
+    pub const COLLECTION_ASSET_SEED: &[u8] = b"reborn_collection_asset";
     pub const MINT_AUTHORITY_SEED: &[u8] = b"mint_authority";
     pub const MINT_CONFIG_SEED: &[u8] = b"mint_config";
+    pub const RECEIVER_OVERRIDE_SEED: &[u8] = b"receiver_override";
+    pub const ASSET_PROVENANCE_SEED: &[u8] = b"asset_provenance";
+    /// Token account PDA holding natively sealed NFTs (`SealMode::Lock`)
+    pub const SEALED_VAULT_SEED: &[u8] = b"sealed_vault";
+    /// Program-owned account collecting `native_seal_fee` until withdrawn to the treasury
+    pub const FEE_VAULT_SEED: &[u8] = b"fee_vault";
+    /// Must match `#[max_len]` on `CollectionRegistry::collections`
+    pub const MAX_REGISTRY_COLLECTIONS: usize = 50;
+    /// Must match `#[max_len]` on `MintConfig::allowed_relayers`
+    pub const MAX_ALLOWED_RELAYERS: usize = 8;
+    /// Default cap on entries in the Ed25519 precompile instruction (`MintConfig::max_signatures_per_tx`)
+    pub const DEFAULT_MAX_SIGNATURES_PER_TX: u8 = 8;
+    /// Admin inactivity after which `recovery_admin` may take over (180 days)
+    pub const ADMIN_RECOVERY_WINDOW_SECS: i64 = 180 * 24 * 60 * 60;
 
     // Max lengths
     /// Max URI length. IPFS URIs are ~80 chars, Arweave ~100 chars;
     /// 512 gives plenty of headroom for future formats.
     pub const MAX_URI_LENGTH: usize = 512;
     pub const MAX_NAME_LENGTH: usize = 32;
+    /// Token ID characters a reborn NFT name keeps even under a long collection name
+    pub const MIN_NAME_TOKEN_ID_LENGTH: usize = 8;
+    /// Collection symbol, as in Metaplex token metadata
+    pub const MAX_SYMBOL_LENGTH: usize = 10;
     pub const MAX_CONTRACT_LENGTH: usize = 64;
     pub const MAX_TOKEN_ID_LENGTH: usize = 64;
+    /// Must match `#[max_len]` on each `AttributeSchema` key
+    pub const MAX_ATTRIBUTE_KEY_LENGTH: usize = 32;
+    /// Must match `#[max_len]` on `MintConfig::reborn_suffix`
+    pub const MAX_REBORN_SUFFIX_LENGTH: usize = 24;
+    /// Default `MintConfig::reborn_suffix`: `"{collection_name} (Reborn)"`
+    pub const DEFAULT_REBORN_SUFFIX: &str = " (Reborn)";
+    /// Placeholder for the collection name in a templated `reborn_suffix`
+    pub const REBORN_NAME_PLACEHOLDER: &str = "{name}";
+
+    // Signed message hash algorithms
+    /// sha256 — default for Sui/NEAR-origin attestations
+    pub const HASH_ALGO_SHA256: u8 = 0;
+    /// keccak256 — native digest for EVM-origin attestations
+    pub const HASH_ALGO_KECCAK256: u8 = 1;
+
+    // MPL Core account size estimates for the pre-mint rent check. Slightly high on
+    // purpose: the check should fail before the CPI would, never the other way round.
+    /// BaseCollectionV1 without name/uri bytes: key, update authority, 2 length
+    /// prefixes, num_minted, current_size
+    pub const MPL_CORE_COLLECTION_BASE_SIZE: usize = 1 + 32 + 4 + 4 + 4 + 4;
+    /// BaseAssetV1 without name/uri bytes: key, owner, update authority (tagged),
+    /// 2 length prefixes, seq option
+    pub const MPL_CORE_ASSET_BASE_SIZE: usize = 1 + 32 + 33 + 4 + 4 + 9;
+    /// Per-plugin allowance: plugin header/registry entry plus plugin data
+    /// (Royalties with two creators is the largest we attach)
+    pub const MPL_CORE_PLUGIN_SIZE: usize = 128;
+}
+
+/// Wormhole chain ID of an NFT's source chain.
+///
+/// The wire format carries it big-endian; PDA seeds use it little-endian. Every seed
+/// derivation goes through `to_le_seed_bytes` so the two can't be mixed up.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SourceChain(pub u16);
+
+impl SourceChain {
+    /// Solana-native NFTs (Wormhole chain ID 1)
+    pub const SOLANA: SourceChain = SourceChain(1);
+
+    /// Little-endian bytes used in PDA seeds.
+    pub fn to_le_seed_bytes(self) -> [u8; 2] {
+        self.0.to_le_bytes()
+    }
+
+    /// Parse the big-endian `source_chain` field of a seal payload.
+    pub fn from_wire_be(bytes: [u8; 2]) -> Self {
+        SourceChain(u16::from_be_bytes(bytes))
+    }
+}
+
+/// PDA derivations for every seed used by this program.
+///
+/// Off-chain tooling (relayer, indexer) should call these instead of re-implementing
+/// seeds by hand. `source_chain` is always encoded little-endian here, exactly as the
+/// on-chain account constraints do — pass the chain ID as a plain `u16`, never the
+/// big-endian wire bytes.
+pub mod pdas {
+    use super::{constants, SourceChain};
+    use anchor_lang::prelude::Pubkey;
+
+    /// ["sig_used", sha256(signature)]
+    pub fn sig_used(sig_hash: &[u8]) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[constants::SIG_USED_SEED, sig_hash], &crate::ID)
+    }
+
+    /// ["provenance", source_chain_le, nft_contract, token_id]
+    pub fn provenance(source_chain: u16, nft_contract: &[u8], token_id: &[u8]) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[constants::PROVENANCE_SEED, &SourceChain(source_chain).to_le_seed_bytes(), nft_contract, token_id],
+            &crate::ID,
+        )
+    }
+
+    /// ["reborn_collection", source_chain_le, nft_contract]
+    pub fn reborn_collection(source_chain: u16, nft_contract: &[u8]) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[constants::COLLECTION_SEED, &SourceChain(source_chain).to_le_seed_bytes(), nft_contract],
+            &crate::ID,
+        )
+    }
+
+    /// ["reborn_collection_asset", source_chain_le, nft_contract] — deterministic MPL Core
+    /// collection asset. Passing this as `collection_asset` on the first mint saves the
+    /// relayer from generating and keeping a collection keypair.
+    pub fn collection_asset(source_chain: u16, nft_contract: &[u8]) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[constants::COLLECTION_ASSET_SEED, &SourceChain(source_chain).to_le_seed_bytes(), nft_contract],
+            &crate::ID,
+        )
+    }
+
+    /// ["mint_authority", source_chain_le, nft_contract]
+    pub fn mint_authority(source_chain: u16, nft_contract: &[u8]) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[constants::MINT_AUTHORITY_SEED, &SourceChain(source_chain).to_le_seed_bytes(), nft_contract],
+            &crate::ID,
+        )
+    }
+
+    /// ["receiver_override", source_chain_le, nft_contract, token_id]
+    pub fn receiver_override(source_chain: u16, nft_contract: &[u8], token_id: &[u8]) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[constants::RECEIVER_OVERRIDE_SEED, &SourceChain(source_chain).to_le_seed_bytes(), nft_contract, token_id],
+            &crate::ID,
+        )
+    }
+
+    /// ["asset_provenance", asset] — `AssetProvenanceLink` for a reborn asset. Read its
+    /// `provenance` field to go from an asset to its `Provenance` without knowing the
+    /// source (chain, contract, token) seeds.
+    pub fn asset_provenance_link(asset: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[constants::ASSET_PROVENANCE_SEED, asset.as_ref()], &crate::ID)
+    }
+
+    /// ["mint_config"]
+    pub fn mint_config() -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[constants::MINT_CONFIG_SEED], &crate::ID)
+    }
+
+    /// ["collection_registry"]
+    pub fn collection_registry() -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[constants::COLLECTION_REGISTRY_SEED], &crate::ID)
+    }
+
+    /// ["fee_vault"]
+    pub fn fee_vault() -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[constants::FEE_VAULT_SEED], &crate::ID)
+    }
+
+    /// ["sealed_vault", nft_mint] — token account holding a `SealMode::Lock` original
+    pub fn sealed_vault(nft_mint: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[constants::SEALED_VAULT_SEED, nft_mint.as_ref()], &crate::ID)
+    }
 }
 
 // ============ Account Contexts ============
@@ -73,6 +235,60 @@ pub struct InitializeMintConfig<'info> {
     pub system_program: Program<'info, System>,
 }
 
+/// Idempotent variant of `InitializeMintConfig` for redeploy scripts.
+/// Creates the config on first call; later calls only verify the signer is the existing admin.
+#[derive(Accounts)]
+pub struct EnsureMintConfig<'info> {
+    #[account(init_if_needed, payer = admin, space = 8 + MintConfig::INIT_SPACE,
+              seeds = [constants::MINT_CONFIG_SEED], bump)]
+    pub config: Account<'info, MintConfig>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Idempotent variant of `InitializeCollectionRegistry` for redeploy scripts.
+/// Gated on the MintConfig admin so a re-run can never be used to touch the registry.
+#[derive(Accounts)]
+pub struct EnsureCollectionRegistry<'info> {
+    #[account(init_if_needed, payer = payer, space = 8 + CollectionRegistry::INIT_SPACE,
+              seeds = [constants::COLLECTION_REGISTRY_SEED], bump)]
+    pub registry: Account<'info, CollectionRegistry>,
+    #[account(mut, constraint = payer.key() == config.admin @ ErrorCode::Unauthorized)]
+    pub payer: Signer<'info>,
+    #[account(seeds = [constants::MINT_CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, MintConfig>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Admin-only upgrade of a `MintConfig` written before `strict_ed25519` and the later
+/// fields were added. The account is reallocated in place (the admin tops up its rent).
+#[derive(Accounts)]
+pub struct MigrateMintConfig<'info> {
+    /// CHECK: Legacy MintConfig; owner, size, discriminator and admin checked in the handler
+    #[account(mut, seeds = [constants::MINT_CONFIG_SEED], bump)]
+    pub config: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Admin-only upgrade of one `RebornCollection` or `Provenance` PDA still in its first
+/// deployed layout, reallocated in place (the admin tops up its rent). The account is
+/// identified by owner and discriminator, so big-endian-seeded duplicates qualify too.
+#[derive(Accounts)]
+pub struct MigrateAccount<'info> {
+    #[account(mut, seeds = [constants::MINT_CONFIG_SEED], bump = config.bump, has_one = admin)]
+    pub config: Box<Account<'info, MintConfig>>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    /// CHECK: Legacy RebornCollection / Provenance; owner, size and discriminator checked
+    /// in the handler
+    #[account(mut)]
+    pub account: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
 /// Update the mint config (admin only). For key rotation.
 #[derive(Accounts)]
 pub struct UpdateMintConfig<'info> {
@@ -82,26 +298,89 @@ pub struct UpdateMintConfig<'info> {
     pub admin: Signer<'info>,
 }
 
+/// Read-only access to the `MintConfig` PDA (see `get_mint_config`).
+#[derive(Accounts)]
+pub struct GetMintConfig<'info> {
+    #[account(seeds = [constants::MINT_CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, MintConfig>,
+}
+
+/// Create the native seal fee vault (admin only).
+#[derive(Accounts)]
+pub struct InitializeFeeVault<'info> {
+    #[account(seeds = [constants::MINT_CONFIG_SEED], bump = config.bump, has_one = admin)]
+    pub config: Box<Account<'info, MintConfig>>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(init, payer = admin, space = 8 + FeeVault::INIT_SPACE,
+              seeds = [constants::FEE_VAULT_SEED], bump)]
+    pub fee_vault: Account<'info, FeeVault>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Move collected native seal fees from the vault to `config.treasury` (admin only).
+#[derive(Accounts)]
+pub struct WithdrawFees<'info> {
+    #[account(mut, seeds = [constants::MINT_CONFIG_SEED], bump = config.bump, has_one = admin)]
+    pub config: Box<Account<'info, MintConfig>>,
+    pub admin: Signer<'info>,
+    #[account(mut, seeds = [constants::FEE_VAULT_SEED], bump = fee_vault.bump)]
+    pub fee_vault: Account<'info, FeeVault>,
+    /// CHECK: Withdrawal destination; must match `config.treasury`.
+    #[account(mut, address = config.treasury @ ErrorCode::InvalidTreasury)]
+    pub treasury: UncheckedAccount<'info>,
+}
+
+/// Accounts checked by `validate_accounts`: the PDAs `mint_reborn` derives, as the
+/// relayer would pass them. Nothing is loaded or written.
+#[derive(Accounts)]
+pub struct ValidateAccounts<'info> {
+    /// CHECK: Compared against ["sig_used", sig_hash]
+    pub sig_record: UncheckedAccount<'info>,
+    /// CHECK: Compared against ["provenance", source_chain_le, nft_contract, token_id]
+    pub provenance: UncheckedAccount<'info>,
+    /// CHECK: Compared against ["reborn_collection", source_chain_le, nft_contract]
+    pub collection: UncheckedAccount<'info>,
+    /// CHECK: Compared against ["mint_authority", source_chain_le, nft_contract]
+    pub mint_authority: UncheckedAccount<'info>,
+    /// CHECK: Compared against ["mint_config"]
+    pub config: UncheckedAccount<'info>,
+}
+
+/// Claim `admin` with the `recovery_admin` key once the admin has been inactive for
+/// `ADMIN_RECOVERY_WINDOW_SECS`.
+#[derive(Accounts)]
+pub struct RecoverAdmin<'info> {
+    #[account(mut, seeds = [constants::MINT_CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, MintConfig>,
+    pub recovery_admin: Signer<'info>,
+}
+
 /// Update the URI of a minted Metaplex Core asset (admin only).
 /// The mint_authority PDA is the update authority on the asset.
 #[derive(Accounts)]
 #[instruction(source_chain: u16, nft_contract: Vec<u8>)]
 pub struct UpdateAssetUri<'info> {
-    #[account(seeds = [constants::MINT_CONFIG_SEED], bump = config.bump, has_one = admin)]
+    #[account(mut, seeds = [constants::MINT_CONFIG_SEED], bump = config.bump, has_one = admin)]
     pub config: Box<Account<'info, MintConfig>>,
     pub admin: Signer<'info>,
     #[account(mut)]
     pub payer: Signer<'info>,
+    /// Checked for `immutable_metadata` and to pin `collection_asset`
+    #[account(seeds = [constants::COLLECTION_SEED, &SourceChain(source_chain).to_le_seed_bytes(), &nft_contract],
+              bump = collection.bump)]
+    pub collection: Box<Account<'info, RebornCollection>>,
     /// The Metaplex Core asset to update
     /// CHECK: Validated by MPL Core CPI
     #[account(mut)]
     pub asset: UncheckedAccount<'info>,
     /// The collection the asset belongs to
-    /// CHECK: Validated by MPL Core CPI
+    /// CHECK: Must be the collection's MPL Core collection asset; membership validated by MPL Core CPI
+    #[account(address = collection.collection_asset_address @ ErrorCode::CollectionAssetMismatch)]
     pub collection_asset: UncheckedAccount<'info>,
     /// Mint authority PDA - update authority on the asset
     /// CHECK: PDA signer
-    #[account(seeds = [constants::MINT_AUTHORITY_SEED, &source_chain.to_le_bytes(), &nft_contract], bump)]
+    #[account(seeds = [constants::MINT_AUTHORITY_SEED, &SourceChain(source_chain).to_le_seed_bytes(), &nft_contract], bump)]
     pub mint_authority: UncheckedAccount<'info>,
     /// CHECK: Metaplex Core program
     #[account(address = mpl_core::ID)]
@@ -116,7 +395,7 @@ pub struct UpdateAssetUri<'info> {
 #[instruction(source_chain: u16, nft_contract: Vec<u8>)]
 pub struct InitRebornCollection<'info> {
     #[account(init, payer = payer, space = 8 + RebornCollection::INIT_SPACE,
-              seeds = [constants::COLLECTION_SEED, &source_chain.to_le_bytes(), &nft_contract], bump)]
+              seeds = [constants::COLLECTION_SEED, &SourceChain(source_chain).to_le_seed_bytes(), &nft_contract], bump)]
     pub collection: Box<Account<'info, RebornCollection>>,
     /// Payer — must be admin to prevent front-running of collection initialization
     #[account(mut, constraint = payer.key() == config.admin @ ErrorCode::Unauthorized)]
@@ -126,6 +405,64 @@ pub struct InitRebornCollection<'info> {
     pub system_program: Program<'info, System>,
 }
 
+/// Pause or unpause minting for a single source collection (admin only).
+/// The RebornCollection must exist; since `init_reborn_collection` is admin-only and
+/// required before the first mint, a not-yet-minted collection can be pre-paused by
+/// initializing it and pausing it in the same transaction.
+#[derive(Accounts)]
+#[instruction(source_chain: u16, nft_contract: Vec<u8>)]
+pub struct SetCollectionPaused<'info> {
+    #[account(mut, seeds = [constants::MINT_CONFIG_SEED], bump = config.bump, has_one = admin)]
+    pub config: Box<Account<'info, MintConfig>>,
+    pub admin: Signer<'info>,
+    #[account(mut, seeds = [constants::COLLECTION_SEED, &SourceChain(source_chain).to_le_seed_bytes(), &nft_contract],
+              bump = collection.bump)]
+    pub collection: Box<Account<'info, RebornCollection>>,
+}
+
+/// Restrict (or unrestrict) the receivers of one source collection (admin only).
+#[derive(Accounts)]
+#[instruction(source_chain: u16, nft_contract: Vec<u8>)]
+pub struct SetReceiverAllowlist<'info> {
+    #[account(mut, seeds = [constants::MINT_CONFIG_SEED], bump = config.bump, has_one = admin)]
+    pub config: Box<Account<'info, MintConfig>>,
+    pub admin: Signer<'info>,
+    #[account(mut, seeds = [constants::COLLECTION_SEED, &SourceChain(source_chain).to_le_seed_bytes(), &nft_contract],
+              bump = collection.bump)]
+    pub collection: Box<Account<'info, RebornCollection>>,
+}
+
+/// Lock (or unlock) metadata for future mints of one source collection (admin only).
+#[derive(Accounts)]
+#[instruction(source_chain: u16, nft_contract: Vec<u8>)]
+pub struct SetCollectionImmutableMetadata<'info> {
+    #[account(mut, seeds = [constants::MINT_CONFIG_SEED], bump = config.bump, has_one = admin)]
+    pub config: Box<Account<'info, MintConfig>>,
+    pub admin: Signer<'info>,
+    #[account(mut, seeds = [constants::COLLECTION_SEED, &SourceChain(source_chain).to_le_seed_bytes(), &nft_contract],
+              bump = collection.bump)]
+    pub collection: Box<Account<'info, RebornCollection>>,
+}
+
+/// Fold the `RebornCollection` created under big-endian `source_chain` seeds into the
+/// canonical little-endian one (admin only). The duplicate is closed to `admin`.
+#[derive(Accounts)]
+#[instruction(source_chain: u16, nft_contract: Vec<u8>)]
+pub struct MergeCollections<'info> {
+    #[account(mut, seeds = [constants::MINT_CONFIG_SEED], bump = config.bump, has_one = admin)]
+    pub config: Box<Account<'info, MintConfig>>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(mut, seeds = [constants::COLLECTION_SEED, &SourceChain(source_chain).to_le_seed_bytes(), &nft_contract],
+              bump = canonical.bump)]
+    pub canonical: Box<Account<'info, RebornCollection>>,
+    /// Seeded with the chain ID's big-endian bytes, i.e. the byte-swapped ID in LE
+    #[account(mut, close = admin,
+              seeds = [constants::COLLECTION_SEED, &SourceChain(source_chain.swap_bytes()).to_le_seed_bytes(), &nft_contract],
+              bump = duplicate.bump)]
+    pub duplicate: Box<Account<'info, RebornCollection>>,
+}
+
 /// Mint a reborn NFT - main entry point
 ///
 /// REPLAY PROTECTION: Instead of a ring buffer (which overflows after N entries),
@@ -140,8 +477,9 @@ pub struct InitRebornCollection<'info> {
     token_id: Vec<u8>,
 )]
 pub struct MintReborn<'info> {
-    /// Payer for the transaction — must be the admin (relayer) to prevent front-running
-    #[account(mut, constraint = payer.key() == config.admin @ ErrorCode::Unauthorized)]
+    /// Payer for the transaction — must be the admin or an allowlisted relayer to prevent
+    /// front-running
+    #[account(mut, constraint = is_authorized_relayer(&config, &payer.key()) @ ErrorCode::Unauthorized)]
     pub payer: Signer<'info>,
 
     /// Receiver of the reborn NFT.
@@ -162,28 +500,31 @@ pub struct MintReborn<'info> {
     pub sig_record: Box<Account<'info, SigUsed>>,
 
     /// Provenance record for this NFT (PDA).
-    /// `init` ensures each (source_chain, nft_contract, token_id) can only be minted once.
+    /// Each (source_chain, nft_contract, token_id) can only be minted once: `init` fails
+    /// if the PDA already exists, including a slot held by `reserve_provenance` (mint
+    /// those with `claim_reserved`).
     ///
-    /// NOTE: Seeds use `source_chain.to_le_bytes()` (little-endian). The relayer must
+    /// NOTE: Seeds use `SourceChain::to_le_seed_bytes()` (little-endian). The relayer must
     /// convert the Sui big-endian chain ID to LE before calling.
     #[account(init, payer = payer, space = 8 + Provenance::INIT_SPACE,
-              seeds = [constants::PROVENANCE_SEED, &source_chain.to_le_bytes(), &nft_contract, &token_id],
+              seeds = [constants::PROVENANCE_SEED, &SourceChain(source_chain).to_le_seed_bytes(), &nft_contract, &token_id],
               bump)]
     pub provenance: Box<Account<'info, Provenance>>,
 
     /// Our metadata PDA tracking per-collection state.
     /// Must be pre-created via `init_reborn_collection` before the first mint.
     /// This uses `mut` instead of `init_if_needed` to stay under the BPF 4KB stack limit.
-    #[account(mut, seeds = [constants::COLLECTION_SEED, &source_chain.to_le_bytes(), &nft_contract], bump = collection.bump)]
+    #[account(mut, seeds = [constants::COLLECTION_SEED, &SourceChain(source_chain).to_le_seed_bytes(), &nft_contract], bump = collection.bump)]
     pub collection: Box<Account<'info, RebornCollection>>,
 
     /// Mint authority PDA - signs the Metaplex Core CPIs
     /// CHECK: This is a PDA owned by our program; used only as a signer in CPIs.
-    #[account(seeds = [constants::MINT_AUTHORITY_SEED, &source_chain.to_le_bytes(), &nft_contract], bump)]
+    #[account(seeds = [constants::MINT_AUTHORITY_SEED, &SourceChain(source_chain).to_le_seed_bytes(), &nft_contract], bump)]
     pub mint_authority: UncheckedAccount<'info>,
 
     /// The Metaplex Core collection asset account.
-    /// On first mint this is created by `CreateCollectionV2CpiBuilder` (must be a signer/new keypair).
+    /// On first mint this is created by `CreateCollectionV2CpiBuilder`: either a new keypair
+    /// signing the outer tx, or the `pdas::collection_asset` PDA, which the program signs for.
     /// On subsequent mints this is the existing collection asset; we pass it to `CreateV2`
     /// so the new NFT is linked to the collection.
     /// CHECK: Owned by MPL Core after the first mint CPI; validated via CPI.
@@ -195,6 +536,11 @@ pub struct MintReborn<'info> {
     #[account(mut)]
     pub asset: UncheckedAccount<'info>,
 
+    /// Reverse index asset -> provenance PDA, so clients can resolve an asset's origin
+    #[account(init, payer = payer, space = 8 + AssetProvenanceLink::INIT_SPACE,
+              seeds = [constants::ASSET_PROVENANCE_SEED, asset.key().as_ref()], bump)]
+    pub asset_link: Box<Account<'info, AssetProvenanceLink>>,
+
     /// CHECK: Metaplex Core program
     #[account(address = mpl_core::ID)]
     pub mpl_core_program: AccountInfo<'info>,
@@ -209,710 +555,4403 @@ pub struct MintReborn<'info> {
     /// Loaded on every mint to verify the signature was produced by the correct key.
     #[account(seeds = [constants::MINT_CONFIG_SEED], bump = config.bump)]
     pub config: Box<Account<'info, MintConfig>>,
-}
 
-// NOTE: SealAndMintNative (Solana-native path) is disabled pending anchor-spl 0.30.1 / spl-token 4.x
-// compatibility fix for TokenAccount/Mint Discriminator traits. The cross-chain flow (mint_reborn)
-// is unaffected. Re-enable by removing the cfg gate and fixing the token type imports.
-// See: https://github.com/coral-xyz/anchor/issues/3025
+    /// Receiver override PDA for this token. Always passed (even when never created)
+    /// so an existing override cannot be bypassed by omitting it.
+    /// CHECK: Address verified by seeds; deserialized in the body only if initialized.
+    #[account(seeds = [constants::RECEIVER_OVERRIDE_SEED, &SourceChain(source_chain).to_le_seed_bytes(), &nft_contract, &token_id], bump)]
+    pub receiver_override: UncheckedAccount<'info>,
 
-// ============ Account Structs ============
+    /// Redirected owner of the reborn NFT; required when `receiver_override` exists.
+    /// CHECK: Must equal `receiver_override.new_receiver` (checked in body).
+    pub override_receiver: Option<UncheckedAccount<'info>>,
 
-/// Stores the shared IKA minting dWallet's Ed25519 public key.
-/// This is the single key that signs ALL seal attestations.
-/// Set once at init, updatable only by admin (for key rotation).
-/// Seeds: ["mint_config"]. Never accepted as instruction input.
-#[account]
-#[derive(InitSpace)]
-pub struct MintConfig {
-    /// Ed25519 public key of the shared IKA minting dWallet (32 bytes)
-    pub minting_pubkey: [u8; 32],
-    /// Admin who can update the config
-    pub admin: Pubkey,
-    /// PDA bump
-    pub bump: u8,
+    /// Enumerable collection registry. Only required when `config.maintain_registry`
+    /// is set; omit it otherwise to keep first-mint transactions light.
+    #[account(mut, seeds = [constants::COLLECTION_REGISTRY_SEED], bump = registry.bump)]
+    pub registry: Option<Box<Account<'info, CollectionRegistry>>>,
 }
 
-/// Marker account proving a signature was used. Its mere existence blocks replays.
-/// Seeds: ["sig_used", sha256(signature)]. Space: 8 + 1 = 9 bytes per signature.
-#[account]
-#[derive(InitSpace)]
-pub struct SigUsed {
-    /// Bump stored for completeness (account existence is the actual protection).
-    pub bump: u8,
-}
+/// Mint into a reserved provenance slot. Same accounts as `MintReborn`, except that
+/// `provenance` already exists (held by `reserve_provenance`) instead of being created.
+#[derive(Accounts)]
+#[instruction(
+    sig_hash: Vec<u8>,
+    source_chain: u16,
+    nft_contract: Vec<u8>,
+    token_id: Vec<u8>,
+)]
+pub struct ClaimReserved<'info> {
+    /// Payer for the transaction — must be the admin or an allowlisted relayer to prevent
+    /// front-running
+    #[account(mut, constraint = is_authorized_relayer(&config, &payer.key()) @ ErrorCode::Unauthorized)]
+    pub payer: Signer<'info>,
 
-/// Registry of source collections that have been created on Solana
-#[account]
-#[derive(InitSpace)]
-pub struct CollectionRegistry {
-    /// Number of collections registered
-    pub count: u64,
-    /// Bump for PDA
-    pub bump: u8,
-    #[max_len(50)]
-    pub collections: Vec<CollectionEntry>,
-}
+    /// Receiver of the reborn NFT.
+    ///
+    /// SECURITY NOTE: We do not restrict this to any particular account type.
+    /// Sending to a PDA or program-owned account is allowed but the owner
+    /// will need to know how to handle MPL Core assets. The caller (relayer)
+    /// is responsible for ensuring this is a sensible destination.
+    /// We reject the system program address and the zero key as obviously wrong.
+    /// CHECK: validated in instruction body (not system program / zero key)
+    pub receiver: UncheckedAccount<'info>,
 
-impl CollectionRegistry {
-    /// Check if a collection exists
-    pub fn find_collection(&self, source_chain: u16, nft_contract: &[u8]) -> Option<&CollectionEntry> {
-        self.collections.iter().find(|c| c.source_chain == source_chain && c.nft_contract == nft_contract)
-    }
+    /// Per-signature PDA for replay protection.
+    /// Seeds: ["sig_used", sig_hash]. `init` fails if already exists → replay blocked.
+    /// Space: 8 (discriminator) + 1 (bump byte) = 9 bytes.
+    #[account(init, payer = payer, space = 8 + SigUsed::INIT_SPACE,
+              seeds = [constants::SIG_USED_SEED, &sig_hash], bump)]
+    pub sig_record: Box<Account<'info, SigUsed>>,
 
-    /// Add a new collection
-    pub fn add_collection(&mut self, entry: CollectionEntry) {
-        self.collections.push(entry);
-        self.count = self.count.saturating_add(1);
-    }
-}
+    /// Provenance slot created by `reserve_provenance`; must still be reserved and
+    /// unminted (see `check_reservation`).
+    #[account(mut,
+              seeds = [constants::PROVENANCE_SEED, &SourceChain(source_chain).to_le_seed_bytes(), &nft_contract, &token_id],
+              bump = provenance.bump)]
+    pub provenance: Box<Account<'info, Provenance>>,
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
-#[derive(InitSpace)]
-pub struct CollectionEntry {
-    pub source_chain: u16,
-    #[max_len(64)]
-    pub nft_contract: Vec<u8>,
-    pub collection_address: Pubkey,
-    pub created_at: i64,
-}
+    /// Our metadata PDA tracking per-collection state.
+    /// Must be pre-created via `init_reborn_collection` before the first mint.
+    /// This uses `mut` instead of `init_if_needed` to stay under the BPF 4KB stack limit.
+    #[account(mut, seeds = [constants::COLLECTION_SEED, &SourceChain(source_chain).to_le_seed_bytes(), &nft_contract], bump = collection.bump)]
+    pub collection: Box<Account<'info, RebornCollection>>,
 
-/// Per-collection metadata stored in our program's PDA.
-/// NOTE: This is separate from the Metaplex Core collection asset account (`collection_asset`).
-/// The MPL Core collection asset is owned by the MPL Core program; this PDA is owned
-/// by our program and tracks reborn-specific metadata.
-#[account]
-#[derive(InitSpace)]
-pub struct RebornCollection {
-    pub source_chain: u16,
-    #[max_len(64)]
-    pub nft_contract: Vec<u8>,
-    #[max_len(32)]
-    pub name: String,
-    /// Address of the Metaplex Core collection asset account
-    pub collection_asset_address: Pubkey,
-    pub total_minted: u64,
-    pub is_initialized: bool,
-    pub bump: u8,
-}
+    /// Mint authority PDA - signs the Metaplex Core CPIs
+    /// CHECK: This is a PDA owned by our program; used only as a signer in CPIs.
+    #[account(seeds = [constants::MINT_AUTHORITY_SEED, &SourceChain(source_chain).to_le_seed_bytes(), &nft_contract], bump)]
+    pub mint_authority: UncheckedAccount<'info>,
 
-/// Provenance record for a reborn NFT
-#[account]
-#[derive(InitSpace)]
-pub struct Provenance {
-    pub source_chain: u16,
-    #[max_len(64)]
-    pub nft_contract: Vec<u8>,
-    #[max_len(64)]
-    pub token_id: Vec<u8>,
-    /// URI up to 512 bytes (covers IPFS ~80, Arweave ~100, and future formats)
-    #[max_len(512)]
-    pub token_uri: String,
-    pub dwallet_pubkey: [u8; 32],
-    pub signature: [u8; 64],
-    pub receiver: Pubkey,
-    pub sealed_at: i64,
-    pub is_initialized: bool,
-    pub bump: u8,
-}
+    /// The Metaplex Core collection asset account.
+    /// On first mint this is created by `CreateCollectionV2CpiBuilder`: either a new keypair
+    /// signing the outer tx, or the `pdas::collection_asset` PDA, which the program signs for.
+    /// On subsequent mints this is the existing collection asset; we pass it to `CreateV2`
+    /// so the new NFT is linked to the collection.
+    /// CHECK: Owned by MPL Core after the first mint CPI; validated via CPI.
+    #[account(mut)]
+    pub collection_asset: UncheckedAccount<'info>,
 
-// ============ Program ============
+    /// New Metaplex Core asset (keypair, must be a signer in outer tx)
+    /// CHECK: New account created by MPL Core CPI.
+    #[account(mut)]
+    pub asset: UncheckedAccount<'info>,
 
-#[program]
+    /// Reverse index asset -> provenance PDA, so clients can resolve an asset's origin
+    #[account(init, payer = payer, space = 8 + AssetProvenanceLink::INIT_SPACE,
+              seeds = [constants::ASSET_PROVENANCE_SEED, asset.key().as_ref()], bump)]
+    pub asset_link: Box<Account<'info, AssetProvenanceLink>>,
+
+    /// CHECK: Metaplex Core program
+    #[account(address = mpl_core::ID)]
+    pub mpl_core_program: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: Instructions sysvar - needed for Ed25519 verification
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    /// Mint config PDA — stores the shared IKA minting dWallet pubkey.
+    /// Loaded on every mint to verify the signature was produced by the correct key.
+    #[account(seeds = [constants::MINT_CONFIG_SEED], bump = config.bump)]
+    pub config: Box<Account<'info, MintConfig>>,
+
+    /// Receiver override PDA for this token. Always passed (even when never created)
+    /// so an existing override cannot be bypassed by omitting it.
+    /// CHECK: Address verified by seeds; deserialized in the body only if initialized.
+    #[account(seeds = [constants::RECEIVER_OVERRIDE_SEED, &SourceChain(source_chain).to_le_seed_bytes(), &nft_contract, &token_id], bump)]
+    pub receiver_override: UncheckedAccount<'info>,
+
+    /// Redirected owner of the reborn NFT; required when `receiver_override` exists.
+    /// CHECK: Must equal `receiver_override.new_receiver` (checked in body).
+    pub override_receiver: Option<UncheckedAccount<'info>>,
+
+    /// Enumerable collection registry. Only required when `config.maintain_registry`
+    /// is set; omit it otherwise to keep first-mint transactions light.
+    #[account(mut, seeds = [constants::COLLECTION_REGISTRY_SEED], bump = registry.bump)]
+    pub registry: Option<Box<Account<'info, CollectionRegistry>>>,
+}
+
+/// Re-point a seal made to a stale receiver (admin only). Nothing on-chain proves who
+/// the signed receiver of an unminted token is, so only the admin may create or change
+/// an override. Can be updated until the token is minted.
+#[derive(Accounts)]
+#[instruction(source_chain: u16, nft_contract: Vec<u8>, token_id: Vec<u8>)]
+pub struct SetReceiverOverride<'info> {
+    #[account(mut, seeds = [constants::MINT_CONFIG_SEED], bump = config.bump, has_one = admin)]
+    pub config: Box<Account<'info, MintConfig>>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(init_if_needed, payer = admin, space = 8 + ReceiverOverride::INIT_SPACE,
+              seeds = [constants::RECEIVER_OVERRIDE_SEED, &SourceChain(source_chain).to_le_seed_bytes(), &nft_contract, &token_id],
+              bump)]
+    pub receiver_override: Box<Account<'info, ReceiverOverride>>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Remove a receiver override (admin only), so the token mints to its signed receiver
+/// again. Rent goes back to `admin`.
+#[derive(Accounts)]
+#[instruction(source_chain: u16, nft_contract: Vec<u8>, token_id: Vec<u8>)]
+pub struct ClearReceiverOverride<'info> {
+    #[account(mut, seeds = [constants::MINT_CONFIG_SEED], bump = config.bump, has_one = admin)]
+    pub config: Box<Account<'info, MintConfig>>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(mut, close = admin,
+              seeds = [constants::RECEIVER_OVERRIDE_SEED, &SourceChain(source_chain).to_le_seed_bytes(), &nft_contract, &token_id],
+              bump = receiver_override.bump)]
+    pub receiver_override: Box<Account<'info, ReceiverOverride>>,
+}
+
+/// Backfill provenance for a legacy reborn NFT minted before this program existed (admin only).
+/// Uses the same provenance PDA seeds as `mint_reborn`, so `init` still enforces uniqueness.
+#[derive(Accounts)]
+#[instruction(source_chain: u16, nft_contract: Vec<u8>, token_id: Vec<u8>)]
+pub struct AdminRecordProvenance<'info> {
+    #[account(mut, seeds = [constants::MINT_CONFIG_SEED], bump = config.bump, has_one = admin)]
+    pub config: Box<Account<'info, MintConfig>>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(init, payer = admin, space = 8 + Provenance::INIT_SPACE,
+              seeds = [constants::PROVENANCE_SEED, &SourceChain(source_chain).to_le_seed_bytes(), &nft_contract, &token_id],
+              bump)]
+    pub provenance: Box<Account<'info, Provenance>>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Hold a provenance slot for a later `claim_reserved` (admin only). Same PDA seeds as
+/// `mint_reborn`; `init` fails if the token was already reserved or minted.
+#[derive(Accounts)]
+#[instruction(source_chain: u16, nft_contract: Vec<u8>, token_id: Vec<u8>)]
+pub struct ReserveProvenance<'info> {
+    #[account(mut, seeds = [constants::MINT_CONFIG_SEED], bump = config.bump, has_one = admin)]
+    pub config: Box<Account<'info, MintConfig>>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(init, payer = admin, space = 8 + Provenance::INIT_SPACE,
+              seeds = [constants::PROVENANCE_SEED, &SourceChain(source_chain).to_le_seed_bytes(), &nft_contract, &token_id],
+              bump)]
+    pub provenance: Box<Account<'info, Provenance>>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Import an `ika_tensei` v3 `ReincarnationRecord` as a `Provenance` (admin only).
+/// Same provenance PDA seeds as `mint_reborn`, so a second import (or an import of a
+/// seal already minted here) fails on `init`.
+#[derive(Accounts)]
+#[instruction(record: V3RecordImport)]
+pub struct AdminImportV3Record<'info> {
+    #[account(mut, seeds = [constants::MINT_CONFIG_SEED], bump = config.bump, has_one = admin)]
+    pub config: Box<Account<'info, MintConfig>>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(init, payer = admin, space = 8 + Provenance::INIT_SPACE,
+              seeds = [constants::PROVENANCE_SEED, &SourceChain(record.source_chain).to_le_seed_bytes(), &record.source_contract, &record.token_id],
+              bump)]
+    pub provenance: Box<Account<'info, Provenance>>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Solana-native path: seal an NFT held by `user` and mint its reborn in one instruction.
+/// Token accounts go through `token_interface` (`InterfaceAccount`), which sidesteps the
+/// `TokenAccount`/`Mint` discriminator mismatch between anchor-spl 0.30.1 and spl-token 4.x
+/// (https://github.com/coral-xyz/anchor/issues/3025) and accepts Token-2022 mints.
+#[derive(Accounts)]
+#[instruction(token_uri: String, collection_name: String, seal_mode: SealMode, source_collection: Option<Pubkey>)]
+pub struct SealAndMintNative<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// Original NFT mint. `mut` because `SealMode::Burn` reduces its supply.
+    /// Must be a real NFT (0 decimals, supply 1), not a fungible mint with a balance of 1.
+    /// SPL Token or Token-2022 (`token_interface`); owner is checked against `token_program`.
+    #[account(mut, mint::token_program = token_program,
+              constraint = is_single_edition(nft_mint.decimals, nft_mint.supply) @ ErrorCode::NotAnNft)]
+    pub nft_mint: Box<InterfaceAccount<'info, anchor_spl::token_interface::Mint>>,
+
+    #[account(mut, token::mint = nft_mint, token::authority = user, token::token_program = token_program,
+              constraint = nft_token_account.amount == 1 @ ErrorCode::InvalidNftAmount)]
+    pub nft_token_account: Box<InterfaceAccount<'info, anchor_spl::token_interface::TokenAccount>>,
+
+    /// Replay guard: declared ahead of the vault and collection so a re-seal of the same
+    /// mint is caught by `ensure_not_sealed` before any transfer. `init_if_needed`
+    /// (not `init`) so that case returns `AlreadyMinted` instead of a system-program error.
+    #[account(init_if_needed, payer = user, space = 8 + Provenance::INIT_SPACE,
+              seeds = [constants::PROVENANCE_SEED, &SourceChain::SOLANA.to_le_seed_bytes(),
+                       native_grouping_key(source_collection, &nft_mint.key()).as_ref(), nft_mint.key().as_ref()],
+              bump)]
+    pub provenance: Box<Account<'info, Provenance>>,
+
+    /// Token Metadata account of `nft_mint`. Required with `source_collection`, whose
+    /// verified membership it proves; omit otherwise.
+    #[account(constraint = source_metadata.mint == nft_mint.key() @ ErrorCode::SourceCollectionNotVerified)]
+    pub source_metadata: Option<Box<Account<'info, anchor_spl::metadata::MetadataAccount>>>,
+
+    /// Permanent vault for `SealMode::Lock`; omit for `SealMode::Burn`.
+    #[account(init_if_needed, payer = user, token::mint = nft_mint, token::authority = sealed_nft_vault,
+              token::token_program = token_program,
+              seeds = [constants::SEALED_VAULT_SEED, nft_mint.key().as_ref()], bump)]
+    pub sealed_nft_vault: Option<Box<InterfaceAccount<'info, anchor_spl::token_interface::TokenAccount>>>,
+
+    #[account(init_if_needed, payer = user, space = 8 + RebornCollection::INIT_SPACE,
+              seeds = [constants::COLLECTION_SEED, &SourceChain::SOLANA.to_le_seed_bytes(),
+                       native_grouping_key(source_collection, &nft_mint.key()).as_ref()], bump)]
+    pub collection: Box<Account<'info, RebornCollection>>,
+
+    #[account(mut, seeds = [constants::COLLECTION_REGISTRY_SEED], bump = registry.bump)]
+    pub registry: Option<Box<Account<'info, CollectionRegistry>>>,
+
+    /// CHECK: PDA signer for the Metaplex Core CPIs.
+    #[account(seeds = [constants::MINT_AUTHORITY_SEED, &SourceChain::SOLANA.to_le_seed_bytes(),
+                       native_grouping_key(source_collection, &nft_mint.key()).as_ref()], bump)]
+    pub mint_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Created or validated by MPL Core CPI.
+    #[account(mut)]
+    pub collection_asset: UncheckedAccount<'info>,
+
+    /// CHECK: New asset keypair, created by MPL Core CPI.
+    #[account(mut)]
+    pub asset: Signer<'info>,
+
+    #[account(seeds = [constants::MINT_CONFIG_SEED], bump = config.bump)]
+    pub config: Box<Account<'info, MintConfig>>,
+
+    /// Receives `native_seal_fee`; the admin later withdraws it to `config.treasury`.
+    #[account(mut, seeds = [constants::FEE_VAULT_SEED], bump = fee_vault.bump)]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+
+    /// Programmable NFT (pNFT) accounts, needed only to lock a pNFT: its token account is
+    /// always frozen, so it moves through Token Metadata's `TransferV1` (which also
+    /// enforces the NFT's rule set) instead of an SPL transfer. Requires `source_metadata`.
+    /// Omit all of them for a standard NFT.
+    /// CHECK: Master edition of `nft_mint`, validated by Token Metadata.
+    pub source_edition: Option<UncheckedAccount<'info>>,
+    /// CHECK: Token record of `nft_token_account`, validated by Token Metadata.
+    #[account(mut)]
+    pub owner_token_record: Option<UncheckedAccount<'info>>,
+    /// CHECK: Token record of `sealed_nft_vault`, created by Token Metadata.
+    #[account(mut)]
+    pub vault_token_record: Option<UncheckedAccount<'info>>,
+    /// CHECK: The pNFT's rule set, if it has one; validated by Token Metadata.
+    pub authorization_rules: Option<UncheckedAccount<'info>>,
+    /// CHECK: Token Auth Rules program, with `authorization_rules`.
+    pub authorization_rules_program: Option<UncheckedAccount<'info>>,
+    pub token_metadata_program: Option<Program<'info, anchor_spl::metadata::Metadata>>,
+    /// CHECK: SPL Associated Token program, checked by Token Metadata.
+    pub associated_token_program: Option<UncheckedAccount<'info>>,
+    /// CHECK: Instructions sysvar, read by Token Metadata's rule checks.
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Metaplex Core program
+    #[account(address = mpl_core::ID)]
+    pub mpl_core_program: AccountInfo<'info>,
+    pub token_program: Interface<'info, anchor_spl::token_interface::TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// How `seal_and_mint_native` disposes of the original NFT.
+///
+/// `Burn` is irreversible: the token is burned and its account closed, so there is
+/// nothing left to unseal. `Lock` keeps it in the vault PDA (still permanent, but the
+/// supply on the source collection is unchanged).
+///
+/// Token Metadata NFTs: standard (`NonFungible`) NFTs seal either way with plain SPL
+/// instructions. Programmable NFTs can only be locked, via Token Metadata `TransferV1`
+/// (see `native_transfer_kind`); burning them would need `BurnV1` and is refused.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SealMode {
+    /// Transfer into the `sealed_vault` PDA
+    Lock,
+    /// SPL Token burn + close of the user's token account
+    Burn,
+}
+
+/// How `SealMode::Lock` moves the original into `sealed_nft_vault`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum NativeTransfer {
+    /// SPL `transfer_checked` (plain SPL / Token-2022 mints and standard Token Metadata NFTs)
+    Token,
+    /// Token Metadata `TransferV1`, for programmable NFTs
+    TokenMetadata,
+}
+
+/// Arguments of `mint_reborn` and `claim_reserved`. Borsh encodes a struct as its
+/// fields in order, so the instruction data is the same as the former flat argument
+/// list, and the `#[instruction]` prefix of `MintReborn`/`ClaimReserved` still reads
+/// the first four fields.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct MintRebornArgs {
+    /// sha256(signature) — PDA seed for replay protection
+    pub sig_hash: Vec<u8>,
+    /// Source chain ID (little-endian in PDA seeds)
+    pub source_chain: u16,
+    pub nft_contract: Vec<u8>,
+    pub token_id: Vec<u8>,
+    pub signature: Vec<u8>,
+    pub token_uri: String,
+    pub collection_name: String,
+    pub royalty_basis_points: u16,
+    pub dao_treasury: Pubkey,
+    pub wormhole_sequence: u64,
+    pub source_sequence: u64,
+    pub source_block_hint: [u8; 32],
+    pub valid_until_slot: u64,
+    pub hash_algo: u8,
+    pub delegate: Option<Pubkey>,
+    pub symbol: Option<String>,
+    pub receiver_proof: Vec<[u8; 32]>,
+}
+
+/// Fields of an `ika_tensei` v3 `ReincarnationRecord` needed to rebuild its provenance.
+/// `seal_hash` and `payer` have no `Provenance` counterpart and are not imported.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct V3RecordImport {
+    pub source_chain: u16,
+    pub source_contract: Vec<u8>,
+    pub token_id: Vec<u8>,
+    pub attestation_pubkey: [u8; 32],
+    pub recipient: Pubkey,
+    /// The v3 Metaplex NFT mint (becomes `Provenance.asset`)
+    pub mint: Pubkey,
+    pub minted: bool,
+    pub verified_at: i64,
+}
+
+/// Return data of `seal_and_mint_native`, so callers can reference the new accounts
+/// without re-deriving them.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct NativeSealResult {
+    /// The reborn Metaplex Core asset
+    pub asset: Pubkey,
+    /// The `Provenance` PDA written for the seal
+    pub provenance: Pubkey,
+}
+
+/// Trait names under which `mint_reborn` publishes provenance in the asset's
+/// Attributes plugin, so marketplaces list it with their own trait vocabulary.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, PartialEq, Eq, Debug)]
+pub struct AttributeSchema {
+    /// Key for the source chain ID
+    #[max_len(32)]
+    pub source_chain_key: String,
+    /// Key for the `0x`-prefixed source contract
+    #[max_len(32)]
+    pub source_contract_key: String,
+    /// Key for the source token ID
+    #[max_len(32)]
+    pub token_id_key: String,
+}
+
+/// Return data of `get_mint_config`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct MintConfigView {
+    /// Ed25519 public key of the shared IKA minting dWallet
+    pub minting_pubkey: [u8; 32],
+    /// Admin who can update the config
+    pub admin: Pubkey,
+    /// `MintConfig` PDA bump
+    pub bump: u8,
+}
+
+impl From<&MintConfig> for MintConfigView {
+    fn from(config: &MintConfig) -> Self {
+        Self { minting_pubkey: config.minting_pubkey, admin: config.admin, bump: config.bump }
+    }
+}
+
+// ============ Account Structs ============
+
+/// Stores the shared IKA minting dWallet's Ed25519 public key.
+/// This is the single key that signs ALL seal attestations.
+/// Set once at init, updatable only by admin (for key rotation).
+/// Seeds: ["mint_config"]. Never accepted as instruction input.
+#[account]
+#[derive(InitSpace)]
+pub struct MintConfig {
+    /// Ed25519 public key of the shared IKA minting dWallet (32 bytes)
+    pub minting_pubkey: [u8; 32],
+    /// Admin who can update the config
+    pub admin: Pubkey,
+    /// Require the Ed25519 precompile instruction to carry exactly one signature
+    /// in a single-entry layout (see `verify_ed25519_ix_data`)
+    pub strict_ed25519: bool,
+    /// Append newly created collections to the `CollectionRegistry` (the default, as
+    /// before the flag existed). When false the registry is not touched; the per-collection `RebornCollection` PDA proves existence.
+    pub maintain_registry: bool,
+    /// Lamports charged per `seal_and_mint_native` (0 = free)
+    pub native_seal_fee: u64,
+    /// Destination of `withdraw_fees` (fees accumulate in the `FeeVault` PDA first)
+    pub treasury: Pubkey,
+    /// Backup key that can claim `admin` after `ADMIN_RECOVERY_WINDOW_SECS` of admin inactivity
+    pub recovery_admin: Option<Pubkey>,
+    /// Unix timestamp of the last admin-signed config/admin instruction. Not bumped by
+    /// `mint_reborn`, which keeps `config` read-only so mints don't contend on it.
+    pub last_admin_activity: i64,
+    /// Extra relayers allowed to pay for `mint_reborn` besides `admin`. Empty (the
+    /// default) keeps minting admin-only.
+    #[max_len(8)]
+    pub allowed_relayers: Vec<Pubkey>,
+    /// Most signature entries accepted in the Ed25519 precompile instruction; checked
+    /// before any entry is parsed so an oversized batch is rejected cheaply
+    pub max_signatures_per_tx: u8,
+    /// Trait names for the provenance Attributes plugin on minted assets. `None` (the
+    /// default) attaches no provenance attributes.
+    pub attribute_schema: Option<AttributeSchema>,
+    /// Naming policy for `seal_and_mint_native` assets: appended to the collection name,
+    /// or, if it contains `{name}`, a template with the collection name substituted
+    #[max_len(24)]
+    pub reborn_suffix: String,
+    /// PDA bump
+    pub bump: u8,
+}
+
+/// `MintConfig` layout as first deployed, before `strict_ed25519`.
+/// Only read by `migrate_mint_config`.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace)]
+pub struct LegacyMintConfig {
+    pub minting_pubkey: [u8; 32],
+    pub admin: Pubkey,
+    pub bump: u8,
+}
+
+/// Marker account proving a signature was used. Its mere existence blocks replays.
+/// Seeds: ["sig_used", sha256(signature)]. Space: 8 + 1 = 9 bytes per signature.
+#[account]
+#[derive(InitSpace)]
+pub struct SigUsed {
+    /// Bump stored for completeness (account existence is the actual protection).
+    pub bump: u8,
+}
+
+/// Registry of source collections that have been created on Solana.
+/// Advisory only: it is not updated while `MintConfig.maintain_registry` is false, so it
+/// may be stale or empty. The `RebornCollection` PDA is the source of truth.
+#[account]
+#[derive(InitSpace)]
+pub struct CollectionRegistry {
+    /// Number of collections registered
+    pub count: u64,
+    /// Bump for PDA
+    pub bump: u8,
+    #[max_len(50)]
+    pub collections: Vec<CollectionEntry>,
+}
+
+impl CollectionRegistry {
+    /// Look up a registered collection. `None` does not mean the collection doesn't
+    /// exist on-chain — check the `RebornCollection` PDA for that.
+    pub fn find_collection(&self, source_chain: u16, nft_contract: &[u8]) -> Option<&CollectionEntry> {
+        self.collections.iter().find(|c| c.source_chain == source_chain && c.nft_contract == nft_contract)
+    }
+
+    /// Add a new collection
+    pub fn add_collection(&mut self, entry: CollectionEntry) {
+        self.collections.push(entry);
+        self.count = self.count.saturating_add(1);
+    }
+
+    /// Add a collection unless it is already listed. Returns whether it was added.
+    pub fn register_collection(&mut self, entry: CollectionEntry) -> Result<bool> {
+        if self.find_collection(entry.source_chain, &entry.nft_contract).is_some() {
+            return Ok(false);
+        }
+        require!(
+            self.collections.len() < constants::MAX_REGISTRY_COLLECTIONS,
+            ErrorCode::RegistryFull
+        );
+        self.add_collection(entry);
+        Ok(true)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+#[derive(InitSpace)]
+pub struct CollectionEntry {
+    pub source_chain: u16,
+    #[max_len(64)]
+    pub nft_contract: Vec<u8>,
+    pub collection_address: Pubkey,
+    pub created_at: i64,
+}
+
+/// Per-collection metadata stored in our program's PDA.
+/// NOTE: This is separate from the Metaplex Core collection asset account (`collection_asset`).
+/// The MPL Core collection asset is owned by the MPL Core program; this PDA is owned
+/// by our program and tracks reborn-specific metadata.
+#[account]
+#[derive(InitSpace)]
+pub struct RebornCollection {
+    pub source_chain: u16,
+    #[max_len(64)]
+    pub nft_contract: Vec<u8>,
+    #[max_len(32)]
+    pub name: String,
+    /// Marketplace grouping symbol, empty if none was given on first mint
+    #[max_len(10)]
+    pub symbol: String,
+    /// Address of the Metaplex Core collection asset account
+    pub collection_asset_address: Pubkey,
+    pub total_minted: u64,
+    pub is_initialized: bool,
+    /// Per-collection pause, independent of any protocol-wide switch
+    pub paused: bool,
+    /// Opt-in lock: new mints carry MPL Core's ImmutableMetadata plugin and
+    /// `update_asset_uri` is refused. Off by default, so URIs stay refreshable.
+    pub immutable_metadata: bool,
+    /// Merkle root of the receivers this collection may mint to (see
+    /// `verify_receiver_allowlist`). None = any receiver.
+    pub receiver_allowlist_root: Option<[u8; 32]>,
+    pub bump: u8,
+}
+
+/// `RebornCollection` layout as first deployed, before `symbol`, `paused`,
+/// `immutable_metadata` and `receiver_allowlist_root`. Only read by `migrate_collection`.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace)]
+pub struct LegacyRebornCollection {
+    pub source_chain: u16,
+    #[max_len(64)]
+    pub nft_contract: Vec<u8>,
+    #[max_len(32)]
+    pub name: String,
+    pub collection_asset_address: Pubkey,
+    pub total_minted: u64,
+    pub is_initialized: bool,
+    pub bump: u8,
+}
+
+/// Redirects a mint away from the receiver committed to in the signed message.
+/// Seeds: ["receiver_override", source_chain_le, nft_contract, token_id].
+#[account]
+#[derive(InitSpace)]
+pub struct ReceiverOverride {
+    /// Receiver the dWallet signed over (signature is still verified against this)
+    pub original_receiver: Pubkey,
+    /// Receiver that actually gets the reborn NFT
+    pub new_receiver: Pubkey,
+    pub bump: u8,
+}
+
+/// Reverse index from a reborn asset to its `Provenance` PDA, written at mint.
+/// Seeds: ["asset_provenance", asset]. Resolve with `pdas::asset_provenance_link`.
+#[account]
+#[derive(InitSpace)]
+pub struct AssetProvenanceLink {
+    pub provenance: Pubkey,
+    pub bump: u8,
+}
+
+/// Program-owned vault holding native seal fees, with a running ledger of what was
+/// collected and withdrawn. Seeds: ["fee_vault"].
+#[account]
+#[derive(InitSpace)]
+pub struct FeeVault {
+    /// Lifetime lamports paid in as `native_seal_fee`
+    pub total_collected: u64,
+    /// Lifetime lamports withdrawn to the treasury
+    pub total_withdrawn: u64,
+    pub bump: u8,
+}
+
+impl FeeVault {
+    /// Fees collected and not yet withdrawn. The vault's rent reserve is excluded.
+    pub fn balance(&self) -> u64 {
+        self.total_collected.saturating_sub(self.total_withdrawn)
+    }
+
+    pub fn record_fee(&mut self, amount: u64) -> Result<()> {
+        self.total_collected = self.total_collected.checked_add(amount).ok_or(ErrorCode::FeeOverflow)?;
+        Ok(())
+    }
+
+    pub fn record_withdrawal(&mut self, amount: u64) -> Result<()> {
+        require!(amount <= self.balance(), ErrorCode::InsufficientFeeVaultBalance);
+        self.total_withdrawn += amount;
+        Ok(())
+    }
+}
+
+/// Provenance record for a reborn NFT
+#[account]
+#[derive(InitSpace)]
+pub struct Provenance {
+    pub source_chain: u16,
+    #[max_len(64)]
+    pub nft_contract: Vec<u8>,
+    #[max_len(64)]
+    pub token_id: Vec<u8>,
+    /// URI up to 512 bytes (covers IPFS ~80, Arweave ~100, and future formats)
+    #[max_len(512)]
+    pub token_uri: String,
+    pub dwallet_pubkey: [u8; 32],
+    pub signature: [u8; 64],
+    pub receiver: Pubkey,
+    /// The reborn Metaplex Core asset
+    pub asset: Pubkey,
+    /// Wormhole sequence of the seal message that authorized this mint (covered by the signature)
+    pub wormhole_sequence: u64,
+    /// Source-chain seal sequence/block number (covered by the signature; 0 if not applicable)
+    pub source_sequence: u64,
+    /// Source-chain block hash or other reorg marker (covered by the signature; zeroed if not applicable)
+    pub source_block_hint: [u8; 32],
+    pub sealed_at: i64,
+    pub is_initialized: bool,
+    /// True when recorded by `admin_record_provenance` for a legacy off-protocol mint
+    /// (no signature was verified; `dwallet_pubkey` and `signature` are zeroed)
+    pub backfilled: bool,
+    /// True when copied from an `ika_tensei` v3 `ReincarnationRecord` by
+    /// `admin_import_v3_record`; `dwallet_pubkey` holds the v3 attestation key
+    pub imported_from_v3: bool,
+    /// Held by `reserve_provenance` for a staged drop: only the source fields are set,
+    /// `is_initialized` is false, and only `claim_reserved` can mint into it
+    pub reserved: bool,
+    pub bump: u8,
+}
+
+/// `Provenance` layout as first deployed, before `asset` and the fields after it.
+/// Only read by `migrate_provenance`.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace)]
+pub struct LegacyProvenance {
+    pub source_chain: u16,
+    #[max_len(64)]
+    pub nft_contract: Vec<u8>,
+    #[max_len(64)]
+    pub token_id: Vec<u8>,
+    #[max_len(512)]
+    pub token_uri: String,
+    pub dwallet_pubkey: [u8; 32],
+    pub signature: [u8; 64],
+    pub receiver: Pubkey,
+    pub sealed_at: i64,
+    pub is_initialized: bool,
+    pub bump: u8,
+}
+
+// ============ Events ============
+
+/// A native seal locked `nft_mint` in its `sealed_nft_vault` (`SealMode::Lock` only;
+/// burns emit nothing). `asset` is the reborn minted for it.
+#[event]
+pub struct NftSealed {
+    pub user: Pubkey,
+    pub nft_mint: Pubkey,
+    pub sealed_nft_vault: Pubkey,
+    pub asset: Pubkey,
+}
+
+// ============ Program ============
+
+#[program]
 pub mod ika_tensei_reborn {
     use super::*;
 
-    /// Initialize the mint config with the shared IKA minting dWallet pubkey.
-    /// Called once after deployment. The pubkey is stored on-chain and loaded
-    /// by mint_reborn — never accepted as instruction input.
-    pub fn initialize_mint_config(
-        ctx: Context<InitializeMintConfig>,
-        minting_pubkey: [u8; 32],
-    ) -> Result<()> {
-        let config = &mut ctx.accounts.config;
-        config.minting_pubkey = minting_pubkey;
-        config.admin = ctx.accounts.admin.key();
-        config.bump = ctx.bumps.config;
-        msg!("MintConfig initialized with minting pubkey: {}", hex::encode(&minting_pubkey));
-        Ok(())
+    /// Initialize the mint config with the shared IKA minting dWallet pubkey.
+    /// Called once after deployment. The pubkey is stored on-chain and loaded
+    /// by mint_reborn — never accepted as instruction input.
+    pub fn initialize_mint_config(
+        ctx: Context<InitializeMintConfig>,
+        minting_pubkey: [u8; 32],
+    ) -> Result<()> {
+        let admin = ctx.accounts.admin.key();
+        init_mint_config(&mut ctx.accounts.config, minting_pubkey, admin, ctx.bumps.config, Clock::get()?.unix_timestamp)?;
+        msg!("MintConfig initialized with minting pubkey: {}", hex::encode(&minting_pubkey));
+        Ok(())
+    }
+
+    /// Idempotent `initialize_mint_config`. On an existing config this is a no-op
+    /// that only checks the signer is the current admin; state is never changed.
+    pub fn ensure_mint_config(
+        ctx: Context<EnsureMintConfig>,
+        minting_pubkey: [u8; 32],
+    ) -> Result<()> {
+        let admin = ctx.accounts.admin.key();
+        if is_initialized_account(&ctx.accounts.config.to_account_info(), &MintConfig::DISCRIMINATOR)? {
+            require!(ctx.accounts.config.admin == admin, ErrorCode::Unauthorized);
+            msg!("MintConfig already initialized, leaving unchanged");
+            return Ok(());
+        }
+        init_mint_config(&mut ctx.accounts.config, minting_pubkey, admin, ctx.bumps.config, Clock::get()?.unix_timestamp)?;
+        msg!("MintConfig initialized with minting pubkey: {}", hex::encode(&minting_pubkey));
+        Ok(())
+    }
+
+    /// Upgrade a `MintConfig` still in its original `{minting_pubkey, admin, bump}` layout
+    /// (admin only). Until this runs the config doesn't deserialize, so `mint_reborn`
+    /// and every admin instruction fail. Fields added since get their
+    /// `initialize_mint_config` defaults.
+    pub fn migrate_mint_config(ctx: Context<MigrateMintConfig>) -> Result<()> {
+        let info = ctx.accounts.config.to_account_info();
+        let legacy: LegacyMintConfig =
+            read_legacy_account(&info, LegacyMintConfig::INIT_SPACE, &MintConfig::DISCRIMINATOR)?;
+        require_keys_eq!(legacy.admin, ctx.accounts.admin.key(), ErrorCode::Unauthorized);
+
+        let config = migrated_mint_config(legacy, Clock::get()?.unix_timestamp);
+        grow_account(&info, 8 + MintConfig::INIT_SPACE, &ctx.accounts.admin, &ctx.accounts.system_program)?;
+        config.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+        msg!("MintConfig migrated, admin {}", config.admin);
+        Ok(())
+    }
+
+    /// Upgrade a `RebornCollection` still in its first deployed layout (admin only).
+    /// Until this runs `mint_reborn` and the collection setters can't load it. The new
+    /// flags start off, with no symbol and no receiver allowlist.
+    pub fn migrate_collection(ctx: Context<MigrateAccount>) -> Result<()> {
+        let info = ctx.accounts.account.to_account_info();
+        let legacy: LegacyRebornCollection =
+            read_legacy_account(&info, LegacyRebornCollection::INIT_SPACE, &RebornCollection::DISCRIMINATOR)?;
+        let collection = migrated_collection(legacy);
+        grow_account(&info, 8 + RebornCollection::INIT_SPACE, &ctx.accounts.admin, &ctx.accounts.system_program)?;
+        collection.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+        record_admin_activity(&mut ctx.accounts.config)?;
+        msg!("RebornCollection {} migrated", info.key());
+        Ok(())
+    }
+
+    /// Upgrade a `Provenance` still in its first deployed layout (admin only). `asset`
+    /// is the reborn MPL Core asset, which the old layout didn't record; the seal
+    /// sequences and block hint, covered by signatures the old layout didn't keep,
+    /// are zeroed.
+    pub fn migrate_provenance(ctx: Context<MigrateAccount>, asset: Pubkey) -> Result<()> {
+        let info = ctx.accounts.account.to_account_info();
+        let legacy: LegacyProvenance =
+            read_legacy_account(&info, LegacyProvenance::INIT_SPACE, &Provenance::DISCRIMINATOR)?;
+        let provenance = migrated_provenance(legacy, asset);
+        grow_account(&info, 8 + Provenance::INIT_SPACE, &ctx.accounts.admin, &ctx.accounts.system_program)?;
+        provenance.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+        record_admin_activity(&mut ctx.accounts.config)?;
+        msg!("Provenance {} migrated, asset {}", info.key(), asset);
+        Ok(())
+    }
+
+    /// Update the minting pubkey (admin only). For key rotation.
+    pub fn update_mint_config(
+        ctx: Context<UpdateMintConfig>,
+        new_minting_pubkey: [u8; 32],
+    ) -> Result<()> {
+        ctx.accounts.config.minting_pubkey = new_minting_pubkey;
+        record_admin_activity(&mut ctx.accounts.config)?;
+        msg!("MintConfig updated with new minting pubkey: {}", hex::encode(&new_minting_pubkey));
+        Ok(())
+    }
+
+    /// Return the active minting pubkey, admin and bump (via return data). Intended to be
+    /// simulated; needs no signer.
+    pub fn get_mint_config(ctx: Context<GetMintConfig>) -> Result<MintConfigView> {
+        Ok(MintConfigView::from(&*ctx.accounts.config))
+    }
+
+    /// Relayer pre-flight: check each passed account against the PDA `mint_reborn` would
+    /// derive from the same leading args, failing with an error naming the first
+    /// mismatch. Read-only; intended to be simulated.
+    pub fn validate_accounts(
+        ctx: Context<ValidateAccounts>,
+        sig_hash: Vec<u8>,
+        source_chain: u16,
+        nft_contract: Vec<u8>,
+        token_id: Vec<u8>,
+    ) -> Result<()> {
+        require!(sig_hash.len() == 32, ErrorCode::InvalidSigHash);
+        let accounts = &ctx.accounts;
+        check_mint_accounts(
+            &sig_hash,
+            source_chain,
+            &nft_contract,
+            &token_id,
+            &MintAccountKeys {
+                sig_record: accounts.sig_record.key(),
+                provenance: accounts.provenance.key(),
+                collection: accounts.collection.key(),
+                mint_authority: accounts.mint_authority.key(),
+                config: accounts.config.key(),
+            },
+        )?;
+        msg!("mint_reborn accounts valid");
+        Ok(())
+    }
+
+    /// Toggle strict Ed25519 verification (admin only). When enabled, mint_reborn
+    /// rejects precompile instructions bundling more than our single signature.
+    pub fn set_strict_ed25519(ctx: Context<UpdateMintConfig>, strict: bool) -> Result<()> {
+        ctx.accounts.config.strict_ed25519 = strict;
+        record_admin_activity(&mut ctx.accounts.config)?;
+        msg!("Strict Ed25519 verification: {}", strict);
+        Ok(())
+    }
+
+    /// Toggle collection registry maintenance (admin only). On by default: mint_reborn
+    /// must be passed the registry and appends each new collection to it. Turning it off
+    /// lets first mints skip the registry account.
+    pub fn set_maintain_registry(ctx: Context<UpdateMintConfig>, maintain: bool) -> Result<()> {
+        ctx.accounts.config.maintain_registry = maintain;
+        record_admin_activity(&mut ctx.accounts.config)?;
+        msg!("Collection registry maintenance: {}", maintain);
+        Ok(())
+    }
+
+    /// Set the fee charged by seal_and_mint_native and the treasury `withdraw_fees`
+    /// pays out to (admin only). A zero fee keeps the native path free.
+    pub fn set_native_seal_fee(
+        ctx: Context<UpdateMintConfig>,
+        fee_lamports: u64,
+        treasury: Pubkey,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.native_seal_fee = fee_lamports;
+        config.treasury = treasury;
+        record_admin_activity(config)?;
+        msg!("Native seal fee: {} lamports to {}", fee_lamports, treasury);
+        Ok(())
+    }
+
+    /// Create the `FeeVault` that collects native seal fees (admin only).
+    pub fn initialize_fee_vault(ctx: Context<InitializeFeeVault>) -> Result<()> {
+        let fee_vault = &mut ctx.accounts.fee_vault;
+        fee_vault.total_collected = 0;
+        fee_vault.total_withdrawn = 0;
+        fee_vault.bump = ctx.bumps.fee_vault;
+        msg!("Fee vault initialized: {}", fee_vault.key());
+        Ok(())
+    }
+
+    /// Withdraw `amount` lamports of collected fees to `config.treasury` (admin only).
+    pub fn withdraw_fees(ctx: Context<WithdrawFees>, amount: u64) -> Result<()> {
+        ctx.accounts.fee_vault.record_withdrawal(amount)?;
+        ctx.accounts.fee_vault.sub_lamports(amount)?;
+        ctx.accounts.treasury.add_lamports(amount)?;
+        record_admin_activity(&mut ctx.accounts.config)?;
+        msg!("Withdrew {} lamports of fees to {}", amount, ctx.accounts.treasury.key());
+        Ok(())
+    }
+
+    /// Cap the number of signature entries in the Ed25519 precompile instruction
+    /// (admin only). Must be at least 1.
+    pub fn set_max_signatures_per_tx(ctx: Context<UpdateMintConfig>, max_signatures: u8) -> Result<()> {
+        require!(max_signatures >= 1, ErrorCode::InvalidSignatureLimit);
+        let config = &mut ctx.accounts.config;
+        config.max_signatures_per_tx = max_signatures;
+        record_admin_activity(config)?;
+        msg!("Max signatures per tx: {}", max_signatures);
+        Ok(())
+    }
+
+    /// Set or clear the provenance attribute schema for future mints (admin only).
+    /// Already minted assets keep the trait names they were minted with.
+    pub fn set_attribute_schema(ctx: Context<UpdateMintConfig>, schema: Option<AttributeSchema>) -> Result<()> {
+        if let Some(schema) = &schema {
+            validate_attribute_schema(schema)?;
+        }
+        let config = &mut ctx.accounts.config;
+        config.attribute_schema = schema;
+        record_admin_activity(config)?;
+        msg!("Attribute schema set: {}", config.attribute_schema.is_some());
+        Ok(())
+    }
+
+    /// Set the `seal_and_mint_native` naming policy (admin only). See
+    /// `MintConfig::reborn_suffix`.
+    pub fn set_reborn_suffix(ctx: Context<UpdateMintConfig>, reborn_suffix: String) -> Result<()> {
+        validate_reborn_suffix(&reborn_suffix)?;
+        let config = &mut ctx.accounts.config;
+        config.reborn_suffix = reborn_suffix;
+        record_admin_activity(config)?;
+        msg!("Reborn suffix: {:?}", config.reborn_suffix);
+        Ok(())
+    }
+
+    /// Set or clear the recovery admin (admin only).
+    pub fn set_recovery_admin(ctx: Context<UpdateMintConfig>, recovery_admin: Option<Pubkey>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.recovery_admin = recovery_admin;
+        record_admin_activity(config)?;
+        msg!("Recovery admin: {:?}", recovery_admin);
+        Ok(())
+    }
+
+    /// Replace the relayer allowlist for mint_reborn (admin only). Pass an empty list to
+    /// go back to admin-only minting.
+    pub fn set_allowed_relayers(ctx: Context<UpdateMintConfig>, relayers: Vec<Pubkey>) -> Result<()> {
+        require!(relayers.len() <= constants::MAX_ALLOWED_RELAYERS, ErrorCode::TooManyRelayers);
+        let config = &mut ctx.accounts.config;
+        config.allowed_relayers = relayers;
+        record_admin_activity(config)?;
+        msg!("Allowed relayers: {}", config.allowed_relayers.len());
+        Ok(())
+    }
+
+    /// No-op admin liveness proof; resets the recovery window (admin only).
+    pub fn admin_heartbeat(ctx: Context<UpdateMintConfig>) -> Result<()> {
+        record_admin_activity(&mut ctx.accounts.config)
+    }
+
+    /// Take over `admin` with the recovery key after the inactivity window. The recovery
+    /// key stays configured; the new admin can rotate or clear it.
+    pub fn recover_admin(ctx: Context<RecoverAdmin>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let config = &mut ctx.accounts.config;
+        let new_admin = ctx.accounts.recovery_admin.key();
+        check_admin_recovery(config, &new_admin, now)?;
+        msg!("Admin recovered: {} -> {}", config.admin, new_admin);
+        config.admin = new_admin;
+        config.last_admin_activity = now;
+        Ok(())
+    }
+
+    /// Update the URI of a minted Metaplex Core asset (admin only), e.g. after an IPFS
+    /// re-pin or gateway change. Refused for collections with `immutable_metadata`.
+    /// Provenance keeps the URI that was signed at seal time.
+    pub fn update_asset_uri(
+        ctx: Context<UpdateAssetUri>,
+        source_chain: u16,
+        nft_contract: Vec<u8>,
+        new_uri: String,
+    ) -> Result<()> {
+        require!(new_uri.len() <= constants::MAX_URI_LENGTH, ErrorCode::UriTooLong);
+        check_metadata_refreshable(&ctx.accounts.collection)?;
+
+        let mint_authority_bump = ctx.bumps.mint_authority;
+        let mint_authority_seeds: &[&[u8]] = &[
+            constants::MINT_AUTHORITY_SEED,
+            &SourceChain(source_chain).to_le_seed_bytes(),
+            &nft_contract,
+            &[mint_authority_bump],
+        ];
+
+        UpdateV1CpiBuilder::new(&ctx.accounts.mpl_core_program)
+            .asset(&ctx.accounts.asset)
+            .collection(Some(&ctx.accounts.collection_asset))
+            .authority(Some(&ctx.accounts.mint_authority))
+            .payer(&ctx.accounts.payer)
+            .system_program(&ctx.accounts.system_program)
+            .new_uri(new_uri.clone())
+            .invoke_signed(&[mint_authority_seeds])
+            .map_err(|_e| ErrorCode::MetaplexError)?;
+        record_admin_activity(&mut ctx.accounts.config)?;
+
+        msg!("Asset URI updated to: {}", new_uri);
+        Ok(())
+    }
+
+    /// Initialize the collection registry
+    pub fn initialize_collection_registry(ctx: Context<InitializeCollectionRegistry>) -> Result<()> {
+        let registry = &mut ctx.accounts.registry;
+        registry.count = 0;
+        registry.bump = ctx.bumps.registry;
+        registry.collections = Vec::new();
+        msg!("CollectionRegistry initialized");
+        Ok(())
+    }
+
+    /// Idempotent `initialize_collection_registry`. No-op if the registry already exists.
+    pub fn ensure_collection_registry(ctx: Context<EnsureCollectionRegistry>) -> Result<()> {
+        if is_initialized_account(&ctx.accounts.registry.to_account_info(), &CollectionRegistry::DISCRIMINATOR)? {
+            msg!("CollectionRegistry already initialized, leaving unchanged");
+            return Ok(());
+        }
+        let registry = &mut ctx.accounts.registry;
+        registry.count = 0;
+        registry.bump = ctx.bumps.registry;
+        registry.collections = Vec::new();
+        msg!("CollectionRegistry initialized");
+        Ok(())
+    }
+
+    /// Initialize a RebornCollection PDA. Called once per source collection
+    /// before the first mint_reborn.
+    pub fn init_reborn_collection(
+        ctx: Context<InitRebornCollection>,
+        source_chain: u16,
+        nft_contract: Vec<u8>,
+    ) -> Result<()> {
+        let collection = &mut ctx.accounts.collection;
+        collection.source_chain = source_chain;
+        collection.nft_contract = nft_contract;
+        collection.name = String::new();
+        collection.symbol = String::new();
+        collection.collection_asset_address = Pubkey::default();
+        collection.total_minted = 0;
+        collection.is_initialized = false;
+        collection.paused = false;
+        collection.immutable_metadata = false;
+        collection.receiver_allowlist_root = None;
+        collection.bump = ctx.bumps.collection;
+        msg!("RebornCollection PDA created");
+        Ok(())
+    }
+
+    /// Pause or unpause minting for one source collection (admin only).
+    pub fn set_collection_paused(
+        ctx: Context<SetCollectionPaused>,
+        _source_chain: u16,
+        _nft_contract: Vec<u8>,
+        paused: bool,
+    ) -> Result<()> {
+        ctx.accounts.collection.paused = paused;
+        record_admin_activity(&mut ctx.accounts.config)?;
+        msg!("Collection paused: {}", paused);
+        Ok(())
+    }
+
+    /// Lock (or unlock) metadata for one source collection (admin only). Assets minted
+    /// while locked carry ImmutableMetadata and stay immutable even after an unlock.
+    pub fn set_collection_immutable_metadata(
+        ctx: Context<SetCollectionImmutableMetadata>,
+        _source_chain: u16,
+        _nft_contract: Vec<u8>,
+        immutable: bool,
+    ) -> Result<()> {
+        ctx.accounts.collection.immutable_metadata = immutable;
+        record_admin_activity(&mut ctx.accounts.config)?;
+        msg!("Collection immutable metadata: {}", immutable);
+        Ok(())
+    }
+
+    /// Set the Merkle root of approved receivers for one source collection (admin only),
+    /// or `None` to allow any receiver. Mints must then pass a `receiver_proof`.
+    pub fn set_receiver_allowlist(
+        ctx: Context<SetReceiverAllowlist>,
+        _source_chain: u16,
+        _nft_contract: Vec<u8>,
+        root: Option<[u8; 32]>,
+    ) -> Result<()> {
+        ctx.accounts.collection.receiver_allowlist_root = root;
+        record_admin_activity(&mut ctx.accounts.config)?;
+        msg!("Receiver allowlist root: {:?}", root.map(hex::encode));
+        Ok(())
+    }
+
+    /// Merge the `RebornCollection` that was created with `source_chain` seeded
+    /// big-endian into the canonical (little-endian) record for the same contract
+    /// (admin only). Supply counters are summed and the duplicate PDA is closed. MPL Core
+    /// assets and collection assets are not touched; assets minted under the duplicate
+    /// stay in its MPL Core collection, and the canonical record keeps (or, if it never
+    /// minted, later creates) its own.
+    pub fn merge_collections(ctx: Context<MergeCollections>, source_chain: u16, _nft_contract: Vec<u8>) -> Result<()> {
+        require!(source_chain.swap_bytes() != source_chain, ErrorCode::NoDuplicateEncoding);
+        merge_collection_records(&mut ctx.accounts.canonical, &ctx.accounts.duplicate)?;
+        record_admin_activity(&mut ctx.accounts.config)?;
+        msg!(
+            "Merged collection {} into {} (duplicate MPL Core collection {}), total minted {}",
+            ctx.accounts.duplicate.key(),
+            ctx.accounts.canonical.key(),
+            ctx.accounts.duplicate.collection_asset_address,
+            ctx.accounts.canonical.total_minted
+        );
+        Ok(())
+    }
+
+    /// Mint a reborn NFT after verifying IKA dWallet signature
+    ///
+    /// Flow:
+    /// 1. Validate inputs
+    /// 2. Verify sig_hash == sha256(signature) to prevent PDA seed manipulation
+    /// 3. Load minting pubkey from Config PDA (never accepted as input)
+    /// 4. Reconstruct message: H(token_uri || token_id || receiver || wormhole_sequence_be
+    ///    || source_sequence_be || source_block_hint || valid_until_slot_be || hash_algo),
+    ///    after rejecting the attestation if the current slot is past `valid_until_slot`
+    ///    where H is sha256 or keccak256 as selected by `hash_algo`
+    /// 5. Verify Ed25519 signature (pubkey, message, AND signature bytes) via precompile
+    /// 6. sig_record PDA init provides replay protection (Anchor init fails if PDA exists)
+    /// 7. Create Metaplex Core collection if first NFT from this source collection
+    ///    (a `receiver_override`, if set, redirects ownership; the signature still covers `receiver`)
+    ///    The final owner must be in the collection's receiver allowlist, if one is set
+    ///    (`receiver_proof`; pass an empty proof otherwise)
+    /// 8. Mint Metaplex Core NFT (linked to collection) to receiver, optionally with a
+    ///    TransferDelegate plugin so `delegate` can manage the asset until the receiver claims it
+    /// 9. Store provenance
+    ///
+    /// CPI: another program may invoke this with the same accounts (its `payer` must be
+    /// the admin or an allowed relayer). Place the Ed25519 precompile instruction
+    /// anywhere before the caller's top-level instruction; the nearest one is used, and
+    /// its header may point at data inside the caller's instruction (permissive mode only,
+    /// since `strict_ed25519` requires a self-contained precompile instruction).
+    pub fn mint_reborn(ctx: Context<MintReborn>, args: MintRebornArgs) -> Result<()> {
+        let accounts = ctx.accounts;
+        let mint_accounts = MintAccounts {
+            payer: &accounts.payer,
+            receiver: &accounts.receiver,
+            sig_record: &mut accounts.sig_record,
+            provenance: &mut accounts.provenance,
+            collection: &mut accounts.collection,
+            mint_authority: &accounts.mint_authority,
+            collection_asset: &accounts.collection_asset,
+            asset: &accounts.asset,
+            asset_link: &mut accounts.asset_link,
+            mpl_core_program: &accounts.mpl_core_program,
+            system_program: &accounts.system_program,
+            instructions_sysvar: &accounts.instructions_sysvar,
+            config: &accounts.config,
+            receiver_override: &accounts.receiver_override,
+            override_receiver: accounts.override_receiver.as_ref(),
+            registry: accounts.registry.as_deref_mut().map(|r| &mut **r),
+            sig_record_bump: ctx.bumps.sig_record,
+            provenance_bump: ctx.bumps.provenance,
+            mint_authority_bump: ctx.bumps.mint_authority,
+            asset_link_bump: ctx.bumps.asset_link,
+        };
+        process_mint_reborn(mint_accounts, args, false)
+    }
+
+    /// Mint into a provenance slot held by `reserve_provenance`. Same arguments and
+    /// checks as `mint_reborn` (the signature is verified now, at claim time); the
+    /// provenance PDA must be reserved and not yet minted.
+    pub fn claim_reserved(ctx: Context<ClaimReserved>, args: MintRebornArgs) -> Result<()> {
+        let accounts = ctx.accounts;
+        let mint_accounts = MintAccounts {
+            payer: &accounts.payer,
+            receiver: &accounts.receiver,
+            sig_record: &mut accounts.sig_record,
+            provenance: &mut accounts.provenance,
+            collection: &mut accounts.collection,
+            mint_authority: &accounts.mint_authority,
+            collection_asset: &accounts.collection_asset,
+            asset: &accounts.asset,
+            asset_link: &mut accounts.asset_link,
+            mpl_core_program: &accounts.mpl_core_program,
+            system_program: &accounts.system_program,
+            instructions_sysvar: &accounts.instructions_sysvar,
+            config: &accounts.config,
+            receiver_override: &accounts.receiver_override,
+            override_receiver: accounts.override_receiver.as_ref(),
+            registry: accounts.registry.as_deref_mut().map(|r| &mut **r),
+            sig_record_bump: ctx.bumps.sig_record,
+            provenance_bump: ctx.bumps.provenance,
+            mint_authority_bump: ctx.bumps.mint_authority,
+            asset_link_bump: ctx.bumps.asset_link,
+        };
+        process_mint_reborn(mint_accounts, args, true)
+    }
+
+    /// Set (or re-point) the receiver override for a not-yet-minted token (admin only).
+    /// The original receiver stays bound to the signature; mint_reborn mints to `new_receiver`.
+    /// An override created for the wrong `original_receiver` is removed with
+    /// `clear_receiver_override`.
+    pub fn set_receiver_override(
+        ctx: Context<SetReceiverOverride>,
+        _source_chain: u16,
+        _nft_contract: Vec<u8>,
+        _token_id: Vec<u8>,
+        original_receiver: Pubkey,
+        new_receiver: Pubkey,
+    ) -> Result<()> {
+        require!(
+            new_receiver != System::id() && new_receiver != Pubkey::default(),
+            ErrorCode::InvalidReceiver
+        );
+
+        let receiver_override = &mut ctx.accounts.receiver_override;
+        if receiver_override.original_receiver != Pubkey::default() {
+            require!(
+                receiver_override.original_receiver == original_receiver,
+                ErrorCode::ReceiverOverrideMismatch
+            );
+        }
+        receiver_override.original_receiver = original_receiver;
+        receiver_override.new_receiver = new_receiver;
+        receiver_override.bump = ctx.bumps.receiver_override;
+        record_admin_activity(&mut ctx.accounts.config)?;
+
+        msg!("Receiver override set: {} -> {}", original_receiver, new_receiver);
+        Ok(())
+    }
+
+    /// Close a receiver override (admin only). The PDA is closed to `admin`.
+    pub fn clear_receiver_override(
+        ctx: Context<ClearReceiverOverride>,
+        _source_chain: u16,
+        _nft_contract: Vec<u8>,
+        _token_id: Vec<u8>,
+    ) -> Result<()> {
+        record_admin_activity(&mut ctx.accounts.config)?;
+        msg!("Receiver override cleared: {}", ctx.accounts.receiver_override.key());
+        Ok(())
+    }
+
+    /// Reserve the provenance slot of (source_chain, nft_contract, token_id) for a staged
+    /// drop (admin only). Nothing is minted or verified yet; the slot now exists, so
+    /// `mint_reborn` for the token fails on its provenance `init` and only
+    /// `claim_reserved` can mint into it.
+    pub fn reserve_provenance(
+        ctx: Context<ReserveProvenance>,
+        source_chain: u16,
+        nft_contract: Vec<u8>,
+        token_id: Vec<u8>,
+    ) -> Result<()> {
+        require!(!nft_contract.is_empty() && nft_contract.len() <= constants::MAX_CONTRACT_LENGTH, ErrorCode::ContractTooLong);
+        require!(!token_id.is_empty() && token_id.len() <= constants::MAX_TOKEN_ID_LENGTH, ErrorCode::TokenIdTooLong);
+        fill_reservation(&mut ctx.accounts.provenance, source_chain, nft_contract, token_id, ctx.bumps.provenance);
+        record_admin_activity(&mut ctx.accounts.config)?;
+        msg!("Provenance reserved: {}", ctx.accounts.provenance.key());
+        Ok(())
+    }
+
+    /// Record provenance for a legacy reborn NFT minted before this program existed.
+    /// Admin only. No signature is verified and nothing is minted; the record is
+    /// flagged `backfilled` with a zeroed dWallet pubkey and signature so it is never
+    /// mistaken for a dWallet-attested mint.
+    pub fn admin_record_provenance(
+        ctx: Context<AdminRecordProvenance>,
+        source_chain: u16,
+        nft_contract: Vec<u8>,
+        token_id: Vec<u8>,
+        token_uri: String,
+        receiver: Pubkey,
+        asset: Pubkey,
+    ) -> Result<()> {
+        require!(!nft_contract.is_empty() && nft_contract.len() <= constants::MAX_CONTRACT_LENGTH, ErrorCode::ContractTooLong);
+        require!(!token_id.is_empty() && token_id.len() <= constants::MAX_TOKEN_ID_LENGTH, ErrorCode::TokenIdTooLong);
+        require!(!token_uri.is_empty() && token_uri.len() <= constants::MAX_URI_LENGTH, ErrorCode::UriTooLong);
+        require!(
+            receiver != System::id() && receiver != Pubkey::default(),
+            ErrorCode::InvalidReceiver
+        );
+
+        let provenance = &mut ctx.accounts.provenance;
+        provenance.source_chain = source_chain;
+        provenance.nft_contract = nft_contract;
+        provenance.token_id = token_id;
+        provenance.token_uri = token_uri;
+        provenance.dwallet_pubkey = [0u8; 32];
+        provenance.signature = [0u8; 64];
+        provenance.receiver = receiver;
+        provenance.asset = asset;
+        provenance.wormhole_sequence = 0;
+        provenance.source_sequence = 0;
+        provenance.source_block_hint = [0u8; 32];
+        provenance.sealed_at = Clock::get()?.unix_timestamp;
+        provenance.is_initialized = true;
+        provenance.backfilled = true;
+        provenance.imported_from_v3 = false;
+        provenance.reserved = false;
+        provenance.bump = ctx.bumps.provenance;
+        record_admin_activity(&mut ctx.accounts.config)?;
+
+        msg!("Provenance backfilled for asset {}", asset);
+        Ok(())
+    }
+
+    /// Migrate a v3 `ReincarnationRecord` into a `Provenance` without re-minting, so
+    /// marketplaces keep showing history after v3 is retired. Admin only.
+    /// `token_uri` is supplied separately because v3 records don't store it.
+    pub fn admin_import_v3_record(
+        ctx: Context<AdminImportV3Record>,
+        record: V3RecordImport,
+        token_uri: String,
+    ) -> Result<()> {
+        let asset = record.mint;
+        fill_imported_provenance(&mut ctx.accounts.provenance, record, token_uri, ctx.bumps.provenance)?;
+        record_admin_activity(&mut ctx.accounts.config)?;
+        msg!("v3 record imported for asset {}", asset);
+        Ok(())
+    }
+
+    /// Seal a Solana NFT the caller holds and mint its reborn, with no dWallet
+    /// signature: the original is locked in its `sealed_vault` PDA or burned
+    /// (`seal_mode`). With `source_collection` the reborn joins the collection of every
+    /// NFT verified into that Metaplex collection; otherwise the mint is its own
+    /// collection. Charges `native_seal_fee` into the fee vault.
+    pub fn seal_and_mint_native(
+        ctx: Context<SealAndMintNative>,
+        token_uri: String,
+        collection_name: String,
+        seal_mode: SealMode,
+        source_collection: Option<Pubkey>,
+    ) -> Result<NativeSealResult> {
+        // ============ 1. Input validation ============
+        require!(token_uri.len() <= constants::MAX_URI_LENGTH, ErrorCode::UriTooLong);
+        validate_collection_name(&collection_name)?;
+
+        // A mint is sealed at most once. Checked before the fee and the token transfer,
+        // so a replay fails cheaply with nothing moved.
+        ensure_not_sealed(&ctx.accounts.provenance)?;
+        require!(!ctx.accounts.collection.paused, ErrorCode::CollectionPaused);
+
+        let user_key = ctx.accounts.user.key();
+        let nft_mint_key = ctx.accounts.nft_mint.key();
+
+        // Derive nft_contract from the on-chain accounts. Without a source collection the
+        // mint address IS the contract and the token ID (SPL tokens are 1:1 mint-to-NFT).
+        // With one, the verified Metaplex collection is the contract, so NFTs from the
+        // same original collection share one reborn collection. This is tamper-proof.
+        if source_collection.is_some() {
+            let metadata = ctx.accounts.source_metadata.as_ref()
+                .ok_or(ErrorCode::SourceCollectionNotVerified)?;
+            check_source_collection(
+                metadata.collection.as_ref().map(|c| (c.key, c.verified)),
+                source_collection.as_ref(),
+            )?;
+        }
+        let grouping_key = native_grouping_key(source_collection, &nft_mint_key);
+        let nft_contract = grouping_key.to_bytes().to_vec();
+        let source_chain = SourceChain::SOLANA.0;
+
+        // Token-2022 mints may carry extensions that block the seal (a plain SPL mint
+        // unpacks with no extensions).
+        {
+            use anchor_spl::token_2022::spl_token_2022::{extension::{BaseStateWithExtensions, StateWithExtensions}, state::Mint};
+            let mint_info = ctx.accounts.nft_mint.to_account_info();
+            let mint_data = mint_info.try_borrow_data()?;
+            let mint_state = StateWithExtensions::<Mint>::unpack(&mint_data)?;
+            check_native_seal_extensions(&mint_state.get_extension_types()?, seal_mode)?;
+        }
+
+        // Programmable NFTs are frozen in place and move only through Token Metadata
+        let transfer_kind = native_transfer_kind(
+            ctx.accounts.source_metadata.as_ref().and_then(|m| m.token_standard.as_ref()),
+            ctx.accounts.nft_token_account.is_frozen(),
+            seal_mode,
+        )?;
+
+        // Protocol fee, before any CPI so a user who can't pay never gets a reborn
+        if let Some(fee) = native_seal_fee_due(&ctx.accounts.config) {
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: ctx.accounts.user.to_account_info(),
+                        to: ctx.accounts.fee_vault.to_account_info(),
+                    },
+                ),
+                fee,
+            )?;
+            ctx.accounts.fee_vault.record_fee(fee)?;
+            msg!("Native seal fee: {} lamports", fee);
+        }
+
+        // ORDERING: the reborn is created *before* the original leaves the user. A
+        // Solana transaction is all-or-nothing, so any failure (collection CPI, asset
+        // CPI, seal CPI, provenance write) reverts every step and the NFT stays in
+        // `nft_token_account`. Keep all of these in this one instruction: splitting
+        // them across transactions (e.g. a batch variant) would lose that guarantee.
+
+        // ============ 2. Create collection if first mint ============
+        let collection_asset_key = ctx.accounts.collection_asset.key();
+        check_asset_collection_distinct(&collection_asset_key, &ctx.accounts.asset.key())?;
+        let is_new_collection = !ctx.accounts.collection.is_initialized;
+
+        let display_name = if is_new_collection { &collection_name } else { &ctx.accounts.collection.name };
+        let nft_name = native_display_name(display_name, &ctx.accounts.config.reborn_suffix);
+        let rent_needed = mpl_core_rent_needed(
+            &Rent::get()?,
+            is_new_collection.then(|| mpl_core_collection_space(collection_name.len(), 96, 0)),
+            mpl_core_asset_space(nft_name.len(), token_uri.len(), 0),
+        );
+        check_payer_funds(ctx.accounts.user.lamports(), rent_needed)?;
+
+        let mint_authority_bump = ctx.bumps.mint_authority;
+        let nft_contract_ref = grouping_key.as_ref();
+        let source_chain_bytes = SourceChain(source_chain).to_le_seed_bytes();
+        let mint_authority_seeds: &[&[u8]] = &[
+            constants::MINT_AUTHORITY_SEED,
+            &source_chain_bytes,
+            nft_contract_ref,
+            &[mint_authority_bump],
+        ];
+
+        if is_new_collection {
+            CreateCollectionV2CpiBuilder::new(&ctx.accounts.mpl_core_program)
+                .collection(&ctx.accounts.collection_asset)
+                .update_authority(Some(&ctx.accounts.mint_authority))
+                .payer(&ctx.accounts.user)
+                .system_program(&ctx.accounts.system_program)
+                .name(collection_name.clone())
+                .uri(format!(
+                    "https://ika-tensei.io/collections/{}/{}",
+                    source_chain,
+                    grouping_key
+                ))
+                .invoke_signed(&[mint_authority_seeds])
+                .map_err(|_e| ErrorCode::MetaplexError)?;
+
+            let collection = &mut ctx.accounts.collection;
+            collection.source_chain = source_chain;
+            collection.nft_contract = nft_contract.clone();
+            collection.name = collection_name.clone();
+            collection.symbol = String::new();
+            collection.collection_asset_address = collection_asset_key;
+            collection.total_minted = 0;
+            collection.is_initialized = true;
+            collection.paused = false;
+            collection.immutable_metadata = false;
+            collection.receiver_allowlist_root = None;
+            collection.bump = ctx.bumps.collection;
+
+            maybe_register_collection(
+                ctx.accounts.config.maintain_registry,
+                ctx.accounts.registry.as_deref_mut().map(|r| &mut **r),
+                CollectionEntry {
+                    source_chain,
+                    nft_contract: nft_contract.clone(),
+                    collection_address: collection_asset_key,
+                    created_at: Clock::get()?.unix_timestamp,
+                },
+            )?;
+        } else {
+            check_collection_asset(&ctx.accounts.collection, &collection_asset_key)?;
+        }
+
+        // ============ 3. Mint reborn NFT ============
+        let collection = &mut ctx.accounts.collection;
+
+        CreateV2CpiBuilder::new(&ctx.accounts.mpl_core_program)
+            .asset(&ctx.accounts.asset)
+            .collection(Some(&ctx.accounts.collection_asset))
+            .authority(Some(&ctx.accounts.mint_authority))
+            .payer(&ctx.accounts.user)
+            .owner(Some(&ctx.accounts.user))
+            .update_authority(Some(&ctx.accounts.mint_authority))
+            .system_program(&ctx.accounts.system_program)
+            .data_state(DataState::AccountState)
+            .name(nft_name)
+            .uri(token_uri.clone())
+            .invoke_signed(&[mint_authority_seeds])
+            .map_err(|_e| ErrorCode::MetaplexError)?;
+
+        record_collection_mint(collection)?;
+        msg!("Reborn NFT minted to {}", user_key);
+
+        // ============ 4. Lock in vault PDA, or burn (irreversible) ============
+        let token_program = ctx.accounts.token_program.to_account_info();
+        match seal_mode {
+            SealMode::Lock => {
+                let vault = ctx.accounts.sealed_nft_vault.as_ref().ok_or(ErrorCode::VaultRequired)?;
+                match transfer_kind {
+                    NativeTransfer::Token => {
+                        // transfer_checked: the plain `transfer` is deprecated under Token-2022
+                        let cpi_accounts = anchor_spl::token_interface::TransferChecked {
+                            from: ctx.accounts.nft_token_account.to_account_info(),
+                            mint: ctx.accounts.nft_mint.to_account_info(),
+                            to: vault.to_account_info(),
+                            authority: ctx.accounts.user.to_account_info(),
+                        };
+                        anchor_spl::token_interface::transfer_checked(CpiContext::new(token_program, cpi_accounts), 1, 0)?;
+                    }
+                    NativeTransfer::TokenMetadata => {
+                        use anchor_spl::metadata::mpl_token_metadata::instructions::TransferV1CpiBuilder;
+                        let missing = || error!(ErrorCode::ProgrammableAccountsRequired);
+                        let metadata = ctx.accounts.source_metadata.as_ref().ok_or_else(missing)?.to_account_info();
+                        let edition = ctx.accounts.source_edition.as_ref().ok_or_else(missing)?.to_account_info();
+                        let owner_record = ctx.accounts.owner_token_record.as_ref().ok_or_else(missing)?.to_account_info();
+                        let vault_record = ctx.accounts.vault_token_record.as_ref().ok_or_else(missing)?.to_account_info();
+                        let tm_program = ctx.accounts.token_metadata_program.as_ref().ok_or_else(missing)?.to_account_info();
+                        let ata_program = ctx.accounts.associated_token_program.as_ref().ok_or_else(missing)?.to_account_info();
+                        let instructions = ctx.accounts.instructions_sysvar.as_ref().ok_or_else(missing)?.to_account_info();
+                        let rules = ctx.accounts.authorization_rules.as_ref().map(|a| a.to_account_info());
+                        let rules_program = ctx.accounts.authorization_rules_program.as_ref().map(|a| a.to_account_info());
+                        let user = ctx.accounts.user.to_account_info();
+                        let vault_info = vault.to_account_info();
+                        TransferV1CpiBuilder::new(&tm_program)
+                            .token(&ctx.accounts.nft_token_account.to_account_info())
+                            .token_owner(&user)
+                            .destination_token(&vault_info)
+                            .destination_owner(&vault_info)
+                            .mint(&ctx.accounts.nft_mint.to_account_info())
+                            .metadata(&metadata)
+                            .edition(Some(&edition))
+                            .token_record(Some(&owner_record))
+                            .destination_token_record(Some(&vault_record))
+                            .authority(&user)
+                            .payer(&user)
+                            .system_program(&ctx.accounts.system_program.to_account_info())
+                            .sysvar_instructions(&instructions)
+                            .spl_token_program(&token_program)
+                            .spl_ata_program(&ata_program)
+                            .authorization_rules(rules.as_ref())
+                            .authorization_rules_program(rules_program.as_ref())
+                            .amount(1)
+                            .invoke()?;
+                    }
+                }
+                msg!("NFT {} sealed permanently in vault PDA", nft_mint_key);
+                emit!(NftSealed {
+                    user: user_key,
+                    nft_mint: nft_mint_key,
+                    sealed_nft_vault: vault.key(),
+                    asset: ctx.accounts.asset.key(),
+                });
+            }
+            SealMode::Burn => {
+                let burn_accounts = anchor_spl::token_interface::Burn {
+                    mint: ctx.accounts.nft_mint.to_account_info(),
+                    from: ctx.accounts.nft_token_account.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                };
+                anchor_spl::token_interface::burn(CpiContext::new(token_program.clone(), burn_accounts), 1)?;
+
+                let close_accounts = anchor_spl::token_interface::CloseAccount {
+                    account: ctx.accounts.nft_token_account.to_account_info(),
+                    destination: ctx.accounts.user.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                };
+                anchor_spl::token_interface::close_account(CpiContext::new(token_program, close_accounts))?;
+                msg!("NFT {} burned", nft_mint_key);
+            }
+        }
+
+        // ============ 5. Store provenance (identical for Lock and Burn) ============
+        fill_native_provenance(
+            &mut ctx.accounts.provenance,
+            &grouping_key,
+            &nft_mint_key,
+            &user_key,
+            &ctx.accounts.asset.key(),
+            token_uri,
+            Clock::get()?.unix_timestamp,
+            ctx.bumps.provenance,
+        );
+
+        msg!("Provenance stored for native seal of {}", nft_mint_key);
+
+        // Anchor writes the return value with set_return_data
+        Ok(NativeSealResult {
+            asset: ctx.accounts.asset.key(),
+            provenance: ctx.accounts.provenance.key(),
+        })
+    }
+}
+
+// ============ Helpers ============
+
+/// Accounts `process_mint_reborn` works on, borrowed from `MintReborn` or
+/// `ClaimReserved` (which differ only in how `provenance` is constrained), plus the
+/// bumps it stores.
+struct MintAccounts<'a, 'info> {
+    payer: &'a Signer<'info>,
+    receiver: &'a UncheckedAccount<'info>,
+    sig_record: &'a mut Account<'info, SigUsed>,
+    provenance: &'a mut Account<'info, Provenance>,
+    collection: &'a mut Account<'info, RebornCollection>,
+    mint_authority: &'a UncheckedAccount<'info>,
+    collection_asset: &'a UncheckedAccount<'info>,
+    asset: &'a UncheckedAccount<'info>,
+    asset_link: &'a mut Account<'info, AssetProvenanceLink>,
+    mpl_core_program: &'a AccountInfo<'info>,
+    system_program: &'a Program<'info, System>,
+    instructions_sysvar: &'a UncheckedAccount<'info>,
+    config: &'a Account<'info, MintConfig>,
+    receiver_override: &'a UncheckedAccount<'info>,
+    override_receiver: Option<&'a UncheckedAccount<'info>>,
+    registry: Option<&'a mut CollectionRegistry>,
+    sig_record_bump: u8,
+    provenance_bump: u8,
+    mint_authority_bump: u8,
+    asset_link_bump: u8,
+}
+
+/// Body of `mint_reborn` and `claim_reserved`. `claim_reservation` selects which
+/// provenance state is accepted: a fresh PDA, or one reserved by `reserve_provenance`.
+fn process_mint_reborn(mut accounts: MintAccounts, args: MintRebornArgs, claim_reservation: bool) -> Result<()> {
+    let MintRebornArgs {
+        sig_hash,
+        source_chain,
+        nft_contract,
+        token_id,
+        signature,
+        token_uri,
+        collection_name,
+        royalty_basis_points,
+        dao_treasury,
+        wormhole_sequence,
+        source_sequence,
+        source_block_hint,
+        valid_until_slot,
+        hash_algo,
+        delegate,
+        symbol,
+        receiver_proof,
+    } = args;
+
+    // ============ 1. Input validation ============
+    require!(signature.len() == 64, ErrorCode::InvalidSignature);
+    require!(sig_hash.len() == 32, ErrorCode::InvalidSigHash);
+    require!(!nft_contract.is_empty() && nft_contract.len() <= constants::MAX_CONTRACT_LENGTH, ErrorCode::ContractTooLong);
+    require!(!token_id.is_empty() && token_id.len() <= constants::MAX_TOKEN_ID_LENGTH, ErrorCode::TokenIdTooLong);
+    require!(!token_uri.is_empty() && token_uri.len() <= constants::MAX_URI_LENGTH, ErrorCode::UriTooLong);
+    require!(!collection_name.is_empty(), ErrorCode::NameTooLong);
+    validate_collection_name(&collection_name)?;
+    require!(royalty_basis_points <= 10000, ErrorCode::InvalidRoyalties);
+    validate_symbol(symbol.as_deref())?;
+
+    let receiver_pubkey = accounts.receiver.key();
+
+    // Reject obviously wrong receiver addresses
+    require!(
+        receiver_pubkey != System::id() && receiver_pubkey != Pubkey::default(),
+        ErrorCode::InvalidReceiver
+    );
+
+    require!(!accounts.collection.paused, ErrorCode::CollectionPaused);
+
+    // mint_reborn's provenance `init` already refused any existing slot, reserved or minted
+    if claim_reservation {
+        check_reservation(&accounts.provenance)?;
+    }
+
+    if let Some(delegate) = delegate {
+        require!(
+            delegate != Pubkey::default() && delegate != receiver_pubkey,
+            ErrorCode::InvalidDelegate
+        );
+    }
+
+    check_signature_fresh(Clock::get()?.slot, valid_until_slot)?;
+
+    // ============ 2-5. Verify sig, compute message hash, check Ed25519 ============
+    // Heavy crypto (sha256 + ed25519) is in verify_seal() which has its own
+    // stack frame (#[inline(never)]) to stay under the BPF 4KB limit.
+    let dwallet_pubkey = accounts.config.minting_pubkey;
+    verify_seal(
+        &accounts.instructions_sysvar,
+        &dwallet_pubkey,
+        &signature,
+        &sig_hash,
+        &token_uri,
+        &token_id,
+        &receiver_pubkey,
+        wormhole_sequence,
+        source_sequence,
+        &source_block_hint,
+        valid_until_slot,
+        hash_algo,
+        accounts.config.strict_ed25519,
+        accounts.config.max_signatures_per_tx,
+    )?;
+
+    msg!("Signature verified against stored minting pubkey");
+    compute_checkpoint("verified");
+
+    // ============ 6. Replay protection (PDA-based) ============
+    // The `sig_record` account was created by Anchor's `init` constraint.
+    // If it already existed the transaction would have already failed above.
+    // We store the bump for completeness.
+    let sig_record = &mut accounts.sig_record;
+    sig_record.bump = accounts.sig_record_bump;
+    msg!("Replay protection: sig_record PDA created, replay blocked for this signature");
+
+    // ============ 7. Create collection if first mint ============
+    let collection_asset_key = accounts.collection_asset.key();
+    check_asset_collection_distinct(&collection_asset_key, &accounts.asset.key())?;
+    let is_new_collection = !accounts.collection.is_initialized;
+
+    let mint_authority_bump = accounts.mint_authority_bump;
+    let mint_authority_seeds: &[&[u8]] = &[
+        constants::MINT_AUTHORITY_SEED,
+        &SourceChain(source_chain).to_le_seed_bytes(),
+        &nft_contract,
+        &[mint_authority_bump],
+    ];
+
+    // Build the NFT name: "{CollectionName} #{decimal_token_id}"
+    // token_id is a big-endian uint256 (32 bytes), so strip leading zeros and convert to decimal.
+    // On first mint the collection takes `collection_name` below.
+    let display_name = if is_new_collection { &collection_name } else { &accounts.collection.name };
+    let nft_name = nft_display_name(display_name, &token_id);
+
+    // Pre-flight: fail with a clear error instead of an opaque MetaplexError when
+    // the payer can't fund the new MPL Core account(s)
+    let collection_uri = format!(
+        "https://ika-tensei.io/collections/{}/{}",
+        source_chain,
+        hex::encode(&nft_contract)
+    );
+    // Built here so the estimate counts exactly the plugins the CreateV2 CPI attaches
+    let mut asset_plugins = reborn_asset_plugins(delegate, accounts.collection.immutable_metadata);
+    if let Some(schema) = &accounts.config.attribute_schema {
+        asset_plugins.push(provenance_attributes_plugin(schema, source_chain, &nft_contract, &token_id));
+    }
+    let rent_needed = mpl_core_rent_needed(
+        &Rent::get()?,
+        is_new_collection.then(|| mpl_core_collection_space(collection_name.len(), collection_uri.len(), 2)),
+        mpl_core_asset_space(nft_name.len(), token_uri.len(), asset_plugins.len()),
+    );
+    check_payer_funds(accounts.payer.lamports(), rent_needed)?;
+
+    if is_new_collection {
+        let payer_key = accounts.payer.key();
+
+        // Configure royalties: 6.9% total (690 bps), split 72% DAO (~5%) / 28% team (~1.9%)
+        let royalties_plugin = PluginAuthorityPair {
+            plugin: Plugin::Royalties(Royalties {
+                basis_points: royalty_basis_points,
+                creators: vec![
+                    Creator { address: dao_treasury, percentage: 72 },
+                    Creator { address: payer_key, percentage: 28 },
+                ],
+                rule_set: RuleSet::None,
+            }),
+            authority: Some(PluginAuthority::UpdateAuthority),
+        };
+
+        // A PDA collection asset is signed for here; a keypair already signed the tx
+        let collection_asset_bump = pda_collection_asset_bump(source_chain, &nft_contract, &collection_asset_key);
+        let collection_asset_seeds: &[&[u8]] = &[
+            constants::COLLECTION_ASSET_SEED,
+            &SourceChain(source_chain).to_le_seed_bytes(),
+            &nft_contract,
+            &[collection_asset_bump.unwrap_or_default()],
+        ];
+        let signer_seeds: &[&[&[u8]]] = &[mint_authority_seeds, collection_asset_seeds];
+        let signer_seeds = if collection_asset_bump.is_some() { signer_seeds } else { &signer_seeds[..1] };
+
+        // First NFT from this source collection — create the Metaplex Core collection asset
+        CreateCollectionV2CpiBuilder::new(accounts.mpl_core_program)
+            .collection(accounts.collection_asset)
+            .update_authority(Some(accounts.mint_authority))
+            .payer(accounts.payer)
+            .system_program(accounts.system_program)
+            .name(collection_name.clone())
+            .uri(collection_uri)
+            .plugins(vec![
+                royalties_plugin,
+                collection_attributes_plugin(symbol.as_deref(), royalty_basis_points),
+            ])
+            .invoke_signed(signer_seeds)
+            .map_err(|_e| ErrorCode::MetaplexError)?;
+
+        msg!("Created new Metaplex Core collection: {} (royalties: {}bp)", collection_name, royalty_basis_points);
+        compute_checkpoint("collection created");
+
+        // Finalize our RebornCollection metadata PDA (pre-created by init_reborn_collection)
+        let collection = &mut accounts.collection;
+        collection.name = collection_name.clone();
+        collection.symbol = symbol.unwrap_or_default();
+        collection.collection_asset_address = collection_asset_key;
+        collection.is_initialized = true;
+
+        maybe_register_collection(
+            accounts.config.maintain_registry,
+            accounts.registry,
+            CollectionEntry {
+                source_chain,
+                nft_contract: nft_contract.clone(),
+                collection_address: collection_asset_key,
+                created_at: Clock::get()?.unix_timestamp,
+            },
+        )?;
+
+    } else {
+        check_collection_asset(&accounts.collection, &collection_asset_key)?;
+        msg!("Using existing collection: {}", accounts.collection.name);
+    }
+
+    // ============ 8. Mint reborn NFT ============
+    // The signature was verified against `receiver`; an override only changes the owner.
+    let owner_info = match load_receiver_override(accounts.receiver_override, &receiver_pubkey)? {
+        Some(new_receiver) => {
+            let override_receiver = accounts.override_receiver.ok_or(ErrorCode::ReceiverOverrideMismatch)?;
+            require!(override_receiver.key() == new_receiver, ErrorCode::ReceiverOverrideMismatch);
+            msg!("Receiver overridden: {} -> {}", receiver_pubkey, new_receiver);
+            override_receiver.to_account_info()
+        }
+        None => accounts.receiver.to_account_info(),
+    };
+    let owner_pubkey = owner_info.key();
+    verify_receiver_allowlist(accounts.collection.receiver_allowlist_root, &owner_pubkey, &receiver_proof)?;
+
+    let collection = &mut accounts.collection;
+
+    // CPI to Metaplex Core to mint the NFT, linked to our collection asset
+    CreateV2CpiBuilder::new(accounts.mpl_core_program)
+        .asset(accounts.asset)
+        .collection(Some(accounts.collection_asset))
+        .authority(Some(accounts.mint_authority))
+        .payer(accounts.payer)
+        .owner(Some(&owner_info))
+        .system_program(accounts.system_program)
+        .data_state(DataState::AccountState)
+        .name(nft_name)
+        .uri(token_uri.clone())
+        .plugins(asset_plugins)
+        .invoke_signed(&[mint_authority_seeds])
+        .map_err(|_e| ErrorCode::MetaplexError)?;
+    compute_checkpoint("minted");
+
+    record_collection_mint(collection)?;
+    if let Some(delegate) = delegate {
+        msg!("NFT minted to {} (transfer delegate {})", owner_pubkey, delegate);
+    } else {
+        msg!("NFT minted to {}", owner_pubkey);
+    }
+
+    // ============ 9. Store provenance ============
+    let provenance = &mut accounts.provenance;
+    provenance.source_chain = source_chain;
+    provenance.nft_contract = nft_contract;
+    provenance.token_id = token_id;
+    provenance.token_uri = token_uri;
+    provenance.dwallet_pubkey = dwallet_pubkey;
+    provenance.signature = signature.try_into().unwrap(); // length validated above
+    provenance.receiver = owner_pubkey;
+    provenance.asset = accounts.asset.key();
+    provenance.wormhole_sequence = wormhole_sequence;
+    provenance.source_sequence = source_sequence;
+    provenance.source_block_hint = source_block_hint;
+    provenance.sealed_at = Clock::get()?.unix_timestamp;
+    provenance.is_initialized = true;
+    provenance.backfilled = false;
+    provenance.imported_from_v3 = false;
+    provenance.reserved = false;
+    provenance.bump = accounts.provenance_bump;
+
+    let asset_link = &mut accounts.asset_link;
+    asset_link.provenance = accounts.provenance.key();
+    asset_link.bump = accounts.asset_link_bump;
+
+    msg!("Provenance stored for {} (wormhole seq {})", owner_pubkey, wormhole_sequence);
+
+    Ok(())
+}
+
+/// Reborn NFT name `"{collection_name} #{token_id}"`, capped at `MAX_NAME_LENGTH`.
+/// Token IDs of two or more printable-ASCII bytes that are not all digits are shown
+/// as-is; anything else (including short numbers that happen to be printable, like
+/// `[0x41]`) goes through `token_id_to_decimal` (decimal, or `0x` hex when too large).
+/// A long collection name is cut to leave `MIN_NAME_TOKEN_ID_LENGTH` for the ID, and an
+/// oversized suffix is cut and marked with `...`.
+fn nft_display_name(collection_name: &str, token_id: &[u8]) -> String {
+    let is_text = token_id.len() > 1
+        && token_id.iter().all(|b| (0x20..0x7f).contains(b))
+        && !token_id.iter().all(u8::is_ascii_digit);
+    let suffix = if is_text {
+        // Printable ASCII is valid UTF-8
+        String::from_utf8(token_id.to_vec()).unwrap_or_default()
+    } else {
+        token_id_to_decimal(token_id)
+    };
+
+    let reserved = suffix.len().min(constants::MIN_NAME_TOKEN_ID_LENGTH);
+    let mut name_end = collection_name.len().min(constants::MAX_NAME_LENGTH - 2 - reserved);
+    while !collection_name.is_char_boundary(name_end) {
+        name_end -= 1;
+    }
+    let collection_name = &collection_name[..name_end];
+
+    let room = constants::MAX_NAME_LENGTH - (collection_name.len() + 2);
+    if suffix.len() <= room {
+        return format!("{} #{}", collection_name, suffix);
+    }
+    // suffix is ASCII, so byte slicing is char-safe
+    if room >= 4 {
+        format!("{} #{}...", collection_name, &suffix[..room - 3])
+    } else {
+        format!("{} #{}", collection_name, &suffix[..room])
+    }
+}
+
+/// `seal_and_mint_native` asset name from `reborn_suffix` (see `MintConfig`), capped at
+/// `MAX_NAME_LENGTH`. The suffix/template text is kept whole; the collection name is cut
+/// (on a char boundary) to make room.
+fn native_display_name(collection_name: &str, reborn_suffix: &str) -> String {
+    let (prefix, suffix) = reborn_suffix
+        .split_once(constants::REBORN_NAME_PLACEHOLDER)
+        .unwrap_or(("", reborn_suffix));
+    let room = constants::MAX_NAME_LENGTH.saturating_sub(prefix.len() + suffix.len());
+    let mut end = collection_name.len().min(room);
+    while !collection_name.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}{}{}", prefix, &collection_name[..end], suffix)
+}
+
+/// `reborn_suffix` fits `MAX_REBORN_SUFFIX_LENGTH` and has at most one `{name}`.
+fn validate_reborn_suffix(reborn_suffix: &str) -> Result<()> {
+    require!(reborn_suffix.len() <= constants::MAX_REBORN_SUFFIX_LENGTH, ErrorCode::InvalidRebornSuffix);
+    require!(
+        reborn_suffix.matches(constants::REBORN_NAME_PLACEHOLDER).count() <= 1,
+        ErrorCode::InvalidRebornSuffix
+    );
+    Ok(())
+}
+
+/// Convert a big-endian uint256 byte slice to a decimal string.
+/// Strips leading zeros, then converts to u128 if it fits, otherwise falls back to hex.
+fn token_id_to_decimal(bytes: &[u8]) -> String {
+    let trimmed: &[u8] = match bytes.iter().position(|&b| b != 0) {
+        Some(pos) => &bytes[pos..],
+        None => return "0".to_string(),
+    };
+    if trimmed.len() <= 16 {
+        let mut val: u128 = 0;
+        for &b in trimmed {
+            val = (val << 8) | b as u128;
+        }
+        val.to_string()
+    } else {
+        // Too large for u128, use 0x-prefixed hex
+        format!("0x{}", hex::encode(trimmed))
+    }
+}
+
+/// Provenance for a Solana-native seal: the grouping key (source collection, or the mint
+/// itself) is the contract, the mint is the token ID, and there is no dWallet signature
+/// or Wormhole message. Independent of `SealMode`.
+fn fill_native_provenance(
+    provenance: &mut Provenance,
+    grouping_key: &Pubkey,
+    nft_mint: &Pubkey,
+    user: &Pubkey,
+    asset: &Pubkey,
+    token_uri: String,
+    sealed_at: i64,
+    bump: u8,
+) {
+    provenance.source_chain = 1; // Solana = Wormhole chain ID 1
+    provenance.nft_contract = grouping_key.to_bytes().to_vec();
+    provenance.token_id = nft_mint.to_bytes().to_vec();
+    provenance.token_uri = token_uri;
+    provenance.dwallet_pubkey = [0u8; 32]; // No dWallet for native path
+    provenance.signature = [0u8; 64]; // No signature for native path
+    provenance.receiver = *user;
+    provenance.asset = *asset;
+    provenance.wormhole_sequence = 0; // No Wormhole message for native path
+    provenance.source_sequence = 0;
+    provenance.source_block_hint = [0u8; 32];
+    provenance.sealed_at = sealed_at;
+    provenance.is_initialized = true;
+    provenance.backfilled = false;
+    provenance.imported_from_v3 = false;
+    provenance.reserved = false;
+    provenance.bump = bump;
+}
+
+/// Seed that groups native seals into one reborn collection: the original NFT's
+/// Metaplex collection when given, otherwise the mint (a one-item collection).
+fn native_grouping_key(source_collection: Option<Pubkey>, nft_mint: &Pubkey) -> Pubkey {
+    source_collection.unwrap_or(*nft_mint)
+}
+
+/// Check the `(key, verified)` collection from the NFT's Token Metadata against the
+/// `source_collection` the caller asked to group under. Only a verified membership
+/// counts, since anyone can set an unverified collection on their own NFT.
+fn check_source_collection(metadata_collection: Option<(Pubkey, bool)>, source_collection: Option<&Pubkey>) -> Result<()> {
+    let Some(expected) = source_collection else { return Ok(()) };
+    match metadata_collection {
+        Some((key, true)) if key == *expected => Ok(()),
+        _ => err!(ErrorCode::SourceCollectionNotVerified),
+    }
+}
+
+/// `claim_reserved` only writes into a slot held by `reserve_provenance` and not yet
+/// minted.
+fn check_reservation(provenance: &Provenance) -> Result<()> {
+    require!(!provenance.is_initialized, ErrorCode::AlreadyMinted);
+    require!(provenance.reserved, ErrorCode::ProvenanceNotReserved);
+    Ok(())
+}
+
+/// Mark a new provenance PDA as reserved for its source token.
+fn fill_reservation(provenance: &mut Provenance, source_chain: u16, nft_contract: Vec<u8>, token_id: Vec<u8>, bump: u8) {
+    provenance.source_chain = source_chain;
+    provenance.nft_contract = nft_contract;
+    provenance.token_id = token_id;
+    provenance.is_initialized = false;
+    provenance.reserved = true;
+    provenance.bump = bump;
+}
+
+/// Reject a native seal whose provenance PDA was already written by an earlier seal of
+/// the same mint.
+fn ensure_not_sealed(provenance: &Provenance) -> Result<()> {
+    require!(!provenance.is_initialized, ErrorCode::AlreadyMinted);
+    Ok(())
+}
+
+/// A new MintConfig needs a real minting key and an admin that can sign as a wallet:
+/// a zeroed key would verify nothing, and an off-curve (PDA) admin can't be recovered
+/// from if it was set by mistake.
+fn validate_mint_config_init(minting_pubkey: &[u8; 32], admin: &Pubkey) -> Result<()> {
+    require!(*minting_pubkey != [0u8; 32], ErrorCode::InvalidMintingPubkey);
+    require!(admin.is_on_curve(), ErrorCode::InvalidAdmin);
+    Ok(())
+}
+
+/// Fill a new MintConfig with its defaults. Shared by `initialize_mint_config` and
+/// `ensure_mint_config`.
+fn init_mint_config(config: &mut MintConfig, minting_pubkey: [u8; 32], admin: Pubkey, bump: u8, now: i64) -> Result<()> {
+    validate_mint_config_init(&minting_pubkey, &admin)?;
+    config.minting_pubkey = minting_pubkey;
+    config.admin = admin;
+    config.strict_ed25519 = false;
+    config.maintain_registry = true;
+    config.recovery_admin = None;
+    config.last_admin_activity = now;
+    config.allowed_relayers = Vec::new();
+    config.max_signatures_per_tx = constants::DEFAULT_MAX_SIGNATURES_PER_TX;
+    config.attribute_schema = None;
+    config.native_seal_fee = 0;
+    config.treasury = admin;
+    config.reborn_suffix = constants::DEFAULT_REBORN_SUFFIX.to_string();
+    config.bump = bump;
+    Ok(())
+}
+
+/// `MintConfig` for a legacy config: its fields carried over, everything added since at
+/// the `init_mint_config` defaults.
+fn migrated_mint_config(legacy: LegacyMintConfig, now: i64) -> MintConfig {
+    MintConfig {
+        minting_pubkey: legacy.minting_pubkey,
+        admin: legacy.admin,
+        strict_ed25519: false,
+        maintain_registry: true,
+        recovery_admin: None,
+        last_admin_activity: now,
+        allowed_relayers: Vec::new(),
+        max_signatures_per_tx: constants::DEFAULT_MAX_SIGNATURES_PER_TX,
+        attribute_schema: None,
+        native_seal_fee: 0,
+        treasury: legacy.admin,
+        reborn_suffix: constants::DEFAULT_REBORN_SUFFIX.to_string(),
+        bump: legacy.bump,
+    }
+}
+
+/// `RebornCollection` for a legacy record; the fields added since start unset.
+fn migrated_collection(legacy: LegacyRebornCollection) -> RebornCollection {
+    RebornCollection {
+        source_chain: legacy.source_chain,
+        nft_contract: legacy.nft_contract,
+        name: legacy.name,
+        symbol: String::new(),
+        collection_asset_address: legacy.collection_asset_address,
+        total_minted: legacy.total_minted,
+        is_initialized: legacy.is_initialized,
+        paused: false,
+        immutable_metadata: false,
+        receiver_allowlist_root: None,
+        bump: legacy.bump,
+    }
+}
+
+/// `Provenance` for a legacy record minted into `asset`; the fields added since start
+/// zeroed or unset.
+fn migrated_provenance(legacy: LegacyProvenance, asset: Pubkey) -> Provenance {
+    Provenance {
+        source_chain: legacy.source_chain,
+        nft_contract: legacy.nft_contract,
+        token_id: legacy.token_id,
+        token_uri: legacy.token_uri,
+        dwallet_pubkey: legacy.dwallet_pubkey,
+        signature: legacy.signature,
+        receiver: legacy.receiver,
+        asset,
+        wormhole_sequence: 0,
+        source_sequence: 0,
+        source_block_hint: [0u8; 32],
+        sealed_at: legacy.sealed_at,
+        is_initialized: legacy.is_initialized,
+        backfilled: false,
+        imported_from_v3: false,
+        reserved: false,
+        bump: legacy.bump,
+    }
+}
+
+/// Read an account still in a legacy layout: owned by this program, exactly
+/// `8 + legacy_space` bytes, and carrying `discriminator`.
+fn read_legacy_account<T: AnchorDeserialize>(info: &AccountInfo, legacy_space: usize, discriminator: &[u8]) -> Result<T> {
+    require!(info.owner == &crate::ID, ErrorCode::NotLegacyLayout);
+    decode_legacy_account(&info.try_borrow_data()?, legacy_space, discriminator)
+}
+
+fn decode_legacy_account<T: AnchorDeserialize>(data: &[u8], legacy_space: usize, discriminator: &[u8]) -> Result<T> {
+    require!(
+        data.len() == 8 + legacy_space && has_discriminator(data, discriminator),
+        ErrorCode::NotLegacyLayout
+    );
+    Ok(T::deserialize(&mut &data[8..])?)
+}
+
+/// Realloc a migrated account to `new_len`, `payer` first topping up its rent.
+fn grow_account<'info>(
+    info: &AccountInfo<'info>,
+    new_len: usize,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    let shortfall = Rent::get()?.minimum_balance(new_len).saturating_sub(info.lamports());
+    if shortfall > 0 {
+        let top_up = anchor_lang::system_program::Transfer { from: payer.to_account_info(), to: info.clone() };
+        anchor_lang::system_program::transfer(CpiContext::new(system_program.to_account_info(), top_up), shortfall)?;
+    }
+    info.realloc(new_len, true)?;
+    Ok(())
+}
+
+/// Whether an `init_if_needed` account existed before this instruction. Anchor writes
+/// the discriminator only on exit, so an account created by this instruction still
+/// reads as zeroes here.
+fn is_initialized_account(info: &AccountInfo, discriminator: &[u8]) -> Result<bool> {
+    Ok(has_discriminator(&info.try_borrow_data()?, discriminator))
+}
+
+fn has_discriminator(data: &[u8], discriminator: &[u8]) -> bool {
+    data.len() >= discriminator.len() && &data[..discriminator.len()] == discriminator
+}
+
+/// Whether `payer` may submit `mint_reborn`: the admin always, plus any allowlisted relayer.
+fn is_authorized_relayer(config: &MintConfig, payer: &Pubkey) -> bool {
+    *payer == config.admin || config.allowed_relayers.contains(payer)
+}
+
+/// Stamp the admin as active now, restarting the recovery window.
+fn record_admin_activity(config: &mut MintConfig) -> Result<()> {
+    config.last_admin_activity = Clock::get()?.unix_timestamp;
+    Ok(())
+}
+
+/// `signer` may take over `admin` only if it is the configured recovery key and the
+/// admin has been inactive for at least `ADMIN_RECOVERY_WINDOW_SECS`.
+fn check_admin_recovery(config: &MintConfig, signer: &Pubkey, now: i64) -> Result<()> {
+    require!(config.recovery_admin == Some(*signer), ErrorCode::Unauthorized);
+    let inactive_for = now.saturating_sub(config.last_admin_activity);
+    require!(
+        inactive_for >= constants::ADMIN_RECOVERY_WINDOW_SECS,
+        ErrorCode::RecoveryWindowNotElapsed
+    );
+    Ok(())
+}
+
+/// Validate a v3 record and write it as an `imported_from_v3` provenance. The v3
+/// attestation key is kept in `dwallet_pubkey`; there is no signature to carry over.
+fn fill_imported_provenance(
+    provenance: &mut Provenance,
+    record: V3RecordImport,
+    token_uri: String,
+    bump: u8,
+) -> Result<()> {
+    require!(record.minted, ErrorCode::V3RecordNotMinted);
+    require!(
+        !record.source_contract.is_empty() && record.source_contract.len() <= constants::MAX_CONTRACT_LENGTH,
+        ErrorCode::ContractTooLong
+    );
+    require!(
+        !record.token_id.is_empty() && record.token_id.len() <= constants::MAX_TOKEN_ID_LENGTH,
+        ErrorCode::TokenIdTooLong
+    );
+    require!(token_uri.len() <= constants::MAX_URI_LENGTH, ErrorCode::UriTooLong);
+    require!(
+        record.recipient != System::id() && record.recipient != Pubkey::default(),
+        ErrorCode::InvalidReceiver
+    );
+
+    provenance.source_chain = record.source_chain;
+    provenance.nft_contract = record.source_contract;
+    provenance.token_id = record.token_id;
+    provenance.token_uri = token_uri;
+    provenance.dwallet_pubkey = record.attestation_pubkey;
+    provenance.signature = [0u8; 64];
+    provenance.receiver = record.recipient;
+    provenance.asset = record.mint;
+    provenance.wormhole_sequence = 0;
+    provenance.source_sequence = 0;
+    provenance.source_block_hint = [0u8; 32];
+    provenance.sealed_at = record.verified_at;
+    provenance.is_initialized = true;
+    provenance.backfilled = false;
+    provenance.imported_from_v3 = true;
+    provenance.reserved = false;
+    provenance.bump = bump;
+    Ok(())
+}
+
+/// Plugins for a `mint_reborn` asset. Owner stays the receiver; a delegate only gets
+/// MPL Core's TransferDelegate authority. Collections locked with `immutable_metadata`
+/// add ImmutableMetadata (no authority, so it can never be removed).
+fn reborn_asset_plugins(delegate: Option<Pubkey>, immutable_metadata: bool) -> Vec<PluginAuthorityPair> {
+    let mut plugins = Vec::with_capacity(2);
+    if let Some(address) = delegate {
+        plugins.push(PluginAuthorityPair {
+            plugin: Plugin::TransferDelegate(TransferDelegate {}),
+            authority: Some(PluginAuthority::Address { address }),
+        });
+    }
+    if immutable_metadata {
+        plugins.push(PluginAuthorityPair {
+            plugin: Plugin::ImmutableMetadata(ImmutableMetadata {}),
+            authority: Some(PluginAuthority::None),
+        });
+    }
+    plugins
+}
+
+/// `update_asset_uri` only applies to collections that haven't opted into immutability.
+fn check_metadata_refreshable(collection: &RebornCollection) -> Result<()> {
+    require!(!collection.immutable_metadata, ErrorCode::MetadataImmutable);
+    Ok(())
+}
+
+/// The new asset and its MPL Core collection must be different accounts; one account
+/// passed as both only fails later inside the MPL Core CPI, with a confusing error.
+fn check_asset_collection_distinct(collection_asset: &Pubkey, asset: &Pubkey) -> Result<()> {
+    require!(collection_asset != asset, ErrorCode::AssetCollectionCollision);
+    Ok(())
+}
+
+/// Bump of `collection_asset` if it is the deterministic `pdas::collection_asset` address,
+/// `None` for a keypair-created collection.
+#[inline(never)]
+fn pda_collection_asset_bump(source_chain: u16, nft_contract: &[u8], collection_asset: &Pubkey) -> Option<u8> {
+    let (pda, bump) = pdas::collection_asset(source_chain, nft_contract);
+    (pda == *collection_asset).then_some(bump)
+}
+
+/// On later mints the passed `collection_asset` must be the one recorded on first mint,
+/// or the new asset would join a different MPL Core collection than our PDA records.
+fn check_collection_asset(collection: &RebornCollection, collection_asset: &Pubkey) -> Result<()> {
+    require!(
+        *collection_asset == collection.collection_asset_address,
+        ErrorCode::CollectionAssetMismatch
+    );
+    Ok(())
+}
+
+/// Leaf of the receiver allowlist tree: sha256(receiver).
+fn receiver_allowlist_leaf(receiver: &Pubkey) -> [u8; 32] {
+    Sha256::digest(receiver.as_ref()).into()
+}
+
+/// With a root set, `proof` must lead from `receiver`'s leaf to it. Each step hashes
+/// the sorted pair, sha256(min || max), so proofs carry no left/right flags.
+fn verify_receiver_allowlist(root: Option<[u8; 32]>, receiver: &Pubkey, proof: &[[u8; 32]]) -> Result<()> {
+    let Some(root) = root else {
+        return Ok(());
+    };
+    let computed = proof.iter().fold(receiver_allowlist_leaf(receiver), |node, sibling| {
+        let (a, b) = if node <= *sibling { (node, *sibling) } else { (*sibling, node) };
+        let mut hasher = Sha256::new();
+        hasher.update(a);
+        hasher.update(b);
+        hasher.finalize().into()
+    });
+    require!(computed == root, ErrorCode::ReceiverNotAllowlisted);
+    Ok(())
+}
+
+/// PDA accounts of a `mint_reborn` call, as passed by the relayer.
+struct MintAccountKeys {
+    sig_record: Pubkey,
+    provenance: Pubkey,
+    collection: Pubkey,
+    mint_authority: Pubkey,
+    config: Pubkey,
+}
+
+/// Compare each account with its expected derivation, in `MintReborn` order.
+fn check_mint_accounts(
+    sig_hash: &[u8],
+    source_chain: u16,
+    nft_contract: &[u8],
+    token_id: &[u8],
+    passed: &MintAccountKeys,
+) -> Result<()> {
+    require_keys_eq!(passed.sig_record, pdas::sig_used(sig_hash).0, ErrorCode::SigRecordAccountMismatch);
+    require_keys_eq!(
+        passed.provenance,
+        pdas::provenance(source_chain, nft_contract, token_id).0,
+        ErrorCode::ProvenanceAccountMismatch
+    );
+    require_keys_eq!(
+        passed.collection,
+        pdas::reborn_collection(source_chain, nft_contract).0,
+        ErrorCode::CollectionAccountMismatch
+    );
+    require_keys_eq!(
+        passed.mint_authority,
+        pdas::mint_authority(source_chain, nft_contract).0,
+        ErrorCode::MintAuthorityAccountMismatch
+    );
+    require_keys_eq!(passed.config, pdas::mint_config().0, ErrorCode::ConfigAccountMismatch);
+    Ok(())
+}
+
+/// Count one more mint in `collection`; overflow is an error, never a silent cap.
+fn record_collection_mint(collection: &mut RebornCollection) -> Result<()> {
+    collection.total_minted = collection.total_minted.checked_add(1).ok_or(ErrorCode::SupplyOverflow)?;
+    Ok(())
+}
+
+/// Fold `duplicate` into `canonical` by summing supplies. The duplicate's MPL Core
+/// collection is never taken over: its update authority is the big-endian-seeded
+/// `mint_authority`, which `mint_reborn` can't sign for. A canonical record that never
+/// minted stays uninitialized, so its next mint creates a collection under the
+/// little-endian authority.
+fn merge_collection_records(canonical: &mut RebornCollection, duplicate: &RebornCollection) -> Result<()> {
+    require!(canonical.nft_contract == duplicate.nft_contract, ErrorCode::CollectionAccountMismatch);
+    canonical.total_minted = canonical
+        .total_minted
+        .checked_add(duplicate.total_minted)
+        .ok_or(ErrorCode::SupplyOverflow)?;
+    Ok(())
+}
+
+/// Collection names are shown by marketplaces as-is: no control characters (newlines,
+/// tabs, ...). At most `MAX_NAME_LENGTH` bytes (`NameTooLong`), as that bounds the
+/// `RebornCollection.name` field; the character count is never larger.
+fn validate_collection_name(name: &str) -> Result<()> {
+    require!(name.len() <= constants::MAX_NAME_LENGTH, ErrorCode::NameTooLong);
+    require!(!name.chars().any(char::is_control), ErrorCode::InvalidName);
+    Ok(())
+}
+
+/// Optional collection symbol: non-empty and at most `MAX_SYMBOL_LENGTH` bytes.
+fn validate_symbol(symbol: Option<&str>) -> Result<()> {
+    if let Some(symbol) = symbol {
+        require!(
+            !symbol.is_empty() && symbol.len() <= constants::MAX_SYMBOL_LENGTH,
+            ErrorCode::SymbolTooLong
+        );
+    }
+    Ok(())
+}
+
+/// Attributes plugin on the collection asset carrying the marketplace display fields
+/// (`symbol` when set, and `seller_fee_basis_points`).
+fn collection_attributes_plugin(symbol: Option<&str>, royalty_basis_points: u16) -> PluginAuthorityPair {
+    let mut attribute_list = Vec::with_capacity(2);
+    if let Some(symbol) = symbol {
+        attribute_list.push(Attribute { key: "symbol".to_string(), value: symbol.to_string() });
+    }
+    attribute_list.push(Attribute {
+        key: "seller_fee_basis_points".to_string(),
+        value: royalty_basis_points.to_string(),
+    });
+    PluginAuthorityPair {
+        plugin: Plugin::Attributes(Attributes { attribute_list }),
+        authority: Some(PluginAuthority::UpdateAuthority),
+    }
+}
+
+/// Each schema key must be non-empty, at most `MAX_ATTRIBUTE_KEY_LENGTH` bytes, and
+/// distinct from the others.
+fn validate_attribute_schema(schema: &AttributeSchema) -> Result<()> {
+    let keys = [&schema.source_chain_key, &schema.source_contract_key, &schema.token_id_key];
+    for key in keys {
+        require!(
+            !key.is_empty() && key.len() <= constants::MAX_ATTRIBUTE_KEY_LENGTH,
+            ErrorCode::InvalidAttributeKey
+        );
+    }
+    require!(
+        keys[0] != keys[1] && keys[0] != keys[2] && keys[1] != keys[2],
+        ErrorCode::InvalidAttributeKey
+    );
+    Ok(())
+}
+
+/// Attributes plugin on a reborn asset carrying its provenance under `schema`'s keys.
+/// The token ID is formatted as in the asset name's binary case (`token_id_to_decimal`).
+fn provenance_attributes_plugin(
+    schema: &AttributeSchema,
+    source_chain: u16,
+    nft_contract: &[u8],
+    token_id: &[u8],
+) -> PluginAuthorityPair {
+    let attribute_list = vec![
+        Attribute { key: schema.source_chain_key.clone(), value: source_chain.to_string() },
+        Attribute { key: schema.source_contract_key.clone(), value: format!("0x{}", hex::encode(nft_contract)) },
+        Attribute { key: schema.token_id_key.clone(), value: token_id_to_decimal(token_id) },
+    ];
+    PluginAuthorityPair {
+        plugin: Plugin::Attributes(Attributes { attribute_list }),
+        authority: Some(PluginAuthority::UpdateAuthority),
+    }
+}
+
+/// On-chain size of a new MPL Core collection with `plugins` plugins (estimate).
+fn mpl_core_collection_space(name_len: usize, uri_len: usize, plugins: usize) -> usize {
+    constants::MPL_CORE_COLLECTION_BASE_SIZE + name_len + uri_len + plugins * constants::MPL_CORE_PLUGIN_SIZE
+}
+
+/// On-chain size of a new MPL Core asset with `plugins` plugins (estimate).
+fn mpl_core_asset_space(name_len: usize, uri_len: usize, plugins: usize) -> usize {
+    constants::MPL_CORE_ASSET_BASE_SIZE + name_len + uri_len + plugins * constants::MPL_CORE_PLUGIN_SIZE
+}
+
+/// Rent-exempt lamports for the asset plus, on first mint, the collection.
+fn mpl_core_rent_needed(rent: &Rent, collection_space: Option<usize>, asset_space: usize) -> u64 {
+    let collection = collection_space.map_or(0, |space| rent.minimum_balance(space));
+    collection.saturating_add(rent.minimum_balance(asset_space))
+}
+
+/// Require `available` lamports to cover `needed`, logging both on failure.
+fn check_payer_funds(available: u64, needed: u64) -> Result<()> {
+    if available < needed {
+        msg!("Payer has {} lamports, needs {} for MPL Core accounts", available, needed);
+        return err!(ErrorCode::InsufficientFundsForMint);
+    }
+    Ok(())
+}
+
+/// With the `compute-log` feature, log (and return) the compute units left at a
+/// `mint_reborn` checkpoint so operators can see where the budget goes. No-op otherwise.
+#[inline(always)]
+fn compute_checkpoint(checkpoint: &str) -> Option<String> {
+    #[cfg(feature = "compute-log")]
+    {
+        let remaining = anchor_lang::solana_program::compute_units::sol_remaining_compute_units();
+        let line = format!("Compute units remaining after {}: {}", checkpoint, remaining);
+        msg!("{}", line);
+        Some(line)
+    }
+    #[cfg(not(feature = "compute-log"))]
+    {
+        let _ = checkpoint;
+        None
+    }
+}
+
+/// Lamports to charge for a native seal, or `None` when the path is free.
+fn native_seal_fee_due(config: &MintConfig) -> Option<u64> {
+    (config.native_seal_fee > 0).then_some(config.native_seal_fee)
+}
+
+/// Whether a mint is a single-edition NFT: no decimals and exactly one token.
+fn is_single_edition(decimals: u8, supply: u64) -> bool {
+    decimals == 0 && supply == 1
+}
+
+/// Reject Token-2022 mint extensions the native seal can't honour. Non-transferable
+/// tokens can't move into the vault (burning them is allowed), and transfer hooks
+/// would need extra accounts this instruction doesn't take.
+fn check_native_seal_extensions(extensions: &[ExtensionType], seal_mode: SealMode) -> Result<()> {
+    for ext in extensions {
+        match (ext, seal_mode) {
+            (ExtensionType::NonTransferable, SealMode::Lock)
+            | (ExtensionType::TransferHook, SealMode::Lock) => {
+                return err!(ErrorCode::UnsupportedTokenExtension);
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Pick how a native seal moves the original. A programmable NFT (frozen by design)
+/// goes through Token Metadata and can't be burned here; any other frozen token
+/// (e.g. staked or delegate-frozen) can't move at all.
+fn native_transfer_kind(
+    token_standard: Option<&TokenStandard>,
+    frozen: bool,
+    seal_mode: SealMode,
+) -> Result<NativeTransfer> {
+    let programmable = matches!(
+        token_standard,
+        Some(TokenStandard::ProgrammableNonFungible | TokenStandard::ProgrammableNonFungibleEdition)
+    );
+    if programmable {
+        require!(seal_mode == SealMode::Lock, ErrorCode::ProgrammableBurnUnsupported);
+        return Ok(NativeTransfer::TokenMetadata);
+    }
+    require!(!frozen, ErrorCode::NftFrozen);
+    Ok(NativeTransfer::Token)
+}
+
+/// Append a newly created collection to the registry when maintenance is enabled.
+/// With maintenance off the registry is ignored (and may be omitted), so first mints
+/// skip the large account entirely. Returns whether an entry was added.
+fn maybe_register_collection(
+    maintain_registry: bool,
+    registry: Option<&mut CollectionRegistry>,
+    entry: CollectionEntry,
+) -> Result<bool> {
+    if !maintain_registry {
+        return Ok(false);
+    }
+    let registry = registry.ok_or(ErrorCode::RegistryRequired)?;
+    registry.register_collection(entry)
+}
+
+/// Load the receiver override at its (already seed-checked) PDA, if one was created.
+/// Returns the redirected receiver, or None when no override exists.
+fn load_receiver_override(
+    override_info: &AccountInfo,
+    signed_receiver: &Pubkey,
+) -> Result<Option<Pubkey>> {
+    if override_info.owner != &crate::ID || override_info.data_is_empty() {
+        return Ok(None);
+    }
+    let data = override_info.try_borrow_data()?;
+    let receiver_override = ReceiverOverride::try_deserialize(&mut &data[..])?;
+    require!(
+        receiver_override.original_receiver == *signed_receiver,
+        ErrorCode::ReceiverOverrideMismatch
+    );
+    Ok(Some(receiver_override.new_receiver))
+}
+
+/// Verify the seal: sig_hash matches sha256(signature), message hash is correct,
+/// and Ed25519 signature is valid. Separated into its own stack frame to reduce
+/// the main mint_reborn function's stack usage below the BPF 4KB limit.
+#[inline(never)]
+fn verify_seal(
+    instructions_sysvar: &AccountInfo,
+    minting_pubkey: &[u8; 32],
+    signature: &[u8],
+    sig_hash: &[u8],
+    token_uri: &str,
+    token_id: &[u8],
+    receiver_pubkey: &Pubkey,
+    wormhole_sequence: u64,
+    source_sequence: u64,
+    source_block_hint: &[u8; 32],
+    valid_until_slot: u64,
+    hash_algo: u8,
+    strict: bool,
+    max_signatures: u8,
+) -> Result<()> {
+    // Verify sig_hash == sha256(signature)
+    let computed_sig_hash: [u8; 32] = Sha256::digest(signature).into();
+    require!(
+        constant_time_eq::constant_time_eq(&computed_sig_hash, sig_hash),
+        ErrorCode::InvalidSigHash
+    );
+
+    let message_hash = compute_message_hash(
+        token_uri,
+        token_id,
+        receiver_pubkey,
+        wormhole_sequence,
+        source_sequence,
+        source_block_hint,
+        valid_until_slot,
+        hash_algo,
+    )?;
+
+    // Verify Ed25519 signature via precompile
+    verify_ed25519_signature(
+        instructions_sysvar,
+        minting_pubkey,
+        &message_hash,
+        signature,
+        strict,
+        max_signatures,
+    )?;
+
+    Ok(())
+}
+
+/// Reject an attestation once the current slot is past its signed `valid_until_slot`.
+fn check_signature_fresh(current_slot: u64, valid_until_slot: u64) -> Result<()> {
+    require!(current_slot <= valid_until_slot, ErrorCode::SignatureExpired);
+    Ok(())
+}
+
+/// Reconstruct the signed message hash:
+/// H(token_uri || token_id || receiver || wormhole_sequence || source_sequence
+///   || source_block_hint || valid_until_slot || hash_algo)
+///
+/// `wormhole_sequence` is appended as a big-endian u64, matching the VAA encoding,
+/// so each mint is bound to the specific Wormhole message that authorized it.
+/// `source_sequence` (big-endian u64) and the 32-byte `source_block_hint` identify the
+/// source-chain seal, so an off-chain reconciler can flag mints whose seal reorged out.
+/// `valid_until_slot` (big-endian u64) is the last Solana slot the dWallet allows the
+/// attestation to be redeemed in; `check_signature_fresh` enforces it.
+/// `hash_algo` selects H (sha256 or keccak256) and is itself part of the preimage,
+/// so a signature only verifies under the algorithm it was produced for.
+fn compute_message_hash(
+    token_uri: &str,
+    token_id: &[u8],
+    receiver_pubkey: &Pubkey,
+    wormhole_sequence: u64,
+    source_sequence: u64,
+    source_block_hint: &[u8; 32],
+    valid_until_slot: u64,
+    hash_algo: u8,
+) -> Result<[u8; 32]> {
+    let sequence_bytes = wormhole_sequence.to_be_bytes();
+    let source_sequence_bytes = source_sequence.to_be_bytes();
+    let valid_until_slot_bytes = valid_until_slot.to_be_bytes();
+    let parts: [&[u8]; 8] = [
+        token_uri.as_bytes(),
+        token_id,
+        receiver_pubkey.as_ref(),
+        &sequence_bytes,
+        &source_sequence_bytes,
+        source_block_hint,
+        &valid_until_slot_bytes,
+        &[hash_algo],
+    ];
+    match hash_algo {
+        constants::HASH_ALGO_SHA256 => {
+            let mut hasher = Sha256::new();
+            for part in parts {
+                hasher.update(part);
+            }
+            Ok(hasher.finalize().into())
+        }
+        // keccak::hashv uses the sol_keccak256 syscall on-chain
+        constants::HASH_ALGO_KECCAK256 => Ok(keccak::hashv(&parts).to_bytes()),
+        _ => Err(ErrorCode::UnsupportedHashAlgo.into()),
+    }
+}
+
+/// Strict-mode layout check for an Ed25519 precompile instruction: exactly one
+/// signature entry, no foreign-instruction references, and all offsets inside
+/// the single-entry data region.
+fn verify_single_entry_layout(data: &[u8]) -> Result<()> {
+    const HEADER_LEN: usize = 16;
+    const SINGLE_ENTRY_LEN: usize = HEADER_LEN + 32 + 64 + 32;
+
+    if data[0] != 1 || data.len() != SINGLE_ENTRY_LEN {
+        return Err(ErrorCode::UnexpectedMultipleSignatures.into());
+    }
+
+    let field = |i: usize| u16::from_le_bytes([data[i], data[i + 1]]);
+    let sig_offset = field(2) as usize;
+    let pubkey_offset = field(6) as usize;
+    let message_offset = field(10) as usize;
+    let message_size = field(12) as usize;
+
+    // Instruction indices must all be u16::MAX (data lives in this instruction)
+    if field(4) != u16::MAX || field(8) != u16::MAX || field(14) != u16::MAX {
+        return Err(ErrorCode::UnexpectedMultipleSignatures.into());
+    }
+
+    let in_region = |offset: usize, len: usize| offset >= HEADER_LEN && offset + len <= SINGLE_ENTRY_LEN;
+    if !in_region(sig_offset, 64) || !in_region(pubkey_offset, 32) || !in_region(message_offset, message_size) {
+        return Err(ErrorCode::UnexpectedMultipleSignatures.into());
+    }
+
+    Ok(())
+}
+
+/// Verify an Ed25519 signature using Solana's native precompile.
+///
+/// Uses the nearest Ed25519 program instruction before the current top-level
+/// instruction. When `mint_reborn` is reached via CPI, the current index is the
+/// calling program's instruction, so an integrator composes
+/// `[.., Ed25519 precompile, caller ix]` and the relayer's `[Ed25519, .., mint_reborn]`
+/// layout keeps working. Extracts and verifies **three** fields against our expected
+/// values, reading each from the instruction named by its header index exactly as
+/// the precompile did:
+///
+/// 1. Public key (offset from data[6..7])
+/// 2. Message    (offset from data[10..11], size from data[12..13])
+/// 3. **Signature bytes** (offset from data[2..3]) — previously missing (FIX 1)
+///
+/// Ed25519 instruction header layout (per signature entry, starting at byte 2):
+/// ```text
+///  [0]     num_signatures
+///  [1]     padding
+///  [2..3]  sig_offset          (u16 LE) ← FIX 1: now verified
+///  [4..5]  sig_instruction_idx (u16 LE)
+///  [6..7]  pubkey_offset       (u16 LE)
+///  [8..9]  pubkey_instruction_idx (u16 LE)
+/// [10..11] message_offset      (u16 LE)
+/// [12..13] message_size        (u16 LE)
+/// [14..15] message_instruction_idx (u16 LE)
+/// ```
+fn verify_ed25519_signature(
+    instructions_sysvar: &AccountInfo,
+    expected_pubkey: &[u8],
+    expected_message: &[u8],
+    expected_signature: &[u8],
+    strict: bool,
+    max_signatures: u8,
+) -> Result<()> {
+    // Any Ed25519 instruction is acceptable: the pubkey, message and signature it
+    // verified must all equal ours, so one signed for another purpose never matches.
+    let current_index = ix_sysvar::load_current_index_checked(instructions_sysvar)
+        .map_err(|_| ErrorCode::NoEd25519Instruction)?;
+    let load = |index: usize| ix_sysvar::load_instruction_at_checked(index, instructions_sysvar).ok();
+    let ed25519_ix = find_ed25519_ix(current_index, load).ok_or(ErrorCode::NoEd25519Instruction)?;
+
+    // Fields the header places in other instructions (e.g. the CPI caller's data)
+    let foreign = ed25519_foreign_data(&ed25519_ix.data, load)?;
+
+    verify_ed25519_ix_data(
+        &ed25519_ix.data,
+        &foreign,
+        expected_pubkey,
+        expected_message,
+        expected_signature,
+        strict,
+        max_signatures,
+    )
+}
+
+/// Nearest Ed25519 program instruction before top-level instruction `current_index`.
+fn find_ed25519_ix(current_index: u16, load: impl Fn(usize) -> Option<Instruction>) -> Option<Instruction> {
+    (0..current_index as usize)
+        .rev()
+        .filter_map(&load)
+        .find(|ix| ix.program_id == ed25519_program::ID)
+}
+
+/// Load the data of every instruction the first signature entry's header points at
+/// (indices other than `u16::MAX`, which means the precompile instruction itself).
+fn ed25519_foreign_data(data: &[u8], load: impl Fn(usize) -> Option<Instruction>) -> Result<Vec<(u16, Vec<u8>)>> {
+    let mut foreign: Vec<(u16, Vec<u8>)> = Vec::new();
+    if data.len() < 16 {
+        return Ok(foreign);
+    }
+    for at in [4, 8, 14] {
+        let index = u16::from_le_bytes([data[at], data[at + 1]]);
+        if index != u16::MAX && !foreign.iter().any(|(i, _)| *i == index) {
+            let ix = load(index as usize).ok_or(ErrorCode::InvalidInstructionData)?;
+            foreign.push((index, ix.data));
+        }
+    }
+    Ok(foreign)
+}
+
+/// `len` bytes at `offset` in the instruction a header field points at.
+fn ed25519_field<'a>(
+    data: &'a [u8],
+    foreign: &'a [(u16, Vec<u8>)],
+    ix_index: u16,
+    offset: usize,
+    len: usize,
+) -> Option<&'a [u8]> {
+    let source = if ix_index == u16::MAX {
+        data
+    } else {
+        foreign.iter().find(|(i, _)| *i == ix_index)?.1.as_slice()
+    };
+    source.get(offset..offset + len)
+}
+
+/// Inner verification of Ed25519 instruction data fields.
+/// Checks all three fields: signature bytes, public key, and message. A field whose
+/// header instruction index is not `u16::MAX` is read from that instruction's data,
+/// which the caller supplies in `foreign`.
+///
+/// In `strict` mode the instruction must carry exactly one signature laid out as a
+/// single entry: header (16) + pubkey (32) + signature (64) + message (32), with every
+/// offset pointing past the header into this instruction's own data. The permissive
+/// mode only inspects the first entry and is kept for batch verification.
+///
+/// Either way, a `num_signatures` above `max_signatures` is rejected before any entry
+/// is parsed, so an oversized batch costs no more than reading the count.
+fn verify_ed25519_ix_data(
+    data: &[u8],
+    foreign: &[(u16, Vec<u8>)],
+    expected_pubkey: &[u8],
+    expected_message: &[u8],
+    expected_signature: &[u8],
+    strict: bool,
+    max_signatures: u8,
+) -> Result<()> {
+    // Minimum header size: 2 (count + padding) + 14 (one signature entry header)
+    if data.len() < 16 {
+        return Err(ErrorCode::InvalidInstructionData.into());
+    }
+
+    let num_signatures = data[0];
+    if num_signatures < 1 {
+        return Err(ErrorCode::InvalidInstructionData.into());
+    }
+    if num_signatures > max_signatures {
+        return Err(ErrorCode::TooManySignatures.into());
+    }
+
+    if strict {
+        verify_single_entry_layout(data)?;
+    }
+
+    // Parse offsets from the first signature entry header (starts at byte 2)
+    let sig_offset     = u16::from_le_bytes([data[2],  data[3]])  as usize;
+    let sig_ix         = u16::from_le_bytes([data[4],  data[5]]);
+    let pubkey_offset  = u16::from_le_bytes([data[6],  data[7]])  as usize;
+    let pubkey_ix      = u16::from_le_bytes([data[8],  data[9]]);
+    let message_offset = u16::from_le_bytes([data[10], data[11]]) as usize;
+    let message_size   = u16::from_le_bytes([data[12], data[13]]) as usize;
+    let message_ix     = u16::from_le_bytes([data[14], data[15]]);
+
+    // Verify signature bytes (all 64)
+    match ed25519_field(data, foreign, sig_ix, sig_offset, 64) {
+        Some(sig_data) => {
+            if !constant_time_eq::constant_time_eq(sig_data, expected_signature) {
+                return Err(ErrorCode::SignatureVerificationFailed.into());
+            }
+        }
+        None => return Err(ErrorCode::InvalidInstructionData.into()),
+    }
+
+    // Verify public key (32 bytes)
+    match ed25519_field(data, foreign, pubkey_ix, pubkey_offset, 32) {
+        Some(pubkey_data) => {
+            if !constant_time_eq::constant_time_eq(pubkey_data, expected_pubkey) {
+                return Err(ErrorCode::SignatureVerificationFailed.into());
+            }
+        }
+        None => return Err(ErrorCode::InvalidInstructionData.into()),
+    }
+
+    // Verify message (must be exactly 32 bytes = our SHA256 hash)
+    if message_size != 32 {
+        return Err(ErrorCode::SignatureVerificationFailed.into());
+    }
+    match ed25519_field(data, foreign, message_ix, message_offset, message_size) {
+        Some(message_data) => {
+            if !constant_time_eq::constant_time_eq(message_data, expected_message) {
+                return Err(ErrorCode::SignatureVerificationFailed.into());
+            }
+        }
+        None => return Err(ErrorCode::InvalidInstructionData.into()),
+    }
+
+    Ok(())
+}
+
+// ============ Errors ============
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Invalid Ed25519 signature")]
+    InvalidSignature,
+
+    #[msg("Invalid instruction data")]
+    InvalidInstructionData,
+
+    #[msg("No Ed25519 instruction found in transaction")]
+    NoEd25519Instruction,
+
+    #[msg("Ed25519 signature verification failed (pubkey, message, or signature bytes mismatch)")]
+    SignatureVerificationFailed,
+
+    #[msg("Signature already used — replay protection")]
+    SignatureAlreadyUsed,
+
+    #[msg("sig_hash does not match sha256(signature)")]
+    InvalidSigHash,
+
+    #[msg("Collection already exists")]
+    CollectionAlreadyExists,
+
+    #[msg("NFT already minted")]
+    AlreadyMinted,
+
+    #[msg("Contract address too long")]
+    ContractTooLong,
+
+    #[msg("Token ID too long")]
+    TokenIdTooLong,
+
+    #[msg("URI too long (max 512 bytes)")]
+    UriTooLong,
+
+    #[msg("Name too long")]
+    NameTooLong,
+
+    #[msg("Receiver must not be the system program or zero key")]
+    InvalidReceiver,
+
+    #[msg("Metaplex error")]
+    MetaplexError,
+
+    #[msg("Unauthorized")]
+    Unauthorized,
+
+    #[msg("Source chain must be Solana (chain ID 1) for native seal")]
+    InvalidSourceChain,
+
+    #[msg("NFT token account mint does not match provided nft_mint")]
+    InvalidNftMint,
+
+    #[msg("NFT token account must contain exactly 1 token")]
+    InvalidNftAmount,
+
+    #[msg("Invalid royalty basis points (max 10000)")]
+    InvalidRoyalties,
+
+    #[msg("Unsupported signed message hash algorithm")]
+    UnsupportedHashAlgo,
+
+    #[msg("Minting is paused for this collection")]
+    CollectionPaused,
+
+    #[msg("Ed25519 instruction must carry exactly one signature in strict mode")]
+    UnexpectedMultipleSignatures,
+
+    #[msg("Delegate must not be the zero key or the receiver")]
+    InvalidDelegate,
+
+    #[msg("Receiver override does not match the signed receiver or the passed override_receiver")]
+    ReceiverOverrideMismatch,
+
+    #[msg("Collection registry account required while registry maintenance is enabled")]
+    RegistryRequired,
+
+    #[msg("Collection registry is full")]
+    RegistryFull,
+
+    #[msg("sealed_nft_vault is required for SealMode::Lock")]
+    VaultRequired,
+
+    #[msg("Mint is not an NFT (requires 0 decimals and supply 1)")]
+    NotAnNft,
+
+    #[msg("Token-2022 extension not supported for this seal mode")]
+    UnsupportedTokenExtension,
+
+    #[msg("Treasury account does not match config")]
+    InvalidTreasury,
+
+    #[msg("v3 record was never minted; nothing to import")]
+    V3RecordNotMinted,
+
+    #[msg("Payer cannot cover rent for the new Metaplex Core account(s)")]
+    InsufficientFundsForMint,
+
+    #[msg("Collection symbol must be 1-10 bytes")]
+    SymbolTooLong,
+
+    #[msg("collection_asset does not match the RebornCollection record")]
+    CollectionAssetMismatch,
+
+    #[msg("Admin has been active within the recovery window")]
+    RecoveryWindowNotElapsed,
+
+    #[msg("Too many allowed relayers")]
+    TooManyRelayers,
+
+    #[msg("Ed25519 instruction carries more signatures than max_signatures_per_tx")]
+    TooManySignatures,
+
+    #[msg("max_signatures_per_tx must be at least 1")]
+    InvalidSignatureLimit,
+
+    #[msg("NFT is not a verified member of source_collection")]
+    SourceCollectionNotVerified,
+
+    #[msg("Collection metadata is immutable")]
+    MetadataImmutable,
+
+    #[msg("Fee vault total overflowed")]
+    FeeOverflow,
+
+    #[msg("Withdrawal exceeds collected fees")]
+    InsufficientFeeVaultBalance,
+
+    #[msg("Signature expired (current slot is past valid_until_slot)")]
+    SignatureExpired,
+
+    #[msg("Collection mint counter overflowed")]
+    SupplyOverflow,
+
+    #[msg("Minting pubkey must not be all zeros")]
+    InvalidMintingPubkey,
+
+    #[msg("Admin must be an on-curve wallet key")]
+    InvalidAdmin,
+
+    #[msg("Receiver is not in the collection's allowlist")]
+    ReceiverNotAllowlisted,
+
+    #[msg("sig_record is not the sig_used PDA for sig_hash")]
+    SigRecordAccountMismatch,
+
+    #[msg("provenance is not the PDA for this source NFT")]
+    ProvenanceAccountMismatch,
+
+    #[msg("collection is not the reborn_collection PDA for this source contract")]
+    CollectionAccountMismatch,
+
+    #[msg("mint_authority is not the PDA for this source contract")]
+    MintAuthorityAccountMismatch,
+
+    #[msg("config is not the mint_config PDA")]
+    ConfigAccountMismatch,
+
+    #[msg("Attribute schema keys must be non-empty, distinct, and at most 32 bytes")]
+    InvalidAttributeKey,
+
+    #[msg("source_chain reads the same in both byte orders, there is no duplicate to merge")]
+    NoDuplicateEncoding,
+
+    #[msg("Reborn suffix exceeds 24 bytes or repeats the name placeholder")]
+    InvalidRebornSuffix,
+
+    #[msg("asset and collection_asset must be different accounts")]
+    AssetCollectionCollision,
+
+    #[msg("Collection name contains control characters")]
+    InvalidName,
+
+    #[msg("Provenance is not reserved")]
+    ProvenanceNotReserved,
+
+    #[msg("Account is not in the legacy layout this migration expects")]
+    NotLegacyLayout,
+
+    #[msg("NFT token account is frozen and is not a programmable NFT")]
+    NftFrozen,
+
+    #[msg("Programmable NFTs can only be sealed with SealMode::Lock")]
+    ProgrammableBurnUnsupported,
+
+    #[msg("Sealing a programmable NFT needs its Token Metadata accounts")]
+    ProgrammableAccountsRequired,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build Ed25519 precompile instruction data carrying a single signature entry,
+    /// laid out the way `Ed25519Program.createInstructionWithPublicKey` does.
+    fn ed25519_ix_data(pubkey: &[u8; 32], signature: &[u8; 64], message: &[u8]) -> Vec<u8> {
+        let header_len: u16 = 16;
+        let pubkey_offset = header_len;
+        let sig_offset = pubkey_offset + 32;
+        let message_offset = sig_offset + 64;
+
+        let mut data = vec![1u8, 0u8];
+        data.extend_from_slice(&sig_offset.to_le_bytes());
+        data.extend_from_slice(&u16::MAX.to_le_bytes());
+        data.extend_from_slice(&pubkey_offset.to_le_bytes());
+        data.extend_from_slice(&u16::MAX.to_le_bytes());
+        data.extend_from_slice(&message_offset.to_le_bytes());
+        data.extend_from_slice(&(message.len() as u16).to_le_bytes());
+        data.extend_from_slice(&u16::MAX.to_le_bytes());
+        data.extend_from_slice(pubkey);
+        data.extend_from_slice(signature);
+        data.extend_from_slice(message);
+        data
+    }
+
+    fn find(seeds: &[&[u8]]) -> (Pubkey, u8) {
+        Pubkey::find_program_address(seeds, &crate::ID)
+    }
+
+    const SHA256: u8 = constants::HASH_ALGO_SHA256;
+    const KECCAK256: u8 = constants::HASH_ALGO_KECCAK256;
+    const MAX_SIGS: u8 = constants::DEFAULT_MAX_SIGNATURES_PER_TX;
+
+    #[test]
+    fn test_message_hash_commits_to_wormhole_sequence() {
+        let receiver = Pubkey::new_from_array([7u8; 32]);
+        let a = compute_message_hash("ipfs://Qm", b"42", &receiver, 100, 0, &[0u8; 32], u64::MAX, SHA256).unwrap();
+        let b = compute_message_hash("ipfs://Qm", b"42", &receiver, 100, 0, &[0u8; 32], u64::MAX, SHA256).unwrap();
+        let c = compute_message_hash("ipfs://Qm", b"42", &receiver, 101, 0, &[0u8; 32], u64::MAX, SHA256).unwrap();
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    /// Same vector as `test_signing_message_matches_solana_vector` in the Sui
+    /// `payload_tests`, so the two preimages can't drift apart.
+    #[test]
+    fn test_message_hash_matches_sui_signer() {
+        let receiver = Pubkey::new_from_array([7u8; 32]);
+        let hash = compute_message_hash("ipfs://Qm", b"42", &receiver, 100, 7, &[9u8; 32], 1000, SHA256).unwrap();
+        assert_eq!(
+            hash,
+            [
+                0xed, 0x5d, 0xa7, 0xd6, 0x45, 0xb5, 0x3c, 0xfb, 0x6b, 0xb1, 0x9b, 0x0c, 0xfb, 0x42, 0xe8, 0xea,
+                0xd8, 0x57, 0x21, 0x9c, 0x11, 0xe9, 0x00, 0xf8, 0x08, 0xc4, 0x95, 0x47, 0xd1, 0x92, 0x42, 0xdf,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mismatched_wormhole_sequence_fails_verification() {
+        let pubkey = [1u8; 32];
+        let signature = [2u8; 64];
+        let receiver = Pubkey::new_from_array([7u8; 32]);
+        let signed = compute_message_hash("ipfs://Qm", b"42", &receiver, 100, 0, &[0u8; 32], u64::MAX, SHA256).unwrap();
+        let data = ed25519_ix_data(&pubkey, &signature, &signed);
+
+        assert!(verify_ed25519_ix_data(&data, &[], &pubkey, &signed, &signature, false, MAX_SIGS).is_ok());
+
+        let replayed = compute_message_hash("ipfs://Qm", b"42", &receiver, 99, 0, &[0u8; 32], u64::MAX, SHA256).unwrap();
+        assert!(verify_ed25519_ix_data(&data, &[], &pubkey, &replayed, &signature, false, MAX_SIGS).is_err());
+    }
+
+    #[test]
+    fn test_hash_algo_verifies_only_under_declared_algorithm() {
+        let pubkey = [1u8; 32];
+        let signature = [2u8; 64];
+        let receiver = Pubkey::new_from_array([7u8; 32]);
+        let sha = compute_message_hash("ipfs://Qm", b"42", &receiver, 5, 0, &[0u8; 32], u64::MAX, SHA256).unwrap();
+        let kec = compute_message_hash("ipfs://Qm", b"42", &receiver, 5, 0, &[0u8; 32], u64::MAX, KECCAK256).unwrap();
+        assert_ne!(sha, kec);
+
+        let sha_ix = ed25519_ix_data(&pubkey, &signature, &sha);
+        assert!(verify_ed25519_ix_data(&sha_ix, &[], &pubkey, &sha, &signature, false, MAX_SIGS).is_ok());
+        assert!(verify_ed25519_ix_data(&sha_ix, &[], &pubkey, &kec, &signature, false, MAX_SIGS).is_err());
+
+        let kec_ix = ed25519_ix_data(&pubkey, &signature, &kec);
+        assert!(verify_ed25519_ix_data(&kec_ix, &[], &pubkey, &kec, &signature, false, MAX_SIGS).is_ok());
+        assert!(verify_ed25519_ix_data(&kec_ix, &[], &pubkey, &sha, &signature, false, MAX_SIGS).is_err());
+    }
+
+    #[test]
+    fn test_strict_mode_accepts_single_signature() {
+        let pubkey = [1u8; 32];
+        let signature = [2u8; 64];
+        let message = [3u8; 32];
+        let data = ed25519_ix_data(&pubkey, &signature, &message);
+        assert!(verify_ed25519_ix_data(&data, &[], &pubkey, &message, &signature, true, MAX_SIGS).is_ok());
+    }
+
+    #[test]
+    fn test_strict_mode_rejects_multiple_signatures() {
+        let pubkey = [1u8; 32];
+        let signature = [2u8; 64];
+        let message = [3u8; 32];
+
+        // Same first entry, but the count claims a second (smuggled) signature follows
+        let mut data = ed25519_ix_data(&pubkey, &signature, &message);
+        data[0] = 2;
+        data.extend_from_slice(&[0u8; 14 + 32 + 64 + 32]);
+
+        // Permissive mode only checks the first entry
+        assert!(verify_ed25519_ix_data(&data, &[], &pubkey, &message, &signature, false, MAX_SIGS).is_ok());
+
+        let err = verify_ed25519_ix_data(&data, &[], &pubkey, &message, &signature, true, MAX_SIGS).unwrap_err();
+        assert_eq!(err, ErrorCode::UnexpectedMultipleSignatures.into());
+    }
+
+    #[test]
+    fn test_pdas_use_little_endian_source_chain() {
+        // 0x0102 makes LE and BE encodings differ
+        let chain: u16 = 0x0102;
+        let contract = b"0xabc".as_slice();
+        let token = b"42".as_slice();
+
+        let le = [0x02u8, 0x01];
+        let be = [0x01u8, 0x02];
+
+        assert_eq!(pdas::provenance(chain, contract, token), find(&[b"provenance", &le, contract, token]));
+        assert_ne!(pdas::provenance(chain, contract, token), find(&[b"provenance", &be, contract, token]));
+        assert_eq!(pdas::reborn_collection(chain, contract), find(&[b"reborn_collection", &le, contract]));
+        assert_eq!(pdas::mint_authority(chain, contract), find(&[b"mint_authority", &le, contract]));
+        assert_eq!(
+            pdas::receiver_override(chain, contract, token),
+            find(&[b"receiver_override", &le, contract, token])
+        );
+    }
+
+    #[test]
+    fn test_pdas_match_constant_seeds() {
+        let sig_hash = [9u8; 32];
+
+        assert_eq!(pdas::sig_used(&sig_hash), find(&[constants::SIG_USED_SEED, &sig_hash]));
+        assert_eq!(pdas::mint_config(), find(&[constants::MINT_CONFIG_SEED]));
+        assert_eq!(pdas::collection_registry(), find(&[constants::COLLECTION_REGISTRY_SEED]));
+    }
+
+    #[test]
+    fn test_unknown_hash_algo_rejected() {
+        let receiver = Pubkey::new_from_array([7u8; 32]);
+        assert!(compute_message_hash("ipfs://Qm", b"42", &receiver, 5, 0, &[0u8; 32], u64::MAX, 2).is_err());
+    }
+
+    fn entry(source_chain: u16, nft_contract: &[u8]) -> CollectionEntry {
+        CollectionEntry {
+            source_chain,
+            nft_contract: nft_contract.to_vec(),
+            collection_address: Pubkey::new_unique(),
+            created_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_registry_disabled_skips_without_account() {
+        assert!(!maybe_register_collection(false, None, entry(2, b"bayc")).unwrap());
+
+        let mut registry = CollectionRegistry { count: 0, bump: 255, collections: Vec::new() };
+        assert!(!maybe_register_collection(false, Some(&mut registry), entry(2, b"bayc")).unwrap());
+        assert!(registry.collections.is_empty());
+    }
+
+    #[test]
+    fn test_registry_enabled_requires_account_and_dedupes() {
+        assert!(maybe_register_collection(true, None, entry(2, b"bayc")).is_err());
+
+        // A stale registry that already lists the collection is left unchanged
+        let mut registry = CollectionRegistry { count: 0, bump: 255, collections: Vec::new() };
+        assert!(maybe_register_collection(true, Some(&mut registry), entry(2, b"bayc")).unwrap());
+        assert!(!maybe_register_collection(true, Some(&mut registry), entry(2, b"bayc")).unwrap());
+        assert_eq!(registry.count, 1);
+        assert!(registry.find_collection(2, b"bayc").is_some());
+        assert!(registry.find_collection(1, b"bayc").is_none());
+    }
+
+    fn empty_provenance() -> Provenance {
+        Provenance {
+            source_chain: 0,
+            nft_contract: Vec::new(),
+            token_id: Vec::new(),
+            token_uri: String::new(),
+            dwallet_pubkey: [0u8; 32],
+            signature: [0u8; 64],
+            receiver: Pubkey::default(),
+            asset: Pubkey::default(),
+            wormhole_sequence: 0,
+            source_sequence: 0,
+            source_block_hint: [0u8; 32],
+            sealed_at: 0,
+            is_initialized: false,
+            backfilled: false,
+            imported_from_v3: false,
+            reserved: false,
+            bump: 0,
+        }
     }
 
-    /// Update the minting pubkey (admin only). For key rotation.
-    pub fn update_mint_config(
-        ctx: Context<UpdateMintConfig>,
-        new_minting_pubkey: [u8; 32],
-    ) -> Result<()> {
-        ctx.accounts.config.minting_pubkey = new_minting_pubkey;
-        msg!("MintConfig updated with new minting pubkey: {}", hex::encode(&new_minting_pubkey));
-        Ok(())
+    #[test]
+    fn test_native_provenance_same_for_lock_and_burn() {
+        let mint = Pubkey::new_unique();
+        let user = Pubkey::new_unique();
+        let asset = Pubkey::new_unique();
+
+        // seal_and_mint_native writes provenance through one helper after either
+        // disposal branch, so both modes yield the same record
+        let records: Vec<Provenance> = [SealMode::Lock, SealMode::Burn]
+            .iter()
+            .map(|_mode| {
+                let mut provenance = empty_provenance();
+                fill_native_provenance(&mut provenance, &mint, &mint, &user, &asset, "ipfs://x".into(), 7, 254);
+                provenance
+            })
+            .collect();
+
+        assert_eq!(records[0].try_to_vec().unwrap(), records[1].try_to_vec().unwrap());
+        assert_eq!(records[0].source_chain, 1);
+        assert_eq!(records[0].nft_contract, mint.to_bytes().to_vec());
+        assert_eq!(records[0].token_id, mint.to_bytes().to_vec());
+        assert_eq!(records[0].receiver, user);
+        assert!(!records[0].backfilled);
     }
 
-    /// Update the URI of a minted Metaplex Core asset (admin only).
-    pub fn update_asset_uri(
-        ctx: Context<UpdateAssetUri>,
-        source_chain: u16,
-        nft_contract: Vec<u8>,
-        new_uri: String,
-    ) -> Result<()> {
-        require!(new_uri.len() <= constants::MAX_URI_LENGTH, ErrorCode::UriTooLong);
+    #[test]
+    fn test_native_reseal_rejected_before_transfer() {
+        let mint = Pubkey::new_unique();
+        let user = Pubkey::new_unique();
 
-        let mint_authority_bump = ctx.bumps.mint_authority;
-        let mint_authority_seeds: &[&[u8]] = &[
-            constants::MINT_AUTHORITY_SEED,
-            &source_chain.to_le_bytes(),
-            &nft_contract,
-            &[mint_authority_bump],
-        ];
+        // First seal: the provenance was just created by init_if_needed
+        let mut provenance = empty_provenance();
+        assert!(ensure_not_sealed(&provenance).is_ok());
+        fill_native_provenance(&mut provenance, &mint, &mint, &user, &Pubkey::new_unique(), "ipfs://x".into(), 7, 254);
 
-        UpdateV1CpiBuilder::new(&ctx.accounts.mpl_core_program)
-            .asset(&ctx.accounts.asset)
-            .collection(Some(&ctx.accounts.collection_asset))
-            .authority(Some(&ctx.accounts.mint_authority))
-            .payer(&ctx.accounts.payer)
-            .system_program(&ctx.accounts.system_program)
-            .new_uri(new_uri.clone())
-            .invoke_signed(&[mint_authority_seeds])
-            .map_err(|_e| ErrorCode::MetaplexError)?;
+        // Second seal of the same mint loads the existing record and stops at the
+        // first check, ahead of the fee and vault transfer
+        let err = ensure_not_sealed(&provenance).unwrap_err();
+        assert_eq!(err, ErrorCode::AlreadyMinted.into());
+    }
 
-        msg!("Asset URI updated to: {}", new_uri);
-        Ok(())
+    #[test]
+    fn test_native_seals_group_by_source_collection() {
+        let source_collection = Pubkey::new_unique();
+        let (mint_a, mint_b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let solana = SourceChain::SOLANA.0;
+
+        // Two NFTs from the same verified collection land in one reborn collection
+        let key_a = native_grouping_key(Some(source_collection), &mint_a);
+        let key_b = native_grouping_key(Some(source_collection), &mint_b);
+        assert_eq!(key_a, key_b);
+        assert_eq!(pdas::reborn_collection(solana, key_a.as_ref()), pdas::reborn_collection(solana, key_b.as_ref()));
+        assert_eq!(pdas::mint_authority(solana, key_a.as_ref()), pdas::mint_authority(solana, key_b.as_ref()));
+
+        // Without a source collection each mint stays its own collection
+        let solo_a = native_grouping_key(None, &mint_a);
+        assert_eq!(solo_a, mint_a);
+        assert_ne!(pdas::reborn_collection(solana, solo_a.as_ref()), pdas::reborn_collection(solana, key_a.as_ref()));
+
+        // Provenance keeps the per-mint token ID under the shared contract
+        let mut provenance = empty_provenance();
+        fill_native_provenance(&mut provenance, &key_b, &mint_b, &Pubkey::new_unique(), &Pubkey::new_unique(), "ipfs://b".into(), 7, 254);
+        assert_eq!(provenance.nft_contract, source_collection.to_bytes().to_vec());
+        assert_eq!(provenance.token_id, mint_b.to_bytes().to_vec());
     }
 
-    /// Initialize the collection registry
-    pub fn initialize_collection_registry(ctx: Context<InitializeCollectionRegistry>) -> Result<()> {
-        let registry = &mut ctx.accounts.registry;
-        registry.count = 0;
-        registry.bump = ctx.bumps.registry;
-        registry.collections = Vec::new();
-        msg!("CollectionRegistry initialized");
-        Ok(())
+    #[test]
+    fn test_source_collection_must_be_verified() {
+        let source_collection = Pubkey::new_unique();
+        assert!(check_source_collection(Some((source_collection, true)), Some(&source_collection)).is_ok());
+        // No grouping requested: metadata is irrelevant
+        assert!(check_source_collection(None, None).is_ok());
+
+        for metadata in [None, Some((source_collection, false)), Some((Pubkey::new_unique(), true))] {
+            let err = check_source_collection(metadata, Some(&source_collection)).unwrap_err();
+            assert_eq!(err, ErrorCode::SourceCollectionNotVerified.into());
+        }
     }
 
-    /// Initialize a RebornCollection PDA. Called once per source collection
-    /// before the first mint_reborn.
-    pub fn init_reborn_collection(
-        ctx: Context<InitRebornCollection>,
-        source_chain: u16,
-        nft_contract: Vec<u8>,
-    ) -> Result<()> {
-        let collection = &mut ctx.accounts.collection;
-        collection.source_chain = source_chain;
-        collection.nft_contract = nft_contract;
-        collection.name = String::new();
-        collection.collection_asset_address = Pubkey::default();
-        collection.total_minted = 0;
-        collection.is_initialized = false;
-        collection.bump = ctx.bumps.collection;
-        msg!("RebornCollection PDA created");
-        Ok(())
+    #[test]
+    fn test_single_edition_nft_accepted() {
+        assert!(is_single_edition(0, 1));
     }
 
-    /// Mint a reborn NFT after verifying IKA dWallet signature
-    ///
-    /// Flow:
-    /// 1. Validate inputs
-    /// 2. Verify sig_hash == sha256(signature) to prevent PDA seed manipulation
-    /// 3. Load minting pubkey from Config PDA (never accepted as input)
-    /// 4. Reconstruct message: sha256(token_uri || token_id || receiver) (v7 order)
-    /// 5. Verify Ed25519 signature (pubkey, message, AND signature bytes) via precompile
-    /// 6. sig_record PDA init provides replay protection (Anchor init fails if PDA exists)
-    /// 7. Create Metaplex Core collection if first NFT from this source collection
-    /// 8. Mint Metaplex Core NFT (linked to collection) to receiver
-    /// 9. Store provenance
-    pub fn mint_reborn(
-        ctx: Context<MintReborn>,
-        sig_hash: Vec<u8>,
-        source_chain: u16,
-        nft_contract: Vec<u8>,
-        token_id: Vec<u8>,
-        signature: Vec<u8>,
-        token_uri: String,
-        collection_name: String,
-        royalty_basis_points: u16,
-        dao_treasury: Pubkey,
-    ) -> Result<()> {
-        // ============ 1. Input validation ============
-        require!(signature.len() == 64, ErrorCode::InvalidSignature);
-        require!(sig_hash.len() == 32, ErrorCode::InvalidSigHash);
-        require!(!nft_contract.is_empty() && nft_contract.len() <= constants::MAX_CONTRACT_LENGTH, ErrorCode::ContractTooLong);
-        require!(!token_id.is_empty() && token_id.len() <= constants::MAX_TOKEN_ID_LENGTH, ErrorCode::TokenIdTooLong);
-        require!(!token_uri.is_empty() && token_uri.len() <= constants::MAX_URI_LENGTH, ErrorCode::UriTooLong);
-        require!(!collection_name.is_empty() && collection_name.len() <= constants::MAX_NAME_LENGTH, ErrorCode::NameTooLong);
-        require!(royalty_basis_points <= 10000, ErrorCode::InvalidRoyalties);
+    #[test]
+    fn test_fungible_mint_rejected() {
+        // 6-decimal fungible mint presenting a balance of 1
+        assert!(!is_single_edition(6, 1_000_000));
+        assert!(!is_single_edition(6, 1));
+        // Multi-supply 0-decimal mint (semi-fungible)
+        assert!(!is_single_edition(0, 100));
+    }
 
-        let receiver_pubkey = ctx.accounts.receiver.key();
+    #[test]
+    fn test_token_2022_nft_seal_extensions() {
+        // Plain SPL mint, or Token-2022 NFT with metadata extensions: both modes work
+        let metadata = [ExtensionType::MetadataPointer, ExtensionType::TokenMetadata];
+        for mode in [SealMode::Lock, SealMode::Burn] {
+            assert!(check_native_seal_extensions(&[], mode).is_ok());
+            assert!(check_native_seal_extensions(&metadata, mode).is_ok());
+        }
+    }
 
-        // Reject obviously wrong receiver addresses
-        require!(
-            receiver_pubkey != System::id() && receiver_pubkey != Pubkey::default(),
-            ErrorCode::InvalidReceiver
+    #[test]
+    fn test_token_2022_non_transferable_rejected_for_lock() {
+        let exts = [ExtensionType::MetadataPointer, ExtensionType::NonTransferable];
+        let err = check_native_seal_extensions(&exts, SealMode::Lock).unwrap_err();
+        assert_eq!(err, ErrorCode::UnsupportedTokenExtension.into());
+        // Soulbound tokens can still be burned
+        assert!(check_native_seal_extensions(&exts, SealMode::Burn).is_ok());
+        assert!(check_native_seal_extensions(&[ExtensionType::TransferHook], SealMode::Lock).is_err());
+    }
+
+    fn mint_config(native_seal_fee: u64) -> MintConfig {
+        MintConfig {
+            minting_pubkey: [0u8; 32],
+            admin: Pubkey::new_unique(),
+            strict_ed25519: false,
+            maintain_registry: false,
+            native_seal_fee,
+            treasury: Pubkey::new_unique(),
+            recovery_admin: None,
+            last_admin_activity: 0,
+            allowed_relayers: Vec::new(),
+            max_signatures_per_tx: constants::DEFAULT_MAX_SIGNATURES_PER_TX,
+            attribute_schema: None,
+            reborn_suffix: constants::DEFAULT_REBORN_SUFFIX.to_string(),
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn test_fee_vault_accumulates_and_withdraws() {
+        let config = mint_config(10_000_000);
+        let mut vault = FeeVault { total_collected: 0, total_withdrawn: 0, bump: 255 };
+        for _ in 0..3 {
+            vault.record_fee(native_seal_fee_due(&config).unwrap()).unwrap();
+        }
+        assert_eq!(vault.total_collected, 30_000_000);
+        assert_eq!(vault.balance(), 30_000_000);
+
+        vault.record_withdrawal(25_000_000).unwrap();
+        assert_eq!(vault.balance(), 5_000_000);
+        assert_eq!(vault.total_withdrawn, 25_000_000);
+
+        let err = vault.record_withdrawal(5_000_001).unwrap_err();
+        assert_eq!(err, ErrorCode::InsufficientFeeVaultBalance.into());
+        vault.record_withdrawal(5_000_000).unwrap();
+        assert_eq!(vault.balance(), 0);
+    }
+
+    #[test]
+    fn test_native_seal_fee_charged() {
+        assert_eq!(native_seal_fee_due(&mint_config(10_000_000)), Some(10_000_000));
+    }
+
+    #[test]
+    fn test_native_seal_zero_fee_is_free() {
+        assert_eq!(native_seal_fee_due(&mint_config(0)), None);
+    }
+
+    fn v3_record() -> V3RecordImport {
+        V3RecordImport {
+            source_chain: 2,
+            source_contract: b"0xbc4ca0eda7647a8ab7c2061c2e118a18a936f13d".to_vec(),
+            token_id: vec![0x2a],
+            attestation_pubkey: [7u8; 32],
+            recipient: Pubkey::new_unique(),
+            mint: Pubkey::new_unique(),
+            minted: true,
+            verified_at: 1_700_000_000,
+        }
+    }
+
+    #[test]
+    fn test_import_v3_record() {
+        let record = v3_record();
+        let mut provenance = empty_provenance();
+        fill_imported_provenance(&mut provenance, record.clone(), "ipfs://v3".into(), 253).unwrap();
+
+        assert!(provenance.is_initialized);
+        assert!(provenance.imported_from_v3);
+        assert!(!provenance.backfilled);
+        assert_eq!(provenance.source_chain, 2);
+        assert_eq!(provenance.nft_contract, record.source_contract);
+        assert_eq!(provenance.token_id, record.token_id);
+        assert_eq!(provenance.dwallet_pubkey, [7u8; 32]);
+        assert_eq!(provenance.signature, [0u8; 64]);
+        assert_eq!(provenance.receiver, record.recipient);
+        assert_eq!(provenance.asset, record.mint);
+        assert_eq!(provenance.sealed_at, 1_700_000_000);
+    }
+
+    #[test]
+    fn test_import_v3_record_rejects_duplicates_and_unminted() {
+        // Imports `init` the same PDA mint_reborn uses for this (chain, contract, token),
+        // so importing twice, or importing a seal already minted here, fails at init
+        let record = v3_record();
+        let (pda, _) = pdas::provenance(record.source_chain, &record.source_contract, &record.token_id);
+        let (seeded, _) = Pubkey::find_program_address(
+            &[
+                constants::PROVENANCE_SEED,
+                &record.source_chain.to_le_bytes(),
+                &record.source_contract,
+                &record.token_id,
+            ],
+            &crate::ID,
         );
+        assert_eq!(pda, seeded);
 
-        // ============ 2-5. Verify sig, compute message hash, check Ed25519 ============
-        // Heavy crypto (sha256 + ed25519) is in verify_seal() which has its own
-        // stack frame (#[inline(never)]) to stay under the BPF 4KB limit.
-        let dwallet_pubkey = ctx.accounts.config.minting_pubkey;
-        verify_seal(
-            &ctx.accounts.instructions_sysvar,
-            &dwallet_pubkey,
-            &signature,
-            &sig_hash,
-            &token_uri,
-            &token_id,
-            &receiver_pubkey,
-        )?;
+        let mut unminted = v3_record();
+        unminted.minted = false;
+        let err = fill_imported_provenance(&mut empty_provenance(), unminted, String::new(), 0).unwrap_err();
+        assert_eq!(err, ErrorCode::V3RecordNotMinted.into());
+    }
 
-        msg!("Signature verified against stored minting pubkey");
+    #[test]
+    fn test_underfunded_payer_gets_explicit_error() {
+        let rent = Rent::default();
+        let needed = mpl_core_rent_needed(
+            &rent,
+            Some(mpl_core_collection_space(8, 80, 1)),
+            mpl_core_asset_space(12, 60, 0),
+        );
+        assert!(needed > rent.minimum_balance(mpl_core_asset_space(12, 60, 0)));
 
-        // ============ 6. Replay protection (PDA-based) ============
-        // The `sig_record` account was created by Anchor's `init` constraint.
-        // If it already existed the transaction would have already failed above.
-        // We store the bump for completeness.
-        let sig_record = &mut ctx.accounts.sig_record;
-        sig_record.bump = ctx.bumps.sig_record;
-        msg!("Replay protection: sig_record PDA created, replay blocked for this signature");
+        let err = check_payer_funds(needed - 1, needed).unwrap_err();
+        assert_eq!(err, ErrorCode::InsufficientFundsForMint.into());
+        assert!(check_payer_funds(needed, needed).is_ok());
+    }
 
-        // ============ 7. Create collection if first mint ============
-        let collection_asset_key = ctx.accounts.collection_asset.key();
-        let is_new_collection = !ctx.accounts.collection.is_initialized;
+    #[test]
+    fn test_existing_collection_needs_asset_rent_only() {
+        let rent = Rent::default();
+        let asset_space = mpl_core_asset_space(12, 60, 1);
+        assert_eq!(mpl_core_rent_needed(&rent, None, asset_space), rent.minimum_balance(asset_space));
+    }
 
-        let mint_authority_bump = ctx.bumps.mint_authority;
-        let mint_authority_seeds: &[&[u8]] = &[
-            constants::MINT_AUTHORITY_SEED,
-            &source_chain.to_le_bytes(),
-            &nft_contract,
-            &[mint_authority_bump],
-        ];
+    #[test]
+    fn test_collection_symbol_applied() {
+        assert!(validate_symbol(Some("BAYC")).is_ok());
+        assert!(validate_symbol(None).is_ok());
 
-        if is_new_collection {
-            let payer_key = ctx.accounts.payer.key();
-
-            // Configure royalties: 6.9% total (690 bps), split 72% DAO (~5%) / 28% team (~1.9%)
-            let royalties_plugin = PluginAuthorityPair {
-                plugin: Plugin::Royalties(Royalties {
-                    basis_points: royalty_basis_points,
-                    creators: vec![
-                        Creator { address: dao_treasury, percentage: 72 },
-                        Creator { address: payer_key, percentage: 28 },
-                    ],
-                    rule_set: RuleSet::None,
-                }),
-                authority: Some(PluginAuthority::UpdateAuthority),
-            };
-
-            // First NFT from this source collection — create the Metaplex Core collection asset
-            CreateCollectionV2CpiBuilder::new(&ctx.accounts.mpl_core_program)
-                .collection(&ctx.accounts.collection_asset)
-                .update_authority(Some(&ctx.accounts.mint_authority))
-                .payer(&ctx.accounts.payer)
-                .system_program(&ctx.accounts.system_program)
-                .name(collection_name.clone())
-                .uri(format!(
-                    "https://ika-tensei.io/collections/{}/{}",
-                    source_chain,
-                    hex::encode(&nft_contract)
-                ))
-                .plugins(vec![royalties_plugin])
-                .invoke_signed(&[mint_authority_seeds])
-                .map_err(|_e| ErrorCode::MetaplexError)?;
+        let pair = collection_attributes_plugin(Some("BAYC"), 690);
+        let Plugin::Attributes(attributes) = pair.plugin else {
+            panic!("expected Attributes plugin");
+        };
+        let fields: Vec<(&str, &str)> = attributes
+            .attribute_list
+            .iter()
+            .map(|a| (a.key.as_str(), a.value.as_str()))
+            .collect();
+        assert_eq!(fields, vec![("symbol", "BAYC"), ("seller_fee_basis_points", "690")]);
 
-            msg!("Created new Metaplex Core collection: {} (royalties: {}bp)", collection_name, royalty_basis_points);
+        // No symbol: only the seller fee is published
+        let Plugin::Attributes(attributes) = collection_attributes_plugin(None, 500).plugin else {
+            panic!("expected Attributes plugin");
+        };
+        assert_eq!(attributes.attribute_list.len(), 1);
+    }
 
-            // Finalize our RebornCollection metadata PDA (pre-created by init_reborn_collection)
-            let collection = &mut ctx.accounts.collection;
-            collection.name = collection_name.clone();
-            collection.collection_asset_address = collection_asset_key;
-            collection.is_initialized = true;
+    #[test]
+    fn test_collection_symbol_length_validated() {
+        let err = validate_symbol(Some("TOOLONGSYMBOL")).unwrap_err();
+        assert_eq!(err, ErrorCode::SymbolTooLong.into());
+        assert!(validate_symbol(Some("")).is_err());
+        assert!(validate_symbol(Some(&"S".repeat(constants::MAX_SYMBOL_LENGTH))).is_ok());
+    }
 
-        } else {
-            msg!("Using existing collection: {}", ctx.accounts.collection.name);
+    #[test]
+    fn test_native_seal_return_data_decodes() {
+        let nft_mint = Pubkey::new_unique();
+        let asset = Pubkey::new_unique();
+        let grouping_key = native_grouping_key(None, &nft_mint);
+        let (provenance, _) = pdas::provenance(1, grouping_key.as_ref(), nft_mint.as_ref());
+        let result = NativeSealResult { asset, provenance };
+
+        // What a client reads back from the transaction's return data
+        let return_data = result.try_to_vec().unwrap();
+        assert_eq!(return_data.len(), 64);
+        let decoded = NativeSealResult::try_from_slice(&return_data).unwrap();
+        assert_eq!(decoded.asset, asset);
+        assert_eq!(decoded.provenance, provenance);
+    }
+
+    #[cfg(feature = "compute-log")]
+    #[test]
+    fn test_compute_checkpoints_logged() {
+        for checkpoint in ["verified", "collection created", "minted"] {
+            let line = compute_checkpoint(checkpoint).unwrap();
+            assert!(line.starts_with(&format!("Compute units remaining after {}: ", checkpoint)));
         }
+    }
 
-        // ============ 8. Mint reborn NFT ============
-        let collection = &mut ctx.accounts.collection;
+    #[cfg(not(feature = "compute-log"))]
+    #[test]
+    fn test_compute_checkpoints_silent_by_default() {
+        assert_eq!(compute_checkpoint("verified"), None);
+    }
 
-        // Build the NFT name: "{CollectionName} #{decimal_token_id}"
-        // token_id is a big-endian uint256 (32 bytes), so strip leading zeros and convert to decimal.
-        let nft_name = format!("{} #{}", collection.name, token_id_to_decimal(&token_id));
 
-        // CPI to Metaplex Core to mint the NFT, linked to our collection asset
-        CreateV2CpiBuilder::new(&ctx.accounts.mpl_core_program)
-            .asset(&ctx.accounts.asset)
-            .collection(Some(&ctx.accounts.collection_asset))
-            .authority(Some(&ctx.accounts.mint_authority))
-            .payer(&ctx.accounts.payer)
-            .owner(Some(&ctx.accounts.receiver))
-            .system_program(&ctx.accounts.system_program)
-            .data_state(DataState::AccountState)
-            .name(nft_name)
-            .uri(token_uri.clone())
-            .invoke_signed(&[mint_authority_seeds])
-            .map_err(|_e| ErrorCode::MetaplexError)?;
+    #[test]
+    fn test_get_mint_config_return_data_decodes() {
+        let mut config = mint_config(0);
+        config.minting_pubkey = [7u8; 32];
+        let (_, bump) = pdas::mint_config();
+        config.bump = bump;
 
-        collection.total_minted = collection.total_minted.saturating_add(1);
-        msg!("NFT minted to {}", receiver_pubkey);
+        let return_data = MintConfigView::from(&config).try_to_vec().unwrap();
+        assert_eq!(return_data.len(), 32 + 32 + 1);
+        let decoded = MintConfigView::try_from_slice(&return_data).unwrap();
+        assert_eq!(decoded.minting_pubkey, config.minting_pubkey);
+        assert_eq!(decoded.admin, config.admin);
+        assert_eq!(decoded.bump, config.bump);
+    }
 
-        // ============ 9. Store provenance ============
-        let provenance = &mut ctx.accounts.provenance;
-        provenance.source_chain = source_chain;
-        provenance.nft_contract = nft_contract;
-        provenance.token_id = token_id;
-        provenance.token_uri = token_uri;
-        provenance.dwallet_pubkey = dwallet_pubkey;
-        provenance.signature = signature.try_into().unwrap(); // length validated above
-        provenance.receiver = receiver_pubkey;
-        provenance.sealed_at = Clock::get()?.unix_timestamp;
-        provenance.is_initialized = true;
-        provenance.bump = ctx.bumps.provenance;
+    #[test]
+    fn test_pda_collection_asset_links_later_mints() {
+        let nft_contract = b"0xabc".to_vec();
+        let (derived, bump) = pdas::collection_asset(2, &nft_contract);
+        assert_eq!(
+            (derived, bump),
+            find(&[constants::COLLECTION_ASSET_SEED, &2u16.to_le_bytes(), &nft_contract])
+        );
 
-        msg!("Provenance stored for {}", receiver_pubkey);
+        // First mint: the derived address is recognised and signed for; keypairs are not
+        assert_eq!(pda_collection_asset_bump(2, &nft_contract, &derived), Some(bump));
+        assert_eq!(pda_collection_asset_bump(2, &nft_contract, &Pubkey::new_unique()), None);
+        assert_eq!(pda_collection_asset_bump(3, &nft_contract, &derived), None);
 
-        Ok(())
+        // Later mints re-derive the same address and must link to it
+        let collection = RebornCollection {
+            source_chain: 2,
+            nft_contract: nft_contract.clone(),
+            name: "Test".into(),
+            symbol: String::new(),
+            collection_asset_address: derived,
+            total_minted: 1,
+            is_initialized: true,
+            paused: false,
+            immutable_metadata: false,
+            receiver_allowlist_root: None,
+            bump: 255,
+        };
+        assert!(check_collection_asset(&collection, &pdas::collection_asset(2, &nft_contract).0).is_ok());
+        assert!(check_collection_asset(&collection, &Pubkey::new_unique()).is_err());
     }
 
-    // NOTE: seal_and_mint_native disabled pending anchor-spl token type compatibility fix.
-    // See comment above SealAndMintNative struct definition.
-    // The cross-chain flow (mint_reborn) is unaffected.
-    /*
-    pub fn seal_and_mint_native(
-        ctx: Context<SealAndMintNative>,
-        token_uri: String,
-        collection_name: String,
-    ) -> Result<()> {
-        // ============ 1. Input validation ============
-        require!(token_uri.len() <= constants::MAX_URI_LENGTH, ErrorCode::UriTooLong);
-        require!(collection_name.len() <= constants::MAX_NAME_LENGTH, ErrorCode::NameTooLong);
+    #[test]
+    fn test_total_minted_overflow_rejected() {
+        let mut collection = RebornCollection {
+            source_chain: 2,
+            nft_contract: b"0xabc".to_vec(),
+            name: "Test".into(),
+            symbol: String::new(),
+            collection_asset_address: Pubkey::new_unique(),
+            total_minted: u64::MAX - 1,
+            is_initialized: true,
+            paused: false,
+            immutable_metadata: false,
+            receiver_allowlist_root: None,
+            bump: 255,
+        };
+        record_collection_mint(&mut collection).unwrap();
+        assert_eq!(collection.total_minted, u64::MAX);
 
-        let user_key = ctx.accounts.user.key();
-        let nft_mint_key = ctx.accounts.nft_mint.key();
+        let err = record_collection_mint(&mut collection).unwrap_err();
+        assert_eq!(err, ErrorCode::SupplyOverflow.into());
+        assert_eq!(collection.total_minted, u64::MAX);
+    }
+
+    #[test]
+    fn test_migrate_legacy_mint_config() {
+        let admin = Pubkey::new_unique();
+        let mut data = MintConfig::DISCRIMINATOR.to_vec();
+        LegacyMintConfig { minting_pubkey: [9u8; 32], admin, bump: 254 }.serialize(&mut data).unwrap();
+        assert_eq!(data.len(), 73);
+        // The deployed layout no longer loads as a MintConfig
+        assert!(MintConfig::try_deserialize(&mut &data[..]).is_err());
+
+        let legacy: LegacyMintConfig =
+            decode_legacy_account(&data, LegacyMintConfig::INIT_SPACE, &MintConfig::DISCRIMINATOR).unwrap();
+        let config = migrated_mint_config(legacy, 1_000);
+        assert_eq!((config.minting_pubkey, config.admin, config.bump), ([9u8; 32], admin, 254));
+        assert!(config.maintain_registry && !config.strict_ed25519);
+        assert_eq!(config.max_signatures_per_tx, constants::DEFAULT_MAX_SIGNATURES_PER_TX);
+        assert_eq!(config.last_admin_activity, 1_000);
+        assert_eq!((config.native_seal_fee, config.treasury), (0, admin));
+        assert_eq!(config.reborn_suffix, constants::DEFAULT_REBORN_SUFFIX);
+
+        let mut migrated = Vec::new();
+        config.try_serialize(&mut migrated).unwrap();
+        migrated.resize(8 + MintConfig::INIT_SPACE, 0);
+        let loaded = MintConfig::try_deserialize(&mut &migrated[..]).unwrap();
+        assert_eq!((loaded.admin, loaded.bump), (admin, 254));
+
+        // A migrated (or freshly initialized) config is not migrated again
+        let err = decode_legacy_account::<LegacyMintConfig>(&migrated, LegacyMintConfig::INIT_SPACE, &MintConfig::DISCRIMINATOR)
+            .err()
+            .unwrap();
+        assert_eq!(err, ErrorCode::NotLegacyLayout.into());
+    }
+
+    #[test]
+    fn test_migrate_legacy_collection_and_provenance() {
+        let asset_address = Pubkey::new_unique();
+        let mut data = RebornCollection::DISCRIMINATOR.to_vec();
+        LegacyRebornCollection {
+            source_chain: 2,
+            nft_contract: b"0xabc".to_vec(),
+            name: "Test".into(),
+            collection_asset_address: asset_address,
+            total_minted: 3,
+            is_initialized: true,
+            bump: 253,
+        }
+        .serialize(&mut data)
+        .unwrap();
+        data.resize(8 + LegacyRebornCollection::INIT_SPACE, 0);
+        // Read as the current layout the legacy bytes fail or come out shifted
+        assert!(RebornCollection::try_deserialize(&mut &data[..])
+            .map_or(true, |collection| collection.collection_asset_address != asset_address));
+
+        let legacy: LegacyRebornCollection =
+            decode_legacy_account(&data, LegacyRebornCollection::INIT_SPACE, &RebornCollection::DISCRIMINATOR).unwrap();
+        let collection = migrated_collection(legacy);
+        let mut migrated = Vec::new();
+        collection.try_serialize(&mut migrated).unwrap();
+        migrated.resize(8 + RebornCollection::INIT_SPACE, 0);
+        let loaded = RebornCollection::try_deserialize(&mut &migrated[..]).unwrap();
+        assert_eq!((loaded.collection_asset_address, loaded.total_minted, loaded.bump), (asset_address, 3, 253));
+        assert!(loaded.is_initialized && !loaded.paused && !loaded.immutable_metadata);
+        assert!(loaded.symbol.is_empty() && loaded.receiver_allowlist_root.is_none());
+        assert!(decode_legacy_account::<LegacyRebornCollection>(
+            &migrated,
+            LegacyRebornCollection::INIT_SPACE,
+            &RebornCollection::DISCRIMINATOR
+        )
+        .is_err());
+
+        let (receiver, asset) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut data = Provenance::DISCRIMINATOR.to_vec();
+        LegacyProvenance {
+            source_chain: 2,
+            nft_contract: b"0xabc".to_vec(),
+            token_id: b"7".to_vec(),
+            token_uri: "ipfs://seven".into(),
+            dwallet_pubkey: [4u8; 32],
+            signature: [5u8; 64],
+            receiver,
+            sealed_at: 99,
+            is_initialized: true,
+            bump: 252,
+        }
+        .serialize(&mut data)
+        .unwrap();
+        data.resize(8 + LegacyProvenance::INIT_SPACE, 0);
+        assert!(Provenance::try_deserialize(&mut &data[..]).map_or(true, |provenance| provenance.sealed_at != 99));
+
+        let legacy: LegacyProvenance =
+            decode_legacy_account(&data, LegacyProvenance::INIT_SPACE, &Provenance::DISCRIMINATOR).unwrap();
+        let mut migrated = Vec::new();
+        migrated_provenance(legacy, asset).try_serialize(&mut migrated).unwrap();
+        migrated.resize(8 + Provenance::INIT_SPACE, 0);
+        let loaded = Provenance::try_deserialize(&mut &migrated[..]).unwrap();
+        assert_eq!((loaded.receiver, loaded.asset, loaded.sealed_at, loaded.bump), (receiver, asset, 99, 252));
+        assert_eq!((loaded.token_uri.as_str(), loaded.signature), ("ipfs://seven", [5u8; 64]));
+        assert!(loaded.is_initialized && !loaded.reserved && !loaded.backfilled && !loaded.imported_from_v3);
+    }
+
+    #[test]
+    fn test_native_reborn_suffix_naming() {
+        let config = mint_config(0);
+        assert_eq!(native_display_name("Mad Lads", &config.reborn_suffix), "Mad Lads (Reborn)");
+        assert_eq!(native_display_name("Mad Lads", " [Tensei]"), "Mad Lads [Tensei]");
+        assert_eq!(native_display_name("Mad Lads", "Reborn {name}"), "Reborn Mad Lads");
+        assert_eq!(native_display_name("Mad Lads", ""), "Mad Lads");
+
+        // Suffix is kept whole; the collection name is cut to fit MAX_NAME_LENGTH
+        let long = "A".repeat(constants::MAX_NAME_LENGTH);
+        let name = native_display_name(&long, " (Reborn)");
+        assert_eq!(name.len(), constants::MAX_NAME_LENGTH);
+        assert!(name.ends_with(" (Reborn)"));
+        // Multi-byte names are cut on a char boundary
+        let name = native_display_name(&"é".repeat(16), " (Reborn)");
+        assert!(name.len() <= constants::MAX_NAME_LENGTH);
+        assert!(name.ends_with(" (Reborn)"));
+
+        assert!(validate_reborn_suffix(" (Reborn)").is_ok());
+        let err = validate_reborn_suffix(&"x".repeat(constants::MAX_REBORN_SUFFIX_LENGTH + 1)).unwrap_err();
+        assert_eq!(err, ErrorCode::InvalidRebornSuffix.into());
+        assert!(validate_reborn_suffix("{name} {name}").is_err());
+    }
+
+    #[test]
+    fn test_merge_collections_sums_total_minted() {
+        let record = |source_chain: u16, total_minted: u64| RebornCollection {
+            source_chain,
+            nft_contract: b"0xabc".to_vec(),
+            name: "Test".into(),
+            symbol: String::new(),
+            collection_asset_address: Pubkey::new_unique(),
+            total_minted,
+            is_initialized: true,
+            paused: false,
+            immutable_metadata: false,
+            receiver_allowlist_root: None,
+            bump: 255,
+        };
+        // Chain 2 seeded big-endian derives the same PDA as chain 0x0200 little-endian
+        assert_eq!(
+            pdas::reborn_collection(2u16.swap_bytes(), b"0xabc").0,
+            find(&[constants::COLLECTION_SEED, &2u16.to_be_bytes(), b"0xabc"]).0
+        );
+
+        let mut canonical = record(2, 7);
+        let canonical_asset = canonical.collection_asset_address;
+        let duplicate = record(2u16.swap_bytes(), 5);
+        merge_collection_records(&mut canonical, &duplicate).unwrap();
+        assert_eq!(canonical.total_minted, 12);
+        assert_eq!(canonical.collection_asset_address, canonical_asset);
+
+        // A canonical record that never minted keeps no collection asset, so its next mint
+        // creates one whose update authority is the LE-seeded mint_authority that
+        // mint_reborn signs with, not the BE-seeded one owning the duplicate's collection
+        let mut empty = record(2, 0);
+        empty.is_initialized = false;
+        empty.collection_asset_address = Pubkey::default();
+        merge_collection_records(&mut empty, &duplicate).unwrap();
+        assert_eq!(empty.total_minted, 5);
+        assert!(!empty.is_initialized);
+        assert_ne!(empty.collection_asset_address, duplicate.collection_asset_address);
+        let signing_authority = pdas::mint_authority(empty.source_chain, &empty.nft_contract).0;
+        assert_eq!(
+            signing_authority,
+            find(&[constants::MINT_AUTHORITY_SEED, &2u16.to_le_bytes(), b"0xabc"]).0
+        );
+        assert_ne!(signing_authority, pdas::mint_authority(duplicate.source_chain, &duplicate.nft_contract).0);
+
+        let mut full = record(2, u64::MAX);
+        let err = merge_collection_records(&mut full, &duplicate).unwrap_err();
+        assert_eq!(err, ErrorCode::SupplyOverflow.into());
+
+        let mut other = record(2, 1);
+        other.nft_contract = b"0xdef".to_vec();
+        assert!(merge_collection_records(&mut other, &duplicate).is_err());
+    }
+
+    #[test]
+    fn test_receiver_allowlist_proofs() {
+        let hash_pair = |a: [u8; 32], b: [u8; 32]| -> [u8; 32] {
+            let (a, b) = if a <= b { (a, b) } else { (b, a) };
+            Sha256::digest([a, b].concat()).into()
+        };
+        let members: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        let leaves: Vec<[u8; 32]> = members.iter().map(receiver_allowlist_leaf).collect();
+        let left = hash_pair(leaves[0], leaves[1]);
+        let right = hash_pair(leaves[2], leaves[3]);
+        let root = Some(hash_pair(left, right));
+
+        // Allowlisted receiver with a valid proof
+        assert!(verify_receiver_allowlist(root, &members[2], &[leaves[3], left]).is_ok());
+        assert!(verify_receiver_allowlist(root, &members[1], &[leaves[0], right]).is_ok());
+
+        // Non-member, even borrowing a member's proof
+        let outsider = Pubkey::new_unique();
+        let err = verify_receiver_allowlist(root, &outsider, &[leaves[3], left]).unwrap_err();
+        assert_eq!(err, ErrorCode::ReceiverNotAllowlisted.into());
+        assert!(verify_receiver_allowlist(root, &members[2], &[]).is_err());
+
+        // No root: any receiver, no proof needed
+        assert!(verify_receiver_allowlist(None, &outsider, &[]).is_ok());
+    }
+
+    #[test]
+    fn test_validate_accounts_names_first_mismatch() {
+        let sig_hash = [4u8; 32];
+        let (chain, contract, token) = (2u16, b"0xabc".as_slice(), b"42".as_slice());
+        let good = || MintAccountKeys {
+            sig_record: pdas::sig_used(&sig_hash).0,
+            provenance: pdas::provenance(chain, contract, token).0,
+            collection: pdas::reborn_collection(chain, contract).0,
+            mint_authority: pdas::mint_authority(chain, contract).0,
+            config: pdas::mint_config().0,
+        };
+        assert!(check_mint_accounts(&sig_hash, chain, contract, token, &good()).is_ok());
+
+        let cases: [(fn(&mut MintAccountKeys), ErrorCode); 5] = [
+            (|k| k.sig_record = Pubkey::new_unique(), ErrorCode::SigRecordAccountMismatch),
+            (|k| k.provenance = Pubkey::new_unique(), ErrorCode::ProvenanceAccountMismatch),
+            (|k| k.collection = Pubkey::new_unique(), ErrorCode::CollectionAccountMismatch),
+            (|k| k.mint_authority = Pubkey::new_unique(), ErrorCode::MintAuthorityAccountMismatch),
+            (|k| k.config = Pubkey::new_unique(), ErrorCode::ConfigAccountMismatch),
+        ];
+        for (corrupt, expected) in cases {
+            let mut keys = good();
+            corrupt(&mut keys);
+            let err = check_mint_accounts(&sig_hash, chain, contract, token, &keys).unwrap_err();
+            assert_eq!(err, expected.into());
+        }
+
+        // A relayer swapping collection and mint_authority is caught at the first slot
+        let mut swapped = good();
+        std::mem::swap(&mut swapped.collection, &mut swapped.mint_authority);
+        let err = check_mint_accounts(&sig_hash, chain, contract, token, &swapped).unwrap_err();
+        assert_eq!(err, ErrorCode::CollectionAccountMismatch.into());
+    }
+
+    #[test]
+    fn test_second_mint_with_wrong_collection_asset_rejected() {
+        let recorded = Pubkey::new_unique();
+        let collection = RebornCollection {
+            source_chain: 2,
+            nft_contract: b"0xabc".to_vec(),
+            name: "Test".to_string(),
+            symbol: String::new(),
+            collection_asset_address: recorded,
+            total_minted: 1,
+            is_initialized: true,
+            paused: false,
+            immutable_metadata: false,
+            receiver_allowlist_root: None,
+            bump: 255,
+        };
+
+        assert!(check_collection_asset(&collection, &recorded).is_ok());
+        let err = check_collection_asset(&collection, &Pubkey::new_unique()).unwrap_err();
+        assert_eq!(err, ErrorCode::CollectionAssetMismatch.into());
+    }
+
+    #[test]
+    fn test_update_asset_uri_respects_immutable_collections() {
+        let mut collection = RebornCollection {
+            source_chain: 2,
+            nft_contract: b"0xabc".to_vec(),
+            name: "Test".to_string(),
+            symbol: String::new(),
+            collection_asset_address: Pubkey::new_unique(),
+            total_minted: 1,
+            is_initialized: true,
+            paused: false,
+            immutable_metadata: false,
+            receiver_allowlist_root: None,
+            bump: 255,
+        };
+
+        // Default mode: mints stay mutable and a URI refresh is allowed
+        assert!(reborn_asset_plugins(None, collection.immutable_metadata).is_empty());
+        assert!(check_metadata_refreshable(&collection).is_ok());
+
+        // Opted into immutability: mints are locked and refresh is rejected
+        collection.immutable_metadata = true;
+        let plugins = reborn_asset_plugins(Some(Pubkey::new_unique()), collection.immutable_metadata);
+        assert!(matches!(plugins[0].plugin, Plugin::TransferDelegate(_)));
+        assert!(matches!(plugins[1].plugin, Plugin::ImmutableMetadata(_)));
+        assert_eq!(plugins[1].authority, Some(PluginAuthority::None));
+        let err = check_metadata_refreshable(&collection).unwrap_err();
+        assert_eq!(err, ErrorCode::MetadataImmutable.into());
+    }
+
+    #[test]
+    fn test_mint_config_init_validation() {
+        // Any ed25519 public key is on-curve, e.g. the basepoint
+        let wallet = Pubkey::new_from_array([
+            0x58, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66,
+            0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66,
+        ]);
+        assert!(validate_mint_config_init(&[1u8; 32], &wallet).is_ok());
+
+        let err = validate_mint_config_init(&[0u8; 32], &wallet).unwrap_err();
+        assert_eq!(err, ErrorCode::InvalidMintingPubkey.into());
+
+        let (pda, _) = pdas::mint_config();
+        let err = validate_mint_config_init(&[1u8; 32], &pda).unwrap_err();
+        assert_eq!(err, ErrorCode::InvalidAdmin.into());
+    }
+
+    #[test]
+    fn test_admin_recovery_after_window() {
+        let recovery = Pubkey::new_unique();
+        let mut config = mint_config(0);
+        config.recovery_admin = Some(recovery);
+        config.last_admin_activity = 1_000;
+
+        let now = 1_000 + constants::ADMIN_RECOVERY_WINDOW_SECS;
+        assert!(check_admin_recovery(&config, &recovery, now).is_ok());
+    }
+
+    #[test]
+    fn test_admin_recovery_rejected_before_window_or_wrong_key() {
+        let recovery = Pubkey::new_unique();
+        let mut config = mint_config(0);
+        config.last_admin_activity = 1_000;
+        let late = 1_000 + constants::ADMIN_RECOVERY_WINDOW_SECS;
+
+        // No recovery key configured
+        let err = check_admin_recovery(&config, &recovery, late).unwrap_err();
+        assert_eq!(err, ErrorCode::Unauthorized.into());
 
-        // Derive nft_contract and token_id from the on-chain mint key.
-        // For Solana-native NFTs, the mint address IS the contract and the token ID
-        // (SPL tokens are 1:1 mint-to-NFT). This is tamper-proof.
-        let nft_contract = nft_mint_key.to_bytes().to_vec();
-        let token_id = nft_mint_key.to_bytes().to_vec();
-        let source_chain: u16 = 1; // Solana = Wormhole chain ID 1
-
-        // ============ 2. Transfer NFT to sealed vault PDA (permanent lock) ============
-        let cpi_accounts = anchor_spl::token::Transfer {
-            from: ctx.accounts.nft_token_account.to_account_info(),
-            to: ctx.accounts.sealed_nft_vault.to_account_info(),
-            authority: ctx.accounts.user.to_account_info(),
-        };
-        let cpi_ctx = CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            cpi_accounts,
-        );
-        anchor_spl::token::transfer(cpi_ctx, 1)?;
+        config.recovery_admin = Some(recovery);
+        let err = check_admin_recovery(&config, &recovery, late - 1).unwrap_err();
+        assert_eq!(err, ErrorCode::RecoveryWindowNotElapsed.into());
 
-        msg!("NFT {} sealed permanently in vault PDA", nft_mint_key);
+        let err = check_admin_recovery(&config, &Pubkey::new_unique(), late).unwrap_err();
+        assert_eq!(err, ErrorCode::Unauthorized.into());
+    }
 
-        // ============ 3. Create collection if first mint ============
-        let collection_asset_key = ctx.accounts.collection_asset.key();
-        let is_new_collection = !ctx.accounts.collection.is_initialized;
+    #[test]
+    fn test_allowed_relayer_can_mint() {
+        let relayer = Pubkey::new_unique();
+        let mut config = mint_config(0);
+        config.allowed_relayers = vec![relayer];
+        assert!(is_authorized_relayer(&config, &relayer));
+        assert!(is_authorized_relayer(&config, &config.admin));
+    }
 
-        let mint_authority_bump = ctx.bumps.mint_authority;
-        let nft_contract_ref = nft_mint_key.as_ref();
-        let source_chain_bytes = source_chain.to_le_bytes();
-        let mint_authority_seeds: &[&[u8]] = &[
-            constants::MINT_AUTHORITY_SEED,
-            &source_chain_bytes,
-            nft_contract_ref,
-            &[mint_authority_bump],
-        ];
+    #[test]
+    fn test_disallowed_relayer_rejected() {
+        let mut config = mint_config(0);
+        config.allowed_relayers = vec![Pubkey::new_unique()];
+        assert!(!is_authorized_relayer(&config, &Pubkey::new_unique()));
+    }
 
-        if is_new_collection {
-            CreateCollectionV2CpiBuilder::new(&ctx.accounts.mpl_core_program)
-                .collection(&ctx.accounts.collection_asset)
-                .update_authority(Some(&ctx.accounts.mint_authority))
-                .payer(&ctx.accounts.user)
-                .system_program(&ctx.accounts.system_program)
-                .name(collection_name.clone())
-                .uri(format!(
-                    "https://ika-tensei.io/collections/{}/{}",
-                    source_chain,
-                    nft_mint_key
-                ))
-                .invoke_signed(&[mint_authority_seeds])
-                .map_err(|_e| ErrorCode::MetaplexError)?;
+    #[test]
+    fn test_empty_relayer_allowlist_is_admin_only() {
+        let config = mint_config(0);
+        assert!(is_authorized_relayer(&config, &config.admin));
+        assert!(!is_authorized_relayer(&config, &Pubkey::new_unique()));
+    }
 
-            let collection = &mut ctx.accounts.collection;
-            collection.source_chain = source_chain;
-            collection.nft_contract = nft_contract.clone();
-            collection.name = collection_name.clone();
-            collection.collection_asset_address = collection_asset_key;
-            collection.total_minted = 0;
-            collection.is_initialized = true;
-            collection.bump = ctx.bumps.collection;
+    #[test]
+    fn test_message_hash_commits_to_source_marker() {
+        let pubkey = [1u8; 32];
+        let signature = [2u8; 64];
+        let receiver = Pubkey::new_from_array([7u8; 32]);
+        let hint = [0x5au8; 32];
+        let signed = compute_message_hash("ipfs://Qm", b"42", &receiver, 5, 18_000_000, &hint, u64::MAX, SHA256).unwrap();
+        let data = ed25519_ix_data(&pubkey, &signature, &signed);
+        assert!(verify_ed25519_ix_data(&data, &[], &pubkey, &signed, &signature, false, MAX_SIGS).is_ok());
 
-            ctx.accounts.registry.add_collection(CollectionEntry {
-                source_chain,
-                nft_contract: nft_contract.clone(),
-                collection_address: collection_asset_key,
-                created_at: Clock::get()?.unix_timestamp,
-            });
-        }
+        // A relayer can't swap in a different source block or sequence
+        let other_block = compute_message_hash("ipfs://Qm", b"42", &receiver, 5, 18_000_000, &[0u8; 32], u64::MAX, SHA256).unwrap();
+        let other_seq = compute_message_hash("ipfs://Qm", b"42", &receiver, 5, 18_000_001, &hint, u64::MAX, SHA256).unwrap();
+        assert!(verify_ed25519_ix_data(&data, &[], &pubkey, &other_block, &signature, false, MAX_SIGS).is_err());
+        assert!(verify_ed25519_ix_data(&data, &[], &pubkey, &other_seq, &signature, false, MAX_SIGS).is_err());
+    }
 
-        // ============ 4. Mint reborn NFT ============
-        let collection = &mut ctx.accounts.collection;
+    #[test]
+    fn test_signature_freshness_window() {
+        // In window, including the last valid slot
+        assert!(check_signature_fresh(1_000, 2_000).is_ok());
+        assert!(check_signature_fresh(2_000, 2_000).is_ok());
 
-        let nft_name = format!("{} (Reborn)", collection.name);
+        let err = check_signature_fresh(2_001, 2_000).unwrap_err();
+        assert_eq!(err, ErrorCode::SignatureExpired.into());
 
-        CreateV2CpiBuilder::new(&ctx.accounts.mpl_core_program)
-            .asset(&ctx.accounts.asset)
-            .collection(Some(&ctx.accounts.collection_asset))
-            .authority(Some(&ctx.accounts.mint_authority))
-            .payer(&ctx.accounts.user)
-            .owner(Some(&ctx.accounts.user))
-            .update_authority(Some(&ctx.accounts.mint_authority))
-            .system_program(&ctx.accounts.system_program)
-            .data_state(DataState::AccountState)
-            .name(nft_name)
-            .uri(token_uri.clone())
-            .invoke_signed(&[mint_authority_seeds])
-            .map_err(|_e| ErrorCode::MetaplexError)?;
+        // The slot bound is signed: a relayer can't extend it
+        let receiver = Pubkey::new_from_array([7u8; 32]);
+        let signed = compute_message_hash("ipfs://Qm", b"42", &receiver, 5, 0, &[0u8; 32], 2_000, SHA256).unwrap();
+        let extended = compute_message_hash("ipfs://Qm", b"42", &receiver, 5, 0, &[0u8; 32], 3_000, SHA256).unwrap();
+        assert_ne!(signed, extended);
+    }
 
-        collection.total_minted = collection.total_minted.saturating_add(1);
-        msg!("Reborn NFT minted to {}", user_key);
+    #[test]
+    fn test_provenance_persists_source_marker() {
+        let mut provenance = empty_provenance();
+        provenance.source_sequence = 18_000_000;
+        provenance.source_block_hint = [0x5au8; 32];
 
-        // ============ 5. Store provenance ============
-        let provenance = &mut ctx.accounts.provenance;
-        provenance.source_chain = source_chain;
-        provenance.nft_contract = nft_contract;
-        provenance.token_id = token_id;
-        provenance.token_uri = token_uri;
-        provenance.dwallet_pubkey = [0u8; 32]; // No dWallet for native path
-        provenance.signature = [0u8; 64];       // No signature for native path
-        provenance.receiver = user_key;
-        provenance.sealed_at = Clock::get()?.unix_timestamp;
-        provenance.is_initialized = true;
-        provenance.bump = ctx.bumps.provenance;
+        let bytes = provenance.try_to_vec().unwrap();
+        let decoded = Provenance::try_from_slice(&bytes).unwrap();
+        assert_eq!(decoded.source_sequence, 18_000_000);
+        assert_eq!(decoded.source_block_hint, [0x5au8; 32]);
+    }
 
-        msg!("Provenance stored for native seal of {}", nft_mint_key);
+    #[test]
+    fn test_nft_name_ascii_token_id() {
+        assert_eq!(nft_display_name("Punks", b"alpha-7"), "Punks #alpha-7");
+    }
 
-        Ok(())
+    #[test]
+    fn test_nft_name_numeric_token_id() {
+        // EVM uint256 42, big-endian with leading zeros
+        let mut id = [0u8; 32];
+        id[31] = 42;
+        assert_eq!(nft_display_name("BAYC", &id), "BAYC #42");
+        // All-digit bytes are a number, not text: 0x31323334
+        assert_eq!(nft_display_name("BAYC", b"1234"), "BAYC #825373492");
     }
-    */
-}
 
-// ============ Helpers ============
+    #[test]
+    fn test_nft_name_single_printable_byte_is_numeric() {
+        assert_eq!(nft_display_name("BAYC", &[0x41]), "BAYC #65");
+        assert_eq!(nft_display_name("BAYC", &[0x00, 0x41]), "BAYC #65");
+    }
 
-/// Convert a big-endian uint256 byte slice to a decimal string.
-/// Strips leading zeros, then converts to u128 if it fits, otherwise falls back to hex.
-fn token_id_to_decimal(bytes: &[u8]) -> String {
-    let trimmed: &[u8] = match bytes.iter().position(|&b| b != 0) {
-        Some(pos) => &bytes[pos..],
-        None => return "0".to_string(),
-    };
-    if trimmed.len() <= 16 {
-        let mut val: u128 = 0;
-        for &b in trimmed {
-            val = (val << 8) | b as u128;
-        }
-        val.to_string()
-    } else {
-        // Too large for u128, use 0x-prefixed hex
-        format!("0x{}", hex::encode(trimmed))
+    #[test]
+    fn test_nft_name_long_collection_name_stays_within_limit() {
+        let long = "A".repeat(constants::MAX_NAME_LENGTH);
+        let name = nft_display_name(&long, &[42]);
+        assert_eq!(name, format!("{} #42", "A".repeat(constants::MAX_NAME_LENGTH - 4)));
+        assert_eq!(name.len(), constants::MAX_NAME_LENGTH);
+
+        // A long ID keeps MIN_NAME_TOKEN_ID_LENGTH characters, cut and marked
+        let name = nft_display_name(&long, b"token-abcdefghijklmnop");
+        assert_eq!(name.len(), constants::MAX_NAME_LENGTH);
+        assert!(name.ends_with(" #token..."));
+
+        // Multi-byte names are cut on a char boundary
+        let name = nft_display_name(&"é".repeat(16), &[7]);
+        assert!(name.len() <= constants::MAX_NAME_LENGTH);
+        assert!(name.ends_with(" #7"));
     }
-}
 
-/// Verify the seal: sig_hash matches sha256(signature), message hash is correct,
-/// and Ed25519 signature is valid. Separated into its own stack frame to reduce
-/// the main mint_reborn function's stack usage below the BPF 4KB limit.
-#[inline(never)]
-fn verify_seal(
-    instructions_sysvar: &AccountInfo,
-    minting_pubkey: &[u8; 32],
-    signature: &[u8],
-    sig_hash: &[u8],
-    token_uri: &str,
-    token_id: &[u8],
-    receiver_pubkey: &Pubkey,
-) -> Result<()> {
-    // Verify sig_hash == sha256(signature)
-    let computed_sig_hash: [u8; 32] = Sha256::digest(signature).into();
-    require!(
-        constant_time_eq::constant_time_eq(&computed_sig_hash, sig_hash),
-        ErrorCode::InvalidSigHash
-    );
+    #[test]
+    fn test_nft_name_binary_token_id_is_readable_hex() {
+        // 32-byte hash-style ID: too large for u128, rendered as hex and cut to fit
+        let id = [0x1au8; 32];
+        let name = nft_display_name("Sui Frens", &id);
+        assert!(name.starts_with("Sui Frens #0x1a1a"));
+        assert!(name.ends_with("..."));
+        assert_eq!(name.len(), constants::MAX_NAME_LENGTH);
+        assert!(name.chars().all(|c| c.is_ascii_graphic() || c == ' '));
+    }
 
-    // Reconstruct message hash: sha256(token_uri || token_id || receiver)
-    let mut hasher = Sha256::new();
-    hasher.update(token_uri.as_bytes());
-    hasher.update(token_id);
-    hasher.update(receiver_pubkey.as_ref());
-    let message_hash: [u8; 32] = hasher.finalize().into();
+    #[test]
+    fn test_asset_link_resolves_to_provenance() {
+        let asset = Pubkey::new_unique();
+        let (provenance, _) = pdas::provenance(2, b"0xabc", &[0x2a]);
 
-    // Verify Ed25519 signature via precompile
-    verify_ed25519_signature(
-        instructions_sysvar,
-        minting_pubkey,
-        &message_hash,
-        signature,
-    )?;
+        // What mint_reborn writes for this asset
+        let (link_address, bump) = pdas::asset_provenance_link(&asset);
+        assert_eq!((link_address, bump), find(&[constants::ASSET_PROVENANCE_SEED, asset.as_ref()]));
+        let link = AssetProvenanceLink { provenance, bump };
 
-    Ok(())
-}
+        // A client reads the link account and follows it
+        let bytes = link.try_to_vec().unwrap();
+        let resolved = AssetProvenanceLink::try_from_slice(&bytes).unwrap();
+        assert_eq!(resolved.provenance, provenance);
+        assert_ne!(pdas::asset_provenance_link(&Pubkey::new_unique()).0, link_address);
+    }
 
-/// Verify an Ed25519 signature using Solana's native precompile.
-///
-/// Walks the transaction instruction list looking for a call to the
-/// Ed25519 program. Extracts and verifies **three** fields from the
-/// instruction data against our expected values:
-///
-/// 1. Public key (offset from data[6..7])
-/// 2. Message    (offset from data[10..11], size from data[12..13])
-/// 3. **Signature bytes** (offset from data[2..3]) — previously missing (FIX 1)
-///
-/// Ed25519 instruction header layout (per signature entry, starting at byte 2):
-/// ```
-///  [0]     num_signatures
-///  [1]     padding
-///  [2..3]  sig_offset          (u16 LE) ← FIX 1: now verified
-///  [4..5]  sig_instruction_idx (u16 LE)
-///  [6..7]  pubkey_offset       (u16 LE)
-///  [8..9]  pubkey_instruction_idx (u16 LE)
-/// [10..11] message_offset      (u16 LE)
-/// [12..13] message_size        (u16 LE)
-/// [14..15] message_instruction_idx (u16 LE)
-/// ```
-fn verify_ed25519_signature(
-    instructions_sysvar: &AccountInfo,
-    expected_pubkey: &[u8],
-    expected_message: &[u8],
-    expected_signature: &[u8],
-) -> Result<()> {
-    // The Ed25519 precompile instruction MUST be at index 0 in the transaction.
-    // This prevents confusion with Ed25519 instructions from other programs in
-    // a composed transaction. The relayer always places it at position 0.
-    let ed25519_ix = ix_sysvar::load_instruction_at_checked(0, instructions_sysvar)
-        .map_err(|_| ErrorCode::NoEd25519Instruction)?;
+    #[test]
+    fn test_source_chain_wire_be_to_seed_le() {
+        // NEAR (15) arrives as [0x00, 0x0F] on the wire
+        let chain = SourceChain::from_wire_be([0x00, 0x0F]);
+        assert_eq!(chain, SourceChain(15));
+        assert_eq!(chain.to_le_seed_bytes(), [0x0F, 0x00]);
 
-    if ed25519_ix.program_id != ed25519_program::ID {
-        return Err(ErrorCode::NoEd25519Instruction.into());
+        let chain = SourceChain::from_wire_be([0x27, 0x12]); // 10002, Ethereum Sepolia
+        assert_eq!(chain.0, 10002);
+        assert_eq!(chain.to_le_seed_bytes(), [0x12, 0x27]);
+        assert_eq!(SourceChain::SOLANA.to_le_seed_bytes(), [0x01, 0x00]);
     }
 
-    verify_ed25519_ix_data(&ed25519_ix.data, expected_pubkey, expected_message, expected_signature)
-}
+    #[test]
+    fn test_pdas_route_through_source_chain() {
+        // Wire order is big-endian, seed order little-endian: chain 0x0102 seeds as [0x02, 0x01]
+        let chain = SourceChain::from_wire_be([0x01, 0x02]);
+        assert_eq!(chain.0, 0x0102);
+        let seed = [0x02, 0x01];
+        assert_eq!(chain.to_le_seed_bytes(), seed);
+        let (contract, token) = (b"0xabc".as_slice(), b"42".as_slice());
 
-/// Inner verification of Ed25519 instruction data fields.
-/// Checks all three fields: signature bytes, public key, and message.
-fn verify_ed25519_ix_data(
-    data: &[u8],
-    expected_pubkey: &[u8],
-    expected_message: &[u8],
-    expected_signature: &[u8],
-) -> Result<()> {
-    // Minimum header size: 2 (count + padding) + 14 (one signature entry header)
-    if data.len() < 16 {
-        return Err(ErrorCode::InvalidInstructionData.into());
+        assert_eq!(pdas::provenance(chain.0, contract, token), find(&[constants::PROVENANCE_SEED, &seed, contract, token]));
+        assert_eq!(pdas::reborn_collection(chain.0, contract), find(&[constants::COLLECTION_SEED, &seed, contract]));
+        assert_eq!(pdas::collection_asset(chain.0, contract), find(&[constants::COLLECTION_ASSET_SEED, &seed, contract]));
+        assert_eq!(pdas::mint_authority(chain.0, contract), find(&[constants::MINT_AUTHORITY_SEED, &seed, contract]));
+        assert_eq!(
+            pdas::receiver_override(chain.0, contract, token),
+            find(&[constants::RECEIVER_OVERRIDE_SEED, &seed, contract, token])
+        );
     }
 
-    let num_signatures = data[0];
-    if num_signatures < 1 {
-        return Err(ErrorCode::InvalidInstructionData.into());
+    /// First entry verifies against (pubkey, signature, message); `count - 1` padding
+    /// entries follow, as in a batched precompile instruction.
+    fn batched_ix_data(count: u8, pubkey: &[u8; 32], signature: &[u8; 64], message: &[u8; 32]) -> Vec<u8> {
+        let mut data = ed25519_ix_data(pubkey, signature, message);
+        data[0] = count;
+        data.extend(std::iter::repeat(0u8).take((count as usize - 1) * (14 + 32 + 64 + 32)));
+        data
     }
 
-    // Parse offsets from the first signature entry header (starts at byte 2)
-    let sig_offset     = u16::from_le_bytes([data[2],  data[3]])  as usize;
-    let pubkey_offset  = u16::from_le_bytes([data[6],  data[7]])  as usize;
-    let message_offset = u16::from_le_bytes([data[10], data[11]]) as usize;
-    let message_size   = u16::from_le_bytes([data[12], data[13]]) as usize;
+    #[test]
+    fn test_cpi_composed_ed25519_resolved_by_header_index() {
+        let pubkey = [1u8; 32];
+        let signature = [2u8; 64];
+        let message = [3u8; 32];
 
-    // Verify signature bytes (all 64)
-    match data.get(sig_offset..sig_offset + 64) {
-        Some(sig_data) => {
-            if !constant_time_eq::constant_time_eq(sig_data, expected_signature) {
-                return Err(ErrorCode::SignatureVerificationFailed.into());
+        // The caller's instruction (index 2) carries pubkey || signature || message, and
+        // the precompile at index 1 points into it instead of holding the data itself
+        let mut caller_data = vec![9u8; 8];
+        caller_data.extend_from_slice(&pubkey);
+        caller_data.extend_from_slice(&signature);
+        caller_data.extend_from_slice(&message);
+        let mut header = vec![1u8, 0u8];
+        for (offset, len) in [(8u16 + 32, None), (8, None), (8 + 96, Some(32u16))] {
+            header.extend_from_slice(&offset.to_le_bytes());
+            if let Some(len) = len {
+                header.extend_from_slice(&len.to_le_bytes());
             }
+            header.extend_from_slice(&2u16.to_le_bytes());
         }
-        None => return Err(ErrorCode::InvalidInstructionData.into()),
-    }
+        let ix = |program_id: Pubkey, data: Vec<u8>| Instruction { program_id, accounts: vec![], data };
+        let instructions = vec![
+            ix(Pubkey::new_unique(), vec![]),
+            ix(ed25519_program::ID, header.clone()),
+            ix(Pubkey::new_unique(), caller_data),
+        ];
+        let load = |index: usize| instructions.get(index).cloned();
 
-    // Verify public key (32 bytes)
-    match data.get(pubkey_offset..pubkey_offset + 32) {
-        Some(pubkey_data) => {
-            if !constant_time_eq::constant_time_eq(pubkey_data, expected_pubkey) {
-                return Err(ErrorCode::SignatureVerificationFailed.into());
-            }
-        }
-        None => return Err(ErrorCode::InvalidInstructionData.into()),
-    }
+        let found = find_ed25519_ix(2, load).unwrap();
+        assert_eq!(found.data, header);
+        // Only instructions before the current one are considered
+        assert!(find_ed25519_ix(1, load).is_none());
 
-    // Verify message (must be exactly 32 bytes = our SHA256 hash)
-    if message_size != 32 {
-        return Err(ErrorCode::SignatureVerificationFailed.into());
+        let foreign = ed25519_foreign_data(&found.data, load).unwrap();
+        assert!(verify_ed25519_ix_data(&found.data, &foreign, &pubkey, &message, &signature, false, MAX_SIGS).is_ok());
+        // Strict mode only takes self-contained precompile instructions
+        assert!(verify_ed25519_ix_data(&found.data, &foreign, &pubkey, &message, &signature, true, MAX_SIGS).is_err());
+
+        // Without the referenced instruction's data the fields can't be read
+        let err = verify_ed25519_ix_data(&found.data, &[], &pubkey, &message, &signature, false, MAX_SIGS).unwrap_err();
+        assert_eq!(err, ErrorCode::InvalidInstructionData.into());
     }
-    match data.get(message_offset..message_offset + message_size) {
-        Some(message_data) => {
-            if !constant_time_eq::constant_time_eq(message_data, expected_message) {
-                return Err(ErrorCode::SignatureVerificationFailed.into());
-            }
-        }
-        None => return Err(ErrorCode::InvalidInstructionData.into()),
+
+    #[test]
+    fn test_ed25519_fields_read_from_referenced_instruction() {
+        // Own data holds our values at the offsets, but the header says the precompile
+        // verified instruction 0's data: that is what must be compared
+        let pubkey = [1u8; 32];
+        let signature = [2u8; 64];
+        let message = [3u8; 32];
+        let mut data = ed25519_ix_data(&pubkey, &signature, &message);
+        data[4..6].copy_from_slice(&0u16.to_le_bytes());
+        let other = vec![(0u16, vec![0u8; 256])];
+        let err = verify_ed25519_ix_data(&data, &other, &pubkey, &message, &signature, false, MAX_SIGS).unwrap_err();
+        assert_eq!(err, ErrorCode::SignatureVerificationFailed.into());
     }
 
-    Ok(())
-}
+    #[test]
+    fn test_batch_at_signature_limit_verifies() {
+        let (pubkey, signature, message) = ([1u8; 32], [2u8; 64], [3u8; 32]);
+        let data = batched_ix_data(4, &pubkey, &signature, &message);
+        assert!(verify_ed25519_ix_data(&data, &[], &pubkey, &message, &signature, false, 4).is_ok());
+    }
 
-// ============ Errors ============
+    #[test]
+    fn test_batch_above_signature_limit_rejected_before_parsing() {
+        let (pubkey, signature, message) = ([1u8; 32], [2u8; 64], [3u8; 32]);
+        let data = batched_ix_data(5, &pubkey, &signature, &message);
+        let err = verify_ed25519_ix_data(&data, &[], &pubkey, &message, &signature, false, 4).unwrap_err();
+        assert_eq!(err, ErrorCode::TooManySignatures.into());
 
-#[error_code]
-pub enum ErrorCode {
-    #[msg("Invalid Ed25519 signature")]
-    InvalidSignature,
+        // Rejected on the count alone: a bare header with garbage offsets fails the same way
+        let mut header = vec![0xFFu8; 16];
+        header[0] = 200;
+        let err = verify_ed25519_ix_data(&header, &[], &pubkey, &message, &signature, false, 4).unwrap_err();
+        assert_eq!(err, ErrorCode::TooManySignatures.into());
+    }
 
-    #[msg("Invalid instruction data")]
-    InvalidInstructionData,
+    #[test]
+    fn test_attribute_schema_names_provenance_traits() {
+        let schema = AttributeSchema {
+            source_chain_key: "Origin Chain".to_string(),
+            source_contract_key: "Origin Contract".to_string(),
+            token_id_key: "Origin Token ID".to_string(),
+        };
+        assert!(validate_attribute_schema(&schema).is_ok());
+        let mut config = mint_config(0);
+        config.attribute_schema = Some(schema);
 
-    #[msg("No Ed25519 instruction found in transaction")]
-    NoEd25519Instruction,
+        let token_id = 1234u32.to_be_bytes();
+        let pair = provenance_attributes_plugin(config.attribute_schema.as_ref().unwrap(), 2, &[0xab, 0xcd], &token_id);
+        let Plugin::Attributes(attributes) = pair.plugin else {
+            panic!("expected Attributes plugin");
+        };
+        let fields: Vec<(&str, &str)> = attributes
+            .attribute_list
+            .iter()
+            .map(|a| (a.key.as_str(), a.value.as_str()))
+            .collect();
+        assert_eq!(
+            fields,
+            vec![("Origin Chain", "2"), ("Origin Contract", "0xabcd"), ("Origin Token ID", "1234")]
+        );
 
-    #[msg("Ed25519 signature verification failed (pubkey, message, or signature bytes mismatch)")]
-    SignatureVerificationFailed,
+        let too_long = AttributeSchema {
+            source_chain_key: "k".repeat(constants::MAX_ATTRIBUTE_KEY_LENGTH + 1),
+            source_contract_key: "contract".to_string(),
+            token_id_key: "token".to_string(),
+        };
+        let err = validate_attribute_schema(&too_long).unwrap_err();
+        assert_eq!(err, ErrorCode::InvalidAttributeKey.into());
+        let duplicate = AttributeSchema {
+            source_chain_key: "id".to_string(),
+            source_contract_key: "contract".to_string(),
+            token_id_key: "id".to_string(),
+        };
+        assert!(validate_attribute_schema(&duplicate).is_err());
+    }
 
-    #[msg("Signature already used — replay protection")]
-    SignatureAlreadyUsed,
+    #[test]
+    fn test_nft_sealed_event_fields() {
+        let user = Pubkey::new_unique();
+        let nft_mint = Pubkey::new_unique();
+        let asset = Pubkey::new_unique();
+        let sealed_nft_vault = pdas::sealed_vault(&nft_mint).0;
+        assert_eq!(sealed_nft_vault, find(&[constants::SEALED_VAULT_SEED, nft_mint.as_ref()]).0);
 
-    #[msg("sig_hash does not match sha256(signature)")]
-    InvalidSigHash,
+        let event = NftSealed { user, nft_mint, sealed_nft_vault, asset };
+        // What indexers read from the log: discriminator, then the Borsh fields in order
+        let data = anchor_lang::Event::data(&event);
+        assert_eq!(&data[..8], NftSealed::DISCRIMINATOR);
+        let decoded = NftSealed::try_from_slice(&data[8..]).unwrap();
+        assert_eq!(decoded.user, user);
+        assert_eq!(decoded.nft_mint, nft_mint);
+        assert_eq!(decoded.sealed_nft_vault, sealed_nft_vault);
+        assert_eq!(decoded.asset, asset);
+    }
 
-    #[msg("Collection already exists")]
-    CollectionAlreadyExists,
+    #[test]
+    fn test_asset_collection_collision_rejected() {
+        let asset = Pubkey::new_unique();
+        let err = check_asset_collection_distinct(&asset, &asset).unwrap_err();
+        assert_eq!(err, ErrorCode::AssetCollectionCollision.into());
+        assert!(check_asset_collection_distinct(&Pubkey::new_unique(), &asset).is_ok());
+    }
 
-    #[msg("NFT already minted")]
-    AlreadyMinted,
+    #[test]
+    fn test_collection_name_validation() {
+        assert!(validate_collection_name("Bored Ape Yacht Club").is_ok());
+        for name in ["Bored\nApes", "Tab\tName", "Null\0Name", "Bell\u{7}"] {
+            let err = validate_collection_name(name).unwrap_err();
+            assert_eq!(err, ErrorCode::InvalidName.into());
+        }
 
-    #[msg("Contract address too long")]
-    ContractTooLong,
+        // 8 four-byte emoji fill MAX_NAME_LENGTH bytes exactly; a 9th trips NameTooLong
+        let emoji = "\u{1F525}".repeat(8);
+        assert_eq!(emoji.len(), constants::MAX_NAME_LENGTH);
+        assert!(validate_collection_name(&emoji).is_ok());
+        let over = format!("{}\u{1F525}", emoji);
+        assert!(over.len() > constants::MAX_NAME_LENGTH);
+        assert_eq!(validate_collection_name(&over).unwrap_err(), ErrorCode::NameTooLong.into());
+    }
 
-    #[msg("Token ID too long")]
-    TokenIdTooLong,
+    #[test]
+    fn test_reserve_then_claim_provenance() {
+        let mut provenance = empty_provenance();
+        let err = check_reservation(&provenance).unwrap_err();
+        assert_eq!(err, ErrorCode::ProvenanceNotReserved.into());
 
-    #[msg("URI too long (max 512 bytes)")]
-    UriTooLong,
+        fill_reservation(&mut provenance, 2, b"0xabc".to_vec(), b"7".to_vec(), 254);
+        assert!(provenance.reserved && !provenance.is_initialized);
+        assert_eq!((provenance.source_chain, provenance.bump), (2, 254));
+        assert!(check_reservation(&provenance).is_ok());
 
-    #[msg("Name too long")]
-    NameTooLong,
+        // After the claim the record is an ordinary minted provenance
+        provenance.is_initialized = true;
+        provenance.reserved = false;
+        let err = check_reservation(&provenance).unwrap_err();
+        assert_eq!(err, ErrorCode::AlreadyMinted.into());
+    }
 
-    #[msg("Receiver must not be the system program or zero key")]
-    InvalidReceiver,
+    #[test]
+    fn test_ensure_detects_existing_accounts_by_discriminator() {
+        // A fresh init_if_needed account is all zeroes until Anchor serializes it on exit
+        let fresh = vec![0u8; 8 + CollectionRegistry::INIT_SPACE];
+        assert!(!has_discriminator(&fresh, &CollectionRegistry::DISCRIMINATOR));
 
-    #[msg("Metaplex error")]
-    MetaplexError,
+        // An existing registry keeps its discriminator even with bump 0 and no entries
+        let registry = CollectionRegistry { count: 0, bump: 0, collections: Vec::new() };
+        let mut existing = Vec::new();
+        registry.try_serialize(&mut existing).unwrap();
+        assert!(has_discriminator(&existing, &CollectionRegistry::DISCRIMINATOR));
+        assert!(!has_discriminator(&existing, &MintConfig::DISCRIMINATOR));
+        assert!(!has_discriminator(&[], &CollectionRegistry::DISCRIMINATOR));
+    }
 
-    #[msg("Unauthorized")]
-    Unauthorized,
+    #[test]
+    fn test_init_mint_config_defaults() {
+        // The ed25519 basepoint, an on-curve wallet key
+        let admin = Pubkey::new_from_array([
+            0x58, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66,
+            0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66,
+        ]);
+        let mut config = mint_config();
+        config.strict_ed25519 = true;
+        config.allowed_relayers = vec![Pubkey::new_unique()];
+        init_mint_config(&mut config, [7u8; 32], admin, 254, 1_700_000_000).unwrap();
+        assert_eq!(config.minting_pubkey, [7u8; 32]);
+        assert_eq!(config.admin, admin);
+        assert!(config.maintain_registry);
+        assert!(!config.strict_ed25519);
+        assert!(config.allowed_relayers.is_empty());
+        assert_eq!(config.last_admin_activity, 1_700_000_000);
+        assert_eq!((config.native_seal_fee, config.treasury), (0, admin));
+        assert_eq!(config.reborn_suffix, constants::DEFAULT_REBORN_SUFFIX);
+        assert_eq!(config.bump, 254);
 
-    #[msg("Source chain must be Solana (chain ID 1) for native seal")]
-    InvalidSourceChain,
+        let err = init_mint_config(&mut config, [0u8; 32], admin, 254, 0).unwrap_err();
+        assert_eq!(err, ErrorCode::InvalidMintingPubkey.into());
+    }
 
-    #[msg("NFT token account mint does not match provided nft_mint")]
-    InvalidNftMint,
+    #[test]
+    fn test_mint_reborn_args_encode_like_flat_arguments() {
+        let args = MintRebornArgs {
+            sig_hash: vec![1u8; 32],
+            source_chain: 2,
+            nft_contract: b"0xabc".to_vec(),
+            token_id: vec![0x2a],
+            signature: vec![3u8; 64],
+            token_uri: "ipfs://x".to_string(),
+            collection_name: "Test".to_string(),
+            royalty_basis_points: 690,
+            dao_treasury: Pubkey::new_unique(),
+            wormhole_sequence: 7,
+            source_sequence: 8,
+            source_block_hint: [9u8; 32],
+            valid_until_slot: 10,
+            hash_algo: constants::HASH_ALGO_SHA256,
+            delegate: None,
+            symbol: Some("TST".to_string()),
+            receiver_proof: vec![[4u8; 32]],
+        };
+        let mut flat = Vec::new();
+        args.sig_hash.serialize(&mut flat).unwrap();
+        args.source_chain.serialize(&mut flat).unwrap();
+        args.nft_contract.serialize(&mut flat).unwrap();
+        args.token_id.serialize(&mut flat).unwrap();
+        args.signature.serialize(&mut flat).unwrap();
+        args.token_uri.serialize(&mut flat).unwrap();
+        args.collection_name.serialize(&mut flat).unwrap();
+        args.royalty_basis_points.serialize(&mut flat).unwrap();
+        args.dao_treasury.serialize(&mut flat).unwrap();
+        args.wormhole_sequence.serialize(&mut flat).unwrap();
+        args.source_sequence.serialize(&mut flat).unwrap();
+        args.source_block_hint.serialize(&mut flat).unwrap();
+        args.valid_until_slot.serialize(&mut flat).unwrap();
+        args.hash_algo.serialize(&mut flat).unwrap();
+        args.delegate.serialize(&mut flat).unwrap();
+        args.symbol.serialize(&mut flat).unwrap();
+        args.receiver_proof.serialize(&mut flat).unwrap();
+        assert_eq!(args.try_to_vec().unwrap(), flat);
+    }
 
-    #[msg("NFT token account must contain exactly 1 token")]
-    InvalidNftAmount,
+    #[test]
+    fn test_native_transfer_kind() {
+        // A standard Token Metadata NFT (and a bare SPL mint) locks with an SPL transfer
+        let standard = TokenStandard::NonFungible;
+        assert_eq!(native_transfer_kind(Some(&standard), false, SealMode::Lock).unwrap(), NativeTransfer::Token);
+        assert_eq!(native_transfer_kind(None, false, SealMode::Lock).unwrap(), NativeTransfer::Token);
+        assert_eq!(native_transfer_kind(Some(&standard), false, SealMode::Burn).unwrap(), NativeTransfer::Token);
+        let err = native_transfer_kind(Some(&standard), true, SealMode::Lock).unwrap_err();
+        assert_eq!(err, ErrorCode::NftFrozen.into());
 
-    #[msg("Invalid royalty basis points (max 10000)")]
-    InvalidRoyalties,
+        // pNFTs are always frozen and go through Token Metadata; burning is out of scope
+        let pnft = TokenStandard::ProgrammableNonFungible;
+        assert_eq!(native_transfer_kind(Some(&pnft), true, SealMode::Lock).unwrap(), NativeTransfer::TokenMetadata);
+        let err = native_transfer_kind(Some(&pnft), true, SealMode::Burn).unwrap_err();
+        assert_eq!(err, ErrorCode::ProgrammableBurnUnsupported.into());
+    }
 }
@@ -6,7 +6,7 @@
 
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::sysvar::instructions as ix_sysvar;
-use mpl_core::instructions::{CreateV2CpiBuilder, CreateCollectionV2CpiBuilder};
+use mpl_core::instructions::{CreateV2CpiBuilder, CreateCollectionV2CpiBuilder, UpdateCollectionV1CpiBuilder};
 use mpl_core::types::{
     DataState, Plugin, PluginAuthority, PluginAuthorityPair,
     Royalties, Creator, RuleSet, ImmutableMetadata,
@@ -21,9 +21,12 @@ pub mod constants {
     pub const MAX_TOKEN_ID_LENGTH: usize = 64;
     pub const CONFIG_SEED: &[u8] = b"ika_config";
     pub const RECORD_SEED: &[u8] = b"reincarnation";
+    pub const CANCELLED_SEAL_SEED: &[u8] = b"cancelled_seal";
     pub const MINT_SEED: &[u8] = b"reincarnation_mint";
     pub const COLLECTION_SEED: &[u8] = b"collection";
     pub const ONCHAIN_COLLECTION_SEED: &[u8] = b"onchain_collection";
+    pub const RESERVE_MINT_SEED: &[u8] = b"reserve_mint";
+    pub const COLLECTION_INDEX_SEED: &[u8] = b"collection_index";
 
     // Chain IDs
     pub const CHAIN_ETHEREUM: u16 = 1;
@@ -35,6 +38,50 @@ pub mod constants {
     // Royalties (in bps)
     pub const GUILD_ROYALTY_BPS: u16 = 500;
     pub const TEAM_ROYALTY_BPS: u16 = 190;
+
+    // mint_reborn plugin selection (bitmask)
+    pub const PLUGIN_ROYALTIES: u8 = 1 << 0;
+    pub const PLUGIN_IMMUTABLE_METADATA: u8 = 1 << 1;
+    pub const PLUGINS_ALL: u8 = PLUGIN_ROYALTIES | PLUGIN_IMMUTABLE_METADATA;
+
+    /// Minimum age of an unminted ReincarnationRecord before its payer may cancel it (7 days)
+    pub const SEAL_CANCEL_TIMEOUT_SECS: i64 = 7 * 24 * 60 * 60;
+
+    /// Max programs in a collection's royalty allow/deny list (matches the
+    /// `#[max_len]` on `RoyaltyRuleSet`)
+    pub const MAX_RULE_SET_PROGRAMS: usize = 8;
+
+    /// Max fee-exempt wallets (matches the `#[max_len]` on `ProtocolConfig.fee_exempt`)
+    pub const MAX_FEE_EXEMPT: usize = 16;
+
+    /// Max royalty creators per collection (matches the `#[max_len]` on
+    /// `CollectionConfig.creators`, and Metaplex's own limit)
+    pub const MAX_CREATORS: usize = 5;
+}
+
+/// PDA derivations, for clients enumerating collections.
+pub mod pdas {
+    use super::constants;
+    use anchor_lang::prelude::Pubkey;
+
+    /// ["ika_config"]
+    pub fn config() -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[constants::CONFIG_SEED], &crate::ID)
+    }
+
+    /// ["collection", source_chain_le, source_contract]
+    pub fn collection(source_chain: u16, source_contract: &[u8]) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[constants::COLLECTION_SEED, &source_chain.to_le_bytes(), source_contract],
+            &crate::ID,
+        )
+    }
+
+    /// ["collection_index", index_le] — the `index`-th registered collection, for
+    /// `index < config.collection_count`
+    pub fn collection_index(index: u64) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[constants::COLLECTION_INDEX_SEED, &index.to_le_bytes()], &crate::ID)
+    }
 }
 
 // ============ Account Contexts ============
@@ -52,11 +99,15 @@ pub struct Initialize<'info> {
 #[derive(Accounts)]
 #[instruction(source_chain: u16, source_contract: Vec<u8>)]
 pub struct RegisterCollection<'info> {
-    #[account(seeds = [constants::CONFIG_SEED], bump = config.bump, has_one = authority)]
+    #[account(mut, seeds = [constants::CONFIG_SEED], bump = config.bump, has_one = authority)]
     pub config: Account<'info, ProtocolConfig>,
     #[account(init, payer = authority, space = 8 + CollectionConfig::INIT_SPACE,
               seeds = [constants::COLLECTION_SEED, &source_chain.to_le_bytes(), &source_contract], bump)]
     pub collection: Account<'info, CollectionConfig>,
+    /// Enumeration slot for this collection, at the next free index
+    #[account(init, payer = authority, space = 8 + CollectionIndex::INIT_SPACE,
+              seeds = [constants::COLLECTION_INDEX_SEED, &config.collection_count.to_le_bytes()], bump)]
+    pub collection_index: Account<'info, CollectionIndex>,
     #[account(mut)]
     pub authority: Signer<'info>,
     pub system_program: Program<'info, System>,
@@ -70,6 +121,11 @@ pub struct VerifySeal<'info> {
     #[account(mut, seeds = [constants::COLLECTION_SEED, &source_chain.to_le_bytes(),
               &collection.source_contract], bump = collection.bump)]
     pub collection: Account<'info, CollectionConfig>,
+    /// CHECK: Tombstone PDA that cancel_seal leaves behind; must not exist. Checked
+    /// before `record` is created, since a cancelled seal's record has been closed.
+    #[account(seeds = [constants::CANCELLED_SEAL_SEED, &seal_hash], bump,
+              constraint = cancelled_seal.data_is_empty() @ ErrorCode::SealCancelled)]
+    pub cancelled_seal: UncheckedAccount<'info>,
     #[account(init, payer = payer, space = 8 + ReincarnationRecord::INIT_SPACE,
               seeds = [constants::RECORD_SEED, &seal_hash], bump)]
     pub record: Account<'info, ReincarnationRecord>,
@@ -92,6 +148,10 @@ pub struct MintReborn<'info> {
     pub config: Account<'info, ProtocolConfig>,
     #[account(mut, seeds = [constants::RECORD_SEED, &seal_hash], bump = record.bump)]
     pub record: Account<'info, ReincarnationRecord>,
+    /// Collection the record was verified against; supplies the royalty rule set
+    #[account(seeds = [constants::COLLECTION_SEED, &record.source_chain.to_le_bytes(),
+              &record.source_contract], bump = collection.bump)]
+    pub collection: Account<'info, CollectionConfig>,
     /// CHECK: Mint authority PDA; seeds = [MINT_SEED, seal_hash]
     #[account(seeds = [constants::MINT_SEED, &seal_hash], bump)]
     pub mint_authority: UncheckedAccount<'info>,
@@ -111,6 +171,32 @@ pub struct MintReborn<'info> {
     pub system_program: Program<'info, System>,
 }
 
+/// Admin mint of a reserve/team NFT for a registered collection. No seal is
+/// consumed and `max_supply` is not checked; the mint is counted in
+/// `collection.reserve_minted` instead.
+#[derive(Accounts)]
+pub struct MintReserve<'info> {
+    #[account(seeds = [constants::CONFIG_SEED], bump = config.bump, has_one = authority)]
+    pub config: Account<'info, ProtocolConfig>,
+    #[account(mut, seeds = [constants::COLLECTION_SEED, &collection.source_chain.to_le_bytes(),
+              &collection.source_contract], bump = collection.bump)]
+    pub collection: Account<'info, CollectionConfig>,
+    /// CHECK: Reserve mint authority PDA; seeds = [RESERVE_MINT_SEED, collection]
+    #[account(seeds = [constants::RESERVE_MINT_SEED, collection.key().as_ref()], bump)]
+    pub mint_authority: UncheckedAccount<'info>,
+    /// CHECK: New Metaplex Core asset (must be signer in outer transaction)
+    #[account(mut)]
+    pub asset: UncheckedAccount<'info>,
+    /// CHECK: Owner of the reserve NFT, chosen by the authority
+    pub recipient: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// CHECK: Metaplex Core program - verified by address
+    #[account(address = mpl_core::ID)]
+    pub mpl_core_program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
 /// Create a Metaplex Core Collection whose address is a PDA of this program.
 #[derive(Accounts)]
 pub struct CreateOnchainCollection<'info> {
@@ -127,6 +213,104 @@ pub struct CreateOnchainCollection<'info> {
     pub system_program: Program<'info, System>,
 }
 
+/// Hand the onchain Metaplex Core collection to a new update authority (e.g. a DAO
+/// multisig). `authority` must be both the protocol authority and the collection's
+/// current update authority.
+#[derive(Accounts)]
+pub struct TransferOnchainCollectionAuthority<'info> {
+    #[account(seeds = [constants::CONFIG_SEED], bump = config.bump, has_one = authority)]
+    pub config: Account<'info, ProtocolConfig>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// CHECK: Collection PDA; seeds = [ONCHAIN_COLLECTION_SEED, config_key]
+    #[account(mut, seeds = [constants::ONCHAIN_COLLECTION_SEED, &config.key().to_bytes()], bump)]
+    pub collection: UncheckedAccount<'info>,
+    /// CHECK: New update authority; only its address is used
+    pub new_authority: UncheckedAccount<'info>,
+    /// CHECK: Metaplex Core program - verified by address
+    #[account(address = mpl_core::ID)]
+    pub mpl_core_program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Cancel a verified-but-never-minted seal. Closes the record, refunding its rent
+/// to the payer, and releases the collection supply slot it consumed. A minimal
+/// `CancelledSeal` tombstone takes over as the seal's replay guard.
+#[derive(Accounts)]
+#[instruction(seal_hash: [u8; 32])]
+pub struct CancelSeal<'info> {
+    #[account(mut, seeds = [constants::COLLECTION_SEED, &record.source_chain.to_le_bytes(),
+              &record.source_contract], bump = collection.bump)]
+    pub collection: Account<'info, CollectionConfig>,
+    #[account(mut, seeds = [constants::RECORD_SEED, &seal_hash], bump = record.bump,
+              has_one = payer @ ErrorCode::Unauthorized, close = payer)]
+    pub record: Account<'info, ReincarnationRecord>,
+    #[account(init, payer = payer, space = 8 + CancelledSeal::INIT_SPACE,
+              seeds = [constants::CANCELLED_SEAL_SEED, &seal_hash], bump)]
+    pub cancelled_seal: Account<'info, CancelledSeal>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Authority-only upgrade of a record written before `payer` and `quoted_fee`
+/// were added. The record is reallocated in place (the authority tops
+/// up its rent) so it stays at the same PDA and keeps guarding its seal.
+#[derive(Accounts)]
+#[instruction(seal_hash: [u8; 32])]
+pub struct MigrateRecord<'info> {
+    #[account(seeds = [constants::CONFIG_SEED], bump = config.bump, has_one = authority)]
+    pub config: Account<'info, ProtocolConfig>,
+    /// CHECK: Legacy ReincarnationRecord; owner, size and discriminator checked in the handler
+    #[account(mut, seeds = [constants::RECORD_SEED, &seal_hash], bump)]
+    pub record: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Authority-only upgrade of the config written before `allow_royalty_opt_out`,
+/// `collection_count` and `fee_exempt` were added, reallocated in place.
+#[derive(Accounts)]
+pub struct MigrateConfig<'info> {
+    /// CHECK: Legacy ProtocolConfig; owner, size, discriminator and authority checked in the handler
+    #[account(mut, seeds = [constants::CONFIG_SEED], bump)]
+    pub config: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Authority-only upgrade of a collection registered before `reserve_minted`,
+/// `royalty_rule_set` and `creators` were added, reallocated in place. Such a
+/// collection predates `CollectionIndex` too, so it takes the next index slot.
+/// Run `migrate_config` first.
+#[derive(Accounts)]
+pub struct MigrateCollection<'info> {
+    #[account(mut, seeds = [constants::CONFIG_SEED], bump = config.bump, has_one = authority)]
+    pub config: Account<'info, ProtocolConfig>,
+    /// CHECK: Legacy CollectionConfig; owner, size and discriminator checked in the handler
+    #[account(mut)]
+    pub collection: UncheckedAccount<'info>,
+    #[account(init, payer = authority, space = 8 + CollectionIndex::INIT_SPACE,
+              seeds = [constants::COLLECTION_INDEX_SEED, &config.collection_count.to_le_bytes()], bump)]
+    pub collection_index: Account<'info, CollectionIndex>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Authority-only change to one registered collection.
+#[derive(Accounts)]
+pub struct UpdateCollection<'info> {
+    #[account(seeds = [constants::CONFIG_SEED], bump = config.bump, has_one = authority)]
+    pub config: Account<'info, ProtocolConfig>,
+    #[account(mut, seeds = [constants::COLLECTION_SEED, &collection.source_chain.to_le_bytes(),
+              &collection.source_contract], bump = collection.bump)]
+    pub collection: Account<'info, CollectionConfig>,
+    pub authority: Signer<'info>,
+}
+
 #[derive(Accounts)]
 pub struct AdminOnly<'info> {
     #[account(seeds = [constants::CONFIG_SEED], bump = config.bump, has_one = authority)]
@@ -146,6 +330,13 @@ pub struct ProtocolConfig {
     pub guild_share_bps: u16,
     pub mint_fee: u64,
     pub paused: bool,
+    /// Admin override allowing mint_reborn to skip the Royalties plugin even when mint_fee > 0
+    pub allow_royalty_opt_out: bool,
+    /// Number of registered collections; `CollectionIndex` PDAs cover 0..collection_count
+    pub collection_count: u64,
+    /// Wallets that mint without paying `mint_fee`, as recipient or payer
+    #[max_len(16)]
+    pub fee_exempt: Vec<Pubkey>,
     pub bump: u8,
 }
 
@@ -159,7 +350,71 @@ pub struct CollectionConfig {
     pub name: String,
     pub max_supply: u64,
     pub total_minted: u64,
+    /// Admin reserve mints; not counted against `max_supply`
+    pub reserve_minted: u64,
     pub active: bool,
+    /// Marketplace rule set applied to the Royalties plugin of every mint
+    pub royalty_rule_set: RoyaltyRuleSet,
+    /// Royalty creators (e.g. guild, team and the original artist); empty = the
+    /// protocol's guild/team split by `guild_share_bps`
+    #[max_len(5)]
+    pub creators: Vec<CollectionCreator>,
+    pub bump: u8,
+}
+
+/// One entry of a collection's royalty creator list, mapped to MPL Core's `Creator`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq, InitSpace)]
+pub struct CollectionCreator {
+    pub address: Pubkey,
+    /// Share of the royalties, 1-100; a collection's shares sum to 100
+    pub percentage: u8,
+}
+
+/// Royalty enforcement for a collection, stored on `CollectionConfig` and mapped to
+/// MPL Core's `RuleSet` at mint time.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq, InitSpace)]
+pub enum RoyaltyRuleSet {
+    /// Royalties are advisory; any program may transfer
+    None,
+    /// Only these programs may transfer the asset
+    ProgramAllowList {
+        #[max_len(8)]
+        programs: Vec<Pubkey>,
+    },
+    /// These programs may not transfer the asset
+    ProgramDenyList {
+        #[max_len(8)]
+        programs: Vec<Pubkey>,
+    },
+}
+
+impl RoyaltyRuleSet {
+    fn validate(&self) -> Result<()> {
+        match self {
+            RoyaltyRuleSet::None => Ok(()),
+            RoyaltyRuleSet::ProgramAllowList { programs } | RoyaltyRuleSet::ProgramDenyList { programs } => {
+                require!(programs.len() <= constants::MAX_RULE_SET_PROGRAMS, ErrorCode::RuleSetTooLarge);
+                Ok(())
+            }
+        }
+    }
+
+    fn to_mpl(&self) -> RuleSet {
+        match self {
+            RoyaltyRuleSet::None => RuleSet::None,
+            RoyaltyRuleSet::ProgramAllowList { programs } => RuleSet::ProgramAllowList(programs.clone()),
+            RoyaltyRuleSet::ProgramDenyList { programs } => RuleSet::ProgramDenyList(programs.clone()),
+        }
+    }
+}
+
+/// Per-index pointer to a registered `CollectionConfig`, so a UI can enumerate
+/// collections by walking indices 0..config.collection_count.
+#[account]
+#[derive(InitSpace)]
+pub struct CollectionIndex {
+    pub index: u64,
+    pub collection: Pubkey,
     pub bump: u8,
 }
 
@@ -177,9 +432,75 @@ pub struct ReincarnationRecord {
     pub mint: Pubkey,
     pub minted: bool,
     pub verified_at: i64,
+    /// Account that paid the record's rent; the only one allowed to cancel it
+    pub payer: Pubkey,
+    /// `config.mint_fee` at verify_seal time; mint_reborn charges exactly this
+    pub quoted_fee: u64,
+    pub bump: u8,
+}
+
+/// Left by cancel_seal at ["cancelled_seal", seal_hash] once the record is closed;
+/// verify_seal refuses any seal that has one.
+#[account]
+#[derive(InitSpace)]
+pub struct CancelledSeal {
+    pub bump: u8,
+}
+
+/// ReincarnationRecord layout before `payer` and `quoted_fee`.
+/// Only read by `migrate_record`.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace)]
+pub struct LegacyReincarnationRecord {
+    pub seal_hash: [u8; 32],
+    pub source_chain: u16,
+    #[max_len(64)]
+    pub source_contract: Vec<u8>,
+    #[max_len(64)]
+    pub token_id: Vec<u8>,
+    pub attestation_pubkey: [u8; 32],
+    pub recipient: Pubkey,
+    pub mint: Pubkey,
+    pub minted: bool,
+    pub verified_at: i64,
+    pub bump: u8,
+}
+
+/// ProtocolConfig layout before `allow_royalty_opt_out`, `collection_count` and
+/// `fee_exempt`. Only read by `migrate_config`.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace)]
+pub struct LegacyProtocolConfig {
+    pub authority: Pubkey,
+    pub guild_treasury: Pubkey,
+    pub team_treasury: Pubkey,
+    pub guild_share_bps: u16,
+    pub mint_fee: u64,
+    pub paused: bool,
+    pub bump: u8,
+}
+
+/// CollectionConfig layout before `reserve_minted`, `royalty_rule_set` and `creators`.
+/// Only read by `migrate_collection`.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace)]
+pub struct LegacyCollectionConfig {
+    pub source_chain: u16,
+    #[max_len(64)]
+    pub source_contract: Vec<u8>,
+    #[max_len(32)]
+    pub name: String,
+    pub max_supply: u64,
+    pub total_minted: u64,
+    pub active: bool,
     pub bump: u8,
 }
 
+// ============ Events ============
+
+#[event]
+pub struct OnchainCollectionCreated {
+    pub collection: Pubkey,
+    pub name: String,
+}
+
 // ============ Program ============
 
 #[program]
@@ -193,7 +514,7 @@ pub mod ika_tensei {
         guild_share_bps: u16,
         mint_fee: u64,
     ) -> Result<()> {
-        require!(guild_share_bps <= 10000, ErrorCode::InvalidBps);
+        creator_percentages(guild_share_bps)?;
         let config = &mut ctx.accounts.config;
         config.authority = ctx.accounts.authority.key();
         config.guild_treasury = guild_treasury;
@@ -201,6 +522,9 @@ pub mod ika_tensei {
         config.guild_share_bps = guild_share_bps;
         config.mint_fee = mint_fee;
         config.paused = false;
+        config.allow_royalty_opt_out = false;
+        config.collection_count = 0;
+        config.fee_exempt = Vec::new();
         config.bump = ctx.bumps.config;
         msg!("Ika Tensei v3 initialized");
         Ok(())
@@ -212,18 +536,31 @@ pub mod ika_tensei {
         source_contract: Vec<u8>,
         name: String,
         max_supply: u64,
+        royalty_rule_set: Option<RoyaltyRuleSet>,
     ) -> Result<()> {
         require!(name.len() <= constants::MAX_NAME_LENGTH, ErrorCode::NameTooLong);
         require!(source_contract.len() <= constants::MAX_CONTRACT_LENGTH, ErrorCode::ContractAddressTooLong);
+        let royalty_rule_set = royalty_rule_set.unwrap_or(RoyaltyRuleSet::None);
+        royalty_rule_set.validate()?;
         let coll = &mut ctx.accounts.collection;
         coll.source_chain = source_chain;
         coll.source_contract = source_contract;
         coll.name = name;
         coll.max_supply = max_supply;
         coll.total_minted = 0;
+        coll.reserve_minted = 0;
         coll.active = true;
+        coll.royalty_rule_set = royalty_rule_set;
+        coll.creators = Vec::new();
         coll.bump = ctx.bumps.collection;
-        msg!("Collection registered");
+
+        let config = &mut ctx.accounts.config;
+        let entry = &mut ctx.accounts.collection_index;
+        entry.index = config.collection_count;
+        entry.collection = ctx.accounts.collection.key();
+        entry.bump = ctx.bumps.collection_index;
+        config.collection_count = config.collection_count.checked_add(1).unwrap();
+        msg!("Collection registered at index {}", entry.index);
         Ok(())
     }
 
@@ -245,7 +582,16 @@ pub mod ika_tensei {
             require!(coll.total_minted < coll.max_supply, ErrorCode::SupplyExhausted);
         }
         require!(source_contract.len() <= constants::MAX_CONTRACT_LENGTH, ErrorCode::ContractAddressTooLong);
+        // The collection PDA is derived from its own source_contract, so tie the record to
+        // it: mint_reborn looks the collection up again from record.source_contract
+        require!(source_contract == coll.source_contract, ErrorCode::InvalidSealData);
         require!(token_id.len() <= constants::MAX_TOKEN_ID_LENGTH, ErrorCode::TokenIdTooLong);
+        // mint_reborn can only mint to record.recipient, so an unusable key here would
+        // strand the NFT. PDAs are off-curve and have no private key.
+        require!(
+            recipient != Pubkey::default() && recipient.is_on_curve(),
+            ErrorCode::InvalidRecipient
+        );
 
         verify_ed25519_signature(
             &ctx.accounts.instructions_sysvar,
@@ -273,6 +619,8 @@ pub mod ika_tensei {
         record.mint = Pubkey::default();
         record.minted = false;
         record.verified_at = Clock::get()?.unix_timestamp;
+        record.payer = ctx.accounts.payer.key();
+        record.quoted_fee = mint_fee;
         record.bump = ctx.bumps.record;
 
         coll.total_minted = coll.total_minted.checked_add(1).unwrap();
@@ -282,26 +630,37 @@ pub mod ika_tensei {
 
     /// Mint a Reborn NFT via Metaplex Core.
     /// Creates a Core Asset with:
-    ///   - Royalties plugin: 690 bps (500 guild + 190 team)
+    ///   - Royalties plugin: 690 bps (500 guild + 190 team), enforced by the
+    ///     collection's `royalty_rule_set`
     ///   - ImmutableMetadata plugin: locks name/uri permanently
     ///   - Owner set to the `recipient` from the ReincarnationRecord
     ///   - Update authority = mint_authority PDA (program-controlled)
+    ///
+    /// `plugins_mask` selects which plugins are attached (bit 0 = royalties,
+    /// bit 1 = immutable metadata); `None` attaches both. Skipping royalties is
+    /// only allowed when `mint_fee == 0` or the admin set `allow_royalty_opt_out`.
     pub fn mint_reborn(
         ctx: Context<MintReborn>,
         seal_hash: [u8; 32],
         name: String,
         uri: String,
+        plugins_mask: Option<u8>,
     ) -> Result<()> {
-        // M8: Fee enforcement
+        // M8: Fee enforcement. Charge the fee quoted at verify_seal, so an admin
+        // update_config between the two steps can't change what the user pays.
         let config = &ctx.accounts.config;
-        let mint_fee = config.mint_fee;
+        let mint_fee = ctx.accounts.record.quoted_fee;
         
         // Verify fee_recipient is not zero address
         let fee_recipient_key = ctx.accounts.fee_recipient.key();
         require!(fee_recipient_key != Pubkey::default(), ErrorCode::InvalidFeeRecipient);
         
-        // Transfer fee from payer to fee_recipient
-        if mint_fee > 0 {
+        // Transfer fee from payer to fee_recipient, unless the recipient or payer is exempt
+        let fee_exempt = config.fee_exempt.contains(&ctx.accounts.recipient.key())
+            || config.fee_exempt.contains(&ctx.accounts.payer.key());
+        if fee_exempt {
+            msg!("Fee waived: exempt wallet");
+        } else if mint_fee > 0 {
             let fee_transfer = anchor_lang::system_program::Transfer {
                 from: ctx.accounts.payer.to_account_info(),
                 to: ctx.accounts.fee_recipient.to_account_info(),
@@ -318,6 +677,15 @@ pub mod ika_tensei {
         require!(!ctx.accounts.config.paused, ErrorCode::Paused);
         require!(!ctx.accounts.record.minted, ErrorCode::AlreadyMinted);
 
+        let plugins_mask = plugins_mask.unwrap_or(constants::PLUGINS_ALL);
+        require!(plugins_mask & !constants::PLUGINS_ALL == 0, ErrorCode::InvalidPluginsMask);
+        if plugins_mask & constants::PLUGIN_ROYALTIES == 0 {
+            require!(
+                mint_fee == 0 || config.allow_royalty_opt_out,
+                ErrorCode::RoyaltiesRequired
+            );
+        }
+
         let mint_authority_bump = ctx.bumps.mint_authority;
         let mint_authority_seeds: &[&[u8]] = &[
             constants::MINT_SEED,
@@ -325,43 +693,19 @@ pub mod ika_tensei {
             &[mint_authority_bump],
         ];
 
-        // Build royalty plugins: 500 bps guild + 190 bps team = 690 bps total
-        let plugins = vec![
-            PluginAuthorityPair {
-                plugin: Plugin::Royalties(Royalties {
-                    basis_points: constants::GUILD_ROYALTY_BPS + constants::TEAM_ROYALTY_BPS,
-                    creators: vec![
-                        Creator { address: config.guild_treasury, percentage: 72 }, // ~72% of royalties
-                        Creator { address: config.team_treasury, percentage: 28 },  // ~28% of royalties
-                    ],
-                    rule_set: RuleSet::None,
-                }),
-                authority: Some(PluginAuthority::UpdateAuthority),
-            },
-            PluginAuthorityPair {
-                plugin: Plugin::ImmutableMetadata(ImmutableMetadata {}),
-                authority: Some(PluginAuthority::None),
-            },
-        ];
-
-        // CPI to Metaplex Core: CreateV2
-        // - asset: new unique keypair (signer in outer tx, propagated through CPI)
-        // - authority: mint_authority PDA (signs via invoke_signed)
-        // - owner: recipient (gets the NFT directly, no transfer needed)
-        // - update_authority: mint_authority PDA (program controls updates)
-        CreateV2CpiBuilder::new(&ctx.accounts.mpl_core_program)
-            .asset(&ctx.accounts.asset)
-            .authority(Some(&ctx.accounts.mint_authority))
-            .payer(&ctx.accounts.payer)
-            .owner(Some(&ctx.accounts.recipient))
-            .update_authority(Some(&ctx.accounts.mint_authority))
-            .system_program(&ctx.accounts.system_program)
-            .data_state(DataState::AccountState)
-            .name(name)
-            .uri(uri)
-            .plugins(plugins)
-            .invoke_signed(&[mint_authority_seeds])
-            .map_err(|e| ProgramError::from(e))?;
+        let plugins = build_reborn_plugins(config, &ctx.accounts.collection, plugins_mask)?;
+        create_reborn_asset(
+            &ctx.accounts.mpl_core_program,
+            &ctx.accounts.asset,
+            &ctx.accounts.mint_authority,
+            &ctx.accounts.payer,
+            &ctx.accounts.recipient,
+            &ctx.accounts.system_program,
+            name,
+            uri,
+            plugins,
+            mint_authority_seeds,
+        )?;
 
         // Record the mint
         let record = &mut ctx.accounts.record;
@@ -372,13 +716,58 @@ pub mod ika_tensei {
         Ok(())
     }
 
+    /// Admin-only reserve mint. Uses the same Core CPI and plugins as `mint_reborn`
+    /// (all plugins, collection rule set) but consumes no seal, charges no fee and
+    /// ignores `max_supply`. The asset's update authority is the collection's
+    /// reserve mint authority PDA.
+    pub fn mint_reserve(ctx: Context<MintReserve>, name: String, uri: String) -> Result<()> {
+        require!(name.len() <= constants::MAX_NAME_LENGTH, ErrorCode::NameTooLong);
+        require!(uri.len() <= constants::MAX_URI_LENGTH, ErrorCode::UriTooLong);
+        require!(!ctx.accounts.config.paused, ErrorCode::Paused);
+        require!(ctx.accounts.collection.active, ErrorCode::CollectionNotActive);
+
+        let collection_key = ctx.accounts.collection.key();
+        let mint_authority_bump = ctx.bumps.mint_authority;
+        let mint_authority_seeds: &[&[u8]] = &[
+            constants::RESERVE_MINT_SEED,
+            collection_key.as_ref(),
+            &[mint_authority_bump],
+        ];
+
+        let plugins = build_reborn_plugins(&ctx.accounts.config, &ctx.accounts.collection, constants::PLUGINS_ALL)?;
+        create_reborn_asset(
+            &ctx.accounts.mpl_core_program,
+            &ctx.accounts.asset,
+            &ctx.accounts.mint_authority,
+            &ctx.accounts.authority,
+            &ctx.accounts.recipient,
+            &ctx.accounts.system_program,
+            name,
+            uri,
+            plugins,
+            mint_authority_seeds,
+        )?;
+
+        let coll = &mut ctx.accounts.collection;
+        coll.reserve_minted = coll.reserve_minted.checked_add(1).unwrap();
+        msg!(
+            "Reserve minted: asset={} recipient={} reserve_minted={}",
+            ctx.accounts.asset.key(),
+            ctx.accounts.recipient.key(),
+            coll.reserve_minted
+        );
+        Ok(())
+    }
+
     /// Create a Metaplex Core Collection PDA for grouping Reborn NFTs.
     /// The collection address is a PDA of this program and can sign via invoke_signed.
+    /// Emits `OnchainCollectionCreated` and returns the collection address (Anchor
+    /// writes it with set_return_data).
     pub fn create_onchain_collection(
         ctx: Context<CreateOnchainCollection>,
         name: String,
         uri: String,
-    ) -> Result<()> {
+    ) -> Result<Pubkey> {
         require!(name.len() <= constants::MAX_NAME_LENGTH, ErrorCode::NameTooLong);
         require!(uri.len() <= constants::MAX_URI_LENGTH, ErrorCode::UriTooLong);
 
@@ -397,12 +786,164 @@ pub mod ika_tensei {
             .update_authority(Some(&ctx.accounts.authority))
             .payer(&ctx.accounts.authority)
             .system_program(&ctx.accounts.system_program)
-            .name(name)
+            .name(name.clone())
             .uri(uri)
             .invoke_signed(&[collection_seeds])
             .map_err(|e| ProgramError::from(e))?;
 
-        msg!("Onchain collection created: {}", ctx.accounts.collection.key());
+        let collection = ctx.accounts.collection.key();
+        emit!(OnchainCollectionCreated { collection, name });
+        msg!("Onchain collection created: {}", collection);
+        Ok(collection)
+    }
+
+    /// Rotate the onchain collection's update authority via Metaplex Core
+    /// UpdateCollectionV1. After this the protocol authority can no longer update
+    /// the collection; only `new_authority` can.
+    pub fn transfer_onchain_collection_authority(
+        ctx: Context<TransferOnchainCollectionAuthority>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        require_keys_eq!(ctx.accounts.new_authority.key(), new_authority, ErrorCode::Unauthorized);
+        require!(new_authority != ctx.accounts.authority.key(), ErrorCode::SameAuthority);
+
+        UpdateCollectionV1CpiBuilder::new(&ctx.accounts.mpl_core_program)
+            .collection(&ctx.accounts.collection)
+            .payer(&ctx.accounts.authority)
+            .authority(Some(&ctx.accounts.authority))
+            .new_update_authority(Some(&ctx.accounts.new_authority))
+            .system_program(&ctx.accounts.system_program)
+            .invoke()
+            .map_err(|e| ProgramError::from(e))?;
+
+        msg!("Onchain collection {} update authority -> {}", ctx.accounts.collection.key(), new_authority);
+        Ok(())
+    }
+
+    /// Cancel a seal that was verified but never minted.
+    /// Only the original payer may cancel, and only after SEAL_CANCEL_TIMEOUT_SECS.
+    /// The collection supply slot is freed and the record's rent refunded to the
+    /// payer; the `CancelledSeal` tombstone keeps the seal from being verified again.
+    pub fn cancel_seal(ctx: Context<CancelSeal>, seal_hash: [u8; 32]) -> Result<()> {
+        let record = &ctx.accounts.record;
+        require!(!record.minted, ErrorCode::AlreadyMinted);
+
+        let now = Clock::get()?.unix_timestamp;
+        let cancellable_at = record
+            .verified_at
+            .checked_add(constants::SEAL_CANCEL_TIMEOUT_SECS)
+            .unwrap();
+        require!(now >= cancellable_at, ErrorCode::CancelTooEarly);
+        ctx.accounts.cancelled_seal.bump = ctx.bumps.cancelled_seal;
+
+        let coll = &mut ctx.accounts.collection;
+        coll.total_minted = coll.total_minted.checked_sub(1).unwrap();
+
+        msg!("Seal cancelled: {}", hex::encode(seal_hash));
+        Ok(())
+    }
+
+    /// Upgrade a legacy ReincarnationRecord in place. Its original payer is not
+    /// known, so the authority becomes the record's payer (and only canceller),
+    /// and the current `mint_fee` becomes its quoted fee.
+    pub fn migrate_record(ctx: Context<MigrateRecord>, seal_hash: [u8; 32]) -> Result<()> {
+        let info = ctx.accounts.record.to_account_info();
+        require!(info.owner == &crate::ID, ErrorCode::RecordNotLegacy);
+        require!(
+            info.data_len() == 8 + LegacyReincarnationRecord::INIT_SPACE,
+            ErrorCode::RecordNotLegacy
+        );
+        let legacy = {
+            let data = info.try_borrow_data()?;
+            require!(data[..8] == ReincarnationRecord::DISCRIMINATOR, ErrorCode::RecordNotLegacy);
+            LegacyReincarnationRecord::deserialize(&mut &data[8..])?
+        };
+        require!(legacy.seal_hash == seal_hash, ErrorCode::InvalidSealData);
+
+        let new_len = 8 + ReincarnationRecord::INIT_SPACE;
+        grow_account(&info, new_len, &ctx.accounts.authority, &ctx.accounts.system_program)?;
+
+        let config = &ctx.accounts.config;
+        let record = ReincarnationRecord {
+            seal_hash: legacy.seal_hash,
+            source_chain: legacy.source_chain,
+            source_contract: legacy.source_contract,
+            token_id: legacy.token_id,
+            attestation_pubkey: legacy.attestation_pubkey,
+            recipient: legacy.recipient,
+            mint: legacy.mint,
+            minted: legacy.minted,
+            verified_at: legacy.verified_at,
+            payer: config.authority,
+            quoted_fee: config.mint_fee,
+            bump: legacy.bump,
+        };
+        let mut data = info.try_borrow_mut_data()?;
+        record.try_serialize(&mut &mut data[..])?;
+
+        msg!("Record migrated: {}", hex::encode(seal_hash));
+        Ok(())
+    }
+
+    /// Upgrade the config from its first deployed layout (authority only). Until this
+    /// runs every instruction that loads the config fails. The royalty opt-out starts
+    /// off, the fee-exempt list empty, and `collection_count` at 0: follow up with
+    /// `migrate_collection` for each registered collection to index it.
+    pub fn migrate_config(ctx: Context<MigrateConfig>) -> Result<()> {
+        let info = ctx.accounts.config.to_account_info();
+        let legacy: LegacyProtocolConfig =
+            read_legacy_account(&info, LegacyProtocolConfig::INIT_SPACE, &ProtocolConfig::DISCRIMINATOR)?;
+        require_keys_eq!(legacy.authority, ctx.accounts.authority.key(), ErrorCode::Unauthorized);
+        grow_account(&info, 8 + ProtocolConfig::INIT_SPACE, &ctx.accounts.authority, &ctx.accounts.system_program)?;
+
+        let config = ProtocolConfig {
+            authority: legacy.authority,
+            guild_treasury: legacy.guild_treasury,
+            team_treasury: legacy.team_treasury,
+            guild_share_bps: legacy.guild_share_bps,
+            mint_fee: legacy.mint_fee,
+            paused: legacy.paused,
+            allow_royalty_opt_out: false,
+            collection_count: 0,
+            fee_exempt: Vec::new(),
+            bump: legacy.bump,
+        };
+        let mut data = info.try_borrow_mut_data()?;
+        config.try_serialize(&mut &mut data[..])?;
+        msg!("Config migrated");
+        Ok(())
+    }
+
+    /// Upgrade one collection from its first deployed layout and index it (authority
+    /// only). The collection keeps its supply counters; `reserve_minted` starts at 0,
+    /// with no royalty rule set and the protocol's default creators.
+    pub fn migrate_collection(ctx: Context<MigrateCollection>) -> Result<()> {
+        let info = ctx.accounts.collection.to_account_info();
+        let legacy: LegacyCollectionConfig =
+            read_legacy_account(&info, LegacyCollectionConfig::INIT_SPACE, &CollectionConfig::DISCRIMINATOR)?;
+        grow_account(&info, 8 + CollectionConfig::INIT_SPACE, &ctx.accounts.authority, &ctx.accounts.system_program)?;
+
+        let collection = CollectionConfig {
+            source_chain: legacy.source_chain,
+            source_contract: legacy.source_contract,
+            name: legacy.name,
+            max_supply: legacy.max_supply,
+            total_minted: legacy.total_minted,
+            reserve_minted: 0,
+            active: legacy.active,
+            royalty_rule_set: RoyaltyRuleSet::None,
+            creators: Vec::new(),
+            bump: legacy.bump,
+        };
+        collection.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+
+        let config = &mut ctx.accounts.config;
+        let entry = &mut ctx.accounts.collection_index;
+        entry.index = config.collection_count;
+        entry.collection = info.key();
+        entry.bump = ctx.bumps.collection_index;
+        config.collection_count = config.collection_count.checked_add(1).unwrap();
+        msg!("Collection migrated and indexed at {}", entry.index);
         Ok(())
     }
 
@@ -429,7 +970,7 @@ pub mod ika_tensei {
         if let Some(v) = guild_treasury { config.guild_treasury = v; }
         if let Some(v) = team_treasury { config.team_treasury = v; }
         if let Some(v) = guild_share_bps {
-            require!(v <= 10000, ErrorCode::InvalidBps);
+            creator_percentages(v)?;
             config.guild_share_bps = v;
         }
         if let Some(v) = mint_fee { config.mint_fee = v; }
@@ -437,6 +978,42 @@ pub mod ika_tensei {
         Ok(())
     }
 
+    /// Allow (or disallow) mint_reborn callers to skip the Royalties plugin while mint_fee > 0.
+    pub fn set_royalty_opt_out(ctx: Context<AdminOnly>, allowed: bool) -> Result<()> {
+        ctx.accounts.config.allow_royalty_opt_out = allowed;
+        msg!("Royalty opt-out allowed: {}", allowed);
+        Ok(())
+    }
+
+    /// Waive mint_fee for `wallet` when it is the mint_reborn recipient or payer.
+    /// Adding a wallet that is already exempt is a no-op.
+    pub fn add_fee_exempt(ctx: Context<AdminOnly>, wallet: Pubkey) -> Result<()> {
+        let fee_exempt = &mut ctx.accounts.config.fee_exempt;
+        if !fee_exempt.contains(&wallet) {
+            require!(fee_exempt.len() < constants::MAX_FEE_EXEMPT, ErrorCode::FeeExemptListFull);
+            fee_exempt.push(wallet);
+        }
+        msg!("Fee exempt added: {}", wallet);
+        Ok(())
+    }
+
+    /// Replace a collection's royalty creators. Shares must be 1-100 each and sum to
+    /// 100; an empty list restores the guild/team split. Applies to later mints only.
+    pub fn set_collection_creators(ctx: Context<UpdateCollection>, creators: Vec<CollectionCreator>) -> Result<()> {
+        if !creators.is_empty() {
+            validate_creators(&creators)?;
+        }
+        msg!("Collection creators: {}", creators.len());
+        ctx.accounts.collection.creators = creators;
+        Ok(())
+    }
+
+    pub fn remove_fee_exempt(ctx: Context<AdminOnly>, wallet: Pubkey) -> Result<()> {
+        ctx.accounts.config.fee_exempt.retain(|w| *w != wallet);
+        msg!("Fee exempt removed: {}", wallet);
+        Ok(())
+    }
+
     pub fn transfer_authority(ctx: Context<AdminOnly>, new_authority: Pubkey) -> Result<()> {
         require!(new_authority != ctx.accounts.config.authority, ErrorCode::SameAuthority);
         ctx.accounts.config.authority = new_authority;
@@ -448,6 +1025,137 @@ pub mod ika_tensei {
 
 // ============ Helpers ============
 
+/// Decode an account still in a legacy layout: owned by this program, exactly
+/// `8 + legacy_space` bytes, and carrying the current type's `discriminator`.
+fn read_legacy_account<T: AnchorDeserialize>(info: &AccountInfo, legacy_space: usize, discriminator: &[u8]) -> Result<T> {
+    require!(info.owner == &crate::ID, ErrorCode::AccountNotLegacy);
+    let data = info.try_borrow_data()?;
+    require!(
+        data.len() == 8 + legacy_space && data[..8] == *discriminator,
+        ErrorCode::AccountNotLegacy
+    );
+    Ok(T::deserialize(&mut &data[8..])?)
+}
+
+/// Realloc a migrated account to `new_len`, `payer` first topping up its rent.
+fn grow_account<'info>(
+    info: &AccountInfo<'info>,
+    new_len: usize,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    let shortfall = Rent::get()?.minimum_balance(new_len).saturating_sub(info.lamports());
+    if shortfall > 0 {
+        let top_up = anchor_lang::system_program::Transfer { from: payer.to_account_info(), to: info.clone() };
+        anchor_lang::system_program::transfer(CpiContext::new(system_program.to_account_info(), top_up), shortfall)?;
+    }
+    info.realloc(new_len, true)?;
+    Ok(())
+}
+
+/// Guild/team royalty creator percentages (0-100) from `guild_share_bps`. Creator
+/// shares are whole percents, so a share that isn't one would leave them short of 100.
+fn creator_percentages(guild_share_bps: u16) -> Result<(u8, u8)> {
+    require!(guild_share_bps <= 10000, ErrorCode::InvalidBps);
+    let guild = (guild_share_bps / 100) as u8;
+    let team = ((10000 - guild_share_bps) / 100) as u8;
+    require!(guild as u16 + team as u16 == 100, ErrorCode::InvalidCreatorSplit);
+    Ok((guild, team))
+}
+
+/// A collection's own creator list: 1-`MAX_CREATORS` entries, each 1-100, summing to 100.
+fn validate_creators(creators: &[CollectionCreator]) -> Result<()> {
+    require!(
+        !creators.is_empty() && creators.len() <= constants::MAX_CREATORS,
+        ErrorCode::TooManyCreators
+    );
+    require!(creators.iter().all(|c| c.percentage > 0), ErrorCode::InvalidCreatorSplit);
+    let total: u16 = creators.iter().map(|c| c.percentage as u16).sum();
+    require!(total == 100, ErrorCode::InvalidCreatorSplit);
+    Ok(())
+}
+
+/// Royalty creators for a mint from `collection`: its own list if set, otherwise the
+/// guild/team treasuries split by guild_share_bps (zero-share creators are left out).
+fn royalty_creators(config: &ProtocolConfig, collection: &CollectionConfig) -> Result<Vec<Creator>> {
+    if !collection.creators.is_empty() {
+        validate_creators(&collection.creators)?;
+        return Ok(collection
+            .creators
+            .iter()
+            .map(|c| Creator { address: c.address, percentage: c.percentage })
+            .collect());
+    }
+    let (guild_pct, team_pct) = creator_percentages(config.guild_share_bps)?;
+    Ok([(config.guild_treasury, guild_pct), (config.team_treasury, team_pct)]
+        .into_iter()
+        .filter(|(_, percentage)| *percentage > 0)
+        .map(|(address, percentage)| Creator { address, percentage })
+        .collect())
+}
+
+/// Plugins selected by `plugins_mask` for a Reborn asset.
+fn build_reborn_plugins(
+    config: &ProtocolConfig,
+    collection: &CollectionConfig,
+    plugins_mask: u8,
+) -> Result<Vec<PluginAuthorityPair>> {
+    let mut plugins = Vec::with_capacity(2);
+    if plugins_mask & constants::PLUGIN_ROYALTIES != 0 {
+        // Royalties: 500 bps guild + 190 bps team = 690 bps total
+        plugins.push(PluginAuthorityPair {
+            plugin: Plugin::Royalties(Royalties {
+                basis_points: constants::GUILD_ROYALTY_BPS + constants::TEAM_ROYALTY_BPS,
+                creators: royalty_creators(config, collection)?,
+                rule_set: collection.royalty_rule_set.to_mpl(),
+            }),
+            authority: Some(PluginAuthority::UpdateAuthority),
+        });
+    }
+    if plugins_mask & constants::PLUGIN_IMMUTABLE_METADATA != 0 {
+        plugins.push(PluginAuthorityPair {
+            plugin: Plugin::ImmutableMetadata(ImmutableMetadata {}),
+            authority: Some(PluginAuthority::None),
+        });
+    }
+    Ok(plugins)
+}
+
+/// CPI to Metaplex Core: CreateV2
+/// - asset: new unique keypair (signer in outer tx, propagated through CPI)
+/// - authority: program PDA (signs via invoke_signed with `authority_seeds`)
+/// - owner: recipient (gets the NFT directly, no transfer needed)
+/// - update_authority: the same PDA (program controls updates)
+#[allow(clippy::too_many_arguments)]
+fn create_reborn_asset<'info>(
+    mpl_core_program: &AccountInfo<'info>,
+    asset: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    owner: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    name: String,
+    uri: String,
+    plugins: Vec<PluginAuthorityPair>,
+    authority_seeds: &[&[u8]],
+) -> Result<()> {
+    CreateV2CpiBuilder::new(mpl_core_program)
+        .asset(asset)
+        .authority(Some(authority))
+        .payer(payer)
+        .owner(Some(owner))
+        .update_authority(Some(authority))
+        .system_program(system_program)
+        .data_state(DataState::AccountState)
+        .name(name)
+        .uri(uri)
+        .plugins(plugins)
+        .invoke_signed(&[authority_seeds])
+        .map_err(|e| ProgramError::from(e))?;
+    Ok(())
+}
+
+
 fn verify_ed25519_signature(
     instructions_sysvar: &AccountInfo,
     expected_signer: &Pubkey,
@@ -528,4 +1236,26 @@ pub enum ErrorCode {
     SameAuthority,
     #[msg("NFT not minted yet")]
     NotMinted,
+    #[msg("Seal cannot be cancelled yet")]
+    CancelTooEarly,
+    #[msg("Invalid plugins mask")]
+    InvalidPluginsMask,
+    #[msg("Royalties plugin required while a mint fee is charged")]
+    RoyaltiesRequired,
+    #[msg("Royalty rule set lists too many programs")]
+    RuleSetTooLarge,
+    #[msg("Creator percentages must sum to 100")]
+    InvalidCreatorSplit,
+    #[msg("Recipient must be a non-zero, on-curve wallet address")]
+    InvalidRecipient,
+    #[msg("Fee exempt list is full")]
+    FeeExemptListFull,
+    #[msg("Collection needs 1-5 royalty creators")]
+    TooManyCreators,
+    #[msg("Seal was cancelled")]
+    SealCancelled,
+    #[msg("Record is not in the legacy layout")]
+    RecordNotLegacy,
+    #[msg("Account is not in the legacy layout")]
+    AccountNotLegacy,
 }
@@ -76,11 +76,19 @@ try {
   name.copy(regData, off); off += name.length;
   // max_supply u64
   regData.writeBigUInt64LE(0n, off); off += 8; // 0 = unlimited
+  regData.writeUInt8(0, off); off += 1; // royalty_rule_set: None
+
+  // The collection takes the next CollectionIndex slot (config.collection_count)
+  const configInfo = await connection.getAccountInfo(configPda);
+  const indexBuf = Buffer.alloc(8);
+  indexBuf.writeBigUInt64LE(configInfo.data.readBigUInt64LE(8 + 32 * 3 + 2 + 8 + 1 + 1));
+  const [collectionIndexPda] = findPda([Buffer.from("collection_index"), indexBuf]);
 
   const regIx = new TransactionInstruction({
     keys: [
-      { pubkey: configPda, isSigner: false, isWritable: false },
+      { pubkey: configPda, isSigner: false, isWritable: true },
       { pubkey: collectionPda, isSigner: false, isWritable: true },
+      { pubkey: collectionIndexPda, isSigner: false, isWritable: true },
       { pubkey: wallet.publicKey, isSigner: true, isWritable: true },
       { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
     ],
@@ -125,6 +133,7 @@ console.log(`✍️  Signature: ${Buffer.from(signature).toString("hex").substri
 
 // Step 5: Build verify_seal transaction with Ed25519 precompile
 const [recordPda] = findPda([Buffer.from("reincarnation"), sealHash]);
+const [cancelledSealPda] = findPda([Buffer.from("cancelled_seal"), sealHash]);
 const recipient = Keypair.generate().publicKey;
 
 // Ed25519 precompile instruction
@@ -157,6 +166,7 @@ const verifySealIx = new TransactionInstruction({
   keys: [
     { pubkey: configPda, isSigner: false, isWritable: false },
     { pubkey: collectionPda, isSigner: false, isWritable: true },
+    { pubkey: cancelledSealPda, isSigner: false, isWritable: false },
     { pubkey: recordPda, isSigner: false, isWritable: true },
     { pubkey: wallet.publicKey, isSigner: true, isWritable: true },
     { pubkey: recipient, isSigner: false, isWritable: false },
@@ -49,6 +49,7 @@ const CHAIN_SUI = 2;
 const CHAIN_ETHEREUM = 1;
 const CONFIG_SEED = Buffer.from("ika_config");
 const RECORD_SEED = Buffer.from("reincarnation");
+const CANCELLED_SEAL_SEED = Buffer.from("cancelled_seal");
 const COLLECTION_SEED = Buffer.from("collection");
 
 // Treasury addresses
@@ -96,6 +97,11 @@ function getRecordPda(sealHash) {
   return findPda([RECORD_SEED, sealHash]);
 }
 
+// Tombstone cancel_seal leaves behind; verify_seal requires it to be absent
+function getCancelledSealPda(sealHash) {
+  return findPda([CANCELLED_SEAL_SEED, sealHash]);
+}
+
 // Test data helpers
 function createSealHash(data) {
   return createHash("sha256").update(data).digest();
@@ -148,8 +154,8 @@ function buildRegisterCollectionIx(
 ) {
   const nameBuf = Buffer.from(name);
   
-  // Calculate size: discriminator(8) + source_chain(2) + vec(source_contract) + vec(name) + option(max_supply)
-  const data = Buffer.alloc(8 + 2 + 4 + sourceContract.length + 4 + nameBuf.length + 1 + 8);
+  // Calculate size: discriminator(8) + source_chain(2) + vec(source_contract) + vec(name) + max_supply(8) + option(royalty_rule_set)
+  const data = Buffer.alloc(8 + 2 + 4 + sourceContract.length + 4 + nameBuf.length + 8 + 1);
   let offset = 0;
   
   // discriminator
@@ -166,9 +172,11 @@ function buildRegisterCollectionIx(
   data.writeUInt32LE(nameBuf.length, offset); offset += 4;
   nameBuf.copy(data, offset); offset += nameBuf.length;
   
-  // max_supply: Option<u64> (Some)
-  data.writeUInt8(1, offset); offset += 1;
+  // max_supply: u64
   data.writeBigUInt64LE(BigInt(maxSupply), offset); offset += 8;
+
+  // royalty_rule_set: Option<RoyaltyRuleSet> (None = program default)
+  data.writeUInt8(0, offset); offset += 1;
   
   return data;
 }
@@ -337,11 +345,18 @@ async function testRegisterCollection(configPda) {
     1000 // max supply
   );
   
+  // The collection takes the next CollectionIndex slot (config.collection_count)
+  const configInfo = await conn.getAccountInfo(configPda);
+  const indexBuf = Buffer.alloc(8);
+  indexBuf.writeBigUInt64LE(configInfo.data.readBigUInt64LE(8 + 32 * 3 + 2 + 8 + 1 + 1));
+  const collectionIndexPda = findPda([Buffer.from("collection_index"), indexBuf]);
+
   const ix = new TransactionInstruction({
     programId: PROGRAM_ID,
     keys: [
-      { pubkey: configPda, isSigner: false, isWritable: false },
+      { pubkey: configPda, isSigner: false, isWritable: true },
       { pubkey: collectionPda, isSigner: false, isWritable: true },
+      { pubkey: collectionIndexPda, isSigner: false, isWritable: true },
       { pubkey: wallet.publicKey, isSigner: true, isWritable: true },
       { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
     ],
@@ -411,6 +426,7 @@ async function testVerifySeal(
     keys: [
       { pubkey: configPda, isSigner: false, isWritable: false },
       { pubkey: collectionPda, isSigner: false, isWritable: true },
+      { pubkey: getCancelledSealPda(sealHash), isSigner: false, isWritable: false },
       { pubkey: recordPda, isSigner: false, isWritable: true },
       { pubkey: wallet.publicKey, isSigner: true, isWritable: true },
       { pubkey: wallet.publicKey, isSigner: false, isWritable: false }, // recipient
@@ -512,6 +528,7 @@ async function testAntiReplay(
     keys: [
       { pubkey: configPda, isSigner: false, isWritable: false },
       { pubkey: collectionPda, isSigner: false, isWritable: true },
+      { pubkey: getCancelledSealPda(sealHash), isSigner: false, isWritable: false },
       { pubkey: recordPda, isSigner: false, isWritable: true },
       { pubkey: wallet.publicKey, isSigner: true, isWritable: true },
       { pubkey: wallet.publicKey, isSigner: false, isWritable: false },
@@ -588,6 +605,7 @@ async function testPauseUnpause(configPda) {
     keys: [
       { pubkey: configPda, isSigner: false, isWritable: false },
       { pubkey: testCollectionPda, isSigner: false, isWritable: true },
+      { pubkey: getCancelledSealPda(testSealHash), isSigner: false, isWritable: false },
       { pubkey: testRecordPda, isSigner: false, isWritable: true },
       { pubkey: wallet.publicKey, isSigner: true, isWritable: true },
       { pubkey: wallet.publicKey, isSigner: false, isWritable: false },
@@ -7,7 +7,8 @@
 //     - Validate emitter address against known SealInitiator contracts
 //     - Decode payload (nft_contract, token_id, deposit_address, receiver, token_uri)
 //     - Validate dWallet against registry + check it hasn't been used before
-//     - Construct signing message: sha256(token_uri || token_id || receiver)
+//     - Construct signing message: H(token_uri || token_id || receiver || wormhole_sequence
+//       || source_sequence || source_block_hint || valid_until_slot || hash_algo)
 //     - Store pending seal, emit SealPending
 //
 //   Phase 1.5: request_sign_seal (called by relayer)
@@ -63,6 +64,7 @@ module ikatensei::orchestrator {
     const E_NFT_ALREADY_SEALED: u64 = 13;
     const E_INVALID_DEPOSIT_ADDRESS: u64 = 14;
     const E_MINTING_PUBKEY_NOT_SET: u64 = 15;
+    const E_UNSUPPORTED_DEST_CHAIN: u64 = 16;
 
     // ==================================================================
     // Events
@@ -82,6 +84,12 @@ module ikatensei::orchestrator {
         signature: vector<u8>,
         vaa_hash: vector<u8>,
         timestamp: u64,
+        // Signed alongside the fields above; mint_reborn needs them to rebuild message_hash
+        wormhole_sequence: u64,
+        source_sequence: u64,
+        source_block_hint: vector<u8>,
+        valid_until_slot: u64,
+        hash_algo: u8,
     }
 
     public struct SealPending has copy, drop {
@@ -115,6 +123,11 @@ module ikatensei::orchestrator {
         dwallet_id_bytes: vector<u8>,
         timestamp: u64,
         completed: bool,
+        wormhole_sequence: u64,
+        source_sequence: u64,
+        source_block_hint: vector<u8>,
+        valid_until_slot: u64,
+        hash_algo: u8,
     }
 
     /// Orchestrator state — shared object.
@@ -306,6 +319,7 @@ module ikatensei::orchestrator {
         registry: &DWalletRegistry,
         vaa_bytes: vector<u8>,
         dwallet_id: ID,
+        valid_until_slot: u64,
         clock: &sui::clock::Clock,
         _ctx: &mut TxContext,
     ) {
@@ -317,6 +331,7 @@ module ikatensei::orchestrator {
         let emitter_chain = vaa::emitter_chain(&verified_vaa);
         let emitter_addr = external_address::to_bytes(vaa::emitter_address(&verified_vaa));
         let vaa_hash = bytes32::to_bytes(vaa::digest(&verified_vaa));
+        let wormhole_sequence = vaa::sequence(&verified_vaa);
 
         let (_chain, _addr, payload_bytes) = vaa::take_emitter_info_and_payload(verified_vaa);
 
@@ -330,6 +345,9 @@ module ikatensei::orchestrator {
 
         // ── Step 4: Decode payload ──
         let seal_payload = payload::decode_seal_payload(&payload_bytes);
+        // Only Solana has a mint path (mint_reborn). Seals bound elsewhere stay locked on
+        // the source chain until its emergency unlock.
+        assert!(payload::is_solana_dest(&seal_payload), E_UNSUPPORTED_DEST_CHAIN);
         let deposit_address = *payload::get_deposit_address(&seal_payload);
 
         // ── Step 5: One-use dWallet check ──
@@ -356,10 +374,19 @@ module ikatensei::orchestrator {
         let dwallet_pubkey = dwallet_registry::get_dwallet_pubkey(registry, &deposit_address);
 
         // ── Step 7: Construct signing message ──
+        // The emitter's sequence is also the source-chain sequence. No block hint: the
+        // guardians only sign after the emitter's consistency level, so it can't reorg out.
+        let source_block_hint = zero_block_hint();
+        let hash_algo = payload::hash_algo_for_chain(source_chain_val);
         let message_hash = payload::construct_signing_message(
             payload::get_token_uri(&seal_payload),
             payload::get_token_id(&seal_payload),
             payload::get_receiver(&seal_payload),
+            wormhole_sequence,
+            wormhole_sequence,
+            &source_block_hint,
+            valid_until_slot,
+            hash_algo,
         );
 
         // ── Step 8: Store pending seal ──
@@ -375,6 +402,11 @@ module ikatensei::orchestrator {
             dwallet_id_bytes,
             timestamp,
             completed: false,
+            wormhole_sequence,
+            source_sequence: wormhole_sequence,
+            source_block_hint,
+            valid_until_slot,
+            hash_algo,
         };
 
         table::add(&mut state.pending_seals, vaa_hash, pending);
@@ -482,6 +514,7 @@ module ikatensei::orchestrator {
         token_uri: vector<u8>,
         deposit_address: vector<u8>,
         receiver: vector<u8>,
+        valid_until_slot: u64,
         clock: &sui::clock::Clock,
         _ctx: &mut TxContext,
     ) {
@@ -522,8 +555,20 @@ module ikatensei::orchestrator {
         // Replay protection
         assert!(!table::contains(&state.processed_vaas, seal_hash), E_VAA_ALREADY_USED);
 
-        // Construct signing message: sha256(token_uri || token_id || receiver)
-        let message_hash = payload::construct_signing_message(&token_uri, &token_id, &receiver);
+        // Construct signing message. No VAA, so no Wormhole sequence; the RPC deposit
+        // check reports no source block either, so both source markers stay zero.
+        let source_block_hint = zero_block_hint();
+        let hash_algo = payload::hash_algo_for_chain(source_chain);
+        let message_hash = payload::construct_signing_message(
+            &token_uri,
+            &token_id,
+            &receiver,
+            0,
+            0,
+            &source_block_hint,
+            valid_until_slot,
+            hash_algo,
+        );
 
         // Store pending seal
         let pending = PendingSeal {
@@ -538,6 +583,11 @@ module ikatensei::orchestrator {
             dwallet_id_bytes: vector::empty(),
             timestamp,
             completed: false,
+            wormhole_sequence: 0,
+            source_sequence: 0,
+            source_block_hint,
+            valid_until_slot,
+            hash_algo,
         };
 
         table::add(&mut state.pending_seals, seal_hash, pending);
@@ -614,9 +664,25 @@ module ikatensei::orchestrator {
             signature,
             vaa_hash,
             timestamp,
+            wormhole_sequence: pending.wormhole_sequence,
+            source_sequence: pending.source_sequence,
+            source_block_hint: pending.source_block_hint,
+            valid_until_slot: pending.valid_until_slot,
+            hash_algo: pending.hash_algo,
         });
     }
 
+    /// 32 zero bytes: the `source_block_hint` of a seal whose source block is unknown.
+    fun zero_block_hint(): vector<u8> {
+        let mut hint = vector::empty<u8>();
+        let mut i = 0;
+        while (i < 32) {
+            vector::push_back(&mut hint, 0u8);
+            i = i + 1;
+        };
+        hint
+    }
+
     // ==================================================================
     // View functions
     // ==================================================================
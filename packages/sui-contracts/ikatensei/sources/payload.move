@@ -13,15 +13,36 @@
 //   [131+]    token_uri: variable length raw bytes (may be empty, e.g. CryptoPunks)
 //
 // Total minimum: 131 bytes (token_uri may be 0 bytes)
+//
+// payload_type 2 (SealAttestation with explicit destination, NEAR sealer) inserts
+//   [3-4]     dest_chain: u16 (big-endian)
+// after source_chain and shifts every later field by 2 (minimum 133 bytes).
+// Type 1 payloads are always Solana-bound.
+//
+// If payload_type has the 0x80 checksum flag set (NEAR sealer), the payload ends with
+// a 4-byte trailer = last 4 bytes of sha256(all preceding bytes), which is verified
+// and stripped before the fields above are read.
 module ikatensei::payload {
 
     // Error codes
     const E_INVALID_PAYLOAD: u64 = 1;
     const E_INVALID_PAYLOAD_TYPE: u64 = 2;
     const E_INVALID_CHAIN_ID: u64 = 3;
+    const E_INVALID_CHECKSUM: u64 = 4;
+    const E_INVALID_BLOCK_HINT: u64 = 5;
+    const E_INVALID_HASH_ALGO: u64 = 6;
 
-    // Payload type constant
+    // Payload type constants
     const PAYLOAD_TYPE_SEAL: u8 = 1;
+    const PAYLOAD_TYPE_SEAL_DEST: u8 = 2;
+
+    // payload_type flag: payload carries a checksum trailer of CHECKSUM_LEN bytes
+    const PAYLOAD_FLAG_CHECKSUM: u8 = 0x80;
+    const CHECKSUM_LEN: u64 = 4;
+
+    // Signed message hash algorithms (must match HASH_ALGO_* in the Solana reborn program)
+    const HASH_ALGO_SHA256: u8 = 0;
+    const HASH_ALGO_KECCAK256: u8 = 1;
 
     // Supported chain IDs (Wormhole chain IDs)
     const CHAIN_SOLANA: u16 = 1;
@@ -45,6 +66,8 @@ module ikatensei::payload {
     /// Decoded seal payload from Wormhole VAA
     public struct SealPayload has copy, drop, store {
         source_chain: u16,
+        /// Wormhole chain the reborn NFT is minted on (CHAIN_SOLANA for type 1 payloads)
+        dest_chain: u16,
         nft_contract: vector<u8>,
         token_id: vector<u8>,
         deposit_address: vector<u8>,
@@ -56,66 +79,60 @@ module ikatensei::payload {
     /// Decode a VAA payload into a SealPayload struct.
     /// Panics if payload is invalid or wrong type.
     /// token_uri is allowed to be empty (CryptoPunks and similar have no URI).
+    /// Aborts with E_INVALID_CHECKSUM if a checksummed payload's trailer doesn't match.
     public fun decode_seal_payload(payload: &vector<u8>): SealPayload {
-        let len = vector::length(payload);
+        let mut len = vector::length(payload);
         assert!(len >= 131, E_INVALID_PAYLOAD); // Minimum: 1 + 2 + 32 + 32 + 32 + 32 = 131
 
         // [0] - payload type
         let payload_type = *vector::borrow(payload, 0);
-        assert!(payload_type == PAYLOAD_TYPE_SEAL, E_INVALID_PAYLOAD_TYPE);
+        let base_type = payload_type & 0x7F;
+        assert!(base_type == PAYLOAD_TYPE_SEAL || base_type == PAYLOAD_TYPE_SEAL_DEST, E_INVALID_PAYLOAD_TYPE);
+
+        // Type 2 carries dest_chain at [3-4]; every later field starts 2 bytes further on
+        let shift = if (base_type == PAYLOAD_TYPE_SEAL_DEST) { 2 } else { 0 };
+        let min_len = 131 + shift;
+        assert!(len >= min_len, E_INVALID_PAYLOAD);
+
+        // Checksum trailer: verify, then treat the payload as ending before it
+        if ((payload_type & PAYLOAD_FLAG_CHECKSUM) != 0) {
+            assert!(len >= min_len + CHECKSUM_LEN, E_INVALID_PAYLOAD);
+            len = len - CHECKSUM_LEN;
+            assert!(checksum_matches(payload, len), E_INVALID_CHECKSUM);
+        };
 
         // [1-2] - source chain (u16 big-endian)
-        let source_chain = ((*vector::borrow(payload, 1) as u16) << 8) | (*vector::borrow(payload, 2) as u16);
+        let source_chain = read_u16_be(payload, 1);
         assert!(is_supported_chain(source_chain), E_INVALID_CHAIN_ID);
 
+        // [3-4] - dest chain (u16 big-endian), type 2 only
+        let dest_chain = if (shift == 0) { CHAIN_SOLANA } else { read_u16_be(payload, 3) };
+        assert!(is_supported_chain(dest_chain), E_INVALID_CHAIN_ID);
+
         // [3-34] - nft_contract (32 bytes, left-padded address)
-        let mut nft_contract = vector::empty<u8>();
-        let mut i = 3;
-        while (i < 35) {
-            vector::push_back(&mut nft_contract, *vector::borrow(payload, i));
-            i = i + 1;
-        };
+        let nft_contract = slice(payload, 3 + shift, 35 + shift);
 
         // [35-66] - token_id (32 bytes, uint256)
-        let mut token_id = vector::empty<u8>();
-        i = 35;
-        while (i < 67) {
-            vector::push_back(&mut token_id, *vector::borrow(payload, i));
-            i = i + 1;
-        };
+        let token_id = slice(payload, 35 + shift, 67 + shift);
 
         // [67-98] - deposit_address (32 bytes, left-padded address)
         // For EVM chains, strip the 12-byte zero padding → 20-byte address.
         // For Ed25519 chains (NEAR, Solana, etc.), keep all 32 bytes.
         let is_evm = is_evm_chain(source_chain);
         let deposit_start = if (is_evm) { 79 } else { 67 }; // 67 + 12 = 79
-        let mut deposit_address = vector::empty<u8>();
-        i = deposit_start;
-        while (i < 99) {
-            vector::push_back(&mut deposit_address, *vector::borrow(payload, i));
-            i = i + 1;
-        };
+        let deposit_address = slice(payload, deposit_start + shift, 99 + shift);
 
         // [99-130] - receiver (32 bytes, Solana pubkey / receiver wallet)
-        let mut receiver = vector::empty<u8>();
-        i = 99;
-        while (i < 131) {
-            vector::push_back(&mut receiver, *vector::borrow(payload, i));
-            i = i + 1;
-        };
+        let receiver = slice(payload, 99 + shift, 131 + shift);
 
         // [131+] - token_uri (variable length, may be empty)
-        let mut token_uri = vector::empty<u8>();
-        i = 131;
-        while (i < len) {
-            vector::push_back(&mut token_uri, *vector::borrow(payload, i));
-            i = i + 1;
-        };
+        let token_uri = slice(payload, 131 + shift, len);
         // NOTE: token_uri intentionally not asserted non-empty.
         // CryptoPunks and other NFTs have no URI — this is valid.
 
         SealPayload {
             source_chain,
+            dest_chain,
             nft_contract,
             token_id,
             deposit_address,
@@ -124,20 +141,93 @@ module ikatensei::payload {
         }
     }
 
+    /// Big-endian u16 at `offset`.
+    fun read_u16_be(payload: &vector<u8>, offset: u64): u16 {
+        ((*vector::borrow(payload, offset) as u16) << 8) | (*vector::borrow(payload, offset + 1) as u16)
+    }
+
+    /// Copy of payload[start..end].
+    fun slice(payload: &vector<u8>, start: u64, end: u64): vector<u8> {
+        let mut out = vector::empty<u8>();
+        let mut i = start;
+        while (i < end) {
+            vector::push_back(&mut out, *vector::borrow(payload, i));
+            i = i + 1;
+        };
+        out
+    }
+
+    /// True if the CHECKSUM_LEN bytes at `body_len` equal the last CHECKSUM_LEN bytes
+    /// of sha256(payload[0..body_len]).
+    fun checksum_matches(payload: &vector<u8>, body_len: u64): bool {
+        let mut body = vector::empty<u8>();
+        let mut i = 0;
+        while (i < body_len) {
+            vector::push_back(&mut body, *vector::borrow(payload, i));
+            i = i + 1;
+        };
+        let hash = std::hash::sha2_256(body);
+        i = 0;
+        while (i < CHECKSUM_LEN) {
+            if (*vector::borrow(&hash, 32 - CHECKSUM_LEN + i) != *vector::borrow(payload, body_len + i)) {
+                return false
+            };
+            i = i + 1;
+        };
+        true
+    }
+
     /// Construct the message hash for IKA signing.
-    /// Format (v7): sha256(token_uri || token_id || receiver)
-    /// All fields are concatenated as raw bytes.
+    /// Format (v8): H(token_uri || token_id || receiver || wormhole_sequence || source_sequence
+    ///                || source_block_hint || valid_until_slot || hash_algo)
+    /// Byte fields are concatenated raw, u64s as 8 bytes big-endian, hash_algo as one byte.
+    /// H is keccak256 for HASH_ALGO_KECCAK256 and sha256 for HASH_ALGO_SHA256.
+    /// Must match `compute_message_hash` in the Solana reborn program.
     /// Works correctly when token_uri is empty.
     public fun construct_signing_message(
         token_uri: &vector<u8>,
         token_id: &vector<u8>,
         receiver: &vector<u8>,
+        wormhole_sequence: u64,
+        source_sequence: u64,
+        source_block_hint: &vector<u8>,
+        valid_until_slot: u64,
+        hash_algo: u8,
     ): vector<u8> {
+        assert!(vector::length(source_block_hint) == 32, E_INVALID_BLOCK_HINT);
+        assert!(hash_algo == HASH_ALGO_SHA256 || hash_algo == HASH_ALGO_KECCAK256, E_INVALID_HASH_ALGO);
+
         let mut data = vector::empty<u8>();
         vector::append(&mut data, *token_uri);
         vector::append(&mut data, *token_id);
         vector::append(&mut data, *receiver);
-        std::hash::sha2_256(data)
+        append_u64_be(&mut data, wormhole_sequence);
+        append_u64_be(&mut data, source_sequence);
+        vector::append(&mut data, *source_block_hint);
+        append_u64_be(&mut data, valid_until_slot);
+        vector::push_back(&mut data, hash_algo);
+
+        if (hash_algo == HASH_ALGO_KECCAK256) {
+            sui::hash::keccak256(&data)
+        } else {
+            std::hash::sha2_256(data)
+        }
+    }
+
+    /// Hash algorithm the dWallet signs seals from `source_chain` under:
+    /// keccak256 for EVM chains (their native digest), sha256 for everything else.
+    public fun hash_algo_for_chain(source_chain: u16): u8 {
+        if (is_evm_chain(source_chain)) { HASH_ALGO_KECCAK256 } else { HASH_ALGO_SHA256 }
+    }
+
+    /// Append `value` as 8 bytes big-endian.
+    fun append_u64_be(data: &mut vector<u8>, value: u64) {
+        let mut shift = 56u8;
+        loop {
+            vector::push_back(data, (((value >> shift) & 0xFF) as u8));
+            if (shift == 0) break;
+            shift = shift - 8;
+        };
     }
 
     /// Encode a seal payload (for testing or rebuilding VAA payloads).
@@ -280,6 +370,8 @@ module ikatensei::payload {
     // ==================================================================
 
     public fun get_source_chain(p: &SealPayload): u16 { p.source_chain }
+    public fun get_dest_chain(p: &SealPayload): u16 { p.dest_chain }
+    public fun is_solana_dest(p: &SealPayload): bool { p.dest_chain == CHAIN_SOLANA }
     public fun get_nft_contract(p: &SealPayload): &vector<u8> { &p.nft_contract }
     public fun get_token_id(p: &SealPayload): &vector<u8> { &p.token_id }
     public fun get_deposit_address(p: &SealPayload): &vector<u8> { &p.deposit_address }
@@ -8,9 +8,59 @@ module ikatensei::payload_tests {
         let token_id = x"0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20";
         let receiver = x"72656365697665725f77616c6c65745f616464726573735f31323334";
 
-        // sha256(token_uri || token_id || receiver) → 32 bytes
-        let message = payload::construct_signing_message(&token_uri, &token_id, &receiver);
+        let hint = x"0000000000000000000000000000000000000000000000000000000000000000";
+
+        // H(token_uri || token_id || receiver || ... || hash_algo) → 32 bytes
+        let message = payload::construct_signing_message(&token_uri, &token_id, &receiver, 1, 1, &hint, 1, 0);
         assert!(vector::length(&message) == 32, 0);
+        let keccak = payload::construct_signing_message(&token_uri, &token_id, &receiver, 1, 1, &hint, 1, 1);
+        assert!(vector::length(&keccak) == 32, 1);
+        assert!(message != keccak, 2);
+    }
+
+    /// Same vector as the Solana program's `test_message_hash_matches_sui_signer`
+    #[test]
+    fun test_signing_message_matches_solana_vector() {
+        let mut receiver = vector::empty<u8>();
+        let mut hint = vector::empty<u8>();
+        let mut i = 0;
+        while (i < 32) {
+            vector::push_back(&mut receiver, 7);
+            vector::push_back(&mut hint, 9);
+            i = i + 1;
+        };
+        let message = payload::construct_signing_message(
+            &x"697066733a2f2f516d", // "ipfs://Qm"
+            &x"3432",               // "42"
+            &receiver,
+            100,
+            7,
+            &hint,
+            1000,
+            0,
+        );
+        assert!(message == x"ed5da7d645b53cfb6bb19b0cfb42e8ead857219c11e900f808c49547d19242df", 0);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 5, location = ikatensei::payload)]
+    fun test_signing_message_rejects_short_block_hint() {
+        payload::construct_signing_message(&x"", &x"01", &x"02", 0, 0, &x"00", 0, 0);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 6, location = ikatensei::payload)]
+    fun test_signing_message_rejects_unknown_hash_algo() {
+        let hint = x"0000000000000000000000000000000000000000000000000000000000000000";
+        payload::construct_signing_message(&x"", &x"01", &x"02", 0, 0, &hint, 0, 2);
+    }
+
+    #[test]
+    fun test_hash_algo_for_chain() {
+        assert!(payload::hash_algo_for_chain(2) == 1, 0);  // Ethereum → keccak256
+        assert!(payload::hash_algo_for_chain(30) == 1, 1); // Base → keccak256
+        assert!(payload::hash_algo_for_chain(15) == 0, 2); // NEAR → sha256
+        assert!(payload::hash_algo_for_chain(21) == 0, 3); // Sui → sha256
     }
 
     #[test]
@@ -52,4 +102,96 @@ module ikatensei::payload_tests {
         
         assert!(payload::get_source_chain(&decoded) == source_chain, 0);
     }
+
+    /// 0x81 (checksummed) encoding of the roundtrip payload: NEAR → Solana, URI "ipfs://x"
+    fun checksummed_payload(): vector<u8> {
+        let mut body = payload::encode_seal_payload(
+            15,
+            x"0101010101010101010101010101010101010101010101010101010101010101",
+            x"0202020202020202020202020202020202020202020202020202020202020202",
+            x"0303030303030303030303030303030303030303030303030303030303030303",
+            x"0404040404040404040404040404040404040404040404040404040404040404",
+            x"697066733a2f2f78",
+        );
+        *vector::borrow_mut(&mut body, 0) = 0x81;
+        let hash = std::hash::sha2_256(body);
+        let mut i = 28;
+        while (i < 32) {
+            vector::push_back(&mut body, *vector::borrow(&hash, i));
+            i = i + 1;
+        };
+        body
+    }
+
+    #[test]
+    fun test_checksum_trailer_verified_and_stripped() {
+        let decoded = payload::decode_seal_payload(&checksummed_payload());
+        assert!(payload::get_source_chain(&decoded) == 15, 0);
+        assert!(*payload::get_token_uri(&decoded) == x"697066733a2f2f78", 1);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 4, location = ikatensei::payload)]
+    fun test_checksum_mismatch_rejected() {
+        let mut encoded = checksummed_payload();
+        // Flip one URI byte
+        let b = vector::borrow_mut(&mut encoded, 131);
+        *b = *b ^ 0x01;
+        payload::decode_seal_payload(&encoded);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 4, location = ikatensei::payload)]
+    fun test_truncated_checksummed_uri_rejected() {
+        let mut encoded = checksummed_payload();
+        // Drop one URI byte; the trailer now covers the wrong range
+        vector::remove(&mut encoded, 131);
+        payload::decode_seal_payload(&encoded);
+    }
+
+    /// 0x82 (explicit destination, checksummed) encoding as built by the NEAR sealer
+    fun dest_payload(dest_chain: u16): vector<u8> {
+        let mut body = payload::encode_seal_payload(
+            15,
+            x"0101010101010101010101010101010101010101010101010101010101010101",
+            x"0202020202020202020202020202020202020202020202020202020202020202",
+            x"0303030303030303030303030303030303030303030303030303030303030303",
+            x"0404040404040404040404040404040404040404040404040404040404040404",
+            x"697066733a2f2f78",
+        );
+        *vector::borrow_mut(&mut body, 0) = 0x82;
+        vector::insert(&mut body, ((dest_chain >> 8) as u8), 3);
+        vector::insert(&mut body, ((dest_chain & 0xFF) as u8), 4);
+        let hash = std::hash::sha2_256(body);
+        let mut i = 28;
+        while (i < 32) {
+            vector::push_back(&mut body, *vector::borrow(&hash, i));
+            i = i + 1;
+        };
+        body
+    }
+
+    #[test]
+    fun test_dest_chain_payload_decoded_with_shifted_fields() {
+        let decoded = payload::decode_seal_payload(&dest_payload(2));
+        assert!(payload::get_source_chain(&decoded) == 15, 0);
+        assert!(payload::get_dest_chain(&decoded) == 2, 1);
+        assert!(!payload::is_solana_dest(&decoded), 2);
+        assert!(*payload::get_nft_contract(&decoded) == x"0101010101010101010101010101010101010101010101010101010101010101", 3);
+        assert!(*payload::get_receiver(&decoded) == x"0404040404040404040404040404040404040404040404040404040404040404", 4);
+        assert!(*payload::get_token_uri(&decoded) == x"697066733a2f2f78", 5);
+    }
+
+    #[test]
+    fun test_legacy_payload_defaults_to_solana() {
+        let decoded = payload::decode_seal_payload(&checksummed_payload());
+        assert!(payload::get_dest_chain(&decoded) == 1, 0);
+        assert!(payload::is_solana_dest(&decoded), 1);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 3, location = ikatensei::payload)]
+    fun test_unknown_dest_chain_rejected() {
+        payload::decode_seal_payload(&dest_payload(9999));
+    }
 }